@@ -0,0 +1,104 @@
+//! Procedural macros of olympus-kleio. Use them through their re-export in `olympus_kleio`.
+use std::{path::{Path, PathBuf}, fs};
+use proc_macro::{TokenStream, TokenTree, Literal};
+
+/// Embed all files of a folder into the binary at compile time as a `KAssetSourceMemory`.
+///
+/// Folder path is relative to the `Cargo.toml` of the crate using the macro. Asset paths are
+/// relative to the folder, with `/` as separator.
+///
+/// # Example(s)
+/// ```ignore
+/// use olympus_kleio::asset::{KAssetBroker, KAssetSourceMemory, kasset_source_embed};
+///
+/// // Fallback assets always available, even if data folder is missing.
+/// let fallback : KAssetSourceMemory = kasset_source_embed!("assets/fallback");
+/// ```
+///
+/// # Note(s)
+/// Files are embedded with [include_bytes!] so modified files trigger a rebuild, but added
+/// or removed files are only picked up on the next rebuild of the crate.
+#[proc_macro]
+pub fn kasset_source_embed(input : TokenStream) -> TokenStream {
+
+    let folder = match parse_string_literal(input) {
+        Some(folder) => folder,
+        None => return compile_error("kasset_source_embed! expects a single string literal folder path."),
+    };
+
+    let manifest_dir = match std::env::var("CARGO_MANIFEST_DIR") {
+        Ok(dir) => PathBuf::from(dir),
+        Err(_) => return compile_error("kasset_source_embed! requires CARGO_MANIFEST_DIR."),
+    };
+
+    let folder_path = manifest_dir.join(&folder);
+    if !folder_path.is_dir() {
+        return compile_error(&format!("kasset_source_embed! folder {:?} not found.", folder_path));
+    }
+
+    let mut files = Vec::new();
+    if let Err(message) = list_files(&folder_path, &mut files) {
+        return compile_error(&message);
+    }
+    files.sort();
+
+    // Generate (path, bytes) tuples
+    let mut assets = String::new();
+    for file in files {
+        let relative : Vec<String> = match file.strip_prefix(&folder_path).unwrap().components()
+            .map(|c| c.as_os_str().to_str().map(|s| s.to_owned())).collect() {
+            Some(relative) => relative,
+            None => return compile_error(&format!("kasset_source_embed! file {:?} is not valid UTF-8.", file)),
+        };
+        let absolute = match file.to_str() {
+            Some(absolute) => absolute,
+            None => return compile_error(&format!("kasset_source_embed! file {:?} is not valid UTF-8.", file)),
+        };
+
+        assets.push_str(&format!("({}, &::core::include_bytes!({})[..]),",
+            Literal::string(&relative.join("/")), Literal::string(absolute)));
+    }
+
+    format!("::olympus_kleio::asset::KAssetSourceMemory::from_static_assets({}, &[{}])", Literal::string(&folder), assets)
+        .parse().unwrap()
+}
+
+/// Parse input made of a single string literal.
+fn parse_string_literal(input : TokenStream) -> Option<String> {
+    let mut tokens = input.into_iter();
+
+    let literal = match (tokens.next(), tokens.next()) {
+        (Some(TokenTree::Literal(literal)), None) => literal.to_string(),
+        _ => return None,
+    };
+
+    // Raw strings are taken as is, regular strings only support escaped quotes and backslashes.
+    if let Some(raw) = literal.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        return raw.get(hashes + 1..raw.len() - hashes - 1).map(|s| s.to_owned());
+    }
+
+    literal.strip_prefix('"')?.strip_suffix('"').map(|s| s.replace("\\\\", "\\").replace("\\\"", "\""))
+}
+
+/// Recursively list files of folder.
+fn list_files(folder_path : &Path, files : &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries = fs::read_dir(folder_path).map_err(|err| format!("kasset_source_embed! cannot read {:?} : {}", folder_path, err))?;
+
+    for entry in entries {
+        let path = entry.map_err(|err| format!("kasset_source_embed! cannot read {:?} : {}", folder_path, err))?.path();
+
+        if path.is_dir() {
+            list_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns a compile_error! with message.
+fn compile_error(message : &str) -> TokenStream {
+    format!("compile_error!({})", Literal::string(message)).parse().unwrap()
+}
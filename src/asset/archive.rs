@@ -0,0 +1,134 @@
+use std::{path::Path, io::{Read, Seek, SeekFrom, Error, ErrorKind}, time::{SystemTime, Duration}};
+use super::{KAssetPath, KAssetSourceMetadata, KAssetSourceKind};
+
+/// List entry names within folder and its sub folders. Entry names are canonical [KAssetPath] strings.
+pub(crate) fn list_archive_entries<'a, I : Iterator<Item = &'a str>>(names : I, folder : &KAssetPath) -> Vec<KAssetPath> {
+    names.filter_map(|name| KAssetPath::new(name).ok())
+        .filter(|path| path.is_within(folder)).collect()
+}
+
+/// Reader of a window of `length` bytes starting at `start` within an archive.
+///
+/// Position is relative to window and reads never go past its end.
+pub(crate) struct KArchiveWindow<R : Read + Seek> {
+    // Archive reader
+    inner : R,
+
+    // Offset of window within archive
+    start : u64,
+
+    // Length of window
+    length : u64,
+
+    // Position within window
+    position : u64,
+}
+
+impl<R : Read + Seek> KArchiveWindow<R> {
+    /// Create a window of `length` bytes starting at `start` within `inner`.
+    pub(crate) fn new(inner : R, start : u64, length : u64) -> KArchiveWindow<R> {
+        KArchiveWindow { inner, start, length, position : 0 }
+    }
+}
+
+impl<R : Read + Seek> Read for KArchiveWindow<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let remaining = self.length.saturating_sub(self.position);
+        if remaining == 0 || buf.is_empty() {
+            return Ok(0);
+        }
+
+        let size = buf.len().min(remaining.min(usize::MAX as u64) as usize);
+        self.inner.seek(SeekFrom::Start(self.start + self.position))?;
+        let read = self.inner.read(&mut buf[..size])?;
+        self.position += read as u64;
+
+        Ok(read)
+    }
+}
+
+impl<R : Read + Seek> Seek for KArchiveWindow<R> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let position = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => self.length.checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+        };
+
+        match position {
+            Some(position) => {
+                self.position = position;
+                Ok(position)
+            },
+            None => Err(Error::new(ErrorKind::InvalidInput, "Invalid seek to a negative or overflowing position!")),
+        }
+    }
+}
+
+/// Most bytes allocated up front from a size read in an archive, so corrupted sizes can't exhaust memory.
+const KARCHIVE_CAPACITY_MAX : u64 = 1024 * 1024;
+
+/// Largest ratio of uncompressed to compressed size of deflate, as each block of 258 bytes takes at least 2 bits.
+pub(crate) const KARCHIVE_DEFLATE_MAX_RATIO : u64 = 1032;
+
+/// Get capacity to allocate up front for `size` bytes read from an archive, capped since sizes of archives aren't trusted.
+pub(crate) fn get_capacity(size : u64) -> usize {
+    size.min(KARCHIVE_CAPACITY_MAX) as usize
+}
+
+/// Create metadata of archive of `kind` at `archive_path` with `entry_count` assets of `size` bytes uncompressed.
+///
+/// Returns `Some(`[KAssetSourceMetadata]`)` with metadata created or `None` if archive metadata couldn't be read.
+pub(crate) fn create_archive_metadata(archive_path : &Path, kind : KAssetSourceKind, entry_count : usize, size : u64) -> Option<KAssetSourceMetadata> {
+
+    let md = archive_path.metadata().ok()?;
+
+    Some(KAssetSourceMetadata {
+        location : Some(archive_path.display().to_string()),
+        created : md.created().ok(),
+        modified : md.modified().ok(),
+        asset_count : Some(entry_count),
+        size : Some(size),
+        name : archive_path.file_stem().map(|name| name.to_string_lossy().into_owned()),
+        ..KAssetSourceMetadata::new(kind)
+    })
+}
+
+/// Convert a MS-DOS `date` and `time`, as found in zip archives, to [SystemTime].
+///
+/// MS-DOS times have no time zone, so they are read as UTC.
+///
+/// Returns `None` if date or time is invalid.
+pub(crate) fn dos_time_to_system_time(date : u16, time : u16) -> Option<SystemTime> {
+    let (year, month, day) = (1980 + (date >> 9) as i64, ((date >> 5) & 0xF) as i64, (date & 0x1F) as i64);
+    let (hour, minute, second) = ((time >> 11) as u64, ((time >> 5) & 0x3F) as u64, ((time & 0x1F) * 2) as u64);
+
+    if !(1..=12).contains(&month) || day == 0 || hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+
+    // Days since UNIX epoch of civil date, with years starting in March so leap days end years
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year / 400;
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = (era * 146097 + day_of_era - 719468) as u64;
+
+    SystemTime::UNIX_EPOCH.checked_add(Duration::from_secs(days * 86400 + hour * 3600 + minute * 60 + second))
+}
+
+/// Read little-endian u16 at position.
+pub(crate) fn read_u16(data : &[u8], position : usize) -> u16 {
+    u16::from_le_bytes([data[position], data[position + 1]])
+}
+
+/// Read little-endian u32 at position.
+pub(crate) fn read_u32(data : &[u8], position : usize) -> u32 {
+    u32::from_le_bytes([data[position], data[position + 1], data[position + 2], data[position + 3]])
+}
+
+/// Read little-endian u64 at position.
+pub(crate) fn read_u64(data : &[u8], position : usize) -> u64 {
+    (read_u32(data, position) as u64) | ((read_u32(data, position + 4) as u64) << 32)
+}
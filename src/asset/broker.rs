@@ -1,42 +1,113 @@
-use std::{io::{Read, ErrorKind}, path::PathBuf};
+use std::{collections::HashMap, io::{Read, Seek, SeekFrom, Cursor, ErrorKind}, sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard, atomic::{AtomicU64, Ordering}}, time::Instant};
 
-use super::KAssetSource;
+use super::{KAssetSource, KAssetSink, KAssetSeekReader, KAssetCache, KAssetPath, KIntoAssetPath};
+use super::loader::KAssetLoaderRegistry;
+use super::integrity::{KIntegrityManifest, get_verified_seekable};
+use super::trust::{KTrustPolicy, KTrustError};
+use super::decompression::KAssetDecompression;
+use super::locale::KAssetLocalization;
+use super::tags::KAssetTags;
+use super::trace::KAssetTraceRecorder;
+
+// Next source ID given. Shared by all brokers so an ID is never found in the wrong broker.
+static NEXT_SOURCE_ID : AtomicU64 = AtomicU64::new(0);
+
+/// Most paths memoised with their resolved variant, so requests of many distinct paths can't exhaust memory.
+const KASSET_RESOLVED_PATHS_MAX : usize = 4096;
 
 /// Middle men between [`0..n`] [KAssetSource] to supply assets according to source priority.
-/// 
+///
 /// [KAssetSource] could be the file system, a database, a blob, etc... depending on the trait implementation.
-/// 
+///
 /// # Priorities
-/// Sources have priorities (0 > n) and the broker will try to get an asset from higher priorities first. This is useful 
+/// Sources have priorities (0 > n) and the broker will try to get an asset from higher priorities first. This is useful
 /// when handling mods or other asset modification. The base file should be the lowest priority
 /// and mods the highest.
-/// 
+///
+/// # Ownership
+/// The broker owns its sources as [Arc] and identifies them with the [KAssetSourceId] given when added.
+/// KAssetBroker is [Send] and [Sync] so it can be kept in a long-lived struct or shared between threads
+/// with an [Arc]. Sources can be added, removed and reprioritised from any thread.
+///
+/// # Mount points
+/// Sources added with [KAssetBroker::add_source_at] are mounted at a folder of the broker, like a mod folder at
+/// `mods/castle` or a pak at `audio`. A mounted source only serves assets within its mount point, with paths
+/// relative to it, so sources don't have to mirror the whole asset tree. Priorities apply to sources serving a path.
+///
+/// # Provenance
+/// [KAssetBroker::get_provenance] tells which source serves an asset and why, and [KAssetBroker::get_override_report]
+/// which sources override assets of other sources. Sources can be named with [KAssetBroker::set_source_name] for reports.
+///
+/// # Paths
+/// Assets are identified by [KAssetPath](super::KAssetPath). Methods accept any [KIntoAssetPath] and return an
+/// error of kind [InvalidInput](ErrorKind::InvalidInput) wrapping [KAssetPathError](super::KAssetPathError) for
+/// absolute paths and paths escaping sources.
+///
+/// # Integrity
+/// Sources verified with [KAssetBroker::set_source_integrity] have each asset checked against a
+/// [KIntegrityManifest] before being served, so corrupted assets fail with a [KIntegrityError](super::KIntegrityError).
+///
+/// # Trust
+/// A [KTrustPolicy] set with [KAssetBroker::set_trust_policy] only accepts sources signed with trusted keys, like
+/// official content of competitive modes. Sources failing policy are refused when added, or disabled with the
+/// reason given by [KAssetBroker::get_source_trust_error] if already added.
+///
+/// # Decompression
+/// With a [KAssetDecompression] set with [KAssetBroker::set_decompression], assets missing from a source are
+/// looked for compressed, like `foo.json.gz` or `foo.json.zst` for `foo.json`, and decompressed within size limits.
+///
+/// # Locales
+/// With a [KAssetLocalization] set with [KAssetBroker::set_localization], assets are resolved to the variant of the
+/// first locale of a chain having it, like `ui/title.fr-CA.png` then `ui/title.fr.png` for `ui/title.png`, falling
+/// back to the default asset. [KAssetBroker::resolve_locale] tells which variant is picked.
+///
+/// # Tags
+/// With active [KAssetTags] set with [KAssetBroker::set_tags], like `quality=high` or `platform=steamdeck`, assets are
+/// resolved to their best tagged variant found in any source, like `textures/rock@quality=high.png` for
+/// `textures/rock.png`, falling back to the untagged asset. [KAssetBroker::resolve_tags] tells which variant is picked.
+///
+/// # Memory mapping
+/// [KAssetBroker::get_asset_bytes] gives a read-only [KAssetBytes](super::KAssetBytes) view of a whole asset,
+/// memory-mapped by sources that can, like [KAssetSourceFolder](super::KAssetSourceFolder), instead of copied.
+///
+/// # Tracing
+/// While recording with [KAssetBroker::start_trace], every call to [KAssetBroker::has_asset] and [KAssetBroker::get_asset]
+/// is logged with its timestamp, source, bytes read and elapsed time. The [KAssetTrace](super::KAssetTrace) recorded is
+/// exported as text and turned into preload bundles per level and reports of unused assets.
+///
+/// # Cache
+/// An optional [KAssetCache] set with [KAssetBroker::set_cache] keeps loaded assets in memory so
+/// assets requested over and over are not read again from sources.
+///
+/// # Sink
+/// A writable [KAssetSink] set with [KAssetBroker::set_sink] is the source of highest priority, so assets
+/// written with [KAssetBroker::write_asset] override assets of other sources.
+///
+/// # Loaders
+/// [KAssetLoader](super::KAssetLoader) registered with [KAssetBroker::register_loader] parse assets into
+/// typed values with [KAssetBroker::load].
+///
 /// # Example(s)
 /// ##### Creating and adding source in [KAssetBroker]
 /// `Note that this example won't run since 'myfolder0', 'myfolder1' don't exists.`
 /// ```no_run
 /// // Import needed components.
-/// use std::path::PathBuf;
-/// use olympus_kleio::asset::{KAssetBroker, KAssetSourceFolder, KAssetSource};
-/// 
-/// // Create KAssetBroker as mutable since we add KAssetSource to it.
-/// let mut kab = KAssetBroker::new();
-/// 
+/// use std::{path::PathBuf, sync::Arc};
+/// use olympus_kleio::asset::{KAssetBroker, KAssetSourceFolder};
+///
+/// // Create KAssetBroker.
+/// let kab = KAssetBroker::new();
+///
 /// // Create KAssetSources.
-/// let kaf0 = KAssetSourceFolder::new(PathBuf::from("myfolder0"));
-/// let kaf1 = KAssetSourceFolder::new(PathBuf::from("myfolder1"));
-/// 
+/// let kaf0 = KAssetSourceFolder::new(PathBuf::from("myfolder0")).unwrap();
+/// let kaf1 = KAssetSourceFolder::new(PathBuf::from("myfolder1")).unwrap();
+///
 /// // Add sources to broker. Will panic if an error occurred.
-/// if let Err(_) = kab.add_source(&kaf0) {
-///     panic!("Cannot add KAssetSource0 to broker.");
-/// }
-/// 
-/// if let Err(_) = kab.add_source(&kaf1) {
-///     panic!("Cannot add KAssetSource1 to broker.");
-/// }
-/// 
+/// let id0 = kab.add_source(Arc::new(kaf0)).expect("Cannot add KAssetSource0 to broker.");
+/// let id1 = kab.add_source(Arc::new(kaf1)).expect("Cannot add KAssetSource1 to broker.");
+///
 /// // Get asset from broker. Will search if asset is in kaf0 then kaf1.
-/// if let Ok(mut asset) = kab.get_asset(PathBuf::from("myasset.txt")){
+/// if let Ok(mut asset) = kab.get_asset("myasset.txt"){
 ///     // Asset implements the trait Read. Here we read the asset into a string and print it.
 ///     let mut str:String = String::new();
 ///     if let Ok(_) = asset.read_to_string(&mut str){
@@ -44,173 +115,836 @@ use super::KAssetSource;
 ///     }
 /// } else {
 ///     panic!("Cannot get asset 'myasset.txt'");
-/// }   
+/// }
+///
+/// // Give kaf1 the highest priority, then remove kaf0.
+/// kab.set_source_priority(id1, 0).unwrap();
+/// kab.remove_source(id0).unwrap();
 /// ```
-pub struct KAssetBroker<'a> {
+pub struct KAssetBroker {
 
     // Vector of sources. Position 0 is highest priority.
-    sources: Vec<&'a dyn KAssetSource>,
+    sources: RwLock<Vec<KAssetBrokerSource>>,
+
+    // Optional cache of loaded assets
+    cache: RwLock<Option<Arc<KAssetCache>>>,
+
+    // Loaders of typed assets
+    loaders: RwLock<KAssetLoaderRegistry>,
+
+    // Optional writable sink with its source identifier
+    sink: RwLock<Option<KAssetBrokerSink>>,
+
+    // Optional policy of sources signatures
+    trust_policy: RwLock<Option<KTrustPolicy>>,
+
+    // Optional decompression of assets stored compressed
+    decompression: RwLock<Option<KAssetDecompression>>,
+
+    // Optional locale chain resolving assets to their locale variants
+    localization: RwLock<Option<KAssetLocalization>>,
+
+    // Active tags resolving assets to their tagged variants
+    tags: RwLock<KAssetTags>,
+
+    // Optional recorder of asset accesses
+    trace: RwLock<Option<Arc<KAssetTraceRecorder>>>,
+
+    // Paths already resolved to their locale and tagged variants
+    resolved: RwLock<KAssetResolvedPaths>,
+}
+
+/// Paths resolved to the variant served, memoised so variants aren't probed in sources on each access.
+#[derive(Default)]
+pub(crate) struct KAssetResolvedPaths {
+    // Incremented each time resolved paths are forgotten
+    generation : u64,
+
+    // Sum of revisions of sources when paths were resolved
+    revision : u64,
+
+    // Variant served for each path requested
+    paths : HashMap<KAssetPath, KAssetPath>,
+}
+
+/// Unique identifier of a [KAssetSource] added to a [KAssetBroker].
+///
+/// Given by [KAssetBroker::add_source] and stays the same when source priority changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct KAssetSourceId(pub(crate) u64);
+
+impl KAssetSourceId {
+    /// Get the numeric value of the identifier.
+    pub fn get_value(&self) -> u64 {
+        self.0
+    }
 }
 
 /// Enumeration of possible errors that can happens within [KAssetBroker].
 pub enum KAssetBrokerError {
-    
+
     /// Happens when a [KAssetSource] is not found within the broker.
     SourceNotFound,
 
     /// Happens when adding the same [KAssetSource] twice to the broker.
     SourceAlreadyExists,
-    
+
     /// Happens when new priority set for [KAssetSource] is higher then the length of sources.
     PriorityOutOfBound,
 
+    /// Happens when mount point of [KAssetSource] is an invalid [KAssetPath].
+    InvalidMountPoint,
+
+    /// Happens when adding a [KAssetSource] refused by the [KTrustPolicy] of broker, with the reason.
+    SourceNotTrusted(KTrustError),
+
 }
 
+impl std::fmt::Debug for KAssetBrokerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SourceNotFound => write!(f, "SourceNotFound"),
+            Self::SourceAlreadyExists => write!(f, "SourceAlreadyExists"),
+            Self::PriorityOutOfBound => write!(f, "PriorityOutOfBound"),
+            Self::InvalidMountPoint => write!(f, "InvalidMountPoint"),
+            Self::SourceNotTrusted(err) => write!(f, "SourceNotTrusted({:?})", err),
+        }
+    }
+}
+
+/// Sink of broker with its identifier as a source.
+pub(crate) type KAssetBrokerSink = (KAssetSourceId, Arc<dyn KAssetSink + Send + Sync>);
+
+/// Source owned by broker with its identifier and mount point.
+#[derive(Clone)]
+pub(crate) struct KAssetBrokerSource {
+    // Identifier given when added
+    pub(crate) id : KAssetSourceId,
+
+    // Source shared with callers of get_source
+    pub(crate) source : Arc<dyn KAssetSource + Send + Sync>,
+
+    // Folder of broker where source is mounted, root by default
+    pub(crate) mount : KAssetPath,
+
+    // Name of source shown in reports, empty by default
+    pub(crate) name : String,
+
+    // Manifest verifying assets read from source, if any
+    pub(crate) integrity : Option<Arc<KIntegrityManifest>>,
 
-impl<'a> KAssetBroker<'a> {
+    // Reason source is disabled by trust policy, if any
+    pub(crate) distrust : Option<KTrustError>,
+
+    // Source is exempt from trust policy, like the sink
+    pub(crate) trust_exempt : bool,
+}
+
+impl KAssetBrokerSource {
+    /// Get path of asset within source, or `None` if asset isn't within mount point.
+    pub(crate) fn get_local_path(&self, path : &KAssetPath) -> Option<KAssetPath> {
+        path.strip_folder(&self.mount)
+    }
+
+    /// Get folder to list within source for a broker `folder`, or `None` if source has nothing within folder.
+    pub(crate) fn get_local_folder(&self, folder : &KAssetPath) -> Option<KAssetPath> {
+        if *folder == self.mount || self.mount.is_within(folder) {
+            Some(KAssetPath::root())
+        } else {
+            self.get_local_path(folder)
+        }
+    }
+
+    /// Get path of broker for a path within source.
+    pub(crate) fn get_broker_path(&self, path : &KAssetPath) -> KAssetPath {
+        self.mount.join_path(path)
+    }
+
+    /// Get asset at local path, verified against integrity manifest if any.
+    pub(crate) fn get_asset(&self, path : &KAssetPath) -> Result<Box<dyn Read>, std::io::Error> {
+        match &self.integrity {
+            Some(manifest) => Ok(Box::new(Cursor::new(manifest.read_verified(path, self.source.get_asset(path)?)?))),
+            None => self.source.get_asset(path),
+        }
+    }
+
+    /// Get asset at local path as a [KAssetSeekReader], verified against integrity manifest if any.
+    pub(crate) fn get_asset_seekable(&self, path : &KAssetPath) -> Result<KAssetSeekReader, std::io::Error> {
+        match &self.integrity {
+            Some(manifest) => get_verified_seekable(self.source.as_ref(), manifest, path),
+            None => self.source.get_asset_seekable(path),
+        }
+    }
+
+    /// Get range of asset at local path, verified against integrity manifest if any.
+    pub(crate) fn get_asset_range(&self, path : &KAssetPath, offset : u64, length : u64) -> Result<Box<dyn Read>, std::io::Error> {
+        match &self.integrity {
+            Some(manifest) => {
+                let mut reader = get_verified_seekable(self.source.as_ref(), manifest, path)?;
+                let length = length.min(reader.get_length().saturating_sub(offset));
+                reader.seek(SeekFrom::Start(offset))?;
+                Ok(Box::new(reader.take(length)))
+            },
+            None => self.source.get_asset_range(path, offset, length),
+        }
+    }
+}
+
+impl KAssetBroker {
 
     /// Create a new instance of KAssetBroker.
-    pub fn new() -> KAssetBroker<'a> {
-        // Create sources vector
-        let sources : Vec<&'a dyn KAssetSource> = Vec::new();
+    pub fn new() -> KAssetBroker {
+        KAssetBroker { sources : RwLock::new(Vec::new()), cache : RwLock::new(None), loaders : RwLock::new(KAssetLoaderRegistry::default()), sink : RwLock::new(None),
+            trust_policy : RwLock::new(None), decompression : RwLock::new(None), localization : RwLock::new(None),
+            tags : RwLock::new(KAssetTags::default()), trace : RwLock::new(None), resolved : RwLock::new(KAssetResolvedPaths::default()) }
+    }
 
-        // Return new data broker
-        KAssetBroker { sources }
+    /// Add a [KAssetSource] to the broker. Added [KAssetSource] are always last in priority.
+    ///
+    /// Returns [`Ok<KAssetSourceId>`][Ok<KAssetSourceId>] identifying the source if successful.
+    /// # Error(s)
+    /// Returns `Err(`[KAssetBrokerError::SourceAlreadyExists]`)`  if the same [Arc] is already within the broker.
+    ///
+    /// Returns `Err(`[KAssetBrokerError::SourceNotTrusted]`)` if source fails the [KTrustPolicy] of broker.
+    pub fn add_source(&self, source : Arc<dyn KAssetSource + Send + Sync>) -> Result<KAssetSourceId, KAssetBrokerError>{
+        self.add_source_at(source, KAssetPath::root())
     }
 
-    /// Add a [KAssetSource] reference to the broker. Added [KAssetSource] are always last in priority.
-    /// 
-    /// Returns [`Ok<usize>`][Ok<usize>] with the priority of source added if successful.
+    /// Add a [KAssetSource] to the broker, mounted at folder `mount`. Added [KAssetSource] are always last in priority.
+    ///
+    /// Asset `mount/path` of broker is asset `path` of source. Mounting at the root is the same as [KAssetBroker::add_source].
+    ///
+    /// Returns [`Ok<KAssetSourceId>`][Ok<KAssetSourceId>] identifying the source if successful.
     /// # Error(s)
-    /// Returns `Err(`[KAssetBrokerError::SourceAlreadyExists]`)`  if [KAssetSource] is already within the broker.
-    pub fn add_source(&mut self, source : &'a dyn KAssetSource) -> Result<usize, KAssetBrokerError>{
+    /// Returns `Err(`[KAssetBrokerError::InvalidMountPoint]`)` if `mount` is an invalid [KAssetPath].
+    ///
+    /// Returns `Err(`[KAssetBrokerError::SourceAlreadyExists]`)`  if the same [Arc] is already within the broker.
+    ///
+    /// Returns `Err(`[KAssetBrokerError::SourceNotTrusted]`)` if source fails the [KTrustPolicy] of broker.
+    ///
+    /// # Example(s)
+    /// ```
+    /// // Import crate module
+    /// use std::{path::PathBuf, sync::Arc, io::Read};
+    /// use olympus_kleio::asset::{KAssetBroker, KAssetSourceMemory};
+    ///
+    /// // Music pak only knows its own layout
+    /// let music = KAssetSourceMemory::new("music");
+    /// music.add_asset(PathBuf::from("theme.ogg"), vec![1, 2, 3]).unwrap();
+    ///
+    /// let kab = KAssetBroker::new();
+    /// kab.add_source_at(Arc::new(music), "audio/music").unwrap();
+    ///
+    /// // Asset is served under mount point only
+    /// assert!(kab.get_asset("audio/music/theme.ogg").is_ok());
+    /// assert!(kab.get_asset("theme.ogg").is_err());
+    /// ```
+    pub fn add_source_at(&self, source : Arc<dyn KAssetSource + Send + Sync>, mount : impl KIntoAssetPath) -> Result<KAssetSourceId, KAssetBrokerError>{
+
+        let mount = mount.into_asset_path().map_err(|_| KAssetBrokerError::InvalidMountPoint)?;
+        self.insert_source(source, mount, None)
+    }
 
-        if self.has_source(source) == false {
-            let priority = self.sources.len();
-            // Add source to broker
-            self.sources.push(source);
+    /// Insert a [KAssetSource] last in priority checked against [KTrustPolicy], or first in priority and recorded as
+    /// the sink of broker if source is `sink`.
+    ///
+    /// Source is checked and inserted under the lock of sources, so a policy set meanwhile can't miss it and readers
+    /// never see a sink at another priority.
+    pub(crate) fn insert_source(&self, source : Arc<dyn KAssetSource + Send + Sync>, mount : KAssetPath, sink : Option<Arc<dyn KAssetSink + Send + Sync>>)
+        -> Result<KAssetSourceId, KAssetBrokerError>{
 
-            Ok(priority)
-        } else {
-            Err(KAssetBrokerError::SourceAlreadyExists)
+        let mut sources = self.write_sources();
+
+        // Compare data pointers only since vtable pointers of a same source may differ
+        if sources.iter().any(|s| std::ptr::addr_eq(Arc::as_ptr(&s.source), Arc::as_ptr(&source))) {
+            return Err(KAssetBrokerError::SourceAlreadyExists);
         }
+
+        let trust_exempt = sink.is_some();
+        let integrity = match self.get_trust_policy() {
+            Some(policy) if !trust_exempt => policy.check_source(source.as_ref()).map_err(KAssetBrokerError::SourceNotTrusted)?.1,
+            _ => None,
+        };
+
+        let id = KAssetSourceId(NEXT_SOURCE_ID.fetch_add(1, Ordering::Relaxed));
+        let source = KAssetBrokerSource { id, source, mount, name : String::new(), integrity, distrust : None, trust_exempt };
+        match sink {
+            Some(sink) => {
+                sources.insert(0, source);
+                *self.write_sink() = Some((id, sink));
+            },
+            None => sources.push(source),
+        }
+
+        Ok(id)
     }
 
-    /// Remove the [KAssetSource] reference from the broker. 
-    /// 
+    /// Remove the [KAssetSource] from the broker. Removing the source of the sink also removes the sink.
+    ///
     /// Returns [`Ok<usize>`][Ok<usize>] with the priority of [KAssetSource] removed if successful.
-    /// 
+    ///
     /// # Error(s)
     /// Returns `Err(`[KAssetBrokerError::SourceNotFound]`)` if [KAssetSource] is not found.
-    pub fn remove_source(&mut self, source : &'a dyn KAssetSource) -> Result<usize, KAssetBrokerError>{
+    pub fn remove_source(&self, id : KAssetSourceId) -> Result<usize, KAssetBrokerError>{
 
-        let priority = self.get_source_priority(source);
+        let mut sources = self.write_sources();
+
+        match Self::find_source(&sources, id) {
+            Some(priority) => {
+                sources.remove(priority);
+
+                let mut sink = self.write_sink();
+                if sink.as_ref().is_some_and(|(sink_id, _)| *sink_id == id) {
+                    *sink = None;
+                }
+
+                if let Some(cache) = self.get_cache() {
+                    cache.invalidate_source(id);
+                }
 
-        match priority {
-            Ok(priority) => {
-                self.sources.remove(priority);
                 Ok(priority)
             },
-            Err(_) => Err(KAssetBrokerError::SourceNotFound),
+            None => Err(KAssetBrokerError::SourceNotFound),
         }
     }
 
     /// Set a [KAssetSource] priority. Will change other sources priorities.
-    /// 
+    ///
     /// Returns [`Ok<usize>`][Ok<usize>] with the new priority of [KAssetSource] if successful.
-    /// 
+    ///
     /// # Error(s)
     /// Returns `Err(`[KAssetBrokerError::PriorityOutOfBound]`)` if the `priority` > broker sources length.
-    /// 
+    ///
     /// Returns `Err(`[KAssetBrokerError::SourceNotFound]`)` if [KAssetSource] is not found.
-    pub fn set_source_priority(&mut self, source : &'a dyn KAssetSource, priority : usize)-> Result<usize, KAssetBrokerError>{
-
-        // Get current position / priority of the source
-        let position = self.get_source_priority(source);
-
-        match position {
-            Ok(mut position) => {
-
-                if priority < self.sources.len() {
-
-                    // Will replace the position at the correct place
-                    while priority > position {
-                        let temp = self.sources[position];
-                        self.sources[position] = self.sources[position + 1];
-                        self.sources[position + 1] = temp;
-                        position += 1;
-                            
-                    }
-                    while priority < position {
-                        let temp = self.sources[position];
-                        self.sources[position] = self.sources[position - 1];
-                        self.sources[position - 1] = temp;                            
-                        position -= 1;
-                    }
+    pub fn set_source_priority(&self, id : KAssetSourceId, priority : usize)-> Result<usize, KAssetBrokerError>{
 
-                    Ok(priority)
+        let mut sources = self.write_sources();
+
+        match Self::find_source(&sources, id) {
+            Some(position) => {
+                if priority < sources.len() {
+                    // Shift other sources between old and new position
+                    let source = sources.remove(position);
+                    sources.insert(priority, source);
 
+                    Ok(priority)
                 } else {
                     Err(KAssetBrokerError::PriorityOutOfBound)
                 }
+            },
+            None => Err(KAssetBrokerError::SourceNotFound),
+        }
+
+    }
+
+    /// Mount the [KAssetSource] identified by `id` at folder `mount`. See [KAssetBroker::add_source_at].
+    ///
+    /// Cached assets of source are invalidated.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KAssetBrokerError::InvalidMountPoint]`)` if `mount` is an invalid [KAssetPath].
+    ///
+    /// Returns `Err(`[KAssetBrokerError::SourceNotFound]`)` if [KAssetSource] is not found.
+    pub fn set_source_mount(&self, id : KAssetSourceId, mount : impl KIntoAssetPath) -> Result<(), KAssetBrokerError> {
+        let mount = mount.into_asset_path().map_err(|_| KAssetBrokerError::InvalidMountPoint)?;
+        let mut sources = self.write_sources();
+
+        match Self::find_source(&sources, id) {
+            Some(priority) => {
+                sources[priority].mount = mount;
+
+                if let Some(cache) = self.get_cache() {
+                    cache.invalidate_source(id);
+                }
 
+                Ok(())
             },
-            Err(_) => Err(KAssetBrokerError::SourceNotFound),
+            None => Err(KAssetBrokerError::SourceNotFound),
         }
+    }
 
+    /// Get the mount point of the [KAssetSource] identified by `id`.
+    ///
+    /// Returns `Ok(`[KAssetPath]`)` with the folder where source is mounted, the root if not mounted.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KAssetBrokerError::SourceNotFound]`)` if [KAssetSource] is not found.
+    pub fn get_source_mount(&self, id : KAssetSourceId) -> Result<KAssetPath, KAssetBrokerError> {
+        let sources = self.read_sources();
+
+        match Self::find_source(&sources, id) {
+            Some(priority) => Ok(sources[priority].mount.clone()),
+            None => Err(KAssetBrokerError::SourceNotFound),
+        }
     }
 
-    /// Get an immutable reference to the broker [KAssetSource] vector.
-    pub fn get_sources(&self) -> &Vec<&'a dyn KAssetSource> {
-        &self.sources
+    /// Set the name of the [KAssetSource] identified by `id`, shown in reports like [KAssetBroker::get_provenance].
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KAssetBrokerError::SourceNotFound]`)` if [KAssetSource] is not found.
+    pub fn set_source_name(&self, id : KAssetSourceId, name : &str) -> Result<(), KAssetBrokerError> {
+        let mut sources = self.write_sources();
+
+        match Self::find_source(&sources, id) {
+            Some(priority) => {
+                sources[priority].name = name.to_owned();
+                Ok(())
+            },
+            None => Err(KAssetBrokerError::SourceNotFound),
+        }
+    }
+
+    /// Get the name of the [KAssetSource] identified by `id`, empty if not named.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KAssetBrokerError::SourceNotFound]`)` if [KAssetSource] is not found.
+    pub fn get_source_name(&self, id : KAssetSourceId) -> Result<String, KAssetBrokerError> {
+        let sources = self.read_sources();
+
+        match Self::find_source(&sources, id) {
+            Some(priority) => Ok(sources[priority].name.clone()),
+            None => Err(KAssetBrokerError::SourceNotFound),
+        }
+    }
+
+    /// Get the [KAssetSourceId] of broker sources ordered by priority.
+    pub fn get_sources(&self) -> Vec<KAssetSourceId> {
+        self.read_sources().iter().map(|s| s.id).collect()
+    }
+
+    /// Get the count of sources in broker.
+    pub fn get_source_count(&self) -> usize {
+        self.read_sources().len()
+    }
+
+    /// Get the [KAssetSource] identified by `id`.
+    ///
+    /// Returns `Ok(`[Arc]`)` sharing the source if found.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KAssetBrokerError::SourceNotFound]`)` if [KAssetSource] is not found.
+    pub fn get_source(&self, id : KAssetSourceId) -> Result<Arc<dyn KAssetSource + Send + Sync>, KAssetBrokerError> {
+        let sources = self.read_sources();
+
+        match Self::find_source(&sources, id) {
+            Some(priority) => Ok(sources[priority].source.clone()),
+            None => Err(KAssetBrokerError::SourceNotFound),
+        }
+    }
+
+    /// Set the [KAssetCache] of broker, or remove it with `None`.
+    pub fn set_cache(&self, cache : Option<Arc<KAssetCache>>) {
+        *self.cache.write().unwrap_or_else(|err| err.into_inner()) = cache;
+    }
+
+    /// Get the [KAssetCache] of broker, if any.
+    pub fn get_cache(&self) -> Option<Arc<KAssetCache>> {
+        self.cache.read().unwrap_or_else(|err| err.into_inner()).clone()
     }
 
     /// Fetch an asset in sources from path.
-    /// 
+    ///
+    /// With a [KAssetLocalization], path is resolved to its locale variant first, as given by [KAssetBroker::resolve_locale].
+    /// With active [KAssetTags], path is then resolved to its best tagged variant, as given by [KAssetBroker::resolve_tags].
+    /// Variant resolved is remembered until sources, decompression, localization or tags of broker change, until a source
+    /// changes its [revision](KAssetSource::get_revision), or until [KAssetBroker::poll_changes] reports a change.
+    ///
+    /// With a [KAssetCache], asset is served from cache if cached for the source of highest priority
+    /// having it. Otherwise asset is read entirely and cached.
+    ///
+    /// While tracing with [KAssetBroker::start_trace], access is recorded with bytes read until asset is dropped.
+    ///
     /// Returns `Ok(Box(`[Read]`))` if asset found.
-    /// 
+    ///
     /// # Error(s)
+    /// Returns [std::io::Error] of kind [InvalidInput](ErrorKind::InvalidInput) if path is invalid.
+    ///
     /// Returns [std::io::Error] if asset not found or IO error occurs.
-    pub fn get_asset(&self, path: PathBuf) ->  Result<Box<dyn Read>, std::io::Error>{
+    pub fn get_asset(&self, path: impl KIntoAssetPath) ->  Result<Box<dyn Read>, std::io::Error>{
+        let requested = path.into_asset_path()?;
+        let started = Instant::now();
+        let path = self.get_resolved_path(&requested);
+        let asset = self.open_asset(&path);
+
+        match self.read_trace().clone() {
+            Some(recorder) => recorder.record_get(requested, path, asset, started),
+            None => asset.map(|(asset, _)| asset),
+        }
+    }
+
+    /// Verify if an asset exists in sources at path, resolved like [KAssetBroker::get_asset] would, without opening it.
+    ///
+    /// Returns false if path is invalid.
+    pub fn has_asset(&self, path: impl KIntoAssetPath) -> bool {
+        let requested = match path.into_asset_path() {
+            Ok(requested) => requested,
+            Err(_) => return false,
+        };
+
+        let started = Instant::now();
+        let path = self.get_resolved_path(&requested);
+        let source = self.find_asset_source(&path);
+
+        if let Some(recorder) = self.read_trace().clone() {
+            recorder.record_has(requested, path, source, started);
+        }
+
+        source.is_some()
+    }
+
+    /// Get identifier of source serving asset at resolved path, or `None` if no source has it.
+    fn find_asset_source(&self, path : &KAssetPath) -> Option<KAssetSourceId> {
+        let cache = self.get_cache();
+        let decompression = self.get_decompression();
+
+        self.get_sources_snapshot().into_iter().find(|src| match src.get_local_path(path) {
+            Some(local) => cache.as_ref().is_some_and(|cache| cache.is_cached(src.id, path)) || src.source.has_asset(&local)
+                || decompression.as_ref().is_some_and(|decompression| decompression.find_asset(src, &local).is_some()),
+            None => false,
+        }).map(|src| src.id)
+    }
+
+    /// Open asset at resolved path with identifier of source serving it.
+    fn open_asset(&self, path : &KAssetPath) -> Result<(Box<dyn Read>, KAssetSourceId), std::io::Error> {
+        // Sources are copied so the lock isn't held during IO
+        let sources = self.get_sources_snapshot();
+        let cache = self.get_cache();
+        let decompression = self.get_decompression();
+
+        // Use for 0.. as priority, skipping sources not mounted over path
+        for src in sources {
+            let id = src.id;
+            let local = match src.get_local_path(path) {
+                Some(local) => local,
+                None => continue,
+            };
+
+            if let Some(cache) = &cache {
+                if let Some(data) = cache.get(id, path) {
+                    return Ok((Box::new(Cursor::new(data)), id));
+                }
+            }
 
-        // Use for 0.. as priority
-        for n in 0..self.sources.len() {
-            let src = self.sources[n];
-            
             // If sources has asset, return it
-            if src.has_asset(path.clone()) {
-                return src.get_asset(path);
-            } 
+            if src.source.has_asset(&local) {
+                let asset : Box<dyn Read> = match &cache {
+                    Some(cache) => {
+                        let mut data = Vec::new();
+                        src.get_asset(&local)?.read_to_end(&mut data)?;
+
+                        let data : Arc<[u8]> = data.into();
+                        cache.insert(id, path, data.clone());
+                        Box::new(Cursor::new(data))
+                    },
+                    None => src.get_asset(&local)?,
+                };
+
+                return Ok((asset, id));
+            }
+
+            if let Some(data) = self.get_decompressed_asset(&src, &local, path, &decompression, &cache)? {
+                return Ok((Box::new(Cursor::new(data)), id));
+            }
+        }
+
+        Err(std::io::Error::new(ErrorKind::NotFound, "Asset not found!"))
+    }
+
+    /// Fetch an asset in sources from path as a [KAssetSeekReader], supporting [Seek](std::io::Seek) and reporting asset length.
+    ///
+    /// With a [KAssetCache], asset is served from cache if cached for the source of highest priority
+    /// having it. Otherwise asset is read with [KAssetSource::get_asset_seekable] and isn't cached, unless decompressed.
+    ///
+    /// Returns `Ok(`[KAssetSeekReader]`)` if asset found.
+    ///
+    /// # Error(s)
+    /// Returns [std::io::Error] of kind [InvalidInput](ErrorKind::InvalidInput) if path is invalid.
+    ///
+    /// Returns [std::io::Error] if asset not found or IO error occurs.
+    pub fn get_asset_seekable(&self, path: impl KIntoAssetPath) -> Result<KAssetSeekReader, std::io::Error> {
+        let path = self.get_resolved_path(&path.into_asset_path()?);
+
+        let sources = self.get_sources_snapshot();
+        let cache = self.get_cache();
+        let decompression = self.get_decompression();
+
+        for src in sources {
+            let local = match src.get_local_path(&path) {
+                Some(local) => local,
+                None => continue,
+            };
+
+            if let Some(data) = cache.as_ref().and_then(|cache| cache.get(src.id, &path)) {
+                let length = data.len() as u64;
+                return Ok(KAssetSeekReader::new(Box::new(Cursor::new(data)), length));
+            }
+
+            if src.source.has_asset(&local) {
+                return src.get_asset_seekable(&local);
+            }
+
+            if let Some(data) = self.get_decompressed_asset(&src, &local, &path, &decompression, &cache)? {
+                let length = data.len() as u64;
+                return Ok(KAssetSeekReader::new(Box::new(Cursor::new(data)), length));
+            }
         }
 
         Err(std::io::Error::new(ErrorKind::NotFound, "Asset not found!"))
     }
 
-    /// Get the priority/position of the given [KAssetSource].
-    /// 
+    /// Fetch `length` bytes of an asset in sources from path, starting at `offset`.
+    ///
+    /// Range is truncated at the end of asset. With a [KAssetCache], range is served from cache if cached
+    /// for the source of highest priority having it. Otherwise range is read with [KAssetSource::get_asset_range]
+    /// and isn't cached, unless decompressed.
+    ///
+    /// Returns `Ok(Box(`[Read]`))` if asset found.
+    ///
+    /// # Error(s)
+    /// Returns [std::io::Error] of kind [InvalidInput](ErrorKind::InvalidInput) if path is invalid.
+    ///
+    /// Returns [std::io::Error] if asset not found or IO error occurs.
+    pub fn get_asset_range(&self, path: impl KIntoAssetPath, offset : u64, length : u64) -> Result<Box<dyn Read>, std::io::Error> {
+        let path = self.get_resolved_path(&path.into_asset_path()?);
+
+        let sources = self.get_sources_snapshot();
+        let cache = self.get_cache();
+        let decompression = self.get_decompression();
+
+        for src in sources {
+            let local = match src.get_local_path(&path) {
+                Some(local) => local,
+                None => continue,
+            };
+
+            if let Some(data) = cache.as_ref().and_then(|cache| cache.get(src.id, &path)) {
+                let mut reader = Cursor::new(data);
+                reader.set_position(offset);
+                return Ok(Box::new(reader.take(length)));
+            }
+
+            if src.source.has_asset(&local) {
+                return src.get_asset_range(&local, offset, length);
+            }
+
+            if let Some(data) = self.get_decompressed_asset(&src, &local, &path, &decompression, &cache)? {
+                let mut reader = Cursor::new(data);
+                reader.set_position(offset);
+                return Ok(Box::new(reader.take(length)));
+            }
+        }
+
+        Err(std::io::Error::new(ErrorKind::NotFound, "Asset not found!"))
+    }
+
+    /// Get asset at path decompressed from a compressed asset of source, caching it, or `None` if source has none
+    /// or decompression isn't set.
+    pub(crate) fn get_decompressed_asset(&self, src : &KAssetBrokerSource, local : &KAssetPath, path : &KAssetPath, decompression : &Option<KAssetDecompression>,
+        cache : &Option<Arc<KAssetCache>>) -> Result<Option<Arc<[u8]>>, std::io::Error> {
+        let data = match decompression {
+            Some(decompression) => src.get_decompressed_asset(local, decompression)?,
+            None => None,
+        };
+
+        if let (Some(data), Some(cache)) = (&data, cache) {
+            cache.insert(src.id, path, data.clone());
+        }
+
+        Ok(data)
+    }
+
+    /// Get the priority/position of the [KAssetSource] identified by `id`.
+    ///
     /// Returns [`Ok<usize>`][Ok<usize>] with the priority if found.
-    /// 
+    ///
     /// # Error(s)
     /// Returns `Err(`[KAssetBrokerError::SourceNotFound]`)` if [KAssetSource] is not found.
-    pub fn get_source_priority(&self, source : &'a dyn KAssetSource) -> Result<usize, KAssetBrokerError>{
+    pub fn get_source_priority(&self, id : KAssetSourceId) -> Result<usize, KAssetBrokerError>{
+        match Self::find_source(&self.read_sources(), id) {
+            Some(priority) => Ok(priority),
+            None => Err(KAssetBrokerError::SourceNotFound),
+        }
+    }
+
+    /// Verify if broker contains the [KAssetSource] identified by `id`.
+    ///
+    /// Returns True if broker contain [KAssetSource], false otherwise
+    pub fn has_source(&self, id : KAssetSourceId) -> bool{
+        self.get_source_priority(id).is_ok()
+    }
 
-        for n in 0..self.sources.len() {
-            if std::ptr::eq(self.sources[n], source) {
-                return Ok(n) 
+    /// Get sources enabled with their identifier and mount point ordered by priority.
+    ///
+    /// Sources disabled by trust policy are skipped.
+    pub(crate) fn get_sources_snapshot(&self) -> Vec<KAssetBrokerSource> {
+        self.read_sources().iter().filter(|src| src.distrust.is_none()).cloned().collect()
+    }
+
+    /// Get all sources with their identifier and mount point ordered by priority, including sources disabled.
+    pub(crate) fn get_all_sources_snapshot(&self) -> Vec<KAssetBrokerSource> {
+        self.read_sources().clone()
+    }
+
+    /// Find position of source in sources.
+    fn find_source(sources : &[KAssetBrokerSource], id : KAssetSourceId) -> Option<usize> {
+        sources.iter().position(|s| s.id == id)
+    }
+
+    /// Lock sources for reading. A poisoned lock is recovered since sources are always left consistent.
+    fn read_sources(&self) -> RwLockReadGuard<'_, Vec<KAssetBrokerSource>> {
+        self.sources.read().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Lock sink for reading. A poisoned lock is recovered since sink is always left consistent.
+    pub(crate) fn read_sink(&self) -> RwLockReadGuard<'_, Option<KAssetBrokerSink>> {
+        self.sink.read().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Lock sink for writing. A poisoned lock is recovered since sink is always left consistent.
+    pub(crate) fn write_sink(&self) -> RwLockWriteGuard<'_, Option<KAssetBrokerSink>> {
+        self.sink.write().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Lock loaders for reading. A poisoned lock is recovered since loaders are always left consistent.
+    pub(crate) fn read_loaders(&self) -> RwLockReadGuard<'_, KAssetLoaderRegistry> {
+        self.loaders.read().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Lock loaders for writing. A poisoned lock is recovered since loaders are always left consistent.
+    pub(crate) fn write_loaders(&self) -> RwLockWriteGuard<'_, KAssetLoaderRegistry> {
+        self.loaders.write().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Lock trust policy for reading. A poisoned lock is recovered since policy is always left consistent.
+    pub(crate) fn read_trust_policy(&self) -> RwLockReadGuard<'_, Option<KTrustPolicy>> {
+        self.trust_policy.read().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Lock trust policy for writing. A poisoned lock is recovered since policy is always left consistent.
+    pub(crate) fn write_trust_policy(&self) -> RwLockWriteGuard<'_, Option<KTrustPolicy>> {
+        self.trust_policy.write().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Lock decompression for reading. A poisoned lock is recovered since decompression is always left consistent.
+    pub(crate) fn read_decompression(&self) -> RwLockReadGuard<'_, Option<KAssetDecompression>> {
+        self.decompression.read().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Lock decompression for writing, forgetting resolved paths. A poisoned lock is recovered since decompression is always left consistent.
+    pub(crate) fn write_decompression(&self) -> RwLockWriteGuard<'_, Option<KAssetDecompression>> {
+        let decompression = self.decompression.write().unwrap_or_else(|err| err.into_inner());
+        self.forget_resolved_paths();
+        decompression
+    }
+
+    /// Lock localization for reading. A poisoned lock is recovered since localization is always left consistent.
+    pub(crate) fn read_localization(&self) -> RwLockReadGuard<'_, Option<KAssetLocalization>> {
+        self.localization.read().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Lock localization for writing, forgetting resolved paths. A poisoned lock is recovered since localization is always left consistent.
+    pub(crate) fn write_localization(&self) -> RwLockWriteGuard<'_, Option<KAssetLocalization>> {
+        let localization = self.localization.write().unwrap_or_else(|err| err.into_inner());
+        self.forget_resolved_paths();
+        localization
+    }
+
+    /// Lock tags for reading. A poisoned lock is recovered since tags are always left consistent.
+    pub(crate) fn read_tags(&self) -> RwLockReadGuard<'_, KAssetTags> {
+        self.tags.read().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Lock tags for writing, forgetting resolved paths. A poisoned lock is recovered since tags are always left consistent.
+    pub(crate) fn write_tags(&self) -> RwLockWriteGuard<'_, KAssetTags> {
+        let tags = self.tags.write().unwrap_or_else(|err| err.into_inner());
+        self.forget_resolved_paths();
+        tags
+    }
+
+    /// Lock trace recorder for reading. A poisoned lock is recovered since recorder is always left consistent.
+    pub(crate) fn read_trace(&self) -> RwLockReadGuard<'_, Option<Arc<KAssetTraceRecorder>>> {
+        self.trace.read().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Lock trace recorder for writing. A poisoned lock is recovered since recorder is always left consistent.
+    pub(crate) fn write_trace(&self) -> RwLockWriteGuard<'_, Option<Arc<KAssetTraceRecorder>>> {
+        self.trace.write().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Lock sources for writing, forgetting resolved paths. A poisoned lock is recovered since sources are always left consistent.
+    pub(crate) fn write_sources(&self) -> RwLockWriteGuard<'_, Vec<KAssetBrokerSource>> {
+        let sources = self.sources.write().unwrap_or_else(|err| err.into_inner());
+        self.forget_resolved_paths();
+        sources
+    }
+
+    /// Get path of variant served for asset at path, resolved to its locale variant then to its best tagged variant.
+    ///
+    /// Resolved path is memoised until resolved paths are forgotten with [KAssetBroker::forget_resolved_paths] or
+    /// a [revision](KAssetSource::get_revision) of sources changes. A memoised path no source has anymore is resolved again.
+    pub(crate) fn get_resolved_path(&self, requested : &KAssetPath) -> KAssetPath {
+        let revision = self.get_sources_revision();
+        let (generation, memoised) = {
+            let resolved = self.read_resolved();
+            let memoised = match resolved.revision == revision {
+                true => resolved.paths.get(requested).cloned(),
+                false => None,
+            };
+            (resolved.generation, memoised)
+        };
+
+        // Variant may have been removed from a source not reporting its changes
+        if let Some(path) = memoised {
+            if self.find_asset_source(&path).is_some() {
+                return path;
             }
-        } 
+        }
+
+        // Nothing to probe without locale nor tags
+        if self.read_localization().is_none() && self.read_tags().is_empty() {
+            return requested.clone();
+        }
 
-        Err(KAssetBrokerError::SourceNotFound)
+        let path = self.get_tagged_path(self.get_localized_path(requested.clone()));
+
+        // Resolution is stale if paths were forgotten while resolving
+        let mut resolved = self.write_resolved();
+        if resolved.generation == generation {
+            if resolved.revision != revision || resolved.paths.len() >= KASSET_RESOLVED_PATHS_MAX {
+                resolved.paths.clear();
+                resolved.revision = revision;
+            }
+            resolved.paths.insert(requested.clone(), path.clone());
+        }
+        path
     }
 
-    /// Verify if broker contains given [KAssetSource].
-    /// 
-    /// Returns True if broker contain [KAssetSource], false otherwise
-    pub fn has_source(&self, source : &'a dyn KAssetSource) -> bool{
-        match  self.get_source_priority(source) {
-            Ok(_) => true,
-            Err(_) => false,
-        } 
+    /// Get sum of [revisions](KAssetSource::get_revision) of enabled sources, changed when any of them changes its content.
+    fn get_sources_revision(&self) -> u64 {
+        self.read_sources().iter().filter(|src| src.distrust.is_none()).fold(0, |sum, src| sum.wrapping_add(src.source.get_revision()))
     }
 
+    /// Forget resolved paths when sources, their content, decompression, localization or tags change.
+    pub(crate) fn forget_resolved_paths(&self) {
+        let mut resolved = self.write_resolved();
+        resolved.generation += 1;
+        resolved.paths.clear();
+    }
+
+    /// Lock resolved paths for reading. A poisoned lock is recovered since resolved paths are always left consistent.
+    fn read_resolved(&self) -> RwLockReadGuard<'_, KAssetResolvedPaths> {
+        self.resolved.read().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Lock resolved paths for writing. A poisoned lock is recovered since resolved paths are always left consistent.
+    fn write_resolved(&self) -> RwLockWriteGuard<'_, KAssetResolvedPaths> {
+        self.resolved.write().unwrap_or_else(|err| err.into_inner())
+    }
+
+}
+
+impl Default for KAssetBroker {
+    fn default() -> Self {
+        Self::new()
+    }
 }
@@ -0,0 +1,308 @@
+use std::{collections::{HashMap, HashSet, BTreeMap}, sync::{Arc, Mutex, MutexGuard}};
+
+use crate::journal::KJournal;
+use super::{KAssetSourceId, KAssetPath, KIntoAssetPath, KAssetCompression};
+
+/// ##### Memory-budgeted cache of assets loaded by a [KAssetBroker](super::KAssetBroker).
+///
+/// Assets are cached as bytes keyed by path and [KAssetSourceId], so an asset overridden by
+/// a higher priority source is never served from the cache of the lower one.
+///
+/// When the byte budget is exceeded, the least recently used assets are evicted first. Pinned paths
+/// are never evicted and are cached even if budget is exceeded. Invalid paths are never cached nor pinned.
+///
+/// # Example(s)
+/// ```
+/// // Import crate module
+/// use std::{path::PathBuf, sync::Arc, io::Read};
+/// use olympus_kleio::asset::{KAssetBroker, KAssetSourceMemory, KAssetCache};
+///
+/// // Create broker with a source and a cache of 1 MiB
+/// let source = KAssetSourceMemory::new("ui");
+/// source.add_asset(PathBuf::from("atlas.png"), vec![0; 1024]).unwrap();
+/// let broker = KAssetBroker::new();
+/// broker.add_source(Arc::new(source)).unwrap();
+/// broker.set_cache(Some(Arc::new(KAssetCache::new(1024 * 1024))));
+///
+/// // First get is a miss, then atlas is served from cache
+/// for _ in 0..3 {
+///     let mut atlas = Vec::new();
+///     broker.get_asset("atlas.png").unwrap().read_to_end(&mut atlas).unwrap();
+/// }
+///
+/// let statistics = broker.get_cache().unwrap().get_statistics();
+/// assert_eq!((statistics.hits, statistics.misses), (2, 1));
+/// ```
+pub struct KAssetCache {
+    // Entries, usage order and statistics
+    state : Mutex<KAssetCacheState>,
+}
+
+/// Statistics of a [KAssetCache].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KAssetCacheStatistics {
+    /// Count of assets served from cache.
+    pub hits : u64,
+
+    /// Count of assets loaded from sources since not in cache.
+    pub misses : u64,
+
+    /// Count of assets evicted to respect budget.
+    pub evictions : u64,
+
+    /// Count of assets in cache.
+    pub entries : usize,
+
+    /// Count of bytes in cache.
+    pub size : usize,
+
+    /// Byte budget of cache.
+    pub budget : usize,
+}
+
+impl std::fmt::Display for KAssetCacheStatistics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Asset cache : {} hits, {} misses, {} evictions, {} entries, {}/{} bytes",
+            self.hits, self.misses, self.evictions, self.entries, self.size, self.budget)
+    }
+}
+
+/// Key of a cached asset.
+type KAssetCacheKey = (KAssetSourceId, KAssetPath);
+
+/// Cached asset.
+struct KAssetCacheEntry {
+    // Bytes of asset
+    data : Arc<[u8]>,
+
+    // Tick of last use, key of usage order
+    tick : u64,
+}
+
+/// State of cache protected by mutex.
+struct KAssetCacheState {
+    // Cached assets
+    entries : HashMap<KAssetCacheKey, KAssetCacheEntry>,
+
+    // Keys ordered from least to most recently used
+    usage : BTreeMap<u64, KAssetCacheKey>,
+
+    // Paths never evicted
+    pinned : HashSet<KAssetPath>,
+
+    // Next usage tick
+    tick : u64,
+
+    // Statistics, with entries and size kept up to date
+    statistics : KAssetCacheStatistics,
+}
+
+impl KAssetCache {
+    /// Create a new empty [KAssetCache] keeping at most `budget` bytes of unpinned assets.
+    pub fn new(budget : usize) -> KAssetCache {
+        KAssetCache {
+            state : Mutex::new(KAssetCacheState {
+                entries : HashMap::new(),
+                usage : BTreeMap::new(),
+                pinned : HashSet::new(),
+                tick : 0,
+                statistics : KAssetCacheStatistics { budget, ..Default::default() },
+            }),
+        }
+    }
+
+    /// Set the byte budget. Least recently used assets are evicted if size exceeds new budget.
+    pub fn set_budget(&self, budget : usize) {
+        let mut state = self.lock();
+        state.statistics.budget = budget;
+        state.evict(0);
+    }
+
+    /// Get the byte budget.
+    pub fn get_budget(&self) -> usize {
+        self.lock().statistics.budget
+    }
+
+    /// Pin [`path`][KIntoAssetPath] so its assets are never evicted, from any source.
+    ///
+    /// Returns true if path wasn't pinned.
+    pub fn pin(&self, path : impl KIntoAssetPath) -> bool {
+        match path.into_asset_path() {
+            Ok(path) => self.lock().pinned.insert(path),
+            Err(_) => false,
+        }
+    }
+
+    /// Unpin [`path`][KIntoAssetPath]. Its assets become evictable and are evicted if size exceeds budget.
+    ///
+    /// Returns true if path was pinned.
+    pub fn unpin(&self, path : impl KIntoAssetPath) -> bool {
+        let path = match path.into_asset_path() {
+            Ok(path) => path,
+            Err(_) => return false,
+        };
+
+        let mut state = self.lock();
+        let unpinned = state.pinned.remove(&path);
+        state.evict(0);
+        unpinned
+    }
+
+    /// Verify if [`path`][KIntoAssetPath] is pinned.
+    pub fn is_pinned(&self, path : impl KIntoAssetPath) -> bool {
+        path.into_asset_path().is_ok_and(|path| self.lock().pinned.contains(&path))
+    }
+
+    /// Verify if [`path`][KIntoAssetPath] is cached for any source.
+    pub fn contains(&self, path : impl KIntoAssetPath) -> bool {
+        path.into_asset_path().is_ok_and(|path| self.lock().entries.keys().any(|(_, p)| *p == path))
+    }
+
+    /// Remove [`path`][KIntoAssetPath] from cache for all sources. Pinning is kept.
+    ///
+    /// Returns count of assets removed.
+    pub fn invalidate(&self, path : impl KIntoAssetPath) -> usize {
+        match path.into_asset_path() {
+            Ok(path) => self.lock().remove_where(|(_, p)| *p == path),
+            Err(_) => 0,
+        }
+    }
+
+    /// Remove asset changed at path from cache for all sources, with asset decompressed from it if path is compressed.
+    pub(crate) fn invalidate_changed(&self, path : &KAssetPath) {
+        self.invalidate(path);
+
+        // Asset decompressed from a compressed asset is cached without its extension
+        if let Some(decompressed) = KAssetCompression::from_path(path).and_then(|_| path.as_str().rsplit_once('.')) {
+            self.invalidate(decompressed.0);
+        }
+    }
+
+    /// Remove all assets of source identified by `id` from cache.
+    ///
+    /// Returns count of assets removed.
+    pub fn invalidate_source(&self, id : KAssetSourceId) -> usize {
+        self.lock().remove_where(|(source, _)| *source == id)
+    }
+
+    /// Remove all assets from cache. Pinning and statistics are kept.
+    pub fn clear(&self) {
+        self.lock().remove_where(|_| true);
+    }
+
+    /// Get a copy of [KAssetCacheStatistics].
+    pub fn get_statistics(&self) -> KAssetCacheStatistics {
+        self.lock().statistics
+    }
+
+    /// Reset hits, misses and evictions counts to 0.
+    pub fn reset_statistics(&self) {
+        let mut state = self.lock();
+        state.statistics = KAssetCacheStatistics { entries : state.statistics.entries, size : state.statistics.size, budget : state.statistics.budget, ..Default::default() };
+    }
+
+    /// Write [KAssetCacheStatistics] to [KJournal] as an entry of `severity`.
+    pub fn write_statistics(&self, journal : &mut KJournal, severity : u8) {
+        journal.write(severity, &self.get_statistics().to_string());
+    }
+
+    /// Get asset of source from cache, counting a hit if found.
+    pub(crate) fn get(&self, id : KAssetSourceId, path : &KAssetPath) -> Option<Arc<[u8]>> {
+        let mut state = self.lock();
+        let key = (id, path.clone());
+
+        let tick = state.next_tick();
+        let entry = state.entries.get_mut(&key)?;
+        let data = entry.data.clone();
+        let old_tick = std::mem::replace(&mut entry.tick, tick);
+
+        state.usage.remove(&old_tick);
+        state.usage.insert(tick, key);
+        state.statistics.hits += 1;
+
+        Some(data)
+    }
+
+    /// Verify if asset of source is cached, without counting a hit.
+    pub(crate) fn is_cached(&self, id : KAssetSourceId, path : &KAssetPath) -> bool {
+        self.lock().entries.contains_key(&(id, path.clone()))
+    }
+
+    /// Cache asset of source after a miss. Asset isn't cached if bigger than budget and not pinned.
+    pub(crate) fn insert(&self, id : KAssetSourceId, path : &KAssetPath, data : Arc<[u8]>) {
+        let mut state = self.lock();
+        let key = (id, path.clone());
+        state.statistics.misses += 1;
+
+        let pinned = state.pinned.contains(path);
+        if !pinned && data.len() > state.statistics.budget {
+            return;
+        }
+
+        state.remove(&key);
+        state.evict(data.len());
+
+        let tick = state.next_tick();
+        state.statistics.entries += 1;
+        state.statistics.size += data.len();
+        state.usage.insert(tick, key.clone());
+        state.entries.insert(key, KAssetCacheEntry { data, tick });
+    }
+
+    /// Lock state. A poisoned lock is recovered since state is always left consistent.
+    fn lock(&self) -> MutexGuard<'_, KAssetCacheState> {
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl KAssetCacheState {
+    /// Get next usage tick.
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    /// Evict least recently used unpinned assets until `incoming` bytes fit in budget.
+    fn evict(&mut self, incoming : usize) {
+        // Pinned assets already scanned are skipped by next search
+        let mut from = 0;
+
+        while self.statistics.size + incoming > self.statistics.budget {
+            let key = match self.usage.range(from..).find(|(_, (_, path))| !self.pinned.contains(path)) {
+                Some((tick, key)) => {
+                    from = *tick + 1;
+                    key.clone()
+                },
+                None => break,
+            };
+
+            if self.remove(&key) {
+                self.statistics.evictions += 1;
+            }
+        }
+    }
+
+    /// Remove asset of key. Returns true if it was cached.
+    fn remove(&mut self, key : &KAssetCacheKey) -> bool {
+        match self.entries.remove(key) {
+            Some(entry) => {
+                self.usage.remove(&entry.tick);
+                self.statistics.entries -= 1;
+                self.statistics.size -= entry.data.len();
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Remove assets matching predicate. Returns count removed.
+    fn remove_where<F : Fn(&KAssetCacheKey) -> bool>(&mut self, predicate : F) -> usize {
+        let keys : Vec<KAssetCacheKey> = self.entries.keys().filter(|key| predicate(key)).cloned().collect();
+
+        for key in keys.iter() {
+            self.remove(key);
+        }
+
+        keys.len()
+    }
+}
@@ -0,0 +1,69 @@
+use super::{KAssetBroker, KAssetSourceId, KAssetChangeKind, KAssetPath};
+
+/// Change of an asset within a [KAssetBroker] source, given by [KAssetBroker::poll_changes].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KAssetChangeEvent {
+    /// Identifier of source where asset changed.
+    pub source : KAssetSourceId,
+
+    /// Path of asset changed within broker, including mount point of source.
+    pub path : KAssetPath,
+
+    /// Kind of change.
+    pub kind : KAssetChangeKind,
+
+    /// True if no source of higher priority has the asset, meaning [KAssetBroker::get_asset] is affected by change.
+    pub visible : bool,
+}
+
+impl KAssetBroker {
+    /// Get changes of assets reported by sources since last call, ordered by source priority.
+    ///
+    /// Cached assets changed are invalidated so [KAssetBroker::get_asset] returns the new content. Locale and tagged
+    /// variants are resolved again, since a variant may have been added or removed.
+    ///
+    /// # Example(s)
+    /// ```no_run
+    /// // Import crate module
+    /// use std::{path::PathBuf, sync::Arc};
+    /// use olympus_kleio::asset::{KAssetBroker, KAssetSourceFolder};
+    ///
+    /// // Watch data folder
+    /// let source = KAssetSourceFolder::new(PathBuf::from("data")).unwrap();
+    /// source.watch().unwrap();
+    /// let broker = KAssetBroker::new();
+    /// broker.add_source(Arc::new(source)).unwrap();
+    ///
+    /// // Each frame, reload assets changed
+    /// for event in broker.poll_changes().iter().filter(|event| event.visible) {
+    ///     println!("{} was {:?}", event.path, event.kind);
+    /// }
+    /// ```
+    pub fn poll_changes(&self) -> Vec<KAssetChangeEvent> {
+        let sources = self.get_sources_snapshot();
+        let cache = self.get_cache();
+        let mut events = Vec::new();
+
+        for (priority, src) in sources.iter().enumerate() {
+            for change in src.source.poll_changes() {
+                // Changes are reported relative to source, events relative to broker
+                let path = src.get_broker_path(&change.path);
+                let visible = !sources[..priority].iter().any(|higher| higher.get_local_path(&path)
+                    .is_some_and(|local| higher.source.has_asset(&local)));
+
+                if let Some(cache) = &cache {
+                    cache.invalidate_changed(&path);
+                }
+
+                events.push(KAssetChangeEvent { source : src.id, path, kind : change.kind, visible });
+            }
+        }
+
+        // Variants added or removed may change the variant served
+        if !events.is_empty() {
+            self.forget_resolved_paths();
+        }
+
+        events
+    }
+}
@@ -0,0 +1,83 @@
+use std::io::{Read, ErrorKind};
+
+/// Reflected CRC-32 polynomial used by zip, gzip and png.
+const CRC32_POLYNOMIAL : u32 = 0xEDB88320;
+
+/// CRC-32 lookup table computed at compile time.
+const CRC32_TABLE : [u32; 256] = create_table();
+
+/// Create the CRC-32 lookup table.
+const fn create_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut n = 0;
+
+    while n < 256 {
+        let mut c = n as u32;
+        let mut k = 0;
+        while k < 8 {
+            if c & 1 != 0 {
+                c = CRC32_POLYNOMIAL ^ (c >> 1);
+            } else {
+                c >>= 1;
+            }
+            k += 1;
+        }
+        table[n] = c;
+        n += 1;
+    }
+
+    table
+}
+
+/// Update a running CRC-32 with `data`. Start with a `crc` of 0.
+pub(crate) fn crc32_update(crc : u32, data : &[u8]) -> u32 {
+    let mut c = !crc;
+
+    for byte in data {
+        c = CRC32_TABLE[((c ^ *byte as u32) & 0xFF) as usize] ^ (c >> 8);
+    }
+
+    !c
+}
+
+/// Compute the CRC-32 of `data`.
+pub(crate) fn crc32(data : &[u8]) -> u32 {
+    crc32_update(0, data)
+}
+
+/// [Read] wrapper that computes the CRC-32 of everything read and
+/// returns an [ErrorKind::InvalidData] error at the end of stream if it doesn't match.
+pub(crate) struct KCrc32Reader<R : Read> {
+    // Inner reader
+    inner : R,
+
+    // Running CRC-32
+    crc : u32,
+
+    // Expected CRC-32 at end of stream
+    expected : u32,
+}
+
+impl<R : Read> KCrc32Reader<R> {
+    /// Create a new CRC-32 verifying reader.
+    pub(crate) fn new(inner : R, expected : u32) -> KCrc32Reader<R> {
+        KCrc32Reader { inner, crc : 0, expected }
+    }
+}
+
+impl<R : Read> Read for KCrc32Reader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let size = self.inner.read(buf)?;
+
+        if size == 0 && !buf.is_empty() {
+            // End of stream reached, verify checksum
+            if self.crc != self.expected {
+                return Err(std::io::Error::new(ErrorKind::InvalidData, "CRC-32 mismatch!"));
+            }
+        } else {
+            self.crc = crc32_update(self.crc, &buf[..size]);
+        }
+
+        Ok(size)
+    }
+}
@@ -0,0 +1,435 @@
+use std::collections::BinaryHeap;
+use std::cmp::Reverse;
+
+use super::inflate::{MAX_BITS, LENGTH_BASE, LENGTH_EXTRA, DIST_BASE, DIST_EXTRA, CODE_LENGTH_ORDER};
+
+/// Size of the LZ77 sliding window.
+const WINDOW_SIZE : usize = 32768;
+
+/// Bits of the LZ77 hash table.
+const HASH_BITS : u32 = 15;
+
+/// Minimum length of a LZ77 match.
+const MIN_MATCH : usize = 3;
+
+/// Maximum length of a LZ77 match.
+const MAX_MATCH : usize = 258;
+
+/// Maximum count of hash chain entries visited when searching a match.
+const MAX_CHAIN : usize = 128;
+
+/// Maximum count of LZ77 tokens in a single block.
+const BLOCK_TOKENS : usize = 16384;
+
+/// Maximum bits of a code length code.
+const MAX_CODE_LENGTH_BITS : u8 = 7;
+
+/// LZ77 token.
+#[derive(Clone, Copy)]
+enum KLz77Token {
+    // Literal byte
+    Literal(u8),
+
+    // Match of (length, distance)
+    Match(u16, u16),
+}
+
+/// Compress `input` into a raw deflate stream.
+///
+/// Each block is emitted with dynamic Huffman codes, or stored if compression doesn't help.
+pub(crate) fn deflate(input : &[u8]) -> Vec<u8> {
+
+    let mut writer = KBitWriter::new();
+    let tokens = lz77(input);
+
+    if tokens.is_empty() {
+        // Empty input is a single empty stored block
+        writer.bits(1, 1);
+        writer.bits(0, 2);
+        writer.align();
+        writer.bytes(&[0x00, 0x00, 0xFF, 0xFF]);
+        return writer.finish();
+    }
+
+    let mut position = 0;
+    let blocks = tokens.chunks(BLOCK_TOKENS).count();
+
+    for (index, block) in tokens.chunks(BLOCK_TOKENS).enumerate() {
+        let last = index + 1 == blocks;
+        let length : usize = block.iter().map(|token| match token {
+            KLz77Token::Literal(_) => 1,
+            KLz77Token::Match(length, _) => *length as usize,
+        }).sum();
+
+        write_block(&mut writer, block, &input[position..position + length], last);
+        position += length;
+    }
+
+    writer.finish()
+}
+
+/// Write a block of tokens as a dynamic Huffman block, or as stored blocks if smaller.
+fn write_block(writer : &mut KBitWriter, tokens : &[KLz77Token], data : &[u8], last : bool) {
+
+    // Symbols frequencies
+    let mut lit_freqs = [0u32; 286];
+    let mut dist_freqs = [0u32; 30];
+    for token in tokens {
+        match token {
+            KLz77Token::Literal(byte) => lit_freqs[*byte as usize] += 1,
+            KLz77Token::Match(length, distance) => {
+                lit_freqs[257 + length_symbol(*length)] += 1;
+                dist_freqs[distance_symbol(*distance)] += 1;
+            },
+        }
+    }
+    lit_freqs[256] = 1;
+
+    let lit_lengths = code_lengths(&lit_freqs, MAX_BITS as u8);
+    let dist_lengths = code_lengths(&dist_freqs, MAX_BITS as u8);
+
+    let lit_count = 257.max(lit_lengths.iter().rposition(|l| *l != 0).unwrap_or(0) + 1);
+    let dist_count = 1.max(dist_lengths.iter().rposition(|l| *l != 0).unwrap_or(0) + 1);
+
+    // Run length encoded code lengths as (symbol, extra bits count, extra value)
+    let mut all_lengths = lit_lengths[..lit_count].to_vec();
+    all_lengths.extend_from_slice(&dist_lengths[..dist_count]);
+    let runs = run_length_encode(&all_lengths);
+
+    let mut code_freqs = [0u32; 19];
+    for (symbol, _, _) in &runs {
+        code_freqs[*symbol as usize] += 1;
+    }
+    let code_lengths_lengths = code_lengths(&code_freqs, MAX_CODE_LENGTH_BITS);
+    let code_count = 4.max(CODE_LENGTH_ORDER.iter().rposition(|s| code_lengths_lengths[*s] != 0).unwrap_or(0) + 1);
+
+    // Size of dynamic block in bits
+    let mut dynamic_size : usize = 3 + 5 + 5 + 4 + 3 * code_count;
+    for (symbol, extra, _) in &runs {
+        dynamic_size += code_lengths_lengths[*symbol as usize] as usize + *extra as usize;
+    }
+    for (symbol, freq) in lit_freqs.iter().enumerate() {
+        dynamic_size += *freq as usize * (lit_lengths[symbol] as usize + if symbol > 256 { LENGTH_EXTRA[symbol - 257] as usize } else { 0 });
+    }
+    for (symbol, freq) in dist_freqs.iter().enumerate() {
+        dynamic_size += *freq as usize * (dist_lengths[symbol] as usize + DIST_EXTRA[symbol] as usize);
+    }
+
+    // Size of stored blocks in bits (header, alignment, length and complement)
+    let stored_size = (data.len() + data.len().div_ceil(65535).max(1) * 5) * 8;
+
+    if stored_size <= dynamic_size {
+        write_stored(writer, data, last);
+        return;
+    }
+
+    // Block header
+    writer.bits(last as u32, 1);
+    writer.bits(2, 2);
+    writer.bits((lit_count - 257) as u32, 5);
+    writer.bits((dist_count - 1) as u32, 5);
+    writer.bits((code_count - 4) as u32, 4);
+    for symbol in CODE_LENGTH_ORDER.iter().take(code_count) {
+        writer.bits(code_lengths_lengths[*symbol] as u32, 3);
+    }
+
+    let code_codes = canonical_codes(&code_lengths_lengths);
+    for (symbol, extra, value) in &runs {
+        writer.bits(code_codes[*symbol as usize], code_lengths_lengths[*symbol as usize] as u32);
+        writer.bits(*value as u32, *extra as u32);
+    }
+
+    // Block data
+    let lit_codes = canonical_codes(&lit_lengths);
+    let dist_codes = canonical_codes(&dist_lengths);
+    for token in tokens {
+        match token {
+            KLz77Token::Literal(byte) => writer.bits(lit_codes[*byte as usize], lit_lengths[*byte as usize] as u32),
+            KLz77Token::Match(length, distance) => {
+                let symbol = length_symbol(*length);
+                writer.bits(lit_codes[257 + symbol], lit_lengths[257 + symbol] as u32);
+                writer.bits((*length - LENGTH_BASE[symbol]) as u32, LENGTH_EXTRA[symbol] as u32);
+
+                let symbol = distance_symbol(*distance);
+                writer.bits(dist_codes[symbol], dist_lengths[symbol] as u32);
+                writer.bits((*distance - DIST_BASE[symbol]) as u32, DIST_EXTRA[symbol] as u32);
+            },
+        }
+    }
+
+    // End of block
+    writer.bits(lit_codes[256], lit_lengths[256] as u32);
+}
+
+/// Write data as stored blocks of at most 65535 bytes.
+fn write_stored(writer : &mut KBitWriter, data : &[u8], last : bool) {
+    let count = data.chunks(65535).count();
+
+    for (index, chunk) in data.chunks(65535).enumerate() {
+        writer.bits((last && index + 1 == count) as u32, 1);
+        writer.bits(0, 2);
+        writer.align();
+        writer.bytes(&(chunk.len() as u16).to_le_bytes());
+        writer.bytes(&(!(chunk.len() as u16)).to_le_bytes());
+        writer.bytes(chunk);
+    }
+}
+
+/// Find LZ77 matches in input with hash chains.
+fn lz77(input : &[u8]) -> Vec<KLz77Token> {
+
+    let mut tokens = Vec::new();
+    let mut head = vec![usize::MAX; 1 << HASH_BITS];
+    let mut prev = vec![usize::MAX; WINDOW_SIZE];
+
+    let hash = |position : usize| -> usize {
+        let value = (input[position] as u32) << 16 | (input[position + 1] as u32) << 8 | input[position + 2] as u32;
+        (value.wrapping_mul(2654435761) >> (32 - HASH_BITS)) as usize
+    };
+
+    let insert = |position : usize, head : &mut [usize], prev : &mut [usize]| {
+        if position + MIN_MATCH <= input.len() {
+            let h = hash(position);
+            prev[position % WINDOW_SIZE] = head[h];
+            head[h] = position;
+        }
+    };
+
+    let mut position = 0;
+    while position < input.len() {
+        let mut best_length = 0;
+        let mut best_distance = 0;
+
+        if position + MIN_MATCH <= input.len() {
+            let max_length = MAX_MATCH.min(input.len() - position);
+            let mut candidate = head[hash(position)];
+            let mut chain = 0;
+
+            while candidate != usize::MAX && candidate < position && position - candidate <= WINDOW_SIZE && chain < MAX_CHAIN {
+                let length = input[candidate..candidate + max_length].iter().zip(&input[position..position + max_length])
+                    .take_while(|(a, b)| a == b).count();
+
+                if length > best_length {
+                    best_length = length;
+                    best_distance = position - candidate;
+                    if length == max_length {
+                        break;
+                    }
+                }
+
+                // Chain entries are only valid while they go backward
+                let next = prev[candidate % WINDOW_SIZE];
+                if next >= candidate {
+                    break;
+                }
+                candidate = next;
+                chain += 1;
+            }
+        }
+
+        if best_length >= MIN_MATCH {
+            tokens.push(KLz77Token::Match(best_length as u16, best_distance as u16));
+            for offset in 0..best_length {
+                insert(position + offset, &mut head, &mut prev);
+            }
+            position += best_length;
+        } else {
+            tokens.push(KLz77Token::Literal(input[position]));
+            insert(position, &mut head, &mut prev);
+            position += 1;
+        }
+    }
+
+    tokens
+}
+
+/// Returns the length symbol index (0..29) of a match length.
+fn length_symbol(length : u16) -> usize {
+    LENGTH_BASE.iter().rposition(|base| *base <= length).unwrap_or(0)
+}
+
+/// Returns the distance symbol (0..30) of a match distance.
+fn distance_symbol(distance : u16) -> usize {
+    DIST_BASE.iter().rposition(|base| *base <= distance).unwrap_or(0)
+}
+
+/// Run length encode code lengths with symbols 16, 17 and 18.
+///
+/// Returns vector of (symbol, extra bits count, extra value).
+fn run_length_encode(lengths : &[u8]) -> Vec<(u8, u8, u8)> {
+
+    let mut runs = Vec::new();
+    let mut index = 0;
+
+    while index < lengths.len() {
+        let length = lengths[index];
+        let run = lengths[index..].iter().take_while(|l| **l == length).count();
+
+        if length == 0 && run >= 11 {
+            let repeat = run.min(138);
+            runs.push((18, 7, (repeat - 11) as u8));
+            index += repeat;
+        } else if length == 0 && run >= 3 {
+            runs.push((17, 3, (run - 3) as u8));
+            index += run;
+        } else if length != 0 && run >= 4 {
+            // First length is emitted, then repeated with symbol 16
+            runs.push((length, 0, 0));
+            let repeat = (run - 1).min(6);
+            runs.push((16, 2, (repeat - 3) as u8));
+            index += repeat + 1;
+        } else {
+            runs.push((length, 0, 0));
+            index += 1;
+        }
+    }
+
+    runs
+}
+
+/// Compute Huffman code lengths limited to `limit` bits from symbols frequencies.
+///
+/// At least 2 symbols always get a code so that decoders get a complete code.
+fn code_lengths(freqs : &[u32], limit : u8) -> Vec<u8> {
+
+    let mut lengths = vec![0u8; freqs.len()];
+
+    // Symbols used, sorted by frequency then symbol
+    let mut used : Vec<usize> = (0..freqs.len()).filter(|s| freqs[*s] > 0).collect();
+    for symbol in 0..freqs.len() {
+        if used.len() >= 2 {
+            break;
+        }
+        if !used.contains(&symbol) {
+            used.push(symbol);
+        }
+    }
+    used.sort_by_key(|s| (freqs[*s], *s));
+
+    // Build Huffman tree. Nodes 0..used.len() are leaves.
+    let mut parents = vec![0usize; used.len() * 2 - 1];
+    let mut heap : BinaryHeap<Reverse<(u64, usize)>> = used.iter().enumerate().map(|(node, s)| Reverse((freqs[*s].max(1) as u64, node))).collect();
+    let mut next = used.len();
+    while heap.len() > 1 {
+        let Reverse((freq_a, a)) = heap.pop().unwrap();
+        let Reverse((freq_b, b)) = heap.pop().unwrap();
+        parents[a] = next;
+        parents[b] = next;
+        heap.push(Reverse((freq_a + freq_b, next)));
+        next += 1;
+    }
+
+    // Count leaves per depth, overflowing depths are clamped to limit.
+    let root = next - 1;
+    let mut counts = vec![0u32; limit as usize + 1];
+    for leaf in 0..used.len() {
+        let mut depth = 0;
+        let mut node = leaf;
+        while node != root {
+            node = parents[node];
+            depth += 1;
+        }
+        counts[depth.min(limit as usize)] += 1;
+    }
+
+    // Fix Kraft inequality after clamping by lengthening shorter codes.
+    let mut total : u64 = (1..=limit as usize).map(|l| (counts[l] as u64) << (limit as usize - l)).sum();
+    while total > 1 << limit {
+        counts[limit as usize] -= 1;
+        for l in (1..limit as usize).rev() {
+            if counts[l] > 0 {
+                counts[l] -= 1;
+                counts[l + 1] += 2;
+                break;
+            }
+        }
+        total -= 1;
+    }
+
+    // Most frequent symbols get shortest codes.
+    let mut symbols = used.iter().rev();
+    for (length, count) in counts.iter().enumerate().skip(1) {
+        for _ in 0..*count {
+            if let Some(symbol) = symbols.next() {
+                lengths[*symbol] = length as u8;
+            }
+        }
+    }
+
+    lengths
+}
+
+/// Compute canonical codes from code lengths. Codes are bit reversed for LSB-first writing.
+fn canonical_codes(lengths : &[u8]) -> Vec<u32> {
+
+    let mut counts = [0u32; MAX_BITS + 2];
+    for length in lengths {
+        counts[*length as usize] += 1;
+    }
+    counts[0] = 0;
+
+    let mut next = [0u32; MAX_BITS + 2];
+    let mut code = 0;
+    for length in 1..=MAX_BITS + 1 {
+        code = (code + counts[length - 1]) << 1;
+        next[length] = code;
+    }
+
+    lengths.iter().map(|length| {
+        if *length == 0 {
+            return 0;
+        }
+        let code = next[*length as usize];
+        next[*length as usize] += 1;
+        code.reverse_bits() >> (32 - *length as u32)
+    }).collect()
+}
+
+/// LSB-first bit writer.
+struct KBitWriter {
+    // Output bytes
+    output : Vec<u8>,
+
+    // Bit buffer
+    buffer : u64,
+
+    // Count of bits in buffer
+    count : u32,
+}
+
+impl KBitWriter {
+    /// Create a new empty bit writer.
+    fn new() -> KBitWriter {
+        KBitWriter { output: Vec::new(), buffer: 0, count: 0 }
+    }
+
+    /// Write the `count` lowest bits of `value`.
+    fn bits(&mut self, value : u32, count : u32) {
+        self.buffer |= (value as u64 & ((1u64 << count) - 1)) << self.count;
+        self.count += count;
+
+        while self.count >= 8 {
+            self.output.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    /// Pad with zero bits up to next byte boundary.
+    fn align(&mut self) {
+        if self.count > 0 {
+            self.bits(0, 8 - self.count);
+        }
+    }
+
+    /// Write bytes. Writer must be aligned.
+    fn bytes(&mut self, bytes : &[u8]) {
+        self.output.extend_from_slice(bytes);
+    }
+
+    /// Flush remaining bits and returns output.
+    fn finish(mut self) -> Vec<u8> {
+        self.align();
+        self.output
+    }
+}
@@ -0,0 +1,477 @@
+use super::sha512::KSha512;
+
+/// Mask of a 51 bits limb of field element.
+const LIMB_MASK : u64 = (1 << 51) - 1;
+
+/// Exponent p - 2 of field inverse, little endian.
+const EXP_INVERT : [u8; 32] = [0xeb, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f];
+
+/// Exponent (p - 5) / 8 of field square root, little endian.
+const EXP_SQRT : [u8; 32] = [0xfd, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x0f];
+
+/// Exponent (p - 1) / 4 giving the square root of -1, little endian.
+const EXP_SQRT_M1 : [u8; 32] = [0xfb, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x1f];
+
+/// Compressed base point of Ed25519, y = 4/5.
+const BASE_POINT : [u8; 32] = [0x58, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66,
+    0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66];
+
+/// Order L = 2^252 + 27742317777372353535851937790883648493 of base point as 64 bits limbs, little endian.
+const ORDER : [u64; 4] = [0x5812631a5cf5d3ed, 0x14def9dea2f79cd6, 0, 0x1000000000000000];
+
+/// Element of field of integers modulo p = 2^255 - 19, as five 51 bits limbs.
+#[derive(Clone, Copy)]
+struct KFieldElement([u64; 5]);
+
+impl KFieldElement {
+    /// Field element of small value.
+    fn from_u64(value : u64) -> KFieldElement {
+        KFieldElement([value, 0, 0, 0, 0])
+    }
+
+    /// Field element from 32 bytes little endian, ignoring the highest bit.
+    fn from_bytes(bytes : &[u8; 32]) -> KFieldElement {
+        let load = |i : usize| u64::from_le_bytes([bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3], bytes[i + 4], bytes[i + 5], bytes[i + 6], bytes[i + 7]]);
+
+        KFieldElement([load(0) & LIMB_MASK, (load(6) >> 3) & LIMB_MASK, (load(12) >> 6) & LIMB_MASK,
+            (load(19) >> 1) & LIMB_MASK, (load(24) >> 12) & LIMB_MASK])
+    }
+
+    /// Canonical 32 bytes little endian of element, fully reduced modulo p.
+    fn to_bytes(self) -> [u8; 32] {
+        let mut limbs = self.carry().0;
+
+        // q is 1 if value >= p, so value - q * p is canonical
+        let mut q = (limbs[0] + 19) >> 51;
+        for limb in &limbs[1..] {
+            q = (limb + q) >> 51;
+        }
+
+        limbs[0] += 19 * q;
+        for i in 0..4 {
+            limbs[i + 1] += limbs[i] >> 51;
+            limbs[i] &= LIMB_MASK;
+        }
+        limbs[4] &= LIMB_MASK;
+
+        let mut bytes = [0u8; 32];
+        let mut buffer : u128 = 0;
+        let mut bits = 0;
+        let mut position = 0;
+        for limb in limbs {
+            buffer |= (limb as u128) << bits;
+            bits += 51;
+
+            while bits >= 8 && position < 32 {
+                bytes[position] = buffer as u8;
+                buffer >>= 8;
+                bits -= 8;
+                position += 1;
+            }
+        }
+        if position < 32 {
+            bytes[position] = buffer as u8;
+        }
+
+        bytes
+    }
+
+    /// Carry limbs so each fits in 51 bits, plus a small excess on the first.
+    fn carry(self) -> KFieldElement {
+        let mut limbs = self.0;
+
+        for i in 0..4 {
+            limbs[i + 1] += limbs[i] >> 51;
+            limbs[i] &= LIMB_MASK;
+        }
+
+        limbs[0] += 19 * (limbs[4] >> 51);
+        limbs[4] &= LIMB_MASK;
+
+        KFieldElement(limbs)
+    }
+
+    fn add(&self, other : &KFieldElement) -> KFieldElement {
+        let mut limbs = self.0;
+        for (limb, value) in limbs.iter_mut().zip(other.0) {
+            *limb += value;
+        }
+
+        KFieldElement(limbs).carry()
+    }
+
+    fn sub(&self, other : &KFieldElement) -> KFieldElement {
+        // 4p is added so limbs never underflow
+        const FOUR_P : [u64; 5] = [0x1fffffffffffb4, 0x1ffffffffffffc, 0x1ffffffffffffc, 0x1ffffffffffffc, 0x1ffffffffffffc];
+
+        let mut limbs = self.0;
+        for i in 0..5 {
+            limbs[i] = limbs[i] + FOUR_P[i] - other.0[i];
+        }
+
+        KFieldElement(limbs).carry()
+    }
+
+    fn neg(&self) -> KFieldElement {
+        KFieldElement::from_u64(0).sub(self)
+    }
+
+    fn mul(&self, other : &KFieldElement) -> KFieldElement {
+        let a = &self.0;
+        let b = &other.0;
+        let m = |x : u64, y : u64| x as u128 * y as u128;
+
+        // Limbs over 2^255 wrap around multiplied by 19
+        let b19 = [0, b[1] * 19, b[2] * 19, b[3] * 19, b[4] * 19];
+
+        let c0 = m(a[0], b[0]) + m(a[1], b19[4]) + m(a[2], b19[3]) + m(a[3], b19[2]) + m(a[4], b19[1]);
+        let c1 = m(a[0], b[1]) + m(a[1], b[0]) + m(a[2], b19[4]) + m(a[3], b19[3]) + m(a[4], b19[2]);
+        let c2 = m(a[0], b[2]) + m(a[1], b[1]) + m(a[2], b[0]) + m(a[3], b19[4]) + m(a[4], b19[3]);
+        let c3 = m(a[0], b[3]) + m(a[1], b[2]) + m(a[2], b[1]) + m(a[3], b[0]) + m(a[4], b19[4]);
+        let c4 = m(a[0], b[4]) + m(a[1], b[3]) + m(a[2], b[2]) + m(a[3], b[1]) + m(a[4], b[0]);
+
+        let c1 = c1 + (c0 >> 51);
+        let c2 = c2 + (c1 >> 51);
+        let c3 = c3 + (c2 >> 51);
+        let c4 = c4 + (c3 >> 51);
+        let c0 = (c0 & LIMB_MASK as u128) + (c4 >> 51) * 19;
+
+        KFieldElement([c0 as u64 & LIMB_MASK, (c1 as u64 & LIMB_MASK) + (c0 >> 51) as u64, c2 as u64 & LIMB_MASK,
+            c3 as u64 & LIMB_MASK, c4 as u64 & LIMB_MASK])
+    }
+
+    fn square(&self) -> KFieldElement {
+        self.mul(self)
+    }
+
+    /// Element raised to `exponent` given as 32 bytes little endian.
+    fn pow(&self, exponent : &[u8; 32]) -> KFieldElement {
+        let mut result = KFieldElement::from_u64(1);
+
+        for bit in (0..256).rev() {
+            result = result.square();
+            if (exponent[bit / 8] >> (bit % 8)) & 1 == 1 {
+                result = result.mul(self);
+            }
+        }
+
+        result
+    }
+
+    fn invert(&self) -> KFieldElement {
+        self.pow(&EXP_INVERT)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.to_bytes() == [0u8; 32]
+    }
+
+    /// Element is negative if its canonical value is odd.
+    fn is_negative(&self) -> bool {
+        self.to_bytes()[0] & 1 == 1
+    }
+
+    fn equals(&self, other : &KFieldElement) -> bool {
+        self.to_bytes() == other.to_bytes()
+    }
+}
+
+/// Point of twisted Edwards curve -x² + y² = 1 + d x² y² in extended coordinates (X : Y : Z : T) with x = X/Z, y = Y/Z and xy = T/Z.
+#[derive(Clone, Copy)]
+struct KEdwardsPoint {
+    x : KFieldElement,
+    y : KFieldElement,
+    z : KFieldElement,
+    t : KFieldElement,
+}
+
+/// Constants of curve computed once per operation.
+struct KCurve {
+    // Constant d = -121665 / 121666
+    d : KFieldElement,
+
+    // Constant 2d
+    d2 : KFieldElement,
+
+    // Square root of -1
+    sqrt_m1 : KFieldElement,
+}
+
+impl KCurve {
+    fn new() -> KCurve {
+        let d = KFieldElement::from_u64(121665).neg().mul(&KFieldElement::from_u64(121666).invert());
+        KCurve { d, d2 : d.add(&d), sqrt_m1 : KFieldElement::from_u64(2).pow(&EXP_SQRT_M1) }
+    }
+
+    /// Neutral point (0, 1).
+    fn identity(&self) -> KEdwardsPoint {
+        KEdwardsPoint { x : KFieldElement::from_u64(0), y : KFieldElement::from_u64(1), z : KFieldElement::from_u64(1), t : KFieldElement::from_u64(0) }
+    }
+
+    /// Base point B of curve.
+    fn base(&self) -> KEdwardsPoint {
+        // Constant point is always valid
+        self.decompress(&BASE_POINT).unwrap()
+    }
+
+    /// Add two points with the complete formula of RFC 8032, section 5.1.4. Also used for doubling.
+    fn add(&self, p : &KEdwardsPoint, q : &KEdwardsPoint) -> KEdwardsPoint {
+        let a = p.y.sub(&p.x).mul(&q.y.sub(&q.x));
+        let b = p.y.add(&p.x).mul(&q.y.add(&q.x));
+        let c = p.t.mul(&self.d2).mul(&q.t);
+        let d = p.z.add(&p.z).mul(&q.z);
+        let e = b.sub(&a);
+        let f = d.sub(&c);
+        let g = d.add(&c);
+        let h = b.add(&a);
+
+        KEdwardsPoint { x : e.mul(&f), y : g.mul(&h), z : f.mul(&g), t : e.mul(&h) }
+    }
+
+    /// Multiply point by scalar given as 32 bytes little endian.
+    fn mul(&self, p : &KEdwardsPoint, scalar : &[u8; 32]) -> KEdwardsPoint {
+        let mut result = self.identity();
+
+        for bit in (0..256).rev() {
+            result = self.add(&result, &result);
+            if (scalar[bit / 8] >> (bit % 8)) & 1 == 1 {
+                result = self.add(&result, p);
+            }
+        }
+
+        result
+    }
+
+    fn neg(&self, p : &KEdwardsPoint) -> KEdwardsPoint {
+        KEdwardsPoint { x : p.x.neg(), y : p.y, z : p.z, t : p.t.neg() }
+    }
+
+    /// Encode point as y with sign of x in highest bit, RFC 8032, section 5.1.2.
+    fn compress(&self, p : &KEdwardsPoint) -> [u8; 32] {
+        let z = p.z.invert();
+        let mut bytes = p.y.mul(&z).to_bytes();
+        bytes[31] |= (p.x.mul(&z).is_negative() as u8) << 7;
+        bytes
+    }
+
+    /// Decode point, RFC 8032, section 5.1.3.
+    ///
+    /// Returns `None` if y isn't canonical or isn't on curve.
+    fn decompress(&self, bytes : &[u8; 32]) -> Option<KEdwardsPoint> {
+        let y = KFieldElement::from_bytes(bytes);
+        let sign = bytes[31] >> 7 == 1;
+
+        let mut canonical = *bytes;
+        canonical[31] &= 0x7f;
+        if y.to_bytes() != canonical {
+            return None;
+        }
+
+        // x² = u / v
+        let one = KFieldElement::from_u64(1);
+        let y2 = y.square();
+        let u = y2.sub(&one);
+        let v = self.d.mul(&y2).add(&one);
+
+        let v3 = v.square().mul(&v);
+        let v7 = v3.square().mul(&v);
+        let mut x = u.mul(&v3).mul(&u.mul(&v7).pow(&EXP_SQRT));
+
+        let vx2 = v.mul(&x.square());
+        if !vx2.equals(&u) {
+            if vx2.equals(&u.neg()) {
+                x = x.mul(&self.sqrt_m1);
+            } else {
+                return None;
+            }
+        }
+
+        if x.is_zero() && sign {
+            return None;
+        }
+
+        if x.is_negative() != sign {
+            x = x.neg();
+        }
+
+        Some(KEdwardsPoint { x, y, z : one, t : x.mul(&y) })
+    }
+}
+
+/// Get public key of a 32 bytes private key `seed`, RFC 8032, section 5.1.5.
+pub(crate) fn public_key(seed : &[u8; 32]) -> [u8; 32] {
+    let curve = KCurve::new();
+    let (scalar, _) = expand_seed(seed);
+    curve.compress(&curve.mul(&curve.base(), &scalar))
+}
+
+/// Sign `message` with a 32 bytes private key `seed`, RFC 8032, section 5.1.6.
+pub(crate) fn sign(seed : &[u8; 32], message : &[u8]) -> [u8; 64] {
+    let curve = KCurve::new();
+    let (scalar, prefix) = expand_seed(seed);
+    let public = curve.compress(&curve.mul(&curve.base(), &scalar));
+
+    let r = hash_scalar(&[&prefix, message]);
+    let big_r = curve.compress(&curve.mul(&curve.base(), &r));
+    let k = hash_scalar(&[&big_r, &public, message]);
+
+    // S = (r + k * s) mod L
+    let mut wide = mul_wide(&k, &scalar);
+    add_wide(&mut wide, &r);
+    let s = reduce_wide(&wide);
+
+    let mut signature = [0u8; 64];
+    signature[..32].copy_from_slice(&big_r);
+    signature[32..].copy_from_slice(&s);
+    signature
+}
+
+/// Verify `signature` of `message` with `public_key`, RFC 8032, section 5.1.7.
+///
+/// Returns true if signature is valid.
+pub(crate) fn verify(public_key : &[u8; 32], message : &[u8], signature : &[u8; 64]) -> bool {
+    let curve = KCurve::new();
+
+    let a = match curve.decompress(public_key) {
+        Some(a) => a,
+        None => return false,
+    };
+
+    let mut big_r = [0u8; 32];
+    let mut s = [0u8; 32];
+    big_r.copy_from_slice(&signature[..32]);
+    s.copy_from_slice(&signature[32..]);
+
+    // S must be canonical so signatures aren't malleable
+    if !is_below_order(&s) {
+        return false;
+    }
+
+    // [S]B = R + [k]A, checked as [S]B - [k]A = R
+    let k = hash_scalar(&[&big_r, public_key, message]);
+    let point = curve.add(&curve.mul(&curve.base(), &s), &curve.mul(&curve.neg(&a), &k));
+
+    curve.compress(&point) == big_r
+}
+
+/// Hash private key `seed` into clamped secret scalar and prefix of nonces.
+fn expand_seed(seed : &[u8; 32]) -> ([u8; 32], [u8; 32]) {
+    let mut hash = KSha512::new();
+    hash.update(seed);
+    let hash = hash.finish();
+
+    let mut scalar = [0u8; 32];
+    let mut prefix = [0u8; 32];
+    scalar.copy_from_slice(&hash[..32]);
+    prefix.copy_from_slice(&hash[32..]);
+
+    scalar[0] &= 248;
+    scalar[31] &= 127;
+    scalar[31] |= 64;
+
+    (scalar, prefix)
+}
+
+/// SHA-512 of concatenated `parts` reduced modulo L.
+fn hash_scalar(parts : &[&[u8]]) -> [u8; 32] {
+    let mut hash = KSha512::new();
+    for part in parts {
+        hash.update(part);
+    }
+
+    let hash = hash.finish();
+    let mut wide = [0u64; 8];
+    for (limb, bytes) in wide.iter_mut().zip(hash.chunks_exact(8)) {
+        *limb = u64::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7]]);
+    }
+
+    reduce_wide(&wide)
+}
+
+/// 256 bits little endian bytes as 64 bits limbs.
+fn to_limbs(bytes : &[u8; 32]) -> [u64; 4] {
+    let mut limbs = [0u64; 4];
+    for (limb, bytes) in limbs.iter_mut().zip(bytes.chunks_exact(8)) {
+        *limb = u64::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7]]);
+    }
+    limbs
+}
+
+/// Verify that scalar is lower than L.
+fn is_below_order(scalar : &[u8; 32]) -> bool {
+    is_limbs_below_order(&to_limbs(scalar))
+}
+
+/// Multiply two 256 bits scalars into a 512 bits value.
+fn mul_wide(a : &[u8; 32], b : &[u8; 32]) -> [u64; 8] {
+    let a = to_limbs(a);
+    let b = to_limbs(b);
+    let mut wide = [0u64; 8];
+
+    for i in 0..4 {
+        let mut carry : u128 = 0;
+        for j in 0..4 {
+            let value = wide[i + j] as u128 + a[i] as u128 * b[j] as u128 + carry;
+            wide[i + j] = value as u64;
+            carry = value >> 64;
+        }
+        wide[i + 4] = carry as u64;
+    }
+
+    wide
+}
+
+/// Add a 256 bits scalar to a 512 bits value.
+fn add_wide(wide : &mut [u64; 8], scalar : &[u8; 32]) {
+    let scalar = to_limbs(scalar);
+    let mut carry = 0u128;
+
+    for (i, limb) in wide.iter_mut().enumerate() {
+        let value = *limb as u128 + scalar.get(i).copied().unwrap_or(0) as u128 + carry;
+        *limb = value as u64;
+        carry = value >> 64;
+    }
+}
+
+/// Reduce a 512 bits value modulo L with a bitwise long division.
+fn reduce_wide(wide : &[u64; 8]) -> [u8; 32] {
+    let mut remainder = [0u64; 4];
+
+    for bit in (0..512).rev() {
+        // remainder = remainder * 2 + bit, never overflowing since remainder < L < 2^253
+        for i in (1..4).rev() {
+            remainder[i] = (remainder[i] << 1) | (remainder[i - 1] >> 63);
+        }
+        remainder[0] = (remainder[0] << 1) | ((wide[bit / 64] >> (bit % 64)) & 1);
+
+        if !is_limbs_below_order(&remainder) {
+            let mut borrow = 0u64;
+            for i in 0..4 {
+                let (value, under) = remainder[i].overflowing_sub(ORDER[i]);
+                let (value, under_borrow) = value.overflowing_sub(borrow);
+                remainder[i] = value;
+                borrow = (under || under_borrow) as u64;
+            }
+        }
+    }
+
+    let mut bytes = [0u8; 32];
+    for (chunk, limb) in bytes.chunks_exact_mut(8).zip(remainder) {
+        chunk.copy_from_slice(&limb.to_le_bytes());
+    }
+    bytes
+}
+
+/// Verify that 256 bits limbs are lower than L.
+fn is_limbs_below_order(limbs : &[u64; 4]) -> bool {
+    for i in (0..4).rev() {
+        if limbs[i] != ORDER[i] {
+            return limbs[i] < ORDER[i];
+        }
+    }
+
+    false
+}
@@ -0,0 +1,79 @@
+use std::io::{Error, ErrorKind};
+use super::{inflate::inflate, crc32::crc32};
+
+/// Magic bytes and deflate method starting a gzip member.
+const GZIP_MAGIC : [u8; 3] = [0x1F, 0x8B, 0x08];
+
+/// Flags of optional gzip header fields.
+const GZIP_FLAG_HCRC : u8 = 0x02;
+const GZIP_FLAG_EXTRA : u8 = 0x04;
+const GZIP_FLAG_NAME : u8 = 0x08;
+const GZIP_FLAG_COMMENT : u8 = 0x10;
+
+/// Decompress gzip members (RFC 1952) from `input` and append the result to `output`.
+///
+/// CRC-32 and size of each member are verified. Decompression stops with an error if more than `limit`
+/// bytes would be produced.
+///
+/// Returns `Ok(usize)` with the count of `input` bytes consumed, which is all of it.
+///
+/// # Error(s)
+/// Returns [ErrorKind::InvalidData] if a member is corrupted, truncated or exceeds `limit`.
+pub(crate) fn gunzip(input : &[u8], output : &mut Vec<u8>, limit : usize) -> Result<usize, Error> {
+    let start = output.len();
+    let mut position = 0;
+
+    loop {
+        let header = input.get(position..position + 10).ok_or_else(truncated)?;
+        if header[..3] != GZIP_MAGIC || header[3] & 0xE0 != 0 {
+            return Err(corrupted("Invalid gzip header!"));
+        }
+        let flags = header[3];
+        position += 10;
+
+        if flags & GZIP_FLAG_EXTRA != 0 {
+            let length = input.get(position..position + 2).ok_or_else(truncated)?;
+            position += 2 + u16::from_le_bytes([length[0], length[1]]) as usize;
+        }
+
+        // File name and comment are zero terminated
+        for flag in [GZIP_FLAG_NAME, GZIP_FLAG_COMMENT] {
+            if flags & flag != 0 {
+                let end = input.get(position..).and_then(|rest| rest.iter().position(|byte| *byte == 0)).ok_or_else(truncated)?;
+                position += end + 1;
+            }
+        }
+
+        if flags & GZIP_FLAG_HCRC != 0 {
+            position += 2;
+        }
+
+        let member_start = output.len();
+        position += inflate(input.get(position..).ok_or_else(truncated)?, output, limit - (member_start - start))?;
+
+        let trailer = input.get(position..position + 8).ok_or_else(truncated)?;
+        let crc = u32::from_le_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]);
+        let size = u32::from_le_bytes([trailer[4], trailer[5], trailer[6], trailer[7]]);
+        position += 8;
+
+        if crc != crc32(&output[member_start..]) || size != (output.len() - member_start) as u32 {
+            return Err(corrupted("Gzip member checksum mismatch!"));
+        }
+
+        if position == input.len() {
+            break;
+        }
+    }
+
+    Ok(position)
+}
+
+/// Returns an [ErrorKind::InvalidData] error with message.
+fn corrupted(message : &str) -> Error {
+    Error::new(ErrorKind::InvalidData, message)
+}
+
+/// Returns an [ErrorKind::UnexpectedEof] error of a truncated stream.
+fn truncated() -> Error {
+    Error::new(ErrorKind::UnexpectedEof, "Gzip stream truncated!")
+}
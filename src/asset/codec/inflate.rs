@@ -0,0 +1,404 @@
+use std::io::{Error, ErrorKind};
+
+/// Maximum bits in a deflate Huffman code.
+pub(crate) const MAX_BITS : usize = 15;
+
+/// Bits resolved in a single lookup by the fast decoding table.
+const FAST_BITS : u32 = 9;
+
+/// Base lengths for length symbols 257..285.
+pub(crate) const LENGTH_BASE : [u16; 29] = [3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258];
+
+/// Extra bits for length symbols 257..285.
+pub(crate) const LENGTH_EXTRA : [u8; 29] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0];
+
+/// Base distances for distance symbols 0..29.
+pub(crate) const DIST_BASE : [u16; 30] = [1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577];
+
+/// Extra bits for distance symbols 0..29.
+pub(crate) const DIST_EXTRA : [u8; 30] = [0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13];
+
+/// Order in which code length code lengths are stored in a dynamic block header.
+pub(crate) const CODE_LENGTH_ORDER : [usize; 19] = [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+
+/// Decompress a raw deflate stream from `input` and append the result to `output`.
+///
+/// Decompression stops with an error if more than `limit` bytes would be produced.
+///
+/// Returns `Ok(usize)` with the count of `input` bytes consumed by the deflate stream.
+///
+/// # Error(s)
+/// Returns [ErrorKind::InvalidData] if the stream is corrupted, truncated or exceeds `limit`.
+pub(crate) fn inflate(input : &[u8], output : &mut Vec<u8>, limit : usize) -> Result<usize, Error> {
+
+    let mut reader = KBitReader::new(input);
+    let start = output.len();
+
+    loop {
+        let last = reader.bits(1)? == 1;
+
+        match reader.bits(2)? {
+            0 => inflate_stored(&mut reader, output, start, limit)?,
+            1 => {
+                let (lit, dist) = fixed_tables();
+                inflate_codes(&mut reader, output, start, limit, &lit, &dist)?;
+            },
+            2 => {
+                let (lit, dist) = dynamic_tables(&mut reader)?;
+                inflate_codes(&mut reader, output, start, limit, &lit, &dist)?;
+            },
+            _ => return Err(corrupted("Invalid deflate block type!")),
+        }
+
+        if last {
+            break;
+        }
+    }
+
+    Ok(reader.consumed())
+}
+
+/// Error of decompression producing more bytes than its limit, wrapped by an [ErrorKind::InvalidData] error
+/// so callers can tell a limit exceeded from a corrupted stream.
+#[derive(Debug)]
+pub(crate) struct KLimitExceeded;
+
+impl std::fmt::Display for KLimitExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Decompressed size exceeds limit!")
+    }
+}
+
+impl std::error::Error for KLimitExceeded {}
+
+/// Returns an [ErrorKind::InvalidData] error wrapping [KLimitExceeded].
+pub(crate) fn limit_exceeded() -> Error {
+    Error::new(ErrorKind::InvalidData, KLimitExceeded)
+}
+
+/// Returns true if error wraps [KLimitExceeded].
+pub(crate) fn is_limit_exceeded(err : &Error) -> bool {
+    err.get_ref().is_some_and(|inner| inner.is::<KLimitExceeded>())
+}
+
+/// Returns an [ErrorKind::InvalidData] error with message.
+fn corrupted(message : &str) -> Error {
+    Error::new(ErrorKind::InvalidData, message)
+}
+
+/// Copy a stored (uncompressed) block to output.
+fn inflate_stored(reader : &mut KBitReader, output : &mut Vec<u8>, start : usize, limit : usize) -> Result<(), Error> {
+
+    reader.align();
+
+    let length = reader.bits(16)? as usize;
+    let complement = reader.bits(16)? as usize;
+
+    if length != !complement & 0xFFFF {
+        return Err(corrupted("Stored block length mismatch!"));
+    }
+
+    if output.len() - start + length > limit {
+        return Err(limit_exceeded());
+    }
+
+    reader.copy_bytes(output, length)
+}
+
+/// Decode literal / length and distance codes until end of block.
+fn inflate_codes(reader : &mut KBitReader, output : &mut Vec<u8>, start : usize, limit : usize, lit : &KHuffman, dist : &KHuffman) -> Result<(), Error> {
+
+    loop {
+        let symbol = reader.decode(lit)? as usize;
+
+        if symbol < 256 {
+            // Literal
+            if output.len() - start >= limit {
+                return Err(limit_exceeded());
+            }
+            output.push(symbol as u8);
+        } else if symbol == 256 {
+            // End of block
+            return Ok(());
+        } else {
+            // Length / distance pair
+            let symbol = symbol - 257;
+            if symbol >= LENGTH_BASE.len() {
+                return Err(corrupted("Invalid length symbol!"));
+            }
+            let length = LENGTH_BASE[symbol] as usize + reader.bits(LENGTH_EXTRA[symbol] as u32)? as usize;
+
+            let symbol = reader.decode(dist)? as usize;
+            if symbol >= DIST_BASE.len() {
+                return Err(corrupted("Invalid distance symbol!"));
+            }
+            let distance = DIST_BASE[symbol] as usize + reader.bits(DIST_EXTRA[symbol] as u32)? as usize;
+
+            if distance > output.len() - start {
+                return Err(corrupted("Distance too far back!"));
+            }
+
+            if output.len() - start + length > limit {
+                return Err(limit_exceeded());
+            }
+
+            let from = output.len() - distance;
+            if distance >= length {
+                output.extend_from_within(from..from + length);
+            } else {
+                // Overlapping copy, must be done byte per byte
+                for i in 0..length {
+                    let byte = output[from + i];
+                    output.push(byte);
+                }
+            }
+        }
+    }
+}
+
+/// Create the fixed literal / length and distance tables.
+fn fixed_tables() -> (KHuffman, KHuffman) {
+    let mut lengths = [0u8; 288];
+
+    lengths[0..144].fill(8);
+    lengths[144..256].fill(9);
+    lengths[256..280].fill(7);
+    lengths[280..288].fill(8);
+
+    // Fixed tables are always valid
+    (KHuffman::new(&lengths).unwrap(), KHuffman::new(&[5u8; 30]).unwrap())
+}
+
+/// Read the dynamic literal / length and distance tables from a block header.
+fn dynamic_tables(reader : &mut KBitReader) -> Result<(KHuffman, KHuffman), Error> {
+
+    let lit_count = reader.bits(5)? as usize + 257;
+    let dist_count = reader.bits(5)? as usize + 1;
+    let code_count = reader.bits(4)? as usize + 4;
+
+    if lit_count > 286 || dist_count > 30 {
+        return Err(corrupted("Too many dynamic codes!"));
+    }
+
+    // Code length code lengths
+    let mut lengths = [0u8; 19];
+    for position in CODE_LENGTH_ORDER.iter().take(code_count) {
+        lengths[*position] = reader.bits(3)? as u8;
+    }
+    let code_lengths = KHuffman::new(&lengths)?;
+
+    // Literal / length and distance code lengths
+    let mut lengths = [0u8; 286 + 30];
+    let mut index = 0;
+    while index < lit_count + dist_count {
+        let symbol = reader.decode(&code_lengths)?;
+
+        let (length, repeat) = match symbol {
+            0..=15 => (symbol as u8, 1),
+            16 => {
+                if index == 0 {
+                    return Err(corrupted("Repeat without previous length!"));
+                }
+                (lengths[index - 1], 3 + reader.bits(2)? as usize)
+            },
+            17 => (0, 3 + reader.bits(3)? as usize),
+            _ => (0, 11 + reader.bits(7)? as usize),
+        };
+
+        if index + repeat > lit_count + dist_count {
+            return Err(corrupted("Too many code lengths!"));
+        }
+
+        lengths[index..index + repeat].fill(length);
+        index += repeat;
+    }
+
+    if lengths[256] == 0 {
+        return Err(corrupted("Missing end of block code!"));
+    }
+
+    Ok((KHuffman::new(&lengths[0..lit_count])?, KHuffman::new(&lengths[lit_count..lit_count + dist_count])?))
+}
+
+/// Canonical Huffman decoding table.
+struct KHuffman {
+    // Count of codes for each length
+    counts : [u16; MAX_BITS + 1],
+
+    // Symbols ordered by code
+    symbols : Vec<u16>,
+
+    // Fast lookup table indexed by FAST_BITS reversed bits. Entry is (symbol << 4) | length, 0 if absent.
+    fast : Vec<u16>,
+}
+
+impl KHuffman {
+    /// Create a decoding table from code lengths of each symbol.
+    fn new(lengths : &[u8]) -> Result<KHuffman, Error> {
+
+        let mut counts = [0u16; MAX_BITS + 1];
+        for length in lengths {
+            counts[*length as usize] += 1;
+        }
+
+        // Verify that code set isn't over-subscribed
+        let mut left : i32 = 1;
+        for count in counts.iter().skip(1) {
+            left <<= 1;
+            left -= *count as i32;
+            if left < 0 {
+                return Err(corrupted("Over-subscribed Huffman code!"));
+            }
+        }
+
+        // Offsets of each length in symbols table
+        let mut offsets = [0u16; MAX_BITS + 2];
+        for length in 1..=MAX_BITS {
+            offsets[length + 1] = offsets[length] + counts[length];
+        }
+
+        let mut symbols = vec![0u16; offsets[MAX_BITS + 1] as usize];
+        for (symbol, length) in lengths.iter().enumerate() {
+            if *length != 0 {
+                symbols[offsets[*length as usize] as usize] = symbol as u16;
+                offsets[*length as usize] += 1;
+            }
+        }
+
+        // Fill fast lookup table with canonical codes
+        let mut fast = vec![0u16; 1 << FAST_BITS];
+        let mut code : u32 = 0;
+        let mut index = 0;
+        for length in 1..=MAX_BITS as u32 {
+            for _ in 0..counts[length as usize] {
+                if length <= FAST_BITS {
+                    let reversed = code.reverse_bits() >> (32 - length);
+                    let entry = (symbols[index] << 4) | length as u16;
+                    let mut fill = reversed;
+                    while fill < (1 << FAST_BITS) {
+                        fast[fill as usize] = entry;
+                        fill += 1 << length;
+                    }
+                }
+                code += 1;
+                index += 1;
+            }
+            code <<= 1;
+        }
+
+        Ok(KHuffman { counts, symbols, fast })
+    }
+}
+
+/// LSB-first bit reader over a byte slice.
+struct KBitReader<'a> {
+    // Input data
+    input : &'a [u8],
+
+    // Position of next byte to load
+    position : usize,
+
+    // Bit buffer
+    buffer : u64,
+
+    // Count of bits in buffer
+    count : u32,
+}
+
+impl<'a> KBitReader<'a> {
+    /// Create a new bit reader over input.
+    fn new(input : &'a [u8]) -> KBitReader<'a> {
+        KBitReader { input, position: 0, buffer: 0, count: 0 }
+    }
+
+    /// Load bytes until buffer has at least `count` bits or input is exhausted.
+    fn fill(&mut self, count : u32) {
+        while self.count < count && self.position < self.input.len() {
+            self.buffer |= (self.input[self.position] as u64) << self.count;
+            self.position += 1;
+            self.count += 8;
+        }
+    }
+
+    /// Read `count` bits (max 32).
+    fn bits(&mut self, count : u32) -> Result<u32, Error> {
+        if count == 0 {
+            return Ok(0);
+        }
+
+        self.fill(count);
+        if self.count < count {
+            return Err(Error::new(ErrorKind::UnexpectedEof, "Deflate stream truncated!"));
+        }
+
+        let value = (self.buffer & ((1u64 << count) - 1)) as u32;
+        self.buffer >>= count;
+        self.count -= count;
+
+        Ok(value)
+    }
+
+    /// Discard bits up to next byte boundary.
+    fn align(&mut self) {
+        let drop = self.count % 8;
+        self.buffer >>= drop;
+        self.count -= drop;
+    }
+
+    /// Copy `length` bytes to output. Reader must be aligned.
+    fn copy_bytes(&mut self, output : &mut Vec<u8>, length : usize) -> Result<(), Error> {
+        let mut remaining = length;
+
+        // Drain bytes still in bit buffer
+        while remaining > 0 && self.count >= 8 {
+            output.push(self.bits(8)? as u8);
+            remaining -= 1;
+        }
+
+        if self.position + remaining > self.input.len() {
+            return Err(Error::new(ErrorKind::UnexpectedEof, "Deflate stream truncated!"));
+        }
+
+        output.extend_from_slice(&self.input[self.position..self.position + remaining]);
+        self.position += remaining;
+
+        Ok(())
+    }
+
+    /// Decode a symbol with Huffman table.
+    fn decode(&mut self, huffman : &KHuffman) -> Result<u16, Error> {
+
+        // Fast path
+        self.fill(FAST_BITS);
+        let entry = huffman.fast[(self.buffer & ((1 << FAST_BITS) - 1)) as usize];
+        let length = (entry & 0xF) as u32;
+        if length > 0 && length <= self.count {
+            self.buffer >>= length;
+            self.count -= length;
+            return Ok(entry >> 4);
+        }
+
+        // Slow path, canonical decoding bit per bit
+        let mut code : i32 = 0;
+        let mut first : i32 = 0;
+        let mut index : i32 = 0;
+        for length in 1..=MAX_BITS {
+            code |= self.bits(1)? as i32;
+            let count = huffman.counts[length] as i32;
+            if code - count < first {
+                return Ok(huffman.symbols[(index + (code - first)) as usize]);
+            }
+            index += count;
+            first += count;
+            first <<= 1;
+            code <<= 1;
+        }
+
+        Err(corrupted("Invalid Huffman code!"))
+    }
+
+    /// Count of input bytes consumed, including partially read byte.
+    fn consumed(&self) -> usize {
+        self.position - (self.count / 8) as usize
+    }
+}
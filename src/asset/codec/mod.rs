@@ -0,0 +1,23 @@
+// Deflate decompression (RFC 1951)
+pub(crate) mod inflate;
+
+// Deflate compression (RFC 1951)
+pub(crate) mod deflate;
+
+// CRC-32 checksum (ISO-HDLC / IEEE 802.3)
+pub(crate) mod crc32;
+
+// SHA-256 hash (FIPS 180-4)
+pub(crate) mod sha256;
+
+// SHA-512 hash (FIPS 180-4)
+pub(crate) mod sha512;
+
+// Ed25519 signatures (RFC 8032)
+pub(crate) mod ed25519;
+
+// Gzip decompression (RFC 1952)
+pub(crate) mod gzip;
+
+// Zstandard decompression (RFC 8878)
+pub(crate) mod zstd;
@@ -0,0 +1,129 @@
+/// SHA-256 round constants, first 32 bits of the fractional parts of the cube roots of the first 64 primes.
+const SHA256_K : [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// SHA-256 initial hash value, first 32 bits of the fractional parts of the square roots of the first 8 primes.
+const SHA256_H : [u32; 8] = [0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19];
+
+/// Size of a SHA-256 block in bytes.
+const SHA256_BLOCK_SIZE : usize = 64;
+
+/// Running SHA-256 (FIPS 180-4) hash of data given with [KSha256::update].
+#[derive(Clone)]
+pub(crate) struct KSha256 {
+    // Hash value
+    state : [u32; 8],
+
+    // Data not yet making a full block
+    buffer : [u8; SHA256_BLOCK_SIZE],
+
+    // Count of bytes in buffer
+    buffered : usize,
+
+    // Total count of bytes hashed
+    length : u64,
+}
+
+impl KSha256 {
+    /// Create a new SHA-256 hash of no data.
+    pub(crate) fn new() -> KSha256 {
+        KSha256 { state : SHA256_H, buffer : [0; SHA256_BLOCK_SIZE], buffered : 0, length : 0 }
+    }
+
+    /// Hash `data` after data already hashed.
+    pub(crate) fn update(&mut self, mut data : &[u8]) {
+        self.length = self.length.wrapping_add(data.len() as u64);
+
+        if self.buffered > 0 {
+            let size = data.len().min(SHA256_BLOCK_SIZE - self.buffered);
+            self.buffer[self.buffered..self.buffered + size].copy_from_slice(&data[..size]);
+            self.buffered += size;
+            data = &data[size..];
+
+            if self.buffered < SHA256_BLOCK_SIZE {
+                return;
+            }
+
+            let block = self.buffer;
+            self.compress(&block);
+            self.buffered = 0;
+        }
+
+        let mut blocks = data.chunks_exact(SHA256_BLOCK_SIZE);
+        for block in &mut blocks {
+            self.compress(block);
+        }
+
+        let remainder = blocks.remainder();
+        self.buffer[..remainder.len()].copy_from_slice(remainder);
+        self.buffered = remainder.len();
+    }
+
+    /// Pad data hashed and return the 32 bytes hash.
+    pub(crate) fn finish(mut self) -> [u8; 32] {
+        let bits = self.length.wrapping_mul(8);
+
+        // Padding is a 1 bit, zeros, then length in bits so data ends on a block
+        let mut padding = [0u8; SHA256_BLOCK_SIZE + 8];
+        padding[0] = 0x80;
+        let size = match self.buffered < SHA256_BLOCK_SIZE - 8 {
+            true => SHA256_BLOCK_SIZE - 8 - self.buffered,
+            false => 2 * SHA256_BLOCK_SIZE - 8 - self.buffered,
+        };
+        padding[size..size + 8].copy_from_slice(&bits.to_be_bytes());
+        self.update(&padding[..size + 8]);
+
+        let mut hash = [0u8; 32];
+        for (bytes, word) in hash.chunks_exact_mut(4).zip(self.state) {
+            bytes.copy_from_slice(&word.to_be_bytes());
+        }
+
+        hash
+    }
+
+    /// Process a 64 bytes block.
+    fn compress(&mut self, block : &[u8]) {
+        let mut w = [0u32; 64];
+        for (i, word) in block.chunks_exact(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = self.state;
+
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = h.wrapping_add(s1).wrapping_add(ch).wrapping_add(SHA256_K[i]).wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+
+        for (state, value) in self.state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *state = state.wrapping_add(value);
+        }
+    }
+}
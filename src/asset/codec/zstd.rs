@@ -0,0 +1,878 @@
+use std::io::{Error, ErrorKind};
+use super::inflate::limit_exceeded;
+
+/// Magic number starting a Zstandard frame.
+const ZSTD_MAGIC : u32 = 0xFD2FB528;
+
+/// Magic number starting a skippable frame, low 4 bits being free.
+const ZSTD_SKIPPABLE_MAGIC : u32 = 0x184D2A50;
+
+/// Maximum size of a block and of its literals.
+const ZSTD_BLOCK_MAX : usize = 128 * 1024;
+
+/// Maximum accuracy log of the FSE table of Huffman weights.
+const HUFFMAN_WEIGHTS_MAX_LOG : u32 = 6;
+
+/// Maximum length of a Huffman code of literals in bits.
+const HUFFMAN_MAX_BITS : u32 = 11;
+
+/// Base of literals length codes 0..35.
+const LL_BASE : [u32; 36] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 18, 20, 22, 24, 28, 32, 40, 48, 64, 128, 256, 512,
+    1024, 2048, 4096, 8192, 16384, 32768, 65536];
+
+/// Extra bits of literals length codes 0..35.
+const LL_EXTRA : [u8; 36] = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 3, 3, 4, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16];
+
+/// Base of match length codes 0..52.
+const ML_BASE : [u32; 53] = [3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34,
+    35, 37, 39, 41, 43, 47, 51, 59, 67, 83, 99, 131, 259, 515, 1027, 2051, 4099, 8195, 16387, 32771, 65539];
+
+/// Extra bits of match length codes 0..52.
+const ML_EXTRA : [u8; 53] = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    1, 1, 1, 1, 2, 2, 3, 3, 4, 4, 5, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16];
+
+/// Predefined distribution of literals length codes, with an accuracy log of 6.
+const LL_DEFAULT : [i16; 36] = [4, 3, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 1, 1, 1, 2, 2, 2, 2, 2, 2, 2, 2, 2, 3, 2, 1, 1, 1, 1, 1, -1, -1, -1, -1];
+
+/// Predefined distribution of offset codes, with an accuracy log of 5.
+const OF_DEFAULT : [i16; 29] = [1, 1, 1, 1, 1, 1, 2, 2, 2, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, -1, -1, -1, -1, -1];
+
+/// Predefined distribution of match length codes, with an accuracy log of 6.
+const ML_DEFAULT : [i16; 53] = [1, 4, 3, 2, 2, 2, 2, 2, 2, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, -1, -1, -1, -1, -1, -1, -1];
+
+/// Sequence tables as `(predefined distribution, predefined log, maximum log, maximum symbol)`, in order
+/// literals length, offset and match length.
+const SEQUENCE_TABLES : [(&[i16], u32, u32, usize); 3] = [(&LL_DEFAULT, 6, 9, 35), (&OF_DEFAULT, 5, 8, 31), (&ML_DEFAULT, 6, 9, 52)];
+
+/// Decompress Zstandard frames (RFC 8878) from `input` and append the result to `output`.
+///
+/// Skippable frames are ignored and content checksums verified. Frames needing a dictionary are not supported.
+/// Decompression stops with an error if more than `limit` bytes would be produced.
+///
+/// Returns `Ok(usize)` with the count of `input` bytes consumed, which is all of it.
+///
+/// # Error(s)
+/// Returns [ErrorKind::InvalidData] if a frame is corrupted, truncated or exceeds `limit`.
+///
+/// Returns [ErrorKind::Unsupported] if a frame needs a dictionary.
+pub(crate) fn decompress(input : &[u8], output : &mut Vec<u8>, limit : usize) -> Result<usize, Error> {
+    let mut reader = KByteReader { input, position : 0 };
+    let start = output.len();
+
+    loop {
+        let magic = reader.le(4)? as u32;
+
+        if magic & 0xFFFFFFF0 == ZSTD_SKIPPABLE_MAGIC {
+            let size = reader.le(4)? as usize;
+            reader.bytes(size)?;
+        } else if magic == ZSTD_MAGIC {
+            decompress_frame(&mut reader, output, start, limit)?;
+        } else {
+            return Err(corrupted("Invalid Zstandard magic number!"));
+        }
+
+        if reader.position == input.len() {
+            break;
+        }
+    }
+
+    Ok(reader.position)
+}
+
+/// Returns an [ErrorKind::InvalidData] error with message.
+fn corrupted(message : &str) -> Error {
+    Error::new(ErrorKind::InvalidData, message)
+}
+
+/// Returns an [ErrorKind::UnexpectedEof] error of a truncated stream.
+fn truncated() -> Error {
+    Error::new(ErrorKind::UnexpectedEof, "Zstandard stream truncated!")
+}
+
+/// Returns an error if adding `size` bytes to output would exceed `limit` bytes since `start`.
+fn check_limit(output : &[u8], start : usize, limit : usize, size : usize) -> Result<(), Error> {
+    match (output.len() - start).checked_add(size) {
+        Some(total) if total <= limit => Ok(()),
+        _ => Err(limit_exceeded()),
+    }
+}
+
+/// Read little endian integer of `count` bytes (max 8) at start of `data`.
+fn read_le(data : &[u8], count : usize) -> Result<u64, Error> {
+    let bytes = data.get(..count).ok_or_else(truncated)?;
+    Ok(bytes.iter().rev().fold(0u64, |value, byte| (value << 8) | *byte as u64))
+}
+
+/// Decompress a frame, its magic number already read.
+fn decompress_frame(reader : &mut KByteReader, output : &mut Vec<u8>, start : usize, limit : usize) -> Result<(), Error> {
+    let descriptor = reader.byte()?;
+    let single_segment = descriptor & 0x20 != 0;
+    let checksum = descriptor & 0x04 != 0;
+
+    if descriptor & 0x08 != 0 {
+        return Err(corrupted("Zstandard frame reserved bit is set!"));
+    }
+
+    // Window size is ignored since content is kept whole in output
+    if !single_segment {
+        reader.byte()?;
+    }
+
+    if reader.le([0, 1, 2, 4][(descriptor & 0x03) as usize])? != 0 {
+        return Err(Error::new(ErrorKind::Unsupported, "Zstandard dictionaries are not supported!"));
+    }
+
+    let content_size = match descriptor >> 6 {
+        0 if single_segment => Some(reader.le(1)?),
+        0 => None,
+        1 => Some(reader.le(2)? + 256),
+        2 => Some(reader.le(4)?),
+        _ => Some(reader.le(8)?),
+    };
+
+    // Frames declaring a size over limit fail before anything is decompressed
+    if let Some(size) = content_size {
+        check_limit(output, start, limit, usize::try_from(size).unwrap_or(usize::MAX))?;
+    }
+
+    let frame_start = output.len();
+    let mut frame = KZstdFrame::new();
+
+    loop {
+        let header = reader.le(3)?;
+        let size = (header >> 3) as usize;
+
+        match (header >> 1) & 0x03 {
+            0 => {
+                let data = reader.bytes(size)?;
+                check_limit(output, start, limit, size)?;
+                output.extend_from_slice(data);
+            },
+            1 => {
+                let byte = reader.byte()?;
+                check_limit(output, start, limit, size)?;
+                output.resize(output.len() + size, byte);
+            },
+            2 => {
+                if size > ZSTD_BLOCK_MAX {
+                    return Err(corrupted("Zstandard block is too large!"));
+                }
+                let data = reader.bytes(size)?;
+                frame.decompress_block(data, output, frame_start, start, limit)?;
+            },
+            _ => return Err(corrupted("Invalid Zstandard block type!")),
+        }
+
+        if header & 0x01 != 0 {
+            break;
+        }
+    }
+
+    if content_size.is_some_and(|size| size != (output.len() - frame_start) as u64) {
+        return Err(corrupted("Zstandard frame content size mismatch!"));
+    }
+
+    if checksum && reader.le(4)? as u32 != xxh64(&output[frame_start..]) as u32 {
+        return Err(corrupted("Zstandard frame checksum mismatch!"));
+    }
+
+    Ok(())
+}
+
+/// State of a frame kept between its blocks.
+struct KZstdFrame {
+    // Huffman table of previous compressed literals, reused by treeless literals
+    huffman : Option<KHuffmanTable>,
+
+    // Previous tables of literals length, offset and match length, reused by repeat mode
+    tables : [Option<KFseTable>; 3],
+
+    // Repeated offsets, most recent first
+    offsets : [usize; 3],
+}
+
+impl KZstdFrame {
+    /// Create state of a new frame.
+    fn new() -> KZstdFrame {
+        KZstdFrame { huffman : None, tables : [None, None, None], offsets : [1, 4, 8] }
+    }
+
+    /// Decompress a compressed block, executing its sequences.
+    fn decompress_block(&mut self, data : &[u8], output : &mut Vec<u8>, frame_start : usize, start : usize, limit : usize) -> Result<(), Error> {
+        let (literals, consumed) = self.decode_literals(data)?;
+        let mut reader = KByteReader { input : &data[consumed..], position : 0 };
+
+        let count = match reader.byte()? {
+            255 => reader.le(2)? as usize + 0x7F00,
+            byte if byte >= 128 => ((byte as usize - 128) << 8) + reader.byte()? as usize,
+            byte => byte as usize,
+        };
+
+        if count == 0 {
+            check_limit(output, start, limit, literals.len())?;
+            output.extend_from_slice(&literals);
+            return Ok(());
+        }
+
+        let modes = reader.byte()?;
+        if modes & 0x03 != 0 {
+            return Err(corrupted("Zstandard sequences reserved bits are set!"));
+        }
+
+        for (kind, mode) in [modes >> 6, (modes >> 4) & 0x03, (modes >> 2) & 0x03].into_iter().enumerate() {
+            self.read_table(&mut reader, kind, mode)?;
+        }
+
+        let (ll, of, ml) = match &self.tables {
+            [Some(ll), Some(of), Some(ml)] => (ll, of, ml),
+            _ => return Err(corrupted("Zstandard sequence table missing!")),
+        };
+
+        let mut bits = KBackwardReader::new(&reader.input[reader.position..])?;
+        let mut ll_state = bits.read(ll.log) as usize;
+        let mut of_state = bits.read(of.log) as usize;
+        let mut ml_state = bits.read(ml.log) as usize;
+        let mut literal = 0;
+
+        for index in 0..count {
+            let of_code = of.entries[of_state].symbol as u32;
+            let ml_code = ml.entries[ml_state].symbol as usize;
+            let ll_code = ll.entries[ll_state].symbol as usize;
+
+            let offset_value = (1u64 << of_code) + bits.read(of_code);
+            let match_length = (ML_BASE[ml_code] as u64 + bits.read(ML_EXTRA[ml_code] as u32)) as usize;
+            let literal_length = (LL_BASE[ll_code] as u64 + bits.read(LL_EXTRA[ll_code] as u32)) as usize;
+
+            let offset = resolve_offset(&mut self.offsets, offset_value, literal_length);
+
+            // States aren't updated after last sequence
+            if index + 1 < count {
+                ll_state = ll.update(ll_state, &mut bits);
+                ml_state = ml.update(ml_state, &mut bits);
+                of_state = of.update(of_state, &mut bits);
+            }
+
+            let copied = literals.get(literal..literal + literal_length).ok_or_else(|| corrupted("Zstandard sequence exceeds literals!"))?;
+            check_limit(output, start, limit, literal_length.saturating_add(match_length))?;
+            output.extend_from_slice(copied);
+            literal += literal_length;
+
+            if offset == 0 || offset > output.len() - frame_start {
+                return Err(corrupted("Zstandard match offset is out of frame!"));
+            }
+
+            let source = output.len() - offset;
+            if offset >= match_length {
+                output.extend_from_within(source..source + match_length);
+            } else {
+                // Overlapping match repeats bytes as they are copied
+                for position in source..source + match_length {
+                    output.push(output[position]);
+                }
+            }
+        }
+
+        if !bits.is_finished() {
+            return Err(corrupted("Zstandard sequences stream corrupted!"));
+        }
+
+        check_limit(output, start, limit, literals.len() - literal)?;
+        output.extend_from_slice(&literals[literal..]);
+
+        Ok(())
+    }
+
+    /// Read table of sequence `kind` according to its mode.
+    fn read_table(&mut self, reader : &mut KByteReader, kind : usize, mode : u8) -> Result<(), Error> {
+        let (distribution, log, max_log, max_symbol) = SEQUENCE_TABLES[kind];
+
+        let table = match mode {
+            0 => KFseTable::new(distribution, log)?,
+            1 => {
+                let symbol = reader.byte()?;
+                if symbol as usize > max_symbol {
+                    return Err(corrupted("Invalid Zstandard RLE symbol!"));
+                }
+                KFseTable { log : 0, entries : vec![KFseEntry { symbol, bits : 0, baseline : 0 }] }
+            },
+            2 => {
+                let (table, consumed) = KFseTable::read(&reader.input[reader.position..], max_log, max_symbol)?;
+                reader.position += consumed;
+                table
+            },
+            _ => return match self.tables[kind].is_some() {
+                true => Ok(()),
+                false => Err(corrupted("Zstandard repeat mode without previous table!")),
+            },
+        };
+
+        self.tables[kind] = Some(table);
+        Ok(())
+    }
+
+    /// Decode literals section at start of block.
+    ///
+    /// Returns `Ok((literals, consumed))` with literals and the count of bytes of section.
+    fn decode_literals(&mut self, data : &[u8]) -> Result<(Vec<u8>, usize), Error> {
+        let first = *data.first().ok_or_else(truncated)?;
+        let format = (first >> 2) & 0x03;
+
+        match first & 0x03 {
+            // Raw and RLE literals
+            kind @ (0 | 1) => {
+                let (size, header) = match format {
+                    1 => ((read_le(data, 2)? >> 4) as usize, 2),
+                    3 => ((read_le(data, 3)? >> 4) as usize, 3),
+                    _ => ((first >> 3) as usize, 1),
+                };
+
+                if size > ZSTD_BLOCK_MAX {
+                    return Err(corrupted("Zstandard literals are too large!"));
+                }
+
+                match kind {
+                    0 => Ok((data.get(header..header + size).ok_or_else(truncated)?.to_vec(), header + size)),
+                    _ => Ok((vec![*data.get(header).ok_or_else(truncated)?; size], header + 1)),
+                }
+            },
+
+            // Huffman compressed literals, with their table or the previous one
+            kind => {
+                let (streams, header, field) = match format {
+                    0 => (1, 3, 10),
+                    1 => (4, 3, 10),
+                    2 => (4, 4, 14),
+                    _ => (4, 5, 18),
+                };
+
+                let value = read_le(data, header)?;
+                let mask = (1u64 << field) - 1;
+                let size = ((value >> 4) & mask) as usize;
+                let compressed = ((value >> (4 + field)) & mask) as usize;
+                let mut content = data.get(header..header + compressed).ok_or_else(truncated)?;
+
+                if kind == 2 {
+                    let (table, consumed) = KHuffmanTable::read(content)?;
+                    self.huffman = Some(table);
+                    content = &content[consumed..];
+                }
+
+                match &self.huffman {
+                    Some(table) => Ok((table.decode(content, size, streams)?, header + compressed)),
+                    None => Err(corrupted("Zstandard treeless literals without previous table!")),
+                }
+            },
+        }
+    }
+}
+
+/// Get offset of a match from its decoded value, updating repeated offsets.
+fn resolve_offset(offsets : &mut [usize; 3], value : u64, literal_length : usize) -> usize {
+    let [first, second, third] = *offsets;
+
+    if value > 3 {
+        let offset = (value - 3) as usize;
+        *offsets = [offset, first, second];
+        return offset;
+    }
+
+    // Repeated offsets are shifted by one when match has no literal
+    match value as usize + (literal_length == 0) as usize {
+        1 => first,
+        2 => {
+            *offsets = [second, first, third];
+            second
+        },
+        3 => {
+            *offsets = [third, first, second];
+            third
+        },
+        _ => {
+            let offset = first.wrapping_sub(1);
+            *offsets = [offset, first, second];
+            offset
+        },
+    }
+}
+
+/// Entry of a [KFseTable] for a state.
+#[derive(Clone, Copy)]
+struct KFseEntry {
+    // Symbol decoded
+    symbol : u8,
+
+    // Count of bits read for next state
+    bits : u8,
+
+    // Value added to bits read for next state
+    baseline : u16,
+}
+
+/// Finite State Entropy decoding table.
+struct KFseTable {
+    // Accuracy log, table having 2^log states
+    log : u32,
+
+    // Entries of states
+    entries : Vec<KFseEntry>,
+}
+
+impl KFseTable {
+    /// Build table from normalized distribution, where -1 is a probability lower than 1.
+    fn new(distribution : &[i16], log : u32) -> Result<KFseTable, Error> {
+        let size = 1usize << log;
+        let mut entries = vec![KFseEntry { symbol : 0, bits : 0, baseline : 0 }; size];
+        let mut next = vec![0u32; distribution.len()];
+
+        // Symbols of lower probability take the last states
+        let mut high = size;
+        for (symbol, probability) in distribution.iter().enumerate() {
+            if *probability == -1 {
+                high = high.checked_sub(1).ok_or_else(|| corrupted("Invalid Zstandard FSE distribution!"))?;
+                entries[high].symbol = symbol as u8;
+                next[symbol] = 1;
+            } else {
+                next[symbol] = (*probability).max(0) as u32;
+            }
+        }
+
+        // Other symbols are spread over remaining states
+        let step = (size >> 1) + (size >> 3) + 3;
+        let mut position = 0;
+        for (symbol, probability) in distribution.iter().enumerate() {
+            for _ in 0..(*probability).max(0) {
+                entries[position].symbol = symbol as u8;
+                loop {
+                    position = (position + step) & (size - 1);
+                    if position < high {
+                        break;
+                    }
+                }
+            }
+        }
+
+        if position != 0 {
+            return Err(corrupted("Invalid Zstandard FSE distribution!"));
+        }
+
+        for entry in entries.iter_mut() {
+            let state = next[entry.symbol as usize];
+            next[entry.symbol as usize] += 1;
+
+            let bits = log - (31 - state.leading_zeros());
+            entry.bits = bits as u8;
+            entry.baseline = ((state << bits) - size as u32) as u16;
+        }
+
+        Ok(KFseTable { log, entries })
+    }
+
+    /// Read table description at start of `data`.
+    ///
+    /// Returns `Ok((table, consumed))` with table and the count of bytes of description.
+    fn read(data : &[u8], max_log : u32, max_symbol : usize) -> Result<(KFseTable, usize), Error> {
+        let mut bits = KForwardReader { input : data, position : 0 };
+
+        let log = bits.read(4) as u32 + 5;
+        if log > max_log {
+            return Err(corrupted("Zstandard FSE accuracy log is too large!"));
+        }
+
+        let mut remaining = (1i32 << log) + 1;
+        let mut threshold = 1i32 << log;
+        let mut count = log + 1;
+        let mut distribution : Vec<i16> = Vec::new();
+
+        while remaining > 1 {
+            let max = 2 * threshold - 1 - remaining;
+            let value = bits.peek(count) as i32;
+
+            let value = if value & (threshold - 1) < max {
+                bits.skip(count - 1);
+                value & (threshold - 1)
+            } else {
+                bits.skip(count);
+                let value = value & (2 * threshold - 1);
+                if value >= threshold { value - max } else { value }
+            };
+
+            let probability = value - 1;
+            remaining -= probability.abs();
+            distribution.push(probability as i16);
+
+            // Zero probabilities are followed by count of zeros repeated
+            if probability == 0 {
+                loop {
+                    let repeat = bits.read(2);
+                    distribution.extend(std::iter::repeat_n(0, repeat as usize));
+                    if repeat != 3 {
+                        break;
+                    }
+                }
+            }
+
+            if remaining < 1 || distribution.len() > max_symbol + 1 {
+                return Err(corrupted("Invalid Zstandard FSE distribution!"));
+            }
+
+            while remaining < threshold {
+                count -= 1;
+                threshold >>= 1;
+            }
+        }
+
+        let consumed = bits.position.div_ceil(8);
+        if consumed > data.len() {
+            return Err(truncated());
+        }
+
+        Ok((KFseTable::new(&distribution, log)?, consumed))
+    }
+
+    /// Get next state after decoding the symbol of `state`.
+    fn update(&self, state : usize, bits : &mut KBackwardReader) -> usize {
+        let entry = self.entries[state];
+        entry.baseline as usize + bits.read(entry.bits as u32) as usize
+    }
+}
+
+/// Entry of a [KHuffmanTable] for a code prefix.
+#[derive(Clone, Copy)]
+struct KHuffmanEntry {
+    // Symbol decoded
+    symbol : u8,
+
+    // Length of symbol code in bits
+    bits : u8,
+}
+
+/// Huffman decoding table of literals, indexed by the next `max_bits` bits.
+struct KHuffmanTable {
+    // Length of longest code in bits
+    max_bits : u32,
+
+    // Entries of code prefixes
+    entries : Vec<KHuffmanEntry>,
+}
+
+impl KHuffmanTable {
+    /// Build table from weights of symbols, weight of last symbol being implied.
+    fn new(weights : &[u8]) -> Result<KHuffmanTable, Error> {
+        let mut total = 0u32;
+        for weight in weights {
+            match *weight {
+                0 => {},
+                weight if weight as u32 <= HUFFMAN_MAX_BITS => total += 1 << (weight - 1),
+                _ => return Err(corrupted("Invalid Zstandard Huffman weight!")),
+            }
+        }
+
+        // Last weight completes total to a power of 2
+        let max_bits = 32 - total.leading_zeros();
+        let remaining = (1u32 << max_bits) - total;
+        if total == 0 || max_bits > HUFFMAN_MAX_BITS || !remaining.is_power_of_two() {
+            return Err(corrupted("Invalid Zstandard Huffman weights!"));
+        }
+        let last = (remaining.trailing_zeros() + 1) as u8;
+
+        // Codes of lower weights come first, each weight taking 2^(weight - 1) entries
+        let mut starts = [0usize; HUFFMAN_MAX_BITS as usize + 2];
+        for weight in weights.iter().chain([&last]).filter(|weight| **weight > 0) {
+            starts[*weight as usize + 1] += 1 << (*weight - 1);
+        }
+        for weight in 1..starts.len() {
+            starts[weight] += starts[weight - 1];
+        }
+
+        let mut entries = vec![KHuffmanEntry { symbol : 0, bits : 0 }; 1 << max_bits];
+        for (symbol, weight) in weights.iter().chain([&last]).enumerate().filter(|(_, weight)| **weight > 0) {
+            let start = starts[*weight as usize];
+            let length = 1 << (*weight - 1);
+            entries[start..start + length].fill(KHuffmanEntry { symbol : symbol as u8, bits : (max_bits + 1 - *weight as u32) as u8 });
+            starts[*weight as usize] += length;
+        }
+
+        Ok(KHuffmanTable { max_bits, entries })
+    }
+
+    /// Read table description at start of `data`.
+    ///
+    /// Returns `Ok((table, consumed))` with table and the count of bytes of description.
+    fn read(data : &[u8]) -> Result<(KHuffmanTable, usize), Error> {
+        let header = *data.first().ok_or_else(truncated)? as usize;
+
+        // Weights are either FSE compressed or stored as 4 bits each
+        if header < 128 {
+            let content = data.get(1..1 + header).ok_or_else(truncated)?;
+            let (table, consumed) = KFseTable::read(content, HUFFMAN_WEIGHTS_MAX_LOG, HUFFMAN_MAX_BITS as usize + 1)?;
+            let mut bits = KBackwardReader::new(&content[consumed..])?;
+
+            // Two states decode weights in turn until stream is overflowed
+            let mut states = [bits.read(table.log) as usize, bits.read(table.log) as usize];
+            let mut weights = Vec::new();
+            for turn in 0.. {
+                let state = states[turn % 2];
+                weights.push(table.entries[state].symbol);
+                states[turn % 2] = table.update(state, &mut bits);
+
+                if bits.is_overflowed() {
+                    weights.push(table.entries[states[(turn + 1) % 2]].symbol);
+                    break;
+                }
+
+                if weights.len() > 255 {
+                    return Err(corrupted("Too many Zstandard Huffman weights!"));
+                }
+            }
+
+            Ok((KHuffmanTable::new(&weights)?, 1 + header))
+        } else {
+            let count = header - 127;
+            let bytes = data.get(1..1 + count.div_ceil(2)).ok_or_else(truncated)?;
+            let weights : Vec<u8> = (0..count).map(|index| match index % 2 {
+                0 => bytes[index / 2] >> 4,
+                _ => bytes[index / 2] & 0x0F,
+            }).collect();
+
+            Ok((KHuffmanTable::new(&weights)?, 1 + bytes.len()))
+        }
+    }
+
+    /// Decode `size` literals from 1 or 4 streams.
+    fn decode(&self, data : &[u8], size : usize, streams : usize) -> Result<Vec<u8>, Error> {
+        if size > ZSTD_BLOCK_MAX {
+            return Err(corrupted("Zstandard literals are too large!"));
+        }
+
+        let mut literals = Vec::with_capacity(size);
+
+        if streams == 1 {
+            self.decode_stream(data, size, &mut literals)?;
+            return Ok(literals);
+        }
+
+        // Sizes of first 3 streams are given by a jump table, each stream decoding a quarter of literals
+        let jump = data.get(..6).ok_or_else(truncated)?;
+        let quarter = size.div_ceil(4);
+        let mut position = 6;
+
+        for index in 0..4 {
+            let length = match index {
+                3 => data.len().checked_sub(position).ok_or_else(truncated)?,
+                _ => read_le(&jump[index * 2..], 2)? as usize,
+            };
+            let count = match index {
+                3 => size.checked_sub(3 * quarter).ok_or_else(|| corrupted("Invalid Zstandard literals size!"))?,
+                _ => quarter,
+            };
+
+            self.decode_stream(data.get(position..position + length).ok_or_else(truncated)?, count, &mut literals)?;
+            position += length;
+        }
+
+        Ok(literals)
+    }
+
+    /// Decode `count` literals from a stream that must be consumed entirely.
+    fn decode_stream(&self, data : &[u8], count : usize, literals : &mut Vec<u8>) -> Result<(), Error> {
+        let mut bits = KBackwardReader::new(data)?;
+
+        for _ in 0..count {
+            let entry = self.entries[bits.peek(self.max_bits) as usize];
+            literals.push(entry.symbol);
+            bits.skip(entry.bits as u32);
+        }
+
+        match bits.is_finished() {
+            true => Ok(()),
+            false => Err(corrupted("Zstandard literals stream corrupted!")),
+        }
+    }
+}
+
+/// Extract `count` bits (max 32) of `input` starting at `bit`, bits past the end being zeros.
+fn extract_bits(input : &[u8], bit : usize, count : u32) -> u64 {
+    if count == 0 {
+        return 0;
+    }
+
+    let byte = bit / 8;
+    let value = match input.get(byte..byte + 8) {
+        Some(bytes) => u64::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7]]),
+        None => input.get(byte..).unwrap_or_default().iter().rev().fold(0u64, |value, byte| (value << 8) | *byte as u64),
+    };
+
+    (value >> (bit % 8)) & ((1u64 << count) - 1)
+}
+
+/// Reader of bytes of a frame.
+struct KByteReader<'a> {
+    // Frame data
+    input : &'a [u8],
+
+    // Position of next byte
+    position : usize,
+}
+
+impl<'a> KByteReader<'a> {
+    /// Read a byte.
+    fn byte(&mut self) -> Result<u8, Error> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    /// Read `count` bytes.
+    fn bytes(&mut self, count : usize) -> Result<&'a [u8], Error> {
+        let bytes = self.input.get(self.position..self.position.saturating_add(count)).ok_or_else(truncated)?;
+        self.position += count;
+        Ok(bytes)
+    }
+
+    /// Read little endian integer of `count` bytes (max 8).
+    fn le(&mut self, count : usize) -> Result<u64, Error> {
+        let value = read_le(&self.input[self.position..], count)?;
+        self.position += count;
+        Ok(value)
+    }
+}
+
+/// Reader of bits from first to last, least significant bit first, used by FSE table descriptions.
+struct KForwardReader<'a> {
+    // Stream data
+    input : &'a [u8],
+
+    // Position of next bit
+    position : usize,
+}
+
+impl KForwardReader<'_> {
+    /// Get next `count` bits (max 32) without consuming them.
+    fn peek(&self, count : u32) -> u64 {
+        extract_bits(self.input, self.position, count)
+    }
+
+    /// Consume `count` bits.
+    fn skip(&mut self, count : u32) {
+        self.position += count as usize;
+    }
+
+    /// Read `count` bits (max 32).
+    fn read(&mut self, count : u32) -> u64 {
+        let value = self.peek(count);
+        self.skip(count);
+        value
+    }
+}
+
+/// Reader of bits from last to first, most significant bit first, used by entropy coded streams.
+///
+/// Stream ends with a 1 bit marking where bits start. Reading past the start gives zeros.
+struct KBackwardReader<'a> {
+    // Stream data
+    input : &'a [u8],
+
+    // Count of bits not yet read, negative once read past the start
+    position : isize,
+}
+
+impl<'a> KBackwardReader<'a> {
+    /// Create a reader of stream, skipping padding up to the start marker.
+    fn new(input : &'a [u8]) -> Result<KBackwardReader<'a>, Error> {
+        match input.last() {
+            Some(last) if *last != 0 => Ok(KBackwardReader { input, position : (input.len() * 8 - 1 - last.leading_zeros() as usize) as isize }),
+            _ => Err(corrupted("Zstandard bit stream has no start marker!")),
+        }
+    }
+
+    /// Get next `count` bits (max 32) without consuming them.
+    fn peek(&self, count : u32) -> u64 {
+        let low = self.position - count as isize;
+
+        if low >= 0 {
+            extract_bits(self.input, low as usize, count)
+        } else {
+            let available = self.position.max(0) as u32;
+            extract_bits(self.input, 0, available) << (count - available)
+        }
+    }
+
+    /// Consume `count` bits.
+    fn skip(&mut self, count : u32) {
+        self.position -= count as isize;
+    }
+
+    /// Read `count` bits (max 32).
+    fn read(&mut self, count : u32) -> u64 {
+        let value = self.peek(count);
+        self.skip(count);
+        value
+    }
+
+    /// Returns true if all bits were read.
+    fn is_finished(&self) -> bool {
+        self.position == 0
+    }
+
+    /// Returns true if more bits were read than stream has.
+    fn is_overflowed(&self) -> bool {
+        self.position < 0
+    }
+}
+
+/// XXH64 primes.
+const XXH_PRIME_1 : u64 = 0x9E3779B185EBCA87;
+const XXH_PRIME_2 : u64 = 0xC2B2AE3D27D4EB4F;
+const XXH_PRIME_3 : u64 = 0x165667B19E3779F9;
+const XXH_PRIME_4 : u64 = 0x85EBCA77C2B2AE63;
+const XXH_PRIME_5 : u64 = 0x27D4EB2F165667C5;
+
+/// Mix a lane of 8 bytes in an XXH64 accumulator.
+fn xxh64_round(accumulator : u64, lane : u64) -> u64 {
+    accumulator.wrapping_add(lane.wrapping_mul(XXH_PRIME_2)).rotate_left(31).wrapping_mul(XXH_PRIME_1)
+}
+
+/// Compute the XXH64 hash of `data` with a seed of 0, used by frame checksums.
+fn xxh64(data : &[u8]) -> u64 {
+    let lane = |bytes : &[u8]| u64::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7]]);
+    let mut stripes = data.chunks_exact(32);
+
+    let mut hash = if data.len() >= 32 {
+        let mut accumulators = [XXH_PRIME_1.wrapping_add(XXH_PRIME_2), XXH_PRIME_2, 0, XXH_PRIME_1.wrapping_neg()];
+        for stripe in &mut stripes {
+            for (accumulator, bytes) in accumulators.iter_mut().zip(stripe.chunks_exact(8)) {
+                *accumulator = xxh64_round(*accumulator, lane(bytes));
+            }
+        }
+
+        let [a, b, c, d] = accumulators;
+        let mut hash = a.rotate_left(1).wrapping_add(b.rotate_left(7)).wrapping_add(c.rotate_left(12)).wrapping_add(d.rotate_left(18));
+        for accumulator in accumulators {
+            hash = (hash ^ xxh64_round(0, accumulator)).wrapping_mul(XXH_PRIME_1).wrapping_add(XXH_PRIME_4);
+        }
+        hash
+    } else {
+        XXH_PRIME_5
+    };
+
+    hash = hash.wrapping_add(data.len() as u64);
+
+    let mut remainder = stripes.remainder();
+    while remainder.len() >= 8 {
+        hash = (hash ^ xxh64_round(0, lane(remainder))).rotate_left(27).wrapping_mul(XXH_PRIME_1).wrapping_add(XXH_PRIME_4);
+        remainder = &remainder[8..];
+    }
+    if remainder.len() >= 4 {
+        let word = u32::from_le_bytes([remainder[0], remainder[1], remainder[2], remainder[3]]) as u64;
+        hash = (hash ^ word.wrapping_mul(XXH_PRIME_1)).rotate_left(23).wrapping_mul(XXH_PRIME_2).wrapping_add(XXH_PRIME_3);
+        remainder = &remainder[4..];
+    }
+    for byte in remainder {
+        hash = (hash ^ (*byte as u64).wrapping_mul(XXH_PRIME_5)).rotate_left(11).wrapping_mul(XXH_PRIME_1);
+    }
+
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(XXH_PRIME_2);
+    hash ^= hash >> 29;
+    hash = hash.wrapping_mul(XXH_PRIME_3);
+    hash ^ (hash >> 32)
+}
@@ -0,0 +1,242 @@
+use std::{io::{Read, ErrorKind}, sync::Arc};
+use super::{KAssetBroker, KAssetPath, KAssetMetadata};
+use super::broker::KAssetBrokerSource;
+use super::codec::{gzip::gunzip, zstd, inflate::is_limit_exceeded};
+
+/// Compression formats of assets decompressed by [KAssetBroker], tried in this order.
+const KASSET_COMPRESSIONS : [KAssetCompression; 2] = [KAssetCompression::Gzip, KAssetCompression::Zstd];
+
+/// Compression format of an asset, given by the extension appended to its path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KAssetCompression {
+    /// Gzip (RFC 1952), with extension `gz`.
+    Gzip,
+
+    /// Zstandard (RFC 8878), with extension `zst`.
+    Zstd,
+}
+
+impl KAssetCompression {
+    /// Get the extension appended to paths of assets compressed with format, without the dot.
+    pub fn get_extension(&self) -> &'static str {
+        match self {
+            Self::Gzip => "gz",
+            Self::Zstd => "zst",
+        }
+    }
+
+    /// Get compression format of path from its extension, or `None` if path isn't compressed.
+    pub fn from_path(path : &KAssetPath) -> Option<KAssetCompression> {
+        let extension = path.get_extension()?;
+        KASSET_COMPRESSIONS.into_iter().find(|format| format.get_extension() == extension)
+    }
+}
+
+/// Enumeration of errors decompressing an asset.
+///
+/// Assets decompressed by a [KAssetBroker] fail with an [std::io::Error] of kind [InvalidData](ErrorKind::InvalidData)
+/// wrapping KDecompressionError, given by [KDecompressionError::from_io_error].
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum KDecompressionError {
+    /// Happens when decompressed asset, or compressed asset read, would be larger than [KAssetDecompression::max_size].
+    SizeLimitExceeded,
+
+    /// Happens when decompressed asset would be larger than its compressed size times [KAssetDecompression::max_ratio].
+    RatioLimitExceeded,
+
+    /// Happens when compressed asset is corrupted, truncated or needs a Zstandard dictionary.
+    Corrupted,
+}
+
+impl std::fmt::Debug for KDecompressionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SizeLimitExceeded => write!(f, "SizeLimitExceeded"),
+            Self::RatioLimitExceeded => write!(f, "RatioLimitExceeded"),
+            Self::Corrupted => write!(f, "Corrupted"),
+        }
+    }
+}
+
+impl std::fmt::Display for KDecompressionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SizeLimitExceeded => write!(f, "Decompressed asset exceeds size limit!"),
+            Self::RatioLimitExceeded => write!(f, "Decompressed asset exceeds compression ratio limit!"),
+            Self::Corrupted => write!(f, "Compressed asset is corrupted!"),
+        }
+    }
+}
+
+impl std::error::Error for KDecompressionError {}
+
+impl From<KDecompressionError> for std::io::Error {
+    /// Decompression errors are IO errors of kind [InvalidData](ErrorKind::InvalidData) wrapping [KDecompressionError].
+    fn from(err: KDecompressionError) -> Self {
+        std::io::Error::new(ErrorKind::InvalidData, err)
+    }
+}
+
+impl KDecompressionError {
+    /// Get [KDecompressionError] wrapped by an [std::io::Error], or `None` if error isn't a decompression error.
+    pub fn from_io_error(err : &std::io::Error) -> Option<KDecompressionError> {
+        err.get_ref().and_then(|inner| inner.downcast_ref::<KDecompressionError>()).copied()
+    }
+}
+
+/// ##### Decompression of assets stored compressed, set with [KAssetBroker::set_decompression].
+///
+/// When a source doesn't have an asset like `foo.json`, the broker looks for `foo.json.gz` then `foo.json.zst`
+/// in the same source before trying sources of lower priority. A compressed asset found is decompressed entirely
+/// in memory and served as if it was `foo.json`.
+///
+/// Decompressed size is capped by `max_size` and by `max_ratio` times the compressed size, so a small
+/// malicious asset can't expand into gigabytes. Decompression stops as soon as a limit is exceeded.
+///
+/// # Note(s)
+/// Assets aren't decompressed as they are read but buffered : the compressed asset is read up to `max_size`
+/// bytes, then decompressed into memory. A compressed asset larger than `max_size` is rejected without being read entirely.
+///
+/// # Example(s)
+/// ```
+/// use std::{io::Read, sync::Arc};
+/// use olympus_kleio::asset::{KAssetBroker, KAssetSourceMemory, KAssetDecompression};
+///
+/// // "Hello, world!" compressed with gzip
+/// let compressed = vec![0x1F, 0x8B, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF, 0xF3, 0x48, 0xCD, 0xC9, 0xC9, 0xD7, 0x51, 0x28,
+///     0xCF, 0x2F, 0xCA, 0x49, 0x51, 0x04, 0x00, 0xE6, 0xC6, 0xE6, 0xEB, 0x0D, 0x00, 0x00, 0x00];
+///
+/// let source = KAssetSourceMemory::new("memory");
+/// source.add_asset("text.txt.gz", compressed).unwrap();
+///
+/// let broker = KAssetBroker::new();
+/// broker.add_source(Arc::new(source)).unwrap();
+/// broker.set_decompression(Some(KAssetDecompression::default()));
+///
+/// let mut text = String::new();
+/// broker.get_asset("text.txt").unwrap().read_to_string(&mut text).unwrap();
+/// assert_eq!(text, "Hello, world!");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KAssetDecompression {
+    /// Maximum size of a decompressed asset, and of the compressed asset read to decompress it, in bytes.
+    pub max_size : u64,
+
+    /// Maximum ratio of decompressed size over compressed size.
+    pub max_ratio : u64,
+}
+
+impl Default for KAssetDecompression {
+    /// Decompressed assets up to 256 MiB and 200 times their compressed size.
+    fn default() -> Self {
+        KAssetDecompression { max_size : 256 * 1024 * 1024, max_ratio : 200 }
+    }
+}
+
+impl KAssetDecompression {
+    /// Decompress `data` compressed with `format`, within limits.
+    ///
+    /// Returns `Ok(Vec<u8>)` with decompressed data.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KDecompressionError::SizeLimitExceeded]`)` if decompressed data would be larger than `max_size`.
+    ///
+    /// Returns `Err(`[KDecompressionError::RatioLimitExceeded]`)` if decompressed data would be larger than `max_ratio` times `data`.
+    ///
+    /// Returns `Err(`[KDecompressionError::Corrupted]`)` if data is corrupted or truncated.
+    pub fn decompress(&self, data : &[u8], format : KAssetCompression) -> Result<Vec<u8>, KDecompressionError> {
+        let ratio_limit = (data.len() as u64).saturating_mul(self.max_ratio);
+        let limit = usize::try_from(self.max_size.min(ratio_limit)).unwrap_or(usize::MAX);
+
+        let mut output = Vec::new();
+        let result = match format {
+            KAssetCompression::Gzip => gunzip(data, &mut output, limit),
+            KAssetCompression::Zstd => zstd::decompress(data, &mut output, limit),
+        };
+
+        match result {
+            Ok(_) => Ok(output),
+            Err(err) if is_limit_exceeded(&err) => match ratio_limit < self.max_size {
+                true => Err(KDecompressionError::RatioLimitExceeded),
+                false => Err(KDecompressionError::SizeLimitExceeded),
+            },
+            Err(_) => Err(KDecompressionError::Corrupted),
+        }
+    }
+
+    /// Get path and format of a compressed asset of source for an asset it doesn't have, or `None` if source has none.
+    pub(crate) fn find_asset(&self, src : &KAssetBrokerSource, path : &KAssetPath) -> Option<(KAssetPath, KAssetCompression)> {
+        KASSET_COMPRESSIONS.into_iter().find_map(|format| {
+            let compressed = KAssetPath::new(&format!("{}.{}", path, format.get_extension())).ok()?;
+            src.source.has_asset(&compressed).then_some((compressed, format))
+        })
+    }
+}
+
+impl KAssetBrokerSource {
+    /// Get asset at local path decompressed from a compressed asset of source, or `None` if source has none.
+    ///
+    /// Compressed asset is read verified against integrity manifest if any.
+    pub(crate) fn get_decompressed_asset(&self, path : &KAssetPath, decompression : &KAssetDecompression) -> Result<Option<Arc<[u8]>>, std::io::Error> {
+        match decompression.find_asset(self, path) {
+            Some((compressed, format)) => Ok(Some(self.read_decompressed(&compressed, format, decompression)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Get [KAssetMetadata] of asset at local path decompressed from a compressed asset of source, or `None` if source has none.
+    ///
+    /// Size is known by decompressing asset, and CRC-32 isn't known.
+    pub(crate) fn get_decompressed_metadata(&self, path : &KAssetPath, decompression : &KAssetDecompression) -> Result<Option<KAssetMetadata>, std::io::Error> {
+        let (compressed, format) = match decompression.find_asset(self, path) {
+            Some(found) => found,
+            None => return Ok(None),
+        };
+
+        let modified = self.source.get_asset_metadata(&compressed)?.modified;
+        let data = self.read_decompressed(&compressed, format, decompression)?;
+
+        Ok(Some(KAssetMetadata { size : data.len() as u64, modified, hash : None }))
+    }
+
+    /// Read compressed asset at local path entirely and decompress it.
+    ///
+    /// Compressed asset is read up to `max_size`, since a compressed asset larger than that is rejected anyway.
+    fn read_decompressed(&self, compressed : &KAssetPath, format : KAssetCompression, decompression : &KAssetDecompression) -> Result<Arc<[u8]>, std::io::Error> {
+        let mut data = Vec::new();
+        self.get_asset(compressed)?.take(decompression.max_size.saturating_add(1)).read_to_end(&mut data)?;
+
+        if data.len() as u64 > decompression.max_size {
+            return Err(KDecompressionError::SizeLimitExceeded.into());
+        }
+
+        Ok(decompression.decompress(&data, format)?.into())
+    }
+}
+
+impl KAssetBroker {
+    /// Set [KAssetDecompression] of assets stored compressed, or stop decompressing assets with `None`.
+    ///
+    /// Cache is cleared so assets decompressed before aren't served anymore.
+    ///
+    /// # Example(s)
+    /// ```
+    /// use olympus_kleio::asset::{KAssetBroker, KAssetDecompression};
+    ///
+    /// let broker = KAssetBroker::new();
+    /// broker.set_decompression(Some(KAssetDecompression { max_size : 64 * 1024 * 1024, max_ratio : 50 }));
+    /// assert_eq!(broker.get_decompression().unwrap().max_ratio, 50);
+    /// ```
+    pub fn set_decompression(&self, decompression : Option<KAssetDecompression>) {
+        *self.write_decompression() = decompression;
+
+        if let Some(cache) = self.get_cache() {
+            cache.clear();
+        }
+    }
+
+    /// Get [KAssetDecompression] of assets stored compressed, if any.
+    pub fn get_decompression(&self) -> Option<KAssetDecompression> {
+        *self.read_decompression()
+    }
+}
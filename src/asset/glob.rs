@@ -0,0 +1,93 @@
+// Contains glob pattern matching of asset paths
+
+/// Verify if asset `path` using `/` as separator matches glob `pattern`.
+///
+/// Pattern supports :
+/// * `*` matching any characters except `/`.
+/// * `**` matching any characters including `/`. `**/` also matches no folder.
+/// * `?` matching any character except `/`.
+/// * `[abc]`, `[a-z]` and `[!abc]` matching a character of (or not of) a set, never `/`.
+///
+/// Any other character, including an unclosed `[`, matches itself.
+pub(crate) fn glob_match(pattern : &str, path : &str) -> bool {
+    let pattern : Vec<char> = pattern.chars().collect();
+    let path : Vec<char> = path.chars().collect();
+
+    match_from(&pattern, &path)
+}
+
+/// Get the leading folder of `pattern` without any wildcard, using `/` as separator.
+///
+/// Returns an empty string if first folder has a wildcard.
+pub(crate) fn glob_folder(pattern : &str) -> &str {
+    let wildcard = pattern.find(['*', '?', '[']).unwrap_or(pattern.len());
+
+    match pattern[..wildcard].rfind('/') {
+        Some(end) => &pattern[..end],
+        None => "",
+    }
+}
+
+/// Match remaining pattern against remaining path.
+fn match_from(pattern : &[char], path : &[char]) -> bool {
+    match pattern.first() {
+        None => path.is_empty(),
+        Some('*') if pattern.get(1) == Some(&'*') => {
+            // `**/` also matches no folder
+            let rest = &pattern[2..];
+            if rest.first() == Some(&'/') && match_from(&rest[1..], path) {
+                return true;
+            }
+            (0..=path.len()).any(|skip| match_from(rest, &path[skip..]))
+        },
+        Some('*') => {
+            let rest = &pattern[1..];
+            for skip in 0..=path.len() {
+                if match_from(rest, &path[skip..]) {
+                    return true;
+                }
+                if path.get(skip) == Some(&'/') {
+                    break;
+                }
+            }
+            false
+        },
+        Some('?') => matches!(path.first(), Some(c) if *c != '/') && match_from(&pattern[1..], &path[1..]),
+        Some('[') => match match_set(&pattern[1..], path.first()) {
+            Some((matched, len)) => matched && match_from(&pattern[1 + len..], &path[1..]),
+            None => path.first() == Some(&'[') && match_from(&pattern[1..], &path[1..]),
+        },
+        Some(c) => path.first() == Some(c) && match_from(&pattern[1..], &path[1..]),
+    }
+}
+
+/// Match character against set following `[`.
+///
+/// Returns `Some((matched, length))` with length of set including `]`, or `None` if set is not closed.
+fn match_set(set : &[char], c : Option<&char>) -> Option<(bool, usize)> {
+    let negated = set.first() == Some(&'!');
+    let start = if negated { 1 } else { 0 };
+
+    // `]` right after `[` or `[!` is part of set
+    let end = start + 1 + set.get(start + 1..)?.iter().position(|x| *x == ']')?;
+    let items = &set[start..end];
+
+    let c = match c {
+        Some(c) if *c != '/' => *c,
+        _ => return Some((false, end + 1)),
+    };
+
+    let mut matched = false;
+    let mut i = 0;
+    while i < items.len() {
+        if i + 2 < items.len() && items[i + 1] == '-' {
+            matched |= items[i] <= c && c <= items[i + 2];
+            i += 3;
+        } else {
+            matched |= items[i] == c;
+            i += 1;
+        }
+    }
+
+    Some((matched != negated, end + 1))
+}
@@ -0,0 +1,499 @@
+use std::{io::{Read, Cursor, ErrorKind}, collections::BTreeMap, sync::Arc};
+use super::{KAssetBroker, KAssetBrokerError, KAssetSource, KAssetSourceId, KAssetSeekReader, KAssetPath, KPublicKey, KSigningKey};
+use super::codec::sha256::KSha256;
+use super::archive::get_capacity;
+use super::trust::{to_hex, parse_hex};
+
+/// Path of the integrity manifest of a source, ignored when generating and verifying manifests.
+pub const KINTEGRITY_MANIFEST_PATH : &str = "integrity.manifest";
+
+/// Size and SHA-256 of an asset listed in a [KIntegrityManifest].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KIntegrityEntry {
+    /// Size of asset in bytes.
+    pub size : u64,
+
+    /// SHA-256 of asset content.
+    pub hash : [u8; 32],
+}
+
+/// ##### Manifest listing size and SHA-256 of every asset of a source, to detect corrupted or tampered assets.
+///
+/// Manifests are generated from a listable source with [KIntegrityManifest::generate], like a [KAssetSourceFolder](super::KAssetSourceFolder)
+/// or a [KAssetSourcePak](super::KAssetSourcePak) before shipping, and written with [Display](std::fmt::Display).
+/// They are text made of one `hash size path` line per asset sorted by path, with hash as lowercase hexadecimal.
+/// Empty lines and lines starting with `#` are ignored.
+///
+/// Manifests written with [KIntegrityManifest::to_signed_string] end with a `signature key signature` line,
+/// an Ed25519 signature of all text before it verified when parsed. Signed manifests vouch for their source
+/// with a [KTrustPolicy](super::KTrustPolicy).
+///
+/// A source can be verified as a whole with [KIntegrityManifest::verify], or lazily by the broker with
+/// [KAssetBroker::set_source_integrity] so each asset is verified when opened, before any byte is served.
+///
+/// # Example(s)
+/// ```
+/// use olympus_kleio::asset::{KAssetSourceMemory, KIntegrityManifest, KIntegrityError};
+///
+/// let source = KAssetSourceMemory::new("patch");
+/// source.add_asset("textures/wall.png", vec![1, 2, 3]).unwrap();
+///
+/// // Generate manifest before shipping, then parse it back
+/// let manifest = KIntegrityManifest::generate(&source).unwrap();
+/// let manifest = KIntegrityManifest::parse(&manifest.to_string()).unwrap();
+/// assert!(manifest.verify(&source).is_valid());
+///
+/// // Corrupted download
+/// source.add_asset("textures/wall.png", vec![1, 2, 4]).unwrap();
+/// assert_eq!(manifest.verify(&source).issues[0].1, KIntegrityError::HashMismatch);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KIntegrityManifest {
+    // Entries of assets indexed by path
+    entries : BTreeMap<KAssetPath, KIntegrityEntry>,
+
+    // Key that signed manifest parsed, if any
+    signer : Option<KPublicKey>,
+}
+
+/// Enumeration of possible [KIntegrityManifest] parsing errors.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum KIntegrityManifestError {
+    /// Happens when source has no manifest.
+    ManifestNotFound,
+
+    /// Happens when manifest couldn't be read or isn't valid UTF-8.
+    ReadError,
+
+    /// Happens when a line isn't a `hash size path` entry.
+    InvalidLine,
+
+    /// Happens when a path is invalid, the root or listed twice.
+    InvalidPath,
+
+    /// Happens when signature line is invalid or doesn't match manifest content.
+    InvalidSignature,
+}
+
+impl std::fmt::Debug for KIntegrityManifestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ManifestNotFound => write!(f, "ManifestNotFound"),
+            Self::ReadError => write!(f, "ReadError"),
+            Self::InvalidLine => write!(f, "InvalidLine"),
+            Self::InvalidPath => write!(f, "InvalidPath"),
+            Self::InvalidSignature => write!(f, "InvalidSignature"),
+        }
+    }
+}
+
+/// Enumeration of integrity errors of an asset verified against a [KIntegrityManifest].
+///
+/// Assets read through a [KAssetBroker] verifying its source fail with an [std::io::Error] of kind
+/// [InvalidData](ErrorKind::InvalidData) wrapping KIntegrityError, given by [KIntegrityError::from_io_error].
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum KIntegrityError {
+    /// Happens when asset of source isn't listed in manifest.
+    NotListed,
+
+    /// Happens when asset listed in manifest isn't in source.
+    Missing,
+
+    /// Happens when asset size differs from manifest.
+    SizeMismatch,
+
+    /// Happens when asset SHA-256 differs from manifest.
+    HashMismatch,
+
+    /// Happens when asset couldn't be read to be verified.
+    ReadError,
+}
+
+impl std::fmt::Debug for KIntegrityError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotListed => write!(f, "NotListed"),
+            Self::Missing => write!(f, "Missing"),
+            Self::SizeMismatch => write!(f, "SizeMismatch"),
+            Self::HashMismatch => write!(f, "HashMismatch"),
+            Self::ReadError => write!(f, "ReadError"),
+        }
+    }
+}
+
+impl std::fmt::Display for KIntegrityError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotListed => write!(f, "Asset is not listed in integrity manifest!"),
+            Self::Missing => write!(f, "Asset listed in integrity manifest is missing!"),
+            Self::SizeMismatch => write!(f, "Asset size doesn't match integrity manifest!"),
+            Self::HashMismatch => write!(f, "Asset hash doesn't match integrity manifest!"),
+            Self::ReadError => write!(f, "Asset couldn't be read to verify integrity!"),
+        }
+    }
+}
+
+impl std::error::Error for KIntegrityError {}
+
+impl From<KIntegrityError> for std::io::Error {
+    /// Integrity errors are IO errors of kind [InvalidData](ErrorKind::InvalidData) wrapping [KIntegrityError].
+    fn from(err: KIntegrityError) -> Self {
+        std::io::Error::new(ErrorKind::InvalidData, err)
+    }
+}
+
+impl KIntegrityError {
+    /// Get [KIntegrityError] wrapped by an [std::io::Error], or `None` if error isn't an integrity error.
+    pub fn from_io_error(err : &std::io::Error) -> Option<KIntegrityError> {
+        err.get_ref().and_then(|inner| inner.downcast_ref::<KIntegrityError>()).copied()
+    }
+}
+
+/// ##### Result of a whole source verified with [KIntegrityManifest::verify].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KIntegrityReport {
+    /// Count of assets listed in manifest found intact.
+    pub verified : usize,
+
+    /// Assets failing verification with their error, sorted by path.
+    pub issues : Vec<(KAssetPath, KIntegrityError)>,
+}
+
+impl KIntegrityReport {
+    /// Verify that no asset failed verification.
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl KIntegrityManifest {
+    /// Create an empty manifest.
+    pub fn new() -> KIntegrityManifest {
+        KIntegrityManifest { entries : BTreeMap::new(), signer : None }
+    }
+
+    /// Generate manifest of all assets of a listable `source`, reading each asset to hash it.
+    ///
+    /// [KINTEGRITY_MANIFEST_PATH] isn't listed.
+    ///
+    /// # Error(s)
+    /// Returns [std::io::Error] if source can't be listed or an asset can't be read.
+    pub fn generate(source : &dyn KAssetSource) -> Result<KIntegrityManifest, std::io::Error> {
+        let mut manifest = KIntegrityManifest::new();
+
+        for path in source.list_assets(&KAssetPath::root())? {
+            if path != KINTEGRITY_MANIFEST_PATH {
+                let entry = hash_reader(source.get_asset(&path)?)?;
+                manifest.entries.insert(path, entry);
+            }
+        }
+
+        Ok(manifest)
+    }
+
+    /// Parse a manifest from its text content, verifying its signature line if any.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KIntegrityManifestError::InvalidSignature]`)` if signature line is invalid or doesn't match content.
+    ///
+    /// Returns `Err(`[KIntegrityManifestError::InvalidLine]`)` if a line isn't a `hash size path` entry.
+    ///
+    /// Returns `Err(`[KIntegrityManifestError::InvalidPath]`)` if a path is invalid, the root or listed twice.
+    pub fn parse(content : &str) -> Result<KIntegrityManifest, KIntegrityManifestError> {
+        let mut manifest = KIntegrityManifest::new();
+
+        // Signature is the last line and signs all text before it
+        let signed_end = content.trim_end().rfind('\n').map_or(0, |end| end + 1);
+        let content = match content[signed_end..].trim().strip_prefix("signature ") {
+            Some(signature) => {
+                let (key, signature) = match signature.split_once(' ') {
+                    Some((key, signature)) => (KPublicKey::from_hex(key), parse_hex::<64>(signature)),
+                    None => (None, None),
+                };
+
+                match (key, signature) {
+                    (Some(key), Some(signature)) if key.verify(&content.as_bytes()[..signed_end], &signature) => manifest.signer = Some(key),
+                    _ => return Err(KIntegrityManifestError::InvalidSignature),
+                }
+
+                &content[..signed_end]
+            },
+            None => content,
+        };
+
+        for line in content.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')) {
+            // Path is last since it may contain spaces
+            let mut fields = line.splitn(3, ' ');
+            let (hash, size, path) = match (fields.next(), fields.next(), fields.next()) {
+                (Some(hash), Some(size), Some(path)) => (hash, size, path),
+                _ => return Err(KIntegrityManifestError::InvalidLine),
+            };
+
+            let hash = parse_hex(hash).ok_or(KIntegrityManifestError::InvalidLine)?;
+            let size = match size.chars().all(|c| c.is_ascii_digit()) {
+                true => size.parse().map_err(|_| KIntegrityManifestError::InvalidLine)?,
+                false => return Err(KIntegrityManifestError::InvalidLine),
+            };
+
+            match KAssetPath::new(path) {
+                Ok(path) if !path.is_root() && !manifest.entries.contains_key(&path) => { manifest.entries.insert(path, KIntegrityEntry { size, hash }); },
+                _ => return Err(KIntegrityManifestError::InvalidPath),
+            }
+        }
+
+        Ok(manifest)
+    }
+
+    /// Read and parse manifest [KINTEGRITY_MANIFEST_PATH] of a source.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KIntegrityManifestError::ManifestNotFound]`)` if source has no manifest.
+    ///
+    /// Returns `Err(`[KIntegrityManifestError::ReadError]`)` if manifest couldn't be read.
+    ///
+    /// Returns errors of [KIntegrityManifest::parse].
+    pub fn from_source(source : &dyn KAssetSource) -> Result<KIntegrityManifest, KIntegrityManifestError> {
+        let path = KAssetPath::new(KINTEGRITY_MANIFEST_PATH).map_err(|_| KIntegrityManifestError::ManifestNotFound)?;
+        if !source.has_asset(&path) {
+            return Err(KIntegrityManifestError::ManifestNotFound);
+        }
+
+        let mut content = String::new();
+        source.get_asset(&path).and_then(|mut reader| reader.read_to_string(&mut content))
+            .map_err(|_| KIntegrityManifestError::ReadError)?;
+
+        Self::parse(&content)
+    }
+
+    /// List or replace entry of asset at `path`. Manifest isn't signed anymore.
+    pub fn insert(&mut self, path : KAssetPath, entry : KIntegrityEntry) {
+        self.signer = None;
+        self.entries.insert(path, entry);
+    }
+
+    /// Remove entry of asset at `path`. Returns entry removed, if any. Manifest isn't signed anymore.
+    pub fn remove(&mut self, path : &KAssetPath) -> Option<KIntegrityEntry> {
+        self.signer = None;
+        self.entries.remove(path)
+    }
+
+    /// Get the [KPublicKey] that signed manifest parsed, or `None` if manifest isn't signed.
+    pub fn get_signer(&self) -> Option<KPublicKey> {
+        self.signer
+    }
+
+    /// Write manifest as text like [Display](std::fmt::Display), followed by a signature line of all text made with `key`.
+    ///
+    /// Parsing the text gives a manifest signed by the public key of `key`.
+    pub fn to_signed_string(&self, key : &KSigningKey) -> String {
+        let mut content = self.to_string();
+        let signature = key.sign(content.as_bytes());
+        content.push_str(&format!("signature {} {}\n", key.get_public_key(), to_hex(&signature)));
+        content
+    }
+
+    /// Get entry of asset at `path`, or `None` if not listed.
+    pub fn get_entry(&self, path : &KAssetPath) -> Option<&KIntegrityEntry> {
+        self.entries.get(path)
+    }
+
+    /// Get entries of manifest sorted by path.
+    pub fn get_entries(&self) -> impl Iterator<Item = (&KAssetPath, &KIntegrityEntry)> {
+        self.entries.iter()
+    }
+
+    /// Get the count of assets listed.
+    pub fn get_entry_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Verify all assets of `source` against manifest, reading each asset listed.
+    ///
+    /// Assets listed but not in source are [Missing](KIntegrityError::Missing). If source can be listed,
+    /// its assets not listed are [NotListed](KIntegrityError::NotListed).
+    pub fn verify(&self, source : &dyn KAssetSource) -> KIntegrityReport {
+        let mut report = KIntegrityReport::default();
+
+        for path in self.entries.keys() {
+            match self.verify_asset(source, path) {
+                Ok(()) => report.verified += 1,
+                Err(err) => report.issues.push((path.clone(), err)),
+            }
+        }
+
+        if let Ok(assets) = source.list_assets(&KAssetPath::root()) {
+            for path in assets.into_iter().filter(|path| *path != KINTEGRITY_MANIFEST_PATH && !self.entries.contains_key(path)) {
+                report.issues.push((path, KIntegrityError::NotListed));
+            }
+        }
+
+        report.issues.sort_by(|a, b| a.0.cmp(&b.0));
+        report
+    }
+
+    /// Verify asset at `path` of `source` against manifest, reading it.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KIntegrityError::NotListed]`)` if asset isn't listed.
+    ///
+    /// Returns `Err(`[KIntegrityError::Missing]`)` if asset isn't in source.
+    ///
+    /// Returns `Err(`[KIntegrityError::SizeMismatch]`)` or `Err(`[KIntegrityError::HashMismatch]`)` if asset differs.
+    ///
+    /// Returns `Err(`[KIntegrityError::ReadError]`)` if asset couldn't be read.
+    pub fn verify_asset(&self, source : &dyn KAssetSource, path : &KAssetPath) -> Result<(), KIntegrityError> {
+        let expected = self.entries.get(path).ok_or(KIntegrityError::NotListed)?;
+
+        if !source.has_asset(path) {
+            return Err(KIntegrityError::Missing);
+        }
+
+        let entry = source.get_asset(path).and_then(hash_reader).map_err(|_| KIntegrityError::ReadError)?;
+        compare_entry(expected, &entry)
+    }
+
+    /// Verify `data` of asset at `path` against manifest.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KIntegrityError::NotListed]`)` if asset isn't listed.
+    ///
+    /// Returns `Err(`[KIntegrityError::SizeMismatch]`)` or `Err(`[KIntegrityError::HashMismatch]`)` if data differs.
+    pub(crate) fn verify_bytes(&self, path : &KAssetPath, data : &[u8]) -> Result<(), KIntegrityError> {
+        let expected = self.entries.get(path).ok_or(KIntegrityError::NotListed)?;
+
+        let mut hash = KSha256::new();
+        hash.update(data);
+        compare_entry(expected, &KIntegrityEntry { size : data.len() as u64, hash : hash.finish() })
+    }
+
+    /// Read `reader` of asset at `path` entirely and verify it against manifest, so nothing is served before being verified.
+    ///
+    /// Returns `Ok(Vec<u8>)` with verified data of asset.
+    ///
+    /// # Error(s)
+    /// Returns [std::io::Error] wrapping [KIntegrityError::NotListed] if asset isn't listed.
+    ///
+    /// Returns [std::io::Error] wrapping [KIntegrityError::SizeMismatch] or [KIntegrityError::HashMismatch] if asset differs,
+    /// without reading past expected size.
+    ///
+    /// Returns [std::io::Error] if asset couldn't be read.
+    pub(crate) fn read_verified(&self, path : &KAssetPath, reader : Box<dyn Read>) -> Result<Vec<u8>, std::io::Error> {
+        let expected = self.entries.get(path).ok_or(KIntegrityError::NotListed)?;
+
+        let mut data = Vec::with_capacity(get_capacity(expected.size));
+        reader.take(expected.size.saturating_add(1)).read_to_end(&mut data)?;
+        self.verify_bytes(path, &data)?;
+
+        Ok(data)
+    }
+}
+
+impl std::fmt::Display for KIntegrityManifest {
+    /// Write manifest as text without signature, parsable with [KIntegrityManifest::parse].
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "# Kleio integrity manifest")?;
+
+        for (path, entry) in &self.entries {
+            writeln!(f, "{} {} {}", to_hex(&entry.hash), entry.size, path)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl KAssetBroker {
+    /// Set the [KIntegrityManifest] verifying assets of the [KAssetSource] identified by `id`, or `None` to stop verifying.
+    ///
+    /// Assets of source are then verified when opened : every read of a verified source reads the whole asset in memory
+    /// and verifies it before serving any byte, so [KAssetBroker::get_asset] fails with an [std::io::Error] wrapping a
+    /// [KIntegrityError] for a corrupted or unlisted asset. Cached assets of source are invalidated.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KAssetBrokerError::SourceNotFound]`)` if [KAssetSource] is not found.
+    ///
+    /// # Example(s)
+    /// ```
+    /// use std::{sync::Arc, io::Read};
+    /// use olympus_kleio::asset::{KAssetBroker, KAssetSourceMemory, KIntegrityManifest, KIntegrityError};
+    ///
+    /// let patch = Arc::new(KAssetSourceMemory::new("patch"));
+    /// patch.add_asset("config.txt", "speed = 1".as_bytes().to_vec()).unwrap();
+    /// let manifest = KIntegrityManifest::generate(patch.as_ref()).unwrap();
+    ///
+    /// let broker = KAssetBroker::new();
+    /// let id = broker.add_source(patch.clone()).unwrap();
+    /// broker.set_source_integrity(id, Some(Arc::new(manifest))).unwrap();
+    ///
+    /// // Corrupted asset fails when opened
+    /// patch.add_asset("config.txt", "speed = 9".as_bytes().to_vec()).unwrap();
+    /// let err = broker.get_asset("config.txt").err().unwrap();
+    /// assert_eq!(KIntegrityError::from_io_error(&err), Some(KIntegrityError::HashMismatch));
+    /// ```
+    pub fn set_source_integrity(&self, id : KAssetSourceId, manifest : Option<Arc<KIntegrityManifest>>) -> Result<(), KAssetBrokerError> {
+        let mut sources = self.write_sources();
+
+        match sources.iter_mut().find(|src| src.id == id) {
+            Some(src) => {
+                src.integrity = manifest;
+
+                if let Some(cache) = self.get_cache() {
+                    cache.invalidate_source(id);
+                }
+
+                Ok(())
+            },
+            None => Err(KAssetBrokerError::SourceNotFound),
+        }
+    }
+
+    /// Get the [KIntegrityManifest] verifying assets of the [KAssetSource] identified by `id`, if any.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KAssetBrokerError::SourceNotFound]`)` if [KAssetSource] is not found.
+    pub fn get_source_integrity(&self, id : KAssetSourceId) -> Result<Option<Arc<KIntegrityManifest>>, KAssetBrokerError> {
+        match self.get_all_sources_snapshot().into_iter().find(|src| src.id == id) {
+            Some(src) => Ok(src.integrity),
+            None => Err(KAssetBrokerError::SourceNotFound),
+        }
+    }
+}
+
+/// Open asset at `path` of `source` verified against `manifest`, read in memory to be seekable.
+pub(crate) fn get_verified_seekable(source : &dyn KAssetSource, manifest : &KIntegrityManifest, path : &KAssetPath) -> Result<KAssetSeekReader, std::io::Error> {
+    let data = manifest.read_verified(path, source.get_asset(path)?)?;
+
+    let length = data.len() as u64;
+    Ok(KAssetSeekReader::new(Box::new(Cursor::new(data)), length))
+}
+
+/// Read `reader` to its end and return its size and SHA-256.
+fn hash_reader(mut reader : Box<dyn Read>) -> Result<KIntegrityEntry, std::io::Error> {
+    let mut hash = KSha256::new();
+    let mut buffer = [0u8; 8192];
+    let mut size = 0;
+
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+
+        hash.update(&buffer[..read]);
+        size += read as u64;
+    }
+
+    Ok(KIntegrityEntry { size, hash : hash.finish() })
+}
+
+/// Compare `entry` of asset read with `expected` entry of manifest.
+fn compare_entry(expected : &KIntegrityEntry, entry : &KIntegrityEntry) -> Result<(), KIntegrityError> {
+    if entry.size != expected.size {
+        Err(KIntegrityError::SizeMismatch)
+    } else if entry.hash != expected.hash {
+        Err(KIntegrityError::HashMismatch)
+    } else {
+        Ok(())
+    }
+}
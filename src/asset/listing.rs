@@ -0,0 +1,100 @@
+use std::{io::ErrorKind, collections::BTreeMap};
+
+use super::{KAssetBroker, KAssetSourceId, KAssetPath};
+use super::glob::{glob_match, glob_folder};
+
+/// Asset of the merged listing given by [KAssetBroker::list_assets].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KAssetListEntry {
+    /// Path of asset within broker.
+    pub path : KAssetPath,
+
+    /// Identifier of source with highest priority having the asset, used by [KAssetBroker::get_asset].
+    pub source : KAssetSourceId,
+
+    /// Identifiers of sources of lower priority also having the asset, ordered by priority.
+    pub overridden : Vec<KAssetSourceId>,
+}
+
+impl KAssetBroker {
+    /// List assets of all sources matching glob `pattern`, merged and sorted by path.
+    ///
+    /// Pattern supports `*` (any characters except `/`), `**` (any characters including `/`), `?` (any character
+    /// except `/`) and character sets like `[abc]`, `[a-z]` or `[!abc]`. Paths use `/` as separator.
+    ///
+    /// Sources that can't list their assets are ignored. Assets of mounted sources are listed under their mount point.
+    ///
+    /// Returns `Ok(`[Vec]`)` of [KAssetListEntry] telling which source wins for each asset.
+    ///
+    /// # Error(s)
+    /// Returns [std::io::Error] of kind [InvalidInput](ErrorKind::InvalidInput) if folder of pattern is an invalid [KAssetPath].
+    ///
+    /// Returns [std::io::Error] if a source couldn't list its assets.
+    ///
+    /// # Example(s)
+    /// ```
+    /// // Import crate module
+    /// use std::{path::PathBuf, sync::Arc};
+    /// use olympus_kleio::asset::{KAssetBroker, KAssetSourceMemory};
+    ///
+    /// // Mod overrides level 1 of base
+    /// let base = KAssetSourceMemory::new("base");
+    /// base.add_asset(PathBuf::from("levels/1.lvl"), vec![1]).unwrap();
+    /// base.add_asset(PathBuf::from("levels/2.lvl"), vec![2]).unwrap();
+    /// base.add_asset(PathBuf::from("lang/en.lang"), vec![3]).unwrap();
+    /// let mod1 = KAssetSourceMemory::new("mod");
+    /// mod1.add_asset(PathBuf::from("levels/1.lvl"), vec![4]).unwrap();
+    ///
+    /// let broker = KAssetBroker::new();
+    /// let mod_id = broker.add_source(Arc::new(mod1)).unwrap();
+    /// let base_id = broker.add_source(Arc::new(base)).unwrap();
+    ///
+    /// // List all levels
+    /// let levels = broker.list_assets("levels/*.lvl").unwrap();
+    /// assert_eq!(levels.len(), 2);
+    /// assert_eq!((levels[0].source, &levels[0].overridden), (mod_id, &vec![base_id]));
+    /// assert_eq!(levels[1].source, base_id);
+    ///
+    /// // List every .lang file
+    /// assert_eq!(broker.list_assets("**/*.lang").unwrap()[0].path, "lang/en.lang");
+    /// ```
+    pub fn list_assets(&self, pattern : &str) -> Result<Vec<KAssetListEntry>, std::io::Error> {
+        let folder = KAssetPath::new(glob_folder(pattern))?;
+        let mut entries : BTreeMap<KAssetPath, KAssetListEntry> = BTreeMap::new();
+
+        for src in self.get_sources_snapshot() {
+            // Sources mounted outside of folder have nothing to list
+            let local = match src.get_local_folder(&folder) {
+                Some(local) => local,
+                None => continue,
+            };
+
+            let paths = match src.source.list_assets(&local) {
+                Ok(paths) => paths,
+                Err(err) if err.kind() == ErrorKind::Unsupported => continue,
+                Err(err) => return Err(err),
+            };
+
+            let id = src.id;
+            for path in paths.iter().map(|path| src.get_broker_path(path)) {
+                if !glob_match(pattern, path.as_str()) {
+                    continue;
+                }
+
+                match entries.get_mut(&path) {
+                    Some(entry) => {
+                        // Sources may list a path twice
+                        if entry.source != id && !entry.overridden.contains(&id) {
+                            entry.overridden.push(id);
+                        }
+                    },
+                    None => {
+                        entries.insert(path.clone(), KAssetListEntry { path, source : id, overridden : Vec::new() });
+                    },
+                }
+            }
+        }
+
+        Ok(entries.into_values().collect())
+    }
+}
@@ -0,0 +1,213 @@
+use std::{any::{Any, TypeId}, collections::HashMap, io::{Read, ErrorKind}, sync::Arc};
+
+use super::{KAssetBroker, KAssetPath, KIntoAssetPath};
+
+/// ##### Loader turning an asset [Read] handle into a typed value.
+///
+/// Loaders are registered in [KAssetBroker] with [KAssetBroker::register_loader] for file extensions
+/// and for their [KAssetLoader::Asset] type, then used by [KAssetBroker::load].
+///
+/// # Example(s)
+/// ```
+/// // Import crate module
+/// use std::{path::PathBuf, io::Read, sync::Arc};
+/// use olympus_kleio::asset::{KAssetBroker, KAssetSourceMemory, KAssetLoader, KAssetLoaderError, KAssetPath};
+///
+/// // Language file made of `key=value` lines
+/// struct Lang(Vec<(String, String)>);
+///
+/// struct LangLoader;
+///
+/// impl KAssetLoader for LangLoader {
+///     type Asset = Lang;
+///
+///     fn load(&self, _path : &KAssetPath, reader : &mut dyn Read) -> Result<Lang, KAssetLoaderError> {
+///         let mut content = String::new();
+///         reader.read_to_string(&mut content).map_err(|_| KAssetLoaderError::ReadError)?;
+///
+///         content.lines().map(|line| match line.split_once('=') {
+///             Some((key, value)) => Ok((key.to_owned(), value.to_owned())),
+///             None => Err(KAssetLoaderError::ParseError),
+///         }).collect::<Result<Vec<_>, _>>().map(Lang)
+///     }
+/// }
+///
+/// let source = KAssetSourceMemory::new("lang");
+/// source.add_asset(PathBuf::from("en.lang"), "hello=Hello".as_bytes().to_vec()).unwrap();
+///
+/// let kab = KAssetBroker::new();
+/// kab.add_source(Arc::new(source)).unwrap();
+/// kab.register_loader(&["lang"], LangLoader);
+///
+/// // Load typed asset
+/// let lang : Lang = kab.load("en.lang").unwrap();
+/// assert_eq!(lang.0[0].1, "Hello");
+/// ```
+pub trait KAssetLoader : Send + Sync {
+    /// Type of value loaded.
+    type Asset : Any + Send + Sync;
+
+    /// Load asset of `path` from `reader`.
+    ///
+    /// Returns `Ok(`[KAssetLoader::Asset]`)` if loaded.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KAssetLoaderError::ReadError]`)` if reader failed.
+    ///
+    /// Returns `Err(`[KAssetLoaderError::ParseError]`)` if asset content is invalid.
+    fn load(&self, path : &KAssetPath, reader : &mut dyn Read) -> Result<Self::Asset, KAssetLoaderError>;
+
+    /// Get memory size of asset in bytes, reported by [KAssetStorage](super::KAssetStorage).
+    ///
+    /// Returns the size of [KAssetLoader::Asset] by default, without its heap allocations.
+    fn get_memory_size(&self, asset : &Self::Asset) -> usize {
+        std::mem::size_of_val(asset)
+    }
+}
+
+/// Enumeration of possible errors of [KAssetBroker::load].
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum KAssetLoaderError {
+    /// Happens when asset is not found in sources.
+    AssetNotFound,
+
+    /// Happens when an IO error occurred while reading asset.
+    ReadError,
+
+    /// Happens when loader couldn't parse asset content.
+    ParseError,
+
+    /// Happens when no loader of requested type is registered for asset extension or type.
+    LoaderNotFound,
+
+    /// Happens when asset path is an invalid [KAssetPath].
+    InvalidPath,
+}
+
+impl std::fmt::Debug for KAssetLoaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AssetNotFound => write!(f, "AssetNotFound"),
+            Self::ReadError => write!(f, "ReadError"),
+            Self::ParseError => write!(f, "ParseError"),
+            Self::LoaderNotFound => write!(f, "LoaderNotFound"),
+            Self::InvalidPath => write!(f, "InvalidPath"),
+        }
+    }
+}
+
+/// [KAssetLoader] with asset type erased so loaders of different types share the registry.
+trait KAssetLoaderErased : Send + Sync {
+    /// Get [TypeId] of asset loaded.
+    fn get_asset_type(&self) -> TypeId;
+
+    /// Load asset as boxed [Any] with its memory size.
+    fn load_any(&self, path : &KAssetPath, reader : &mut dyn Read) -> Result<(Box<dyn Any + Send + Sync>, usize), KAssetLoaderError>;
+}
+
+impl<L : KAssetLoader> KAssetLoaderErased for L {
+    fn get_asset_type(&self) -> TypeId {
+        TypeId::of::<L::Asset>()
+    }
+
+    fn load_any(&self, path : &KAssetPath, reader : &mut dyn Read) -> Result<(Box<dyn Any + Send + Sync>, usize), KAssetLoaderError> {
+        let asset = self.load(path, reader)?;
+        let size = self.get_memory_size(&asset);
+
+        Ok((Box::new(asset), size))
+    }
+}
+
+/// Registry of loaders of a [KAssetBroker] by extension and asset type.
+#[derive(Default)]
+pub(crate) struct KAssetLoaderRegistry {
+    // Loaders by lowercase extension, last registered first
+    extensions : HashMap<String, Vec<Arc<dyn KAssetLoaderErased>>>,
+
+    // Last loader registered by asset type
+    types : HashMap<TypeId, Arc<dyn KAssetLoaderErased>>,
+}
+
+impl KAssetLoaderRegistry {
+    /// Find loader of asset type for path, by extension first, then by type.
+    fn find(&self, path : &KAssetPath, asset_type : TypeId) -> Option<Arc<dyn KAssetLoaderErased>> {
+        let by_extension = path.get_extension().and_then(|extension| self.extensions.get(&extension.to_lowercase()))
+            .and_then(|loaders| loaders.iter().find(|loader| loader.get_asset_type() == asset_type));
+
+        by_extension.or_else(|| self.types.get(&asset_type)).cloned()
+    }
+}
+
+impl KAssetBroker {
+    /// Register a [KAssetLoader] for file `extensions` (without dot, case-insensitive) and for its asset type.
+    ///
+    /// An extension can have loaders of different asset types. For the same extension and asset type,
+    /// or the same asset type, the last loader registered is used.
+    pub fn register_loader<L : KAssetLoader + 'static>(&self, extensions : &[&str], loader : L) {
+        let loader : Arc<dyn KAssetLoaderErased> = Arc::new(loader);
+        let mut registry = self.write_loaders();
+
+        for extension in extensions {
+            let extension = extension.trim_start_matches('.').to_lowercase();
+            registry.extensions.entry(extension).or_default().insert(0, loader.clone());
+        }
+
+        registry.types.insert(loader.get_asset_type(), loader);
+    }
+
+    /// Verify if a loader of asset type `T` is registered for path extension or for type `T`.
+    ///
+    /// Returns True if [KAssetBroker::load] has a loader for path, false otherwise.
+    pub fn has_loader<T : Any + Send + Sync>(&self, path : impl KIntoAssetPath) -> bool {
+        path.into_asset_path().is_ok_and(|path| self.read_loaders().find(&path, TypeId::of::<T>()).is_some())
+    }
+
+    /// Remove all loaders registered.
+    pub fn clear_loaders(&self) {
+        *self.write_loaders() = KAssetLoaderRegistry::default();
+    }
+
+    /// Load asset of path as type `T` with the loader registered for path extension and type `T`.
+    ///
+    /// If no loader of type `T` is registered for path extension, the last loader registered for type `T` is used.
+    ///
+    /// Returns `Ok(T)` if asset was read and parsed.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KAssetLoaderError::InvalidPath]`)` if path is invalid.
+    ///
+    /// Returns `Err(`[KAssetLoaderError::LoaderNotFound]`)` if no loader of type `T` is registered.
+    ///
+    /// Returns `Err(`[KAssetLoaderError::AssetNotFound]`)` if asset is not found in sources.
+    ///
+    /// Returns `Err(`[KAssetLoaderError::ReadError]`)` if an IO error occurred while reading asset.
+    ///
+    /// Returns `Err(`[KAssetLoaderError::ParseError]`)` if loader couldn't parse asset.
+    pub fn load<T : Any + Send + Sync>(&self, path : impl KIntoAssetPath) -> Result<T, KAssetLoaderError> {
+        match path.into_asset_path() {
+            Ok(path) => self.load_sized(&path).map(|(asset, _)| asset),
+            Err(_) => Err(KAssetLoaderError::InvalidPath),
+        }
+    }
+
+    /// Load asset of path as type `T` like [KAssetBroker::load], with its memory size given by loader.
+    pub(crate) fn load_sized<T : Any + Send + Sync>(&self, path : &KAssetPath) -> Result<(T, usize), KAssetLoaderError> {
+        // Registry lock isn't held during IO
+        let loader = match self.read_loaders().find(path, TypeId::of::<T>()) {
+            Some(loader) => loader,
+            None => return Err(KAssetLoaderError::LoaderNotFound),
+        };
+
+        let mut reader = match self.get_asset(path) {
+            Ok(reader) => reader,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Err(KAssetLoaderError::AssetNotFound),
+            Err(_) => return Err(KAssetLoaderError::ReadError),
+        };
+
+        let (asset, size) = loader.load_any(path, &mut reader)?;
+        match asset.downcast::<T>() {
+            Ok(asset) => Ok((*asset, size)),
+            Err(_) => Err(KAssetLoaderError::LoaderNotFound),
+        }
+    }
+}
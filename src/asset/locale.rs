@@ -0,0 +1,234 @@
+use std::io::ErrorKind;
+use super::{KAssetBroker, KAssetPath, KIntoAssetPath};
+use super::broker::KAssetBrokerSource;
+use super::decompression::KAssetDecompression;
+
+/// Enumeration of possible errors of [KAssetLocalization].
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum KAssetLocaleError {
+    /// Happens when a locale is empty or has characters other than ASCII letters, digits, `-` and `_`.
+    InvalidLocale,
+}
+
+impl std::fmt::Debug for KAssetLocaleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidLocale => write!(f, "InvalidLocale"),
+        }
+    }
+}
+
+/// Layout of localized variants of assets within a [KAssetBroker].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum KAssetLocaleLayout {
+    /// Locale is inserted before extension of file name, like `ui/title.fr-CA.png` for `ui/title.png`.
+    #[default]
+    Suffix,
+
+    /// Variants are in a folder per locale within folder given, like `locale/fr-CA/ui/title.png` for `ui/title.png`
+    /// within `locale`. Sources of a locale can be mounted at its folder with [KAssetBroker::add_source_at].
+    Folder(KAssetPath),
+}
+
+/// ##### Locale chain of a [KAssetBroker], set with [KAssetBroker::set_localization].
+///
+/// Assets are resolved to the variant of the first locale of chain having it, then to the default asset.
+/// A chain created with [KAssetLocalization::new] falls back from a locale to its parents, so `fr-CA`
+/// tries `fr-CA`, then `fr`, then the default asset.
+///
+/// # Example(s)
+/// ```
+/// use std::{io::Read, sync::Arc};
+/// use olympus_kleio::asset::{KAssetBroker, KAssetSourceMemory, KAssetLocalization, KAssetLocaleLayout};
+///
+/// let source = KAssetSourceMemory::new("memory");
+/// source.add_asset("ui/title.txt", "Title".as_bytes().to_vec()).unwrap();
+/// source.add_asset("ui/title.fr.txt", "Titre".as_bytes().to_vec()).unwrap();
+///
+/// let broker = KAssetBroker::new();
+/// broker.add_source(Arc::new(source)).unwrap();
+/// broker.set_localization(Some(KAssetLocalization::new("fr-CA", KAssetLocaleLayout::Suffix).unwrap()));
+///
+/// // No fr-CA variant, so fr is picked
+/// let resolved = broker.resolve_locale("ui/title.txt").unwrap();
+/// assert_eq!(resolved.locale.as_deref(), Some("fr"));
+///
+/// let mut title = String::new();
+/// broker.get_asset("ui/title.txt").unwrap().read_to_string(&mut title).unwrap();
+/// assert_eq!(title, "Titre");
+///
+/// // Switch locale for the rest of the session
+/// broker.set_locale("en-US").unwrap();
+/// assert_eq!(broker.resolve_locale("ui/title.txt").unwrap().locale, None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KAssetLocalization {
+    // Locales tried in order before the default asset
+    chain : Vec<String>,
+
+    // Layout of localized variants
+    layout : KAssetLocaleLayout,
+}
+
+/// Locale variant of an asset picked by [KAssetBroker::resolve_locale].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KAssetLocaleMatch {
+    /// Path of variant served.
+    pub path : KAssetPath,
+
+    /// Locale of variant, or `None` for the default asset.
+    pub locale : Option<String>,
+}
+
+impl KAssetLocalization {
+    /// Create a chain of `locale` followed by its parents, like `zh-Hant-TW`, `zh-Hant` then `zh`.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KAssetLocaleError::InvalidLocale]`)` if locale is invalid.
+    pub fn new(locale : &str, layout : KAssetLocaleLayout) -> Result<KAssetLocalization, KAssetLocaleError> {
+        Self::check_locale(locale)?;
+
+        let mut chain = vec![locale.to_string()];
+        let mut parent = locale;
+        while let Some(separator) = parent.rfind(['-', '_']) {
+            parent = &parent[..separator];
+            chain.push(parent.to_string());
+        }
+
+        Ok(KAssetLocalization { chain, layout })
+    }
+
+    /// Create a chain of locales tried in order given, without parents.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KAssetLocaleError::InvalidLocale]`)` if a locale is invalid.
+    pub fn with_chain(chain : &[&str], layout : KAssetLocaleLayout) -> Result<KAssetLocalization, KAssetLocaleError> {
+        for locale in chain {
+            Self::check_locale(locale)?;
+        }
+
+        Ok(KAssetLocalization { chain : chain.iter().map(|locale| locale.to_string()).collect(), layout })
+    }
+
+    /// Get locales tried in order before the default asset.
+    pub fn get_chain(&self) -> &[String] {
+        &self.chain
+    }
+
+    /// Get layout of localized variants.
+    pub fn get_layout(&self) -> &KAssetLocaleLayout {
+        &self.layout
+    }
+
+    /// Get path of the variant of `locale` of asset at path, or `None` for the root.
+    pub fn get_variant_path(&self, path : &KAssetPath, locale : &str) -> Option<KAssetPath> {
+        match &self.layout {
+            KAssetLocaleLayout::Suffix => {
+                let name = path.get_file_name()?;
+                let name = match name.rfind('.') {
+                    Some(0) | None => format!("{}.{}", name, locale),
+                    Some(dot) => format!("{}.{}{}", &name[..dot], locale, &name[dot..]),
+                };
+
+                match path.get_parent() {
+                    Some(parent) => parent.join(&name).ok(),
+                    None => KAssetPath::new(&name).ok(),
+                }
+            },
+            KAssetLocaleLayout::Folder(folder) => match path.is_root() {
+                true => None,
+                false => folder.join(locale).ok().map(|folder| folder.join_path(path)),
+            },
+        }
+    }
+
+    /// Verify locale has only ASCII letters, digits, `-` and `_`.
+    fn check_locale(locale : &str) -> Result<(), KAssetLocaleError> {
+        match !locale.is_empty() && locale.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+            true => Ok(()),
+            false => Err(KAssetLocaleError::InvalidLocale),
+        }
+    }
+}
+
+impl KAssetBroker {
+    /// Set the [KAssetLocalization] resolving assets to their locale variants, or stop resolving them with `None`.
+    ///
+    /// Assets already loaded, like in a [KAssetStorage](super::KAssetStorage), keep their variant until loaded again.
+    pub fn set_localization(&self, localization : Option<KAssetLocalization>) {
+        *self.write_localization() = localization;
+    }
+
+    /// Get the [KAssetLocalization] resolving assets to their locale variants, if any.
+    pub fn get_localization(&self) -> Option<KAssetLocalization> {
+        self.read_localization().clone()
+    }
+
+    /// Switch to a chain of `locale` followed by its parents, keeping layout of current [KAssetLocalization]
+    /// or [KAssetLocaleLayout::Suffix] if none.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KAssetLocaleError::InvalidLocale]`)` if locale is invalid.
+    pub fn set_locale(&self, locale : &str) -> Result<(), KAssetLocaleError> {
+        let mut localization = self.write_localization();
+        let layout = localization.as_ref().map(|localization| localization.layout.clone()).unwrap_or_default();
+
+        *localization = Some(KAssetLocalization::new(locale, layout)?);
+        Ok(())
+    }
+
+    /// Resolve asset at path to the variant of the first locale of chain having it, or to the default asset.
+    ///
+    /// Returns `Ok(`[KAssetLocaleMatch]`)` with the variant served by [KAssetBroker::get_asset].
+    ///
+    /// # Error(s)
+    /// Returns [std::io::Error] of kind [InvalidInput](ErrorKind::InvalidInput) if path is invalid.
+    ///
+    /// Returns [std::io::Error] of kind [NotFound](ErrorKind::NotFound) if no source has asset nor a variant of it.
+    ///
+    /// # Note(s)
+    /// A locale variant having only tagged variants, like `ui/title.fr@quality=high.png`, is picked too. Its tagged
+    /// variant is given by [KAssetBroker::resolve_tags].
+    pub fn resolve_locale(&self, path : impl KIntoAssetPath) -> Result<KAssetLocaleMatch, std::io::Error> {
+        let path = path.into_asset_path()?;
+        let sources = self.get_sources_snapshot();
+        let decompression = self.get_decompression();
+        let tags = self.get_tags();
+
+        if let Some(localization) = self.get_localization() {
+            for locale in localization.get_chain() {
+                if let Some(variant) = localization.get_variant_path(&path, locale) {
+                    // Locale variant may only exist tagged
+                    if Self::has_asset_in(&sources, &variant, &decompression) || Self::find_tagged_in(&sources, &variant, &decompression, &tags).is_some() {
+                        return Ok(KAssetLocaleMatch { path : variant, locale : Some(locale.clone()) });
+                    }
+                }
+            }
+        }
+
+        match Self::has_asset_in(&sources, &path, &decompression) || Self::find_tagged_in(&sources, &path, &decompression, &tags).is_some() {
+            true => Ok(KAssetLocaleMatch { path, locale : None }),
+            false => Err(std::io::Error::new(ErrorKind::NotFound, "Asset not found!")),
+        }
+    }
+
+    /// Get path of locale variant served for asset at path, or path itself if not localized.
+    pub(crate) fn get_localized_path(&self, path : KAssetPath) -> KAssetPath {
+        if self.read_localization().is_none() {
+            return path;
+        }
+
+        match self.resolve_locale(&path) {
+            Ok(resolved) => resolved.path,
+            Err(_) => path,
+        }
+    }
+
+    /// Returns true if a source has asset at path, compressed or not.
+    pub(crate) fn has_asset_in(sources : &[KAssetBrokerSource], path : &KAssetPath, decompression : &Option<KAssetDecompression>) -> bool {
+        sources.iter().any(|src| match src.get_local_path(path) {
+            Some(local) => src.source.has_asset(&local) || decompression.as_ref().is_some_and(|decompression| decompression.find_asset(src, &local).is_some()),
+            None => false,
+        })
+    }
+}
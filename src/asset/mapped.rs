@@ -0,0 +1,308 @@
+// Contains read-only views of assets bytes, memory-mapped on 64 bits Linux
+use std::{fs::File, io::{Read, Seek, SeekFrom, ErrorKind}, ops::Deref, sync::Arc};
+use super::{KAssetBroker, KAssetPath, KIntoAssetPath};
+use super::broker::KAssetBrokerSource;
+
+#[cfg(all(target_os = "linux", target_pointer_width = "64"))]
+use std::os::{raw::{c_int, c_void}, unix::io::AsRawFd};
+
+#[cfg(all(target_os = "linux", target_pointer_width = "64"))]
+extern "C" {
+    /// Creates a new mapping in the virtual address space of the calling process.
+    ///
+    /// # Reference(s)
+    /// https://man7.org/linux/man-pages/man2/mmap.2.html
+    fn mmap(addr : *mut c_void, length : usize, prot : c_int, flags : c_int, fd : c_int, offset : i64) -> *mut c_void;
+
+    /// Deletes the mappings for the specified address range.
+    ///
+    /// # Reference(s)
+    /// https://man7.org/linux/man-pages/man2/munmap.2.html
+    fn munmap(addr : *mut c_void, length : usize) -> c_int;
+}
+
+// mmap protection and flags
+#[cfg(all(target_os = "linux", target_pointer_width = "64"))]
+const PROT_READ : c_int = 0x1;
+#[cfg(all(target_os = "linux", target_pointer_width = "64"))]
+const MAP_PRIVATE : c_int = 0x2;
+
+// Alignment of mapped offsets, a multiple of every page size
+#[cfg(all(target_os = "linux", target_pointer_width = "64"))]
+const KMAP_ALIGNMENT : u64 = 64 * 1024;
+
+/// ##### Read-only view of the whole bytes of an asset.
+///
+/// Returned by [KAssetBroker::get_asset_bytes] and [KAssetSource::get_asset_bytes](super::KAssetSource::get_asset_bytes).
+/// Bytes are either memory-mapped from a file, so pages are only read when touched and never copied, or in memory.
+/// Cloning never copies the bytes.
+///
+/// Files are memory-mapped on 64 bits Linux only. Other platforms, and files that can't be mapped, fall back to
+/// reading bytes in memory.
+///
+/// # Note(s)
+/// A memory-mapped file truncated by another process while mapped makes the process crash when pages past the
+/// new end are touched. Mapping is unsafe for this reason, see [KAssetBytes::map_file].
+///
+/// # Example(s)
+/// ```
+/// use std::{io::{Read, Cursor}, sync::Arc};
+/// use olympus_kleio::asset::{KAssetBroker, KAssetSourceMemory};
+///
+/// let source = KAssetSourceMemory::new("memory");
+/// source.add_asset("heightmap.raw", vec![0, 1, 2, 3]).unwrap();
+///
+/// let broker = KAssetBroker::new();
+/// broker.add_source(Arc::new(source)).unwrap();
+///
+/// // Bytes are used as a slice, or read with a Cursor
+/// let bytes = broker.get_asset_bytes("heightmap.raw").unwrap();
+/// assert_eq!(&bytes[1..3], &[1, 2]);
+/// assert!(!bytes.is_mapped());
+///
+/// let mut content = Vec::new();
+/// Cursor::new(bytes).read_to_end(&mut content).unwrap();
+/// assert_eq!(content.len(), 4);
+/// ```
+#[derive(Clone)]
+pub struct KAssetBytes {
+    // Bytes of asset
+    data : KAssetBytesData,
+}
+
+/// Storage of [KAssetBytes].
+#[derive(Clone)]
+enum KAssetBytesData {
+    // Bytes embedded in binary
+    Static(&'static [u8]),
+
+    // Bytes in memory
+    Shared(Arc<[u8]>),
+
+    // Bytes mapped from a file
+    #[cfg(all(target_os = "linux", target_pointer_width = "64"))]
+    Mapped(Arc<KMemoryMap>),
+}
+
+/// Read-only private mapping of a file, unmapped when dropped.
+#[cfg(all(target_os = "linux", target_pointer_width = "64"))]
+struct KMemoryMap {
+    // Start of mapping, aligned
+    address : *mut c_void,
+
+    // Length of mapping from aligned start
+    map_length : usize,
+
+    // Offset of bytes from start of mapping
+    offset : usize,
+
+    // Length of bytes
+    length : usize,
+}
+
+// Mapping is read-only and owned, so it's shared between threads like a slice.
+#[cfg(all(target_os = "linux", target_pointer_width = "64"))]
+unsafe impl Send for KMemoryMap {}
+#[cfg(all(target_os = "linux", target_pointer_width = "64"))]
+unsafe impl Sync for KMemoryMap {}
+
+#[cfg(all(target_os = "linux", target_pointer_width = "64"))]
+impl KMemoryMap {
+    /// Map `length` bytes of file starting at `offset`, or `None` if file couldn't be mapped.
+    fn new(file : &File, offset : u64, length : usize) -> Option<KMemoryMap> {
+        let aligned = offset - offset % KMAP_ALIGNMENT;
+        let delta = (offset - aligned) as usize;
+        let map_length = delta.checked_add(length)?;
+
+        let address = unsafe { mmap(std::ptr::null_mut(), map_length, PROT_READ, MAP_PRIVATE, file.as_raw_fd(), i64::try_from(aligned).ok()?) };
+
+        // MAP_FAILED is (void *) -1
+        match address as isize == -1 {
+            true => None,
+            false => Some(KMemoryMap { address, map_length, offset : delta, length }),
+        }
+    }
+
+    /// Get mapped bytes.
+    fn as_slice(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts((self.address as *const u8).add(self.offset), self.length) }
+    }
+}
+
+#[cfg(all(target_os = "linux", target_pointer_width = "64"))]
+impl Drop for KMemoryMap {
+    fn drop(&mut self) {
+        unsafe { munmap(self.address, self.map_length); }
+    }
+}
+
+impl KAssetBytes {
+    /// Map `length` bytes of `file` starting at `offset` in memory, or read them in memory if file can't be mapped.
+    ///
+    /// Returns `Ok(`[KAssetBytes]`)` with bytes of file.
+    ///
+    /// # Error(s)
+    /// Returns [std::io::Error] of kind [UnexpectedEof](ErrorKind::UnexpectedEof) if range ends after the end of file.
+    ///
+    /// Returns [std::io::Error] if an IO error occurs.
+    ///
+    /// # Safety
+    /// File must not be truncated or modified while bytes are alive. Touching pages mapped past a new end of
+    /// file raises `SIGBUS` and crashes the process, and modified pages change bytes already verified.
+    pub unsafe fn map_file(file : &File, offset : u64, length : u64) -> Result<KAssetBytes, std::io::Error> {
+        // Touching pages mapped past the end of file would crash, so range is verified first
+        Self::check_range(file, offset, length)?;
+
+        #[cfg(all(target_os = "linux", target_pointer_width = "64"))]
+        if length > 0 {
+            if let Some(map) = usize::try_from(length).ok().and_then(|length| KMemoryMap::new(file, offset, length)) {
+                return Ok(KAssetBytes { data : KAssetBytesData::Mapped(Arc::new(map)) });
+            }
+        }
+
+        Self::read_file(file, offset, length)
+    }
+
+    /// Read `length` bytes of `file` starting at `offset` in memory.
+    ///
+    /// Returns `Ok(`[KAssetBytes]`)` with bytes of file.
+    ///
+    /// # Error(s)
+    /// Returns [std::io::Error] of kind [UnexpectedEof](ErrorKind::UnexpectedEof) if range ends after the end of file.
+    ///
+    /// Returns [std::io::Error] if an IO error occurs.
+    pub fn read_file(mut file : &File, offset : u64, length : u64) -> Result<KAssetBytes, std::io::Error> {
+        Self::check_range(file, offset, length)?;
+
+        let mut data = Vec::with_capacity(usize::try_from(length).unwrap_or(0));
+        file.seek(SeekFrom::Start(offset))?;
+        file.take(length).read_to_end(&mut data)?;
+
+        match data.len() as u64 == length {
+            true => Ok(data.into()),
+            false => Err(std::io::Error::new(ErrorKind::UnexpectedEof, "Range ends after end of file!")),
+        }
+    }
+
+    /// Verify if bytes are memory-mapped from a file rather than in memory.
+    pub fn is_mapped(&self) -> bool {
+        match &self.data {
+            #[cfg(all(target_os = "linux", target_pointer_width = "64"))]
+            KAssetBytesData::Mapped(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Verify range of `length` bytes starting at `offset` ends within file.
+    fn check_range(file : &File, offset : u64, length : u64) -> Result<(), std::io::Error> {
+        match offset.checked_add(length) {
+            Some(end) if end <= file.metadata()?.len() => Ok(()),
+            _ => Err(std::io::Error::new(ErrorKind::UnexpectedEof, "Range ends after end of file!")),
+        }
+    }
+}
+
+impl Deref for KAssetBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match &self.data {
+            KAssetBytesData::Static(data) => data,
+            KAssetBytesData::Shared(data) => data,
+            #[cfg(all(target_os = "linux", target_pointer_width = "64"))]
+            KAssetBytesData::Mapped(map) => map.as_slice(),
+        }
+    }
+}
+
+impl AsRef<[u8]> for KAssetBytes {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl std::fmt::Debug for KAssetBytes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KAssetBytes").field("length", &self.len()).field("mapped", &self.is_mapped()).finish()
+    }
+}
+
+impl From<Vec<u8>> for KAssetBytes {
+    fn from(data : Vec<u8>) -> Self {
+        KAssetBytes { data : KAssetBytesData::Shared(data.into()) }
+    }
+}
+
+impl From<Arc<[u8]>> for KAssetBytes {
+    fn from(data : Arc<[u8]>) -> Self {
+        KAssetBytes { data : KAssetBytesData::Shared(data) }
+    }
+}
+
+impl From<&'static [u8]> for KAssetBytes {
+    fn from(data : &'static [u8]) -> Self {
+        KAssetBytes { data : KAssetBytesData::Static(data) }
+    }
+}
+
+impl KAssetBrokerSource {
+    /// Get [KAssetBytes] of asset at local path, verified against integrity manifest if any.
+    pub(crate) fn get_asset_bytes(&self, path : &KAssetPath) -> Result<KAssetBytes, std::io::Error> {
+        let bytes = self.source.get_asset_bytes(path)?;
+
+        if let Some(manifest) = &self.integrity {
+            manifest.verify_bytes(path, &bytes)?;
+        }
+
+        Ok(bytes)
+    }
+}
+
+impl KAssetBroker {
+    /// Fetch a read-only [KAssetBytes] view of the whole asset in sources from path.
+    ///
+    /// Sources map large files in memory when they can, like [KAssetSourceFolder](super::KAssetSourceFolder) and
+    /// uncompressed assets of [KAssetSourcePak](super::KAssetSourcePak) set with `set_memory_mapped`. Other sources
+    /// read bytes in memory.
+    ///
+    /// With a [KAssetCache], bytes are served from cache if cached for the source of highest priority
+    /// having it. Otherwise bytes aren't cached, unless decompressed.
+    ///
+    /// Returns `Ok(`[KAssetBytes]`)` if asset found.
+    ///
+    /// # Error(s)
+    /// Returns [std::io::Error] of kind [InvalidInput](ErrorKind::InvalidInput) if path is invalid.
+    ///
+    /// Returns [std::io::Error] if asset not found or IO error occurs.
+    ///
+    /// # Note(s)
+    /// Bytes of a source verified with [KAssetBroker::set_source_integrity] are hashed entirely before being returned.
+    pub fn get_asset_bytes(&self, path : impl KIntoAssetPath) -> Result<KAssetBytes, std::io::Error> {
+        let path = self.get_resolved_path(&path.into_asset_path()?);
+
+        let sources = self.get_sources_snapshot();
+        let cache = self.get_cache();
+        let decompression = self.get_decompression();
+
+        for src in sources {
+            let local = match src.get_local_path(&path) {
+                Some(local) => local,
+                None => continue,
+            };
+
+            if let Some(data) = cache.as_ref().and_then(|cache| cache.get(src.id, &path)) {
+                return Ok(data.into());
+            }
+
+            if src.source.has_asset(&local) {
+                return src.get_asset_bytes(&local);
+            }
+
+            if let Some(data) = self.get_decompressed_asset(&src, &local, &path, &decompression, &cache)? {
+                return Ok(data.into());
+            }
+        }
+
+        Err(std::io::Error::new(ErrorKind::NotFound, "Asset not found!"))
+    }
+}
@@ -0,0 +1,185 @@
+use std::{io::ErrorKind, time::SystemTime};
+use super::{KAssetBroker, KIntoAssetPath};
+
+/// Kind of a [KAssetSource](super::KAssetSource), given by its [KAssetSourceMetadata].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum KAssetSourceKind {
+    /// Source of a file system folder, like [KAssetSourceFolder](super::KAssetSourceFolder).
+    Folder,
+
+    /// Source of a zip archive, like [KAssetSourceZip](super::KAssetSourceZip).
+    Zip,
+
+    /// Source of a Kleio pack, like [KAssetSourcePak](super::KAssetSourcePak).
+    Pak,
+
+    /// Source of buffers in memory, like [KAssetSourceMemory](super::KAssetSourceMemory).
+    Memory,
+
+    /// Source that doesn't tell its kind.
+    #[default]
+    Unknown,
+}
+
+impl std::fmt::Display for KAssetSourceKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Folder => write!(f, "folder"),
+            Self::Zip => write!(f, "zip"),
+            Self::Pak => write!(f, "pak"),
+            Self::Memory => write!(f, "memory"),
+            Self::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// ##### Metadata that identify a [KAssetSource](super::KAssetSource), given by [KAssetSource::get_metadata](super::KAssetSource::get_metadata).
+///
+/// Fields a source can't tell are `None`. [KAssetSourceMetadata::to_json] serialises metadata with
+/// strings escaped, so locations with quotes or backslashes stay valid JSON.
+///
+/// # Example(s)
+/// ```
+/// use olympus_kleio::asset::{KAssetSource, KAssetSourceMemory, KAssetSourceKind};
+///
+/// let source = KAssetSourceMemory::new("fallback");
+/// source.add_asset("lang/en.lang", "hello=Hello".as_bytes().to_vec()).unwrap();
+///
+/// let metadata = source.get_metadata();
+/// assert_eq!(metadata.kind, KAssetSourceKind::Memory);
+/// assert_eq!(metadata.asset_count, Some(1));
+/// assert_eq!(metadata.to_json(), "{\"kind\":\"memory\",\"location\":null,\"created\":null,\"modified\":null,\"asset_count\":1,\"size\":11,\"name\":\"fallback\",\"version\":null}");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KAssetSourceMetadata {
+    /// Kind of source.
+    pub kind : KAssetSourceKind,
+
+    /// Location of source, like the path of its folder or archive.
+    pub location : Option<String>,
+
+    /// Time source was created.
+    pub created : Option<SystemTime>,
+
+    /// Time source was last modified.
+    pub modified : Option<SystemTime>,
+
+    /// Count of assets in source.
+    pub asset_count : Option<usize>,
+
+    /// Total size of assets in bytes, uncompressed.
+    pub size : Option<u64>,
+
+    /// Name of source shown to users.
+    pub name : Option<String>,
+
+    /// Version of source content.
+    pub version : Option<String>,
+}
+
+impl KAssetSourceMetadata {
+    /// Create metadata of source `kind` with all other fields unknown.
+    pub fn new(kind : KAssetSourceKind) -> KAssetSourceMetadata {
+        KAssetSourceMetadata { kind, ..Default::default() }
+    }
+
+    /// Serialise metadata as a JSON object.
+    ///
+    /// Times are milliseconds since UNIX epoch and unknown fields are `null`.
+    pub fn to_json(&self) -> String {
+        let string = |value : &Option<String>| value.as_deref().map_or(String::from("null"), json_string);
+        let number = |value : Option<u128>| value.map_or(String::from("null"), |value| value.to_string());
+        let time = |value : Option<SystemTime>| number(value.and_then(|time| time.duration_since(SystemTime::UNIX_EPOCH).ok()).map(|time| time.as_millis()));
+
+        format!("{{\"kind\":{},\"location\":{},\"created\":{},\"modified\":{},\"asset_count\":{},\"size\":{},\"name\":{},\"version\":{}}}",
+            json_string(&self.kind.to_string()), string(&self.location), time(self.created), time(self.modified),
+            number(self.asset_count.map(|count| count as u128)), number(self.size.map(|size| size as u128)), string(&self.name), string(&self.version))
+    }
+}
+
+/// ##### Metadata of an asset, given by [KAssetSource::get_asset_metadata](super::KAssetSource::get_asset_metadata).
+///
+/// Metadata comes from the source index or file system, so asset isn't opened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KAssetMetadata {
+    /// Size of asset in bytes, uncompressed.
+    pub size : u64,
+
+    /// Time asset was last modified, if known.
+    pub modified : Option<SystemTime>,
+
+    /// CRC-32 of asset content, if known without reading asset.
+    pub hash : Option<u32>,
+}
+
+impl KAssetBroker {
+    /// Get [KAssetMetadata] of asset at path from source of highest priority having it, without opening asset.
+    ///
+    /// Assets decompressed by [KAssetDecompression](super::KAssetDecompression) are decompressed to know their size.
+    ///
+    /// # Error(s)
+    /// Returns [std::io::Error] of kind [InvalidInput](std::io::ErrorKind::InvalidInput) if path is invalid.
+    ///
+    /// Returns [std::io::Error] of kind [NotFound](std::io::ErrorKind::NotFound) if no source has asset.
+    ///
+    /// Returns errors of [KAssetSource::get_asset_metadata](super::KAssetSource::get_asset_metadata) of source having asset.
+    ///
+    /// # Example(s)
+    /// ```
+    /// use std::sync::Arc;
+    /// use olympus_kleio::asset::{KAssetBroker, KAssetSourceMemory};
+    ///
+    /// let source = KAssetSourceMemory::new("memory");
+    /// source.add_asset("text.txt", "Hello, world!".as_bytes().to_vec()).unwrap();
+    ///
+    /// let broker = KAssetBroker::new();
+    /// broker.add_source_at(Arc::new(source), "texts").unwrap();
+    ///
+    /// let metadata = broker.get_asset_metadata("texts/text.txt").unwrap();
+    /// assert_eq!(metadata.size, 13);
+    /// ```
+    pub fn get_asset_metadata(&self, path : impl KIntoAssetPath) -> Result<KAssetMetadata, std::io::Error> {
+        let path = self.get_resolved_path(&path.into_asset_path()?);
+        let decompression = self.get_decompression();
+
+        for src in self.get_sources_snapshot() {
+            let local = match src.get_local_path(&path) {
+                Some(local) => local,
+                None => continue,
+            };
+
+            if src.source.has_asset(&local) {
+                return src.source.get_asset_metadata(&local);
+            }
+
+            if let Some(decompression) = &decompression {
+                if let Some(metadata) = src.get_decompressed_metadata(&local, decompression)? {
+                    return Ok(metadata);
+                }
+            }
+        }
+
+        Err(std::io::Error::new(ErrorKind::NotFound, "Asset not found!"))
+    }
+}
+
+/// Serialise value as a JSON string, escaping quotes, backslashes and control characters.
+fn json_string(value : &str) -> String {
+    let mut json = String::with_capacity(value.len() + 2);
+    json.push('"');
+
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+
+    json.push('"');
+    json
+}
@@ -1,10 +1,101 @@
 /// # Re-export for Public API
 #[doc(inline)]
+pub use path::KAssetPath as KAssetPath;
+pub use path::KAssetPathError as KAssetPathError;
+pub use path::KIntoAssetPath as KIntoAssetPath;
 pub use source::KAssetSource as KAssetSource;
+pub use source::KAssetChange as KAssetChange;
+pub use source::KAssetChangeKind as KAssetChangeKind;
+pub use source::KAssetSeekReader as KAssetSeekReader;
+pub use source::KAssetReadSeek as KAssetReadSeek;
+pub use sink::KAssetSink as KAssetSink;
+pub use metadata::KAssetSourceMetadata as KAssetSourceMetadata;
+pub use metadata::KAssetSourceKind as KAssetSourceKind;
+pub use metadata::KAssetMetadata as KAssetMetadata;
 pub use source_folder::KAssetSourceFolder as KAssetSourceFolder;
 pub use source_folder::KAssetSourceFolderError as KAssetSourceFolderError;
+pub use source_zip::KAssetSourceZip as KAssetSourceZip;
+pub use source_zip::KAssetSourceZipError as KAssetSourceZipError;
+pub use source_memory::KAssetSourceMemory as KAssetSourceMemory;
+pub use source_memory::KAssetSourceMemoryError as KAssetSourceMemoryError;
+pub use olympus_kleio_macros::kasset_source_embed as kasset_source_embed;
+pub use source_pak::KAssetSourcePak as KAssetSourcePak;
+pub use source_pak::KAssetSourcePakError as KAssetSourcePakError;
+pub use source_pak::KPakCompression as KPakCompression;
+pub use pak_writer::KPakWriter as KPakWriter;
+pub use pak_writer::KPakWriterError as KPakWriterError;
+pub use sink_folder::KAssetSinkFolder as KAssetSinkFolder;
+pub use sink_folder::KAssetSinkFolderError as KAssetSinkFolderError;
 pub use broker::KAssetBroker as KAssetBroker;
 pub use broker::KAssetBrokerError as KAssetBrokerError;
+pub use broker::KAssetSourceId as KAssetSourceId;
+pub use change::KAssetChangeEvent as KAssetChangeEvent;
+pub use listing::KAssetListEntry as KAssetListEntry;
+pub use cache::KAssetCache as KAssetCache;
+pub use cache::KAssetCacheStatistics as KAssetCacheStatistics;
+pub use streamer::KAssetStreamer as KAssetStreamer;
+pub use streamer::KAssetStreamerError as KAssetStreamerError;
+pub use streamer::KAssetLoadHandle as KAssetLoadHandle;
+pub use streamer::KAssetLoadStatus as KAssetLoadStatus;
+pub use streamer::KAssetLoadError as KAssetLoadError;
+pub use preload::KAssetBundle as KAssetBundle;
+pub use preload::KAssetBundleError as KAssetBundleError;
+pub use preload::KAssetPreload as KAssetPreload;
+pub use preload::KAssetPreloadProgress as KAssetPreloadProgress;
+pub use loader::KAssetLoader as KAssetLoader;
+pub use loader::KAssetLoaderError as KAssetLoaderError;
+pub use storage::KAssetStorage as KAssetStorage;
+pub use storage::KAssetUnloadPolicy as KAssetUnloadPolicy;
+pub use storage::KAssetHandle as KAssetHandle;
+pub use storage::KAssetWeakHandle as KAssetWeakHandle;
+pub use storage::KAssetReportEntry as KAssetReportEntry;
+pub use provenance::KAssetProvenance as KAssetProvenance;
+pub use provenance::KAssetProvenanceStatus as KAssetProvenanceStatus;
+pub use provenance::KAssetSourceInfo as KAssetSourceInfo;
+pub use provenance::KAssetOverride as KAssetOverride;
+pub use provenance::KAssetOverrideReport as KAssetOverrideReport;
+pub use mod_manifest::KModManifest as KModManifest;
+pub use mod_manifest::KModManifestError as KModManifestError;
+pub use mod_manifest::KModVersion as KModVersion;
+pub use mod_manifest::KModDependency as KModDependency;
+pub use mod_manifest::KMOD_MANIFEST_PATH as KMOD_MANIFEST_PATH;
+pub use mod_manager::KModManager as KModManager;
+pub use mod_manager::KModError as KModError;
+pub use mod_manager::KModIssue as KModIssue;
+pub use mod_manager::KModResolution as KModResolution;
+pub use integrity::KIntegrityManifest as KIntegrityManifest;
+pub use integrity::KIntegrityManifestError as KIntegrityManifestError;
+pub use integrity::KIntegrityEntry as KIntegrityEntry;
+pub use integrity::KIntegrityError as KIntegrityError;
+pub use integrity::KIntegrityReport as KIntegrityReport;
+pub use integrity::KINTEGRITY_MANIFEST_PATH as KINTEGRITY_MANIFEST_PATH;
+pub use trust::KPublicKey as KPublicKey;
+pub use trust::KSigningKey as KSigningKey;
+pub use trust::KTrustPolicy as KTrustPolicy;
+pub use trust::KTrustError as KTrustError;
+pub use decompression::KAssetDecompression as KAssetDecompression;
+pub use decompression::KAssetCompression as KAssetCompression;
+pub use decompression::KDecompressionError as KDecompressionError;
+pub use locale::KAssetLocalization as KAssetLocalization;
+pub use locale::KAssetLocaleLayout as KAssetLocaleLayout;
+pub use locale::KAssetLocaleMatch as KAssetLocaleMatch;
+pub use locale::KAssetLocaleError as KAssetLocaleError;
+pub use tags::KAssetTags as KAssetTags;
+pub use tags::KAssetTagMatch as KAssetTagMatch;
+pub use tags::KAssetTagError as KAssetTagError;
+pub use tags::KASSET_TAGS_MAX_VARIANTS as KASSET_TAGS_MAX_VARIANTS;
+pub use mapped::KAssetBytes as KAssetBytes;
+pub use trace::KAssetTrace as KAssetTrace;
+pub use trace::KAssetTraceEvent as KAssetTraceEvent;
+pub use trace::KAssetTraceError as KAssetTraceError;
+pub use trace::KAssetAccess as KAssetAccess;
+pub use trace::KAssetAccessKind as KAssetAccessKind;
+pub use trace::KAssetUsageReport as KAssetUsageReport;
+pub use trace::KAssetUnused as KAssetUnused;
+
+// Kleio canonical asset paths
+#[doc(hidden)]
+pub mod path;
 
 // Kleio asset source
 #[doc(hidden)]
@@ -14,6 +105,115 @@ pub mod source;
 #[doc(hidden)]
 pub mod source_folder;
 
+// Kleio asset source implementation for zip archives
+#[doc(hidden)]
+pub mod source_zip;
+
+// Kleio asset source implementation for buffers in memory
+#[doc(hidden)]
+pub mod source_memory;
+
+// Kleio asset source implementation for Kleio packs
+#[doc(hidden)]
+pub mod source_pak;
+
+// Kleio pack writer
+#[doc(hidden)]
+pub mod pak_writer;
+
+// Kleio source and asset metadata
+#[doc(hidden)]
+pub mod metadata;
+
+// Kleio writable asset sink
+#[doc(hidden)]
+pub mod sink;
+
+// Kleio asset sink implementation for file system
+#[doc(hidden)]
+pub mod sink_folder;
+
 // Kleio asset broker
 #[doc(hidden)]
-pub mod broker;
\ No newline at end of file
+pub mod broker;
+
+// Kleio asset change events
+#[doc(hidden)]
+pub mod change;
+
+// Kleio merged asset listing
+#[doc(hidden)]
+pub mod listing;
+
+// Kleio asset cache
+#[doc(hidden)]
+pub mod cache;
+
+// Kleio asynchronous asset loading
+#[doc(hidden)]
+pub mod streamer;
+
+// Kleio preload of asset bundles
+#[doc(hidden)]
+pub mod preload;
+
+// Kleio typed asset loaders
+#[doc(hidden)]
+pub mod loader;
+
+// Kleio storage of loaded assets
+#[doc(hidden)]
+pub mod storage;
+
+// Kleio asset provenance and override reports
+#[doc(hidden)]
+pub mod provenance;
+
+// Kleio mod manifests
+#[doc(hidden)]
+pub mod mod_manifest;
+
+// Kleio mod manager ordering mod sources
+#[doc(hidden)]
+pub mod mod_manager;
+
+// Kleio integrity manifests verifying assets
+#[doc(hidden)]
+pub mod integrity;
+
+// Kleio signatures and trust policy of sources
+#[doc(hidden)]
+pub mod trust;
+
+// Decompression of assets stored compressed
+#[doc(hidden)]
+pub mod decompression;
+
+// Locale variants of assets
+#[doc(hidden)]
+pub mod locale;
+
+// Tagged variants of assets
+#[doc(hidden)]
+pub mod tags;
+
+// Memory-mapped bytes of assets
+#[doc(hidden)]
+pub mod mapped;
+
+// Kleio tracing of asset accesses
+#[doc(hidden)]
+pub mod trace;
+
+// Folder watcher of hot reload
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub(crate) mod watch;
+
+// Glob pattern matching of asset paths
+pub(crate) mod glob;
+
+// Shared helpers of archive based sources
+pub(crate) mod archive;
+
+// Kleio compression and checksum codecs
+pub(crate) mod codec;
\ No newline at end of file
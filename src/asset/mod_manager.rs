@@ -0,0 +1,453 @@
+use std::{collections::{BTreeSet, HashMap}, sync::{Arc, Mutex, MutexGuard}};
+
+use super::{KAssetBroker, KAssetBrokerError, KAssetSource, KAssetSourceId, KModManifest, KModManifestError, KModVersion};
+
+/// ##### Manager of mods of a [KAssetBroker], ordering their sources from their [KModManifest].
+///
+/// Mods are [KAssetSource] carrying a [KModManifest]. [KModManager::apply] resolves a load order where each mod is
+/// loaded after its dependencies and its `after` hints, adds the mods loaded to the broker and gives them priorities
+/// so mods loaded last override mods loaded first. Mods come before other sources of broker, after its sink.
+///
+/// # Resolution
+/// Mods disabled with [KModManager::set_mod_enabled] are never loaded. Mods with a missing, disabled or too old
+/// dependency, mods incompatible with a mod loaded and mods within a dependency cycle aren't loaded either and
+/// are reported as [KModIssue]. `after` hints only order mods : hints contradicting dependencies or other hints
+/// are ignored and reported in [KModResolution::ignored_hints]. Mods that can load at the same time are ordered
+/// by identifier so the load order is always the same.
+///
+/// # Example(s)
+/// ```
+/// // Import crate module
+/// use std::{path::PathBuf, sync::Arc, io::Read};
+/// use olympus_kleio::asset::{KAssetBroker, KAssetSourceMemory, KModManager};
+///
+/// // Create a mod source with its manifest
+/// let create_mod = |manifest : &str, asset : &str| {
+///     let source = KAssetSourceMemory::new("mod");
+///     source.add_asset("mod.manifest", manifest.as_bytes().to_vec()).unwrap();
+///     source.add_asset("title.txt", asset.as_bytes().to_vec()).unwrap();
+///     Arc::new(source)
+/// };
+///
+/// let broker = Arc::new(KAssetBroker::new());
+/// let manager = KModManager::new(broker.clone());
+///
+/// // Castle depends on core, so castle is loaded last and overrides core
+/// manager.add_mod(create_mod("id = castle\nversion = 1.0\ndepends = core >= 2", "Castle")).unwrap();
+/// manager.add_mod(create_mod("id = core\nversion = 2.1", "Core")).unwrap();
+/// let resolution = manager.apply().unwrap();
+/// assert_eq!(resolution.order, vec!["core", "castle"]);
+///
+/// let mut title = String::new();
+/// broker.get_asset("title.txt").unwrap().read_to_string(&mut title).unwrap();
+/// assert_eq!(title, "Castle");
+/// ```
+pub struct KModManager {
+    // Broker where mods are applied
+    broker : Arc<KAssetBroker>,
+
+    // Mods in order added
+    mods : Mutex<Vec<KMod>>,
+}
+
+/// Enumeration of possible [KModManager] errors.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum KModError {
+    /// Happens when source has no manifest.
+    ManifestNotFound,
+
+    /// Happens when manifest of mod couldn't be read or parsed. See [KModManifest::from_source] for details.
+    InvalidManifest,
+
+    /// Happens when a mod with the same identifier is already in manager.
+    ModAlreadyExists,
+
+    /// Happens when mod is not found in manager.
+    ModNotFound,
+}
+
+impl std::fmt::Debug for KModError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ManifestNotFound => write!(f, "ManifestNotFound"),
+            Self::InvalidManifest => write!(f, "InvalidManifest"),
+            Self::ModAlreadyExists => write!(f, "ModAlreadyExists"),
+            Self::ModNotFound => write!(f, "ModNotFound"),
+        }
+    }
+}
+
+/// Issue preventing a mod from loading, reported by [KModManager::resolve].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KModIssue {
+    /// Mod `id` depends on mod `dependency` which isn't in manager.
+    MissingDependency { id : String, dependency : String },
+
+    /// Mod `id` depends on mod `dependency` which is disabled or can't be loaded.
+    DisabledDependency { id : String, dependency : String },
+
+    /// Mod `id` requires at least version `required` of mod `dependency`, but version `found` is in manager.
+    VersionMismatch { id : String, dependency : String, required : KModVersion, found : KModVersion },
+
+    /// Mod `id` is incompatible with mod `other` which is loaded.
+    Incompatible { id : String, other : String },
+
+    /// Mods `ids` depend on each other in a cycle, or on mods of a cycle.
+    Cycle { ids : Vec<String> },
+}
+
+/// Load order of mods resolved by [KModManager::resolve].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KModResolution {
+    /// Identifiers of mods loaded, first loaded first. Mods loaded last override mods loaded first.
+    pub order : Vec<String>,
+
+    /// Identifiers of mods enabled that can't be loaded because of issues, in order added.
+    pub failed : Vec<String>,
+
+    /// Issues of mods that can't be loaded.
+    pub issues : Vec<KModIssue>,
+
+    /// `after` hints ignored because they contradict dependencies or other hints, as (mod, mod it should load after).
+    pub ignored_hints : Vec<(String, String)>,
+}
+
+impl KModResolution {
+    /// Verify if all mods enabled are loaded.
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Mod of manager.
+struct KMod {
+    // Manifest of mod
+    manifest : KModManifest,
+
+    // Source of mod assets
+    source : Arc<dyn KAssetSource + Send + Sync>,
+
+    // Enabled by user
+    enabled : bool,
+
+    // Identifier of source when added to broker
+    source_id : Option<KAssetSourceId>,
+}
+
+impl KModManager {
+    /// Create a new [KModManager] applying mods to `broker`.
+    pub fn new(broker : Arc<KAssetBroker>) -> KModManager {
+        KModManager { broker, mods : Mutex::new(Vec::new()) }
+    }
+
+    /// Add a mod enabled from a source having a manifest at [KMOD_MANIFEST_PATH](super::KMOD_MANIFEST_PATH).
+    ///
+    /// Mod is added to broker by [KModManager::apply].
+    ///
+    /// Returns `Ok(`[String]`)` with the identifier of mod.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KModError::ManifestNotFound]`)` if source has no manifest.
+    ///
+    /// Returns `Err(`[KModError::InvalidManifest]`)` if manifest couldn't be read or parsed.
+    ///
+    /// Returns `Err(`[KModError::ModAlreadyExists]`)` if a mod with the same identifier is in manager.
+    pub fn add_mod(&self, source : Arc<dyn KAssetSource + Send + Sync>) -> Result<String, KModError> {
+        let manifest = match KModManifest::from_source(source.as_ref()) {
+            Ok(manifest) => manifest,
+            Err(KModManifestError::ManifestNotFound) => return Err(KModError::ManifestNotFound),
+            Err(_) => return Err(KModError::InvalidManifest),
+        };
+        let id = manifest.id.clone();
+        self.add_mod_with_manifest(source, manifest)?;
+
+        Ok(id)
+    }
+
+    /// Add a mod enabled from a source and its manifest. Mod is added to broker by [KModManager::apply].
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KModError::ModAlreadyExists]`)` if a mod with the same identifier is in manager.
+    pub fn add_mod_with_manifest(&self, source : Arc<dyn KAssetSource + Send + Sync>, manifest : KModManifest) -> Result<(), KModError> {
+        let mut mods = self.lock_mods();
+
+        if mods.iter().any(|m| m.manifest.id == manifest.id) {
+            return Err(KModError::ModAlreadyExists);
+        }
+
+        mods.push(KMod { manifest, source, enabled : true, source_id : None });
+        Ok(())
+    }
+
+    /// Remove mod `id` from manager and its source from broker.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KModError::ModNotFound]`)` if mod is not found.
+    pub fn remove_mod(&self, id : &str) -> Result<(), KModError> {
+        let mut mods = self.lock_mods();
+
+        match mods.iter().position(|m| m.manifest.id == id) {
+            Some(position) => {
+                if let Some(source_id) = mods.remove(position).source_id {
+                    // Source may have been removed from broker by user
+                    let _ = self.broker.remove_source(source_id);
+                }
+
+                Ok(())
+            },
+            None => Err(KModError::ModNotFound),
+        }
+    }
+
+    /// Enable or disable mod `id`. Change is applied to broker by [KModManager::apply].
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KModError::ModNotFound]`)` if mod is not found.
+    pub fn set_mod_enabled(&self, id : &str, enabled : bool) -> Result<(), KModError> {
+        match self.lock_mods().iter_mut().find(|m| m.manifest.id == id) {
+            Some(m) => {
+                m.enabled = enabled;
+                Ok(())
+            },
+            None => Err(KModError::ModNotFound),
+        }
+    }
+
+    /// Verify if mod `id` is enabled.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KModError::ModNotFound]`)` if mod is not found.
+    pub fn is_mod_enabled(&self, id : &str) -> Result<bool, KModError> {
+        match self.lock_mods().iter().find(|m| m.manifest.id == id) {
+            Some(m) => Ok(m.enabled),
+            None => Err(KModError::ModNotFound),
+        }
+    }
+
+    /// Get identifiers of mods in order added.
+    pub fn get_mods(&self) -> Vec<String> {
+        self.lock_mods().iter().map(|m| m.manifest.id.clone()).collect()
+    }
+
+    /// Get manifest of mod `id`.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KModError::ModNotFound]`)` if mod is not found.
+    pub fn get_manifest(&self, id : &str) -> Result<KModManifest, KModError> {
+        match self.lock_mods().iter().find(|m| m.manifest.id == id) {
+            Some(m) => Ok(m.manifest.clone()),
+            None => Err(KModError::ModNotFound),
+        }
+    }
+
+    /// Get [KAssetSourceId] of mod `id` in broker, `None` if mod isn't loaded by [KModManager::apply].
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KModError::ModNotFound]`)` if mod is not found.
+    pub fn get_source_id(&self, id : &str) -> Result<Option<KAssetSourceId>, KModError> {
+        match self.lock_mods().iter().find(|m| m.manifest.id == id) {
+            Some(m) => Ok(m.source_id),
+            None => Err(KModError::ModNotFound),
+        }
+    }
+
+    /// Resolve load order of mods enabled without changing broker.
+    pub fn resolve(&self) -> KModResolution {
+        Self::resolve_mods(&self.lock_mods())
+    }
+
+    /// Resolve load order of mods and apply it to broker.
+    ///
+    /// Mods loaded are added to broker if needed, named with their identifier and version, and given priorities
+    /// after sink of broker, mod loaded last first.
+    /// Sources of mods not loaded are removed from broker.
+    ///
+    /// Returns `Ok(`[KModResolution]`)` applied.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KAssetBrokerError::SourceAlreadyExists]`)` if source of a mod was added to broker without manager.
+    ///
+    /// Returns `Err(`[KAssetBrokerError::SourceNotTrusted]`)` if source of a mod fails the trust policy of broker.
+    ///
+    /// Broker is left unchanged on error.
+    pub fn apply(&self) -> Result<KModResolution, KAssetBrokerError> {
+        let mut mods = self.lock_mods();
+        let resolution = Self::resolve_mods(&mods);
+
+        // Add mods loaded first, removing them all if one fails so broker is left unchanged
+        let mut added = Vec::new();
+        for (index, m) in mods.iter().enumerate() {
+            let loaded = resolution.order.contains(&m.manifest.id);
+            if !loaded || m.source_id.is_some_and(|source_id| self.broker.has_source(source_id)) {
+                continue;
+            }
+
+            let result = self.broker.add_source(m.source.clone()).and_then(|source_id| {
+                added.push((index, source_id));
+                self.broker.set_source_name(source_id, &format!("{} {}", m.manifest.id, m.manifest.version))
+            });
+
+            if let Err(err) = result {
+                for (_, source_id) in added {
+                    let _ = self.broker.remove_source(source_id);
+                }
+                return Err(err);
+            }
+        }
+
+        for (index, source_id) in added {
+            mods[index].source_id = Some(source_id);
+        }
+
+        // Then remove mods not loaded
+        for m in mods.iter_mut() {
+            if !resolution.order.contains(&m.manifest.id) {
+                if let Some(source_id) = m.source_id.take() {
+                    let _ = self.broker.remove_source(source_id);
+                }
+            }
+        }
+
+        // Sink keeps highest priority, then mods loaded last override mods loaded first
+        let mut priority = 0;
+        if let Some(sink_id) = self.broker.get_sink_id() {
+            self.broker.set_source_priority(sink_id, 0)?;
+            priority = 1;
+        }
+
+        for id in resolution.order.iter().rev() {
+            if let Some(source_id) = mods.iter().find(|m| m.manifest.id == *id).and_then(|m| m.source_id) {
+                self.broker.set_source_priority(source_id, priority)?;
+                priority += 1;
+            }
+        }
+
+        Ok(resolution)
+    }
+
+    /// Resolve load order of mods.
+    fn resolve_mods(mods : &[KMod]) -> KModResolution {
+        let manifests : HashMap<&str, &KModManifest> = mods.iter().map(|m| (m.manifest.id.as_str(), &m.manifest)).collect();
+        let mut loadable : Vec<&KModManifest> = mods.iter().filter(|m| m.enabled).map(|m| &m.manifest).collect();
+        let mut resolution = KModResolution::default();
+
+        // Remove mods with issues until no more mods are removed, since removing a mod can break its dependents
+        loop {
+            let ids : BTreeSet<&str> = loadable.iter().map(|m| m.id.as_str()).collect();
+            let issue = loadable.iter().find_map(|m| Self::find_issue(m, &manifests, &ids));
+
+            match issue {
+                Some((id, issue)) => {
+                    loadable.retain(|m| m.id != id);
+                    resolution.issues.push(issue);
+                },
+                None => break,
+            }
+        }
+
+        // Dependencies are hard edges : mods within a dependency cycle, or depending on one, can't be loaded
+        let ids : BTreeSet<&str> = loadable.iter().map(|m| m.id.as_str()).collect();
+        let mut after : HashMap<&str, BTreeSet<&str>> = loadable.iter().map(|m| {
+            (m.id.as_str(), m.dependencies.iter().map(|d| d.id.as_str()).filter(|id| ids.contains(id) && *id != m.id).collect())
+        }).collect();
+
+        let (_, cycle) = Self::sort_mods(after.clone());
+        if !cycle.is_empty() {
+            for id in &cycle {
+                after.remove(id);
+            }
+            resolution.issues.push(KModIssue::Cycle { ids : cycle.iter().map(|id| (*id).to_owned()).collect() });
+        }
+
+        // Hints only order mods : a hint creating a cycle contradicts dependencies or hints kept before it
+        loadable.sort_by(|a, b| a.id.cmp(&b.id));
+        for m in &loadable {
+            for hint in m.load_after.iter().map(String::as_str) {
+                if hint == m.id || !after.contains_key(hint) || !after.contains_key(m.id.as_str()) {
+                    continue;
+                }
+
+                if Self::is_loaded_after(&after, hint, &m.id) {
+                    resolution.ignored_hints.push((m.id.clone(), hint.to_owned()));
+                } else if let Some(mod_after) = after.get_mut(m.id.as_str()) {
+                    mod_after.insert(hint);
+                }
+            }
+        }
+
+        resolution.order = Self::sort_mods(after).0.into_iter().map(str::to_owned).collect();
+
+        resolution.failed = mods.iter().filter(|m| m.enabled && !resolution.order.contains(&m.manifest.id))
+            .map(|m| m.manifest.id.clone()).collect();
+
+        resolution
+    }
+
+    /// Sort mods topologically from mods each mod loads after, mods ready at the same time ordered by identifier.
+    ///
+    /// Returns `(order, cycle)` with mods sorted and mods left within a cycle or after one, ordered by identifier.
+    fn sort_mods<'a>(mut after : HashMap<&'a str, BTreeSet<&'a str>>) -> (Vec<&'a str>, Vec<&'a str>) {
+        let mut order = Vec::new();
+        let mut ready : BTreeSet<&str> = after.iter().filter(|(_, mod_after)| mod_after.is_empty()).map(|(id, _)| *id).collect();
+
+        while let Some(id) = ready.pop_first() {
+            after.remove(id);
+            order.push(id);
+
+            for (other, mod_after) in after.iter_mut() {
+                if mod_after.remove(id) && mod_after.is_empty() {
+                    ready.insert(other);
+                }
+            }
+        }
+
+        let mut cycle : Vec<&str> = after.into_keys().collect();
+        cycle.sort();
+        (order, cycle)
+    }
+
+    /// Verify if mod `id` loads after mod `other`, directly or through other mods.
+    fn is_loaded_after(after : &HashMap<&str, BTreeSet<&str>>, id : &str, other : &str) -> bool {
+        let mut visited = BTreeSet::new();
+        let mut stack = vec![id];
+
+        while let Some(id) = stack.pop() {
+            if id == other {
+                return true;
+            }
+
+            if visited.insert(id) {
+                stack.extend(after.get(id).into_iter().flatten());
+            }
+        }
+
+        false
+    }
+
+    /// Find first issue of mod with mods `ids` loadable.
+    fn find_issue<'a>(manifest : &'a KModManifest, manifests : &HashMap<&str, &KModManifest>, ids : &BTreeSet<&str>) -> Option<(&'a str, KModIssue)> {
+        let id = manifest.id.as_str();
+
+        for dependency in &manifest.dependencies {
+            let issue = match manifests.get(dependency.id.as_str()) {
+                None => KModIssue::MissingDependency { id : id.to_owned(), dependency : dependency.id.clone() },
+                Some(found) => match dependency.min_version {
+                    Some(required) if found.version < required => KModIssue::VersionMismatch { id : id.to_owned(),
+                        dependency : dependency.id.clone(), required, found : found.version },
+                    _ if !ids.contains(dependency.id.as_str()) => KModIssue::DisabledDependency { id : id.to_owned(), dependency : dependency.id.clone() },
+                    _ => continue,
+                },
+            };
+
+            return Some((id, issue));
+        }
+
+        manifest.incompatibilities.iter().find(|other| ids.contains(other.as_str()) && *other != id)
+            .map(|other| (id, KModIssue::Incompatible { id : id.to_owned(), other : other.clone() }))
+    }
+
+    /// Lock mods. A poisoned lock is recovered since mods are always left consistent.
+    fn lock_mods(&self) -> MutexGuard<'_, Vec<KMod>> {
+        self.mods.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
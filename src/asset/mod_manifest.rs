@@ -0,0 +1,269 @@
+use std::io::Read;
+
+use super::{KAssetSource, KAssetPath};
+
+/// Path of the manifest of a mod within its source.
+pub const KMOD_MANIFEST_PATH : &str = "mod.manifest";
+
+/// ##### Version of a mod, as `major.minor.patch`.
+///
+/// Missing parts are 0, so `1.2` is `1.2.0`. Versions are ordered by major, minor then patch.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct KModVersion {
+    /// Major version, changed for incompatible changes.
+    pub major : u32,
+
+    /// Minor version, changed for compatible additions.
+    pub minor : u32,
+
+    /// Patch version, changed for fixes.
+    pub patch : u32,
+}
+
+impl KModVersion {
+    /// Create a new [KModVersion].
+    pub fn new(major : u32, minor : u32, patch : u32) -> KModVersion {
+        KModVersion { major, minor, patch }
+    }
+
+    /// Parse a version like `1`, `1.2` or `1.2.3`.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KModManifestError::InvalidVersion]`)` if version isn't 1 to 3 numbers separated by dots.
+    pub fn parse(version : &str) -> Result<KModVersion, KModManifestError> {
+        let mut parts = [0u32; 3];
+        let split : Vec<&str> = version.trim().split('.').collect();
+
+        if split.len() > parts.len() {
+            return Err(KModManifestError::InvalidVersion);
+        }
+
+        for (value, part) in parts.iter_mut().zip(split) {
+            if part.is_empty() || !part.chars().all(|c| c.is_ascii_digit()) {
+                return Err(KModManifestError::InvalidVersion);
+            }
+
+            *value = part.parse().map_err(|_| KModManifestError::InvalidVersion)?;
+        }
+
+        Ok(KModVersion::new(parts[0], parts[1], parts[2]))
+    }
+}
+
+impl std::fmt::Display for KModVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl std::str::FromStr for KModVersion {
+    type Err = KModManifestError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        KModVersion::parse(s)
+    }
+}
+
+/// Dependency of a mod on another mod, with an optional minimum version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KModDependency {
+    /// Identifier of mod depended on.
+    pub id : String,
+
+    /// Minimum version of mod depended on, if any.
+    pub min_version : Option<KModVersion>,
+}
+
+/// ##### Manifest describing a mod : identity, dependencies, incompatibilities and load order hints.
+///
+/// Manifests are text assets named [KMOD_MANIFEST_PATH] at the root of mod sources, made of `key = value` lines.
+/// Lists are separated by commas, empty lines and lines starting with `#` are ignored, unknown keys are ignored.
+///
+/// | Key            | Value                                                              | Required |
+/// |----------------|--------------------------------------------------------------------|----------|
+/// | `id`           | Identifier of mod, made of letters, digits, `_`, `-` and `.`       | Yes      |
+/// | `version`      | [KModVersion] of mod                                               | Yes      |
+/// | `name`         | Name of mod shown to users, `id` by default                        | No       |
+/// | `depends`      | Mods required, loaded before, like `core >= 1.2, music`            | No       |
+/// | `incompatible` | Mods that can't be loaded with this mod                            | No       |
+/// | `after`        | Mods loaded before this mod if present, without requiring them     | No       |
+///
+/// # Example(s)
+/// ```
+/// use olympus_kleio::asset::{KModManifest, KModVersion};
+///
+/// let manifest = KModManifest::parse("
+///     ## Castle mod
+///     id = castle
+///     name = Castle Siege
+///     version = 1.2
+///     depends = core >= 1.0, music
+///     after = hd_textures
+/// ").unwrap();
+///
+/// assert_eq!(manifest.id, "castle");
+/// assert_eq!(manifest.version, KModVersion::new(1, 2, 0));
+/// assert_eq!(manifest.dependencies[0].min_version, Some(KModVersion::new(1, 0, 0)));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KModManifest {
+    /// Unique identifier of mod.
+    pub id : String,
+
+    /// Name of mod shown to users.
+    pub name : String,
+
+    /// Version of mod.
+    pub version : KModVersion,
+
+    /// Mods required by this mod, always loaded before it.
+    pub dependencies : Vec<KModDependency>,
+
+    /// Identifiers of mods that can't be loaded with this mod.
+    pub incompatibilities : Vec<String>,
+
+    /// Identifiers of mods loaded before this mod if present.
+    pub load_after : Vec<String>,
+}
+
+/// Enumeration of possible [KModManifest] errors.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum KModManifestError {
+    /// Happens when source has no manifest.
+    ManifestNotFound,
+
+    /// Happens when manifest couldn't be read or isn't valid UTF-8.
+    ReadError,
+
+    /// Happens when a line isn't a `key = value` pair or a key is given twice.
+    InvalidLine,
+
+    /// Happens when `id` or `version` is missing.
+    MissingField,
+
+    /// Happens when an identifier of mod is empty or has characters other than letters, digits, `_`, `-` and `.`.
+    InvalidId,
+
+    /// Happens when a version is invalid.
+    InvalidVersion,
+}
+
+impl std::fmt::Debug for KModManifestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ManifestNotFound => write!(f, "ManifestNotFound"),
+            Self::ReadError => write!(f, "ReadError"),
+            Self::InvalidLine => write!(f, "InvalidLine"),
+            Self::MissingField => write!(f, "MissingField"),
+            Self::InvalidId => write!(f, "InvalidId"),
+            Self::InvalidVersion => write!(f, "InvalidVersion"),
+        }
+    }
+}
+
+impl KModManifest {
+    /// Create a manifest of mod `id` at `version`, without dependencies.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KModManifestError::InvalidId]`)` if `id` is invalid.
+    pub fn new(id : &str, version : KModVersion) -> Result<KModManifest, KModManifestError> {
+        let id = Self::parse_id(id)?;
+        Ok(KModManifest { name : id.clone(), id, version, dependencies : Vec::new(), incompatibilities : Vec::new(), load_after : Vec::new() })
+    }
+
+    /// Parse a manifest from its text content.
+    ///
+    /// Returns `Ok(`[KModManifest]`)` if valid.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KModManifestError::InvalidLine]`)` if a line isn't a `key = value` pair or a key is given twice.
+    ///
+    /// Returns `Err(`[KModManifestError::MissingField]`)` if `id` or `version` is missing.
+    ///
+    /// Returns `Err(`[KModManifestError::InvalidId]`)` if an identifier is invalid.
+    ///
+    /// Returns `Err(`[KModManifestError::InvalidVersion]`)` if a version is invalid.
+    pub fn parse(content : &str) -> Result<KModManifest, KModManifestError> {
+        let mut keys : Vec<&str> = Vec::new();
+        let (mut id, mut name, mut version) = (None, None, None);
+        let mut manifest = KModManifest { id : String::new(), name : String::new(), version : KModVersion::default(),
+            dependencies : Vec::new(), incompatibilities : Vec::new(), load_after : Vec::new() };
+
+        for line in content.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')) {
+            let (key, value) = match line.split_once('=') {
+                Some((key, value)) => (key.trim(), value.trim()),
+                None => return Err(KModManifestError::InvalidLine),
+            };
+
+            if keys.contains(&key) {
+                return Err(KModManifestError::InvalidLine);
+            }
+            keys.push(key);
+
+            match key {
+                "id" => id = Some(Self::parse_id(value)?),
+                "name" => name = Some(value.to_owned()),
+                "version" => version = Some(KModVersion::parse(value)?),
+                "depends" => manifest.dependencies = Self::parse_list(value).map(Self::parse_dependency).collect::<Result<_, _>>()?,
+                "incompatible" => manifest.incompatibilities = Self::parse_list(value).map(Self::parse_id).collect::<Result<_, _>>()?,
+                "after" => manifest.load_after = Self::parse_list(value).map(Self::parse_id).collect::<Result<_, _>>()?,
+                _ => {},
+            }
+        }
+
+        match (id, version) {
+            (Some(id), Some(version)) => {
+                manifest.name = name.unwrap_or_else(|| id.clone());
+                manifest.id = id;
+                manifest.version = version;
+                Ok(manifest)
+            },
+            _ => Err(KModManifestError::MissingField),
+        }
+    }
+
+    /// Read and parse manifest [KMOD_MANIFEST_PATH] of a source.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KModManifestError::ManifestNotFound]`)` if source has no manifest.
+    ///
+    /// Returns `Err(`[KModManifestError::ReadError]`)` if manifest couldn't be read.
+    ///
+    /// Returns errors of [KModManifest::parse].
+    pub fn from_source(source : &dyn KAssetSource) -> Result<KModManifest, KModManifestError> {
+        let path = KAssetPath::new(KMOD_MANIFEST_PATH).map_err(|_| KModManifestError::ManifestNotFound)?;
+        if !source.has_asset(&path) {
+            return Err(KModManifestError::ManifestNotFound);
+        }
+
+        let mut content = String::new();
+        source.get_asset(&path).and_then(|mut reader| reader.read_to_string(&mut content))
+            .map_err(|_| KModManifestError::ReadError)?;
+
+        Self::parse(&content)
+    }
+
+    /// Split a comma separated list, ignoring empty items.
+    fn parse_list(value : &str) -> impl Iterator<Item = &str> {
+        value.split(',').map(str::trim).filter(|item| !item.is_empty())
+    }
+
+    /// Parse a dependency like `core` or `core >= 1.2`.
+    fn parse_dependency(value : &str) -> Result<KModDependency, KModManifestError> {
+        match value.split_once(">=") {
+            Some((id, version)) => Ok(KModDependency { id : Self::parse_id(id)?, min_version : Some(KModVersion::parse(version)?) }),
+            None => Ok(KModDependency { id : Self::parse_id(value)?, min_version : None }),
+        }
+    }
+
+    /// Verify identifier of mod.
+    fn parse_id(id : &str) -> Result<String, KModManifestError> {
+        let id = id.trim();
+
+        if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.')) {
+            return Err(KModManifestError::InvalidId);
+        }
+
+        Ok(id.to_owned())
+    }
+}
@@ -0,0 +1,359 @@
+use std::{path::{Path, PathBuf}, fs::{self, File}, io::{Read, Write, Seek, SeekFrom, BufWriter}, collections::HashSet};
+use super::codec::{deflate::deflate, crc32::crc32, sha256::KSha256, sha512::KSha512};
+use super::{KAssetPath, KSigningKey};
+use super::source_pak::{KPakEntry, KPakCompression, get_signed_message, KPAK_MAGIC, KPAK_VERSION, KPAK_HEADER_SIZE, KPAK_FLAG_SIGNED, KPAK_SIGNATURE_MAGIC};
+
+/// ##### Writer of Kleio packs (.kpak) read by [KAssetSourcePak](super::KAssetSourcePak).
+///
+/// Assets are added from a folder or from (path, [Read]) pairs, each with its own compression.
+/// Assets that don't shrink when compressed are stored uncompressed. The index is written
+/// sorted by path when [KPakWriter::finish] is called.
+///
+/// Packs are signed with a [KSigningKey] given to [KPakWriter::set_signing_key] before adding assets, so
+/// a [KTrustPolicy](super::KTrustPolicy) can tell official packs.
+///
+/// # Example(s)
+/// ```no_run
+/// // Import crate module
+/// use std::{path::PathBuf, io::Cursor};
+/// use olympus_kleio::asset::{KPakWriter, KPakCompression};
+///
+/// // Create pack file
+/// let mut writer = KPakWriter::create(PathBuf::from("/base.kpak")).unwrap();
+///
+/// // Add all files of a folder, compressed with deflate
+/// writer.set_compression(KPakCompression::Deflate);
+/// writer.add_folder(PathBuf::from("/base_folder")).unwrap();
+///
+/// // Add an asset from a reader without compression
+/// writer.add_asset_compressed(PathBuf::from("version.txt"), &mut Cursor::new("1.0.0"), KPakCompression::None).unwrap();
+///
+/// // Write index and header
+/// writer.finish().unwrap();
+/// ```
+pub struct KPakWriter<W : Write + Seek> {
+    // Pack output
+    writer : W,
+
+    // Position of pack start in output
+    base : u64,
+
+    // Position of next asset data relative to pack start
+    position : u64,
+
+    // Entries written
+    entries : Vec<KPakEntry>,
+
+    // Paths of entries written
+    paths : HashSet<String>,
+
+    // Compression of added assets
+    compression : KPakCompression,
+
+    // Key signing pack
+    signing : Option<KSigningKey>,
+}
+
+/// Enumeration of possible [KPakWriter] errors.
+pub enum KPakWriterError {
+    /// Happens when an IO error occurred while writing the pack.
+    WriteError,
+
+    /// Happens when an IO error occurred while reading an asset to add.
+    AssetReadError,
+
+    /// Happens when asset path is empty or not a valid [KAssetPath].
+    InvalidAssetPath,
+
+    /// Happens when adding an asset path already in the pack.
+    AssetAlreadyExists,
+
+    /// Happens when [`folder_path`][PathBuf] given to [KPakWriter::add_folder] is not found.
+    FolderNotFound,
+
+    /// Happens when [`folder_path`][PathBuf] given to [KPakWriter::add_folder] is not a folder.
+    PathIsNotFolder,
+
+    /// Happens when signing key is set after assets were added.
+    AssetsAlreadyAdded,
+}
+
+impl std::fmt::Debug for KPakWriterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::WriteError => write!(f, "WriteError"),
+            Self::AssetReadError => write!(f, "AssetReadError"),
+            Self::InvalidAssetPath => write!(f, "InvalidAssetPath"),
+            Self::AssetAlreadyExists => write!(f, "AssetAlreadyExists"),
+            Self::FolderNotFound => write!(f, "FolderNotFound"),
+            Self::PathIsNotFolder => write!(f, "PathIsNotFolder"),
+            Self::AssetsAlreadyAdded => write!(f, "AssetsAlreadyAdded"),
+        }
+    }
+}
+
+impl KPakWriter<BufWriter<File>> {
+    /// Create a new pack file at [`pak_path`][PathBuf]. Existing file is truncated.
+    ///
+    /// Returns `Ok(`[KPakWriter]`)` if successful.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KPakWriterError::WriteError]`)` if file couldn't be created.
+    pub fn create(pak_path : PathBuf) -> Result<KPakWriter<BufWriter<File>>, KPakWriterError> {
+        match File::create(pak_path) {
+            Ok(file) => KPakWriter::new(BufWriter::new(file)),
+            Err(_) => Err(KPakWriterError::WriteError),
+        }
+    }
+}
+
+impl<W : Write + Seek> KPakWriter<W> {
+    /// Create a new [KPakWriter] writing the pack at current position of `writer`.
+    ///
+    /// Added assets are stored without compression by default.
+    ///
+    /// Returns `Ok(`[KPakWriter]`)` if successful.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KPakWriterError::WriteError]`)` if header placeholder couldn't be written.
+    pub fn new(mut writer : W) -> Result<KPakWriter<W>, KPakWriterError> {
+
+        // Header is written when finished
+        let base = match writer.stream_position().and_then(|base| writer.write_all(&[0u8; KPAK_HEADER_SIZE]).map(|_| base)) {
+            Ok(base) => base,
+            Err(_) => return Err(KPakWriterError::WriteError),
+        };
+
+        Ok(KPakWriter {
+            writer,
+            base,
+            position: KPAK_HEADER_SIZE as u64,
+            entries: Vec::new(),
+            paths: HashSet::new(),
+            compression: KPakCompression::None,
+            signing: None,
+        })
+    }
+
+    /// Set the [KPakCompression] used by [KPakWriter::add_asset], [KPakWriter::add_assets] and [KPakWriter::add_folder].
+    pub fn set_compression(&mut self, compression : KPakCompression) {
+        self.compression = compression;
+    }
+
+    /// Get the [KPakCompression] used when adding assets.
+    pub fn get_compression(&self) -> KPakCompression {
+        self.compression
+    }
+
+    /// Sign pack with `key` when finished. Must be set before adding assets since assets are hashed as written.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KPakWriterError::AssetsAlreadyAdded]`)` if assets were already added.
+    pub fn set_signing_key(&mut self, key : KSigningKey) -> Result<(), KPakWriterError> {
+        if !self.entries.is_empty() {
+            return Err(KPakWriterError::AssetsAlreadyAdded);
+        }
+
+        self.signing = Some(key);
+        Ok(())
+    }
+
+    /// Get the count of assets added.
+    pub fn get_entry_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Add an asset read from `reader` at [`path`][PathBuf] in pack with current compression.
+    ///
+    /// # Error(s)
+    /// See [KPakWriter::add_asset_compressed].
+    pub fn add_asset(&mut self, path : PathBuf, reader : &mut dyn Read) -> Result<(), KPakWriterError> {
+        self.add_asset_compressed(path, reader, self.compression)
+    }
+
+    /// Add an asset read from `reader` at [`path`][PathBuf] in pack with given [KPakCompression].
+    ///
+    /// Asset is stored uncompressed if compression doesn't reduce its size.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KPakWriterError::InvalidAssetPath]`)` if path is empty or not a valid [KAssetPath].
+    ///
+    /// Returns `Err(`[KPakWriterError::AssetAlreadyExists]`)` if path is already in pack.
+    ///
+    /// Returns `Err(`[KPakWriterError::AssetReadError]`)` if asset couldn't be read.
+    ///
+    /// Returns `Err(`[KPakWriterError::WriteError]`)` if asset couldn't be written.
+    pub fn add_asset_compressed(&mut self, path : PathBuf, reader : &mut dyn Read, compression : KPakCompression) -> Result<(), KPakWriterError> {
+
+        let name = match KAssetPath::from_path(&path) {
+            Ok(path) if !path.is_root() && path.as_str().len() <= u16::MAX as usize => path.as_str().to_owned(),
+            _ => return Err(KPakWriterError::InvalidAssetPath),
+        };
+
+        if self.paths.contains(&name) {
+            return Err(KPakWriterError::AssetAlreadyExists);
+        }
+
+        let mut data = Vec::new();
+        if reader.read_to_end(&mut data).is_err() {
+            return Err(KPakWriterError::AssetReadError);
+        }
+
+        let compressed = match compression {
+            KPakCompression::None => None,
+            KPakCompression::Deflate => Some(deflate(&data)).filter(|compressed| compressed.len() < data.len()),
+        };
+
+        let mut hash = KSha256::new();
+        hash.update(&data);
+
+        let entry = KPakEntry {
+            path: name.clone(),
+            compression: if compressed.is_some() { compression } else { KPakCompression::None },
+            checksum: crc32(&data),
+            offset: self.position,
+            compressed_size: compressed.as_ref().map_or(data.len(), |c| c.len()) as u64,
+            size: data.len() as u64,
+            hash: hash.finish(),
+        };
+
+        let written = compressed.as_ref().unwrap_or(&data);
+        if self.writer.write_all(written).is_err() {
+            return Err(KPakWriterError::WriteError);
+        }
+
+        self.position += entry.compressed_size;
+        self.paths.insert(name);
+        self.entries.push(entry);
+
+        Ok(())
+    }
+
+    /// Add assets from an iterator of ([`path`][PathBuf], [Read]) pairs with current compression.
+    ///
+    /// Returns `Ok(usize)` with count of assets added.
+    ///
+    /// # Error(s)
+    /// See [KPakWriter::add_asset_compressed]. Assets added before the error stay in pack.
+    pub fn add_assets<I, R>(&mut self, assets : I) -> Result<usize, KPakWriterError> where I : IntoIterator<Item = (PathBuf, R)>, R : Read {
+        let mut count = 0;
+
+        for (path, mut reader) in assets {
+            self.add_asset(path, &mut reader)?;
+            count += 1;
+        }
+
+        Ok(count)
+    }
+
+    /// Add all files of a folder and its sub folders with current compression. Paths in pack are relative to `folder_path`.
+    ///
+    /// Returns `Ok(usize)` with count of assets added.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KPakWriterError::FolderNotFound]`)` if folder is not found.
+    ///
+    /// Returns `Err(`[KPakWriterError::PathIsNotFolder]`)` if [`folder_path`][PathBuf] is not a folder.
+    ///
+    /// See [KPakWriter::add_asset_compressed] for other errors.
+    pub fn add_folder(&mut self, folder_path : PathBuf) -> Result<usize, KPakWriterError> {
+
+        if !folder_path.exists() {
+            return Err(KPakWriterError::FolderNotFound);
+        }
+
+        if !folder_path.is_dir() {
+            return Err(KPakWriterError::PathIsNotFolder);
+        }
+
+        // Files are added in sorted order so packs are reproducible
+        let mut files = Vec::new();
+        Self::list_files(&folder_path, &mut files)?;
+        files.sort();
+
+        let mut count = 0;
+        for file_path in files {
+            let mut file = match File::open(&file_path) {
+                Ok(file) => file,
+                Err(_) => return Err(KPakWriterError::AssetReadError),
+            };
+
+            // Files listed are always within folder_path
+            let path = file_path.strip_prefix(&folder_path).unwrap().to_path_buf();
+            self.add_asset(path, &mut file)?;
+            count += 1;
+        }
+
+        Ok(count)
+    }
+
+    /// Write index and header of pack, followed by signature if signed.
+    ///
+    /// Returns `Ok(W)` with the writer given to [KPakWriter::new].
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KPakWriterError::WriteError]`)` if index or header couldn't be written.
+    pub fn finish(mut self) -> Result<W, KPakWriterError> {
+
+        self.entries.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let mut index = Vec::new();
+        for entry in &self.entries {
+            entry.write(&mut index);
+        }
+
+        let mut header = Vec::with_capacity(KPAK_HEADER_SIZE);
+        header.extend_from_slice(&KPAK_MAGIC);
+        header.extend_from_slice(&KPAK_VERSION.to_le_bytes());
+        header.extend_from_slice(&(if self.signing.is_some() { KPAK_FLAG_SIGNED } else { 0 }).to_le_bytes());
+        header.extend_from_slice(&(self.entries.len() as u32).to_le_bytes());
+        header.extend_from_slice(&crc32(&index).to_le_bytes());
+        header.extend_from_slice(&self.position.to_le_bytes());
+        header.extend_from_slice(&(index.len() as u64).to_le_bytes());
+
+        // Signature follows index
+        let mut trailer = Vec::new();
+        if let Some(key) = self.signing.take() {
+            let mut hash = KSha512::new();
+            hash.update(&index);
+            trailer.extend_from_slice(&KPAK_SIGNATURE_MAGIC);
+            trailer.extend_from_slice(&key.get_public_key().get_bytes());
+            trailer.extend_from_slice(&key.sign(&get_signed_message(&header, &hash.finish())));
+        }
+
+        let result = self.writer.write_all(&index)
+            .and_then(|_| self.writer.write_all(&trailer))
+            .and_then(|_| self.writer.seek(SeekFrom::Start(self.base)))
+            .and_then(|_| self.writer.write_all(&header))
+            .and_then(|_| self.writer.seek(SeekFrom::Start(self.base + self.position + (index.len() + trailer.len()) as u64)))
+            .and_then(|_| self.writer.flush());
+
+        match result {
+            Ok(_) => Ok(self.writer),
+            Err(_) => Err(KPakWriterError::WriteError),
+        }
+    }
+
+    /// Recursively list files of folder.
+    fn list_files(folder_path : &Path, files : &mut Vec<PathBuf>) -> Result<(), KPakWriterError> {
+        let entries = match fs::read_dir(folder_path) {
+            Ok(entries) => entries,
+            Err(_) => return Err(KPakWriterError::AssetReadError),
+        };
+
+        for entry in entries {
+            let path = match entry {
+                Ok(entry) => entry.path(),
+                Err(_) => return Err(KPakWriterError::AssetReadError),
+            };
+
+            if path.is_dir() {
+                Self::list_files(&path, files)?;
+            } else {
+                files.push(path);
+            }
+        }
+
+        Ok(())
+    }
+}
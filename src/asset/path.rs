@@ -0,0 +1,326 @@
+use std::path::{Path, PathBuf};
+
+/// ##### Canonical virtual path of an asset, relative to the root of a source.
+///
+/// KAssetPath is used by the whole asset API so a path named by a mod can never escape a source :
+/// * Absolute paths (`/file`, `\\server\file`, `C:\file`) are rejected.
+/// * Parent segments (`..`) are resolved and rejected if they escape the root.
+/// * `\` and `/` are both separators, normalised to `/`.
+/// * Empty and `.` segments are removed.
+///
+/// The empty path is the root of the source and is only meaningful as a folder.
+///
+/// Comparisons are case-sensitive. Sources like [KAssetSourceFolder](super::KAssetSourceFolder) can match
+/// paths case-insensitively.
+///
+/// # Example(s)
+/// ```
+/// use olympus_kleio::asset::{KAssetPath, KAssetPathError};
+///
+/// let path = KAssetPath::new(".\\textures//./stone/../wood.png").unwrap();
+/// assert_eq!(path.as_str(), "textures/wood.png");
+/// assert_eq!(path.get_extension(), Some("png"));
+///
+/// assert!(matches!(KAssetPath::new("/etc/passwd"), Err(KAssetPathError::AbsolutePath)));
+/// assert!(matches!(KAssetPath::new("mods/../../etc/passwd"), Err(KAssetPathError::ParentEscape)));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct KAssetPath(String);
+
+/// Enumeration of possible [KAssetPath] errors.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum KAssetPathError {
+    /// Happens when path is absolute, starts with a separator or with a drive prefix like `C:`.
+    AbsolutePath,
+
+    /// Happens when parent segments (`..`) escape the root.
+    ParentEscape,
+
+    /// Happens when path contains a nul byte, a control character or `:`.
+    InvalidCharacter,
+
+    /// Happens when path is not valid unicode.
+    NotUnicode,
+}
+
+impl std::fmt::Debug for KAssetPathError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AbsolutePath => write!(f, "AbsolutePath"),
+            Self::ParentEscape => write!(f, "ParentEscape"),
+            Self::InvalidCharacter => write!(f, "InvalidCharacter"),
+            Self::NotUnicode => write!(f, "NotUnicode"),
+        }
+    }
+}
+
+impl std::fmt::Display for KAssetPathError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AbsolutePath => write!(f, "Asset path is absolute!"),
+            Self::ParentEscape => write!(f, "Asset path escapes root!"),
+            Self::InvalidCharacter => write!(f, "Asset path contains an invalid character!"),
+            Self::NotUnicode => write!(f, "Asset path is not valid unicode!"),
+        }
+    }
+}
+
+impl std::error::Error for KAssetPathError {}
+
+impl From<KAssetPathError> for std::io::Error {
+    /// Invalid paths are IO errors of kind [InvalidInput](std::io::ErrorKind::InvalidInput) wrapping [KAssetPathError].
+    fn from(err: KAssetPathError) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, err)
+    }
+}
+
+impl KAssetPath {
+    /// Create a [KAssetPath] from a path using `/` or `\` as separators.
+    ///
+    /// Returns `Ok(`[KAssetPath]`)` canonicalised.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KAssetPathError::AbsolutePath]`)` if path is absolute.
+    ///
+    /// Returns `Err(`[KAssetPathError::ParentEscape]`)` if parent segments escape the root.
+    ///
+    /// Returns `Err(`[KAssetPathError::InvalidCharacter]`)` if path contains a nul byte, a control character or `:`.
+    pub fn new(path : &str) -> Result<KAssetPath, KAssetPathError> {
+
+        if path.starts_with(['/', '\\']) {
+            return Err(KAssetPathError::AbsolutePath);
+        }
+
+        // Drive prefix like `C:`
+        let bytes = path.as_bytes();
+        if bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' {
+            return Err(KAssetPathError::AbsolutePath);
+        }
+
+        if path.chars().any(|c| c.is_control() || c == ':') {
+            return Err(KAssetPathError::InvalidCharacter);
+        }
+
+        let mut segments : Vec<&str> = Vec::new();
+        for segment in path.split(['/', '\\']) {
+            match segment {
+                "" | "." => {},
+                ".." => if segments.pop().is_none() {
+                    return Err(KAssetPathError::ParentEscape);
+                },
+                _ => segments.push(segment),
+            }
+        }
+
+        Ok(KAssetPath(segments.join("/")))
+    }
+
+    /// Create a [KAssetPath] from a [Path].
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KAssetPathError::NotUnicode]`)` if path is not valid unicode, or errors of [KAssetPath::new].
+    pub fn from_path(path : &Path) -> Result<KAssetPath, KAssetPathError> {
+        if path.has_root() {
+            return Err(KAssetPathError::AbsolutePath);
+        }
+
+        match path.to_str() {
+            Some(path) => KAssetPath::new(path),
+            None => Err(KAssetPathError::NotUnicode),
+        }
+    }
+
+    /// Get the root path, which is empty.
+    pub fn root() -> KAssetPath {
+        KAssetPath(String::new())
+    }
+
+    /// Verify if path is the root.
+    pub fn is_root(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Get path as a string using `/` as separator.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Get path as a relative [PathBuf].
+    pub fn to_path_buf(&self) -> PathBuf {
+        PathBuf::from(&self.0)
+    }
+
+    /// Get segments of path.
+    pub fn get_segments(&self) -> impl Iterator<Item = &str> {
+        self.0.split('/').filter(|segment| !segment.is_empty())
+    }
+
+    /// Get the last segment of path, or `None` for the root.
+    pub fn get_file_name(&self) -> Option<&str> {
+        self.get_segments().last()
+    }
+
+    /// Get the extension of the last segment of path, without dot.
+    pub fn get_extension(&self) -> Option<&str> {
+        let name = self.get_file_name()?;
+
+        match name.rfind('.') {
+            Some(0) | None => None,
+            Some(dot) => Some(&name[dot + 1..]),
+        }
+    }
+
+    /// Get the parent folder of path, or `None` for the root.
+    pub fn get_parent(&self) -> Option<KAssetPath> {
+        if self.is_root() {
+            return None;
+        }
+
+        match self.0.rfind('/') {
+            Some(separator) => Some(KAssetPath(self.0[..separator].to_owned())),
+            None => Some(KAssetPath::root()),
+        }
+    }
+
+    /// Join a relative `path` to this path.
+    ///
+    /// # Error(s)
+    /// Returns errors of [KAssetPath::new] for the path joined.
+    pub fn join(&self, path : &str) -> Result<KAssetPath, KAssetPathError> {
+        if path.starts_with(['/', '\\']) {
+            return Err(KAssetPathError::AbsolutePath);
+        }
+
+        if self.is_root() {
+            return KAssetPath::new(path);
+        }
+
+        KAssetPath::new(&format!("{}/{}", self.0, path))
+    }
+
+    /// Join a [KAssetPath] to this path. Never fails since both paths are canonical.
+    pub fn join_path(&self, path : &KAssetPath) -> KAssetPath {
+        match (self.is_root(), path.is_root()) {
+            (true, _) => path.clone(),
+            (false, true) => self.clone(),
+            (false, false) => KAssetPath(format!("{}/{}", self.0, path.0)),
+        }
+    }
+
+    /// Verify if path is within `folder` or its sub folders. Every path is within the root.
+    pub fn is_within(&self, folder : &KAssetPath) -> bool {
+        folder.is_root() || self.0.strip_prefix(folder.as_str()).is_some_and(|rest| rest.starts_with('/'))
+    }
+
+    /// Get path relative to `folder`, or `None` if path isn't within `folder`.
+    pub fn strip_folder(&self, folder : &KAssetPath) -> Option<KAssetPath> {
+        if folder.is_root() {
+            return Some(self.clone());
+        }
+
+        let rest = self.0.strip_prefix(folder.as_str())?.strip_prefix('/')?;
+        Some(KAssetPath(rest.to_owned()))
+    }
+
+    /// Verify if both paths are equal, ignoring case.
+    pub fn eq_ignore_case(&self, other : &KAssetPath) -> bool {
+        self.0.to_lowercase() == other.0.to_lowercase()
+    }
+}
+
+impl std::fmt::Display for KAssetPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl AsRef<str> for KAssetPath {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for KAssetPath {
+    fn as_ref(&self) -> &Path {
+        Path::new(&self.0)
+    }
+}
+
+impl PartialEq<str> for KAssetPath {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for KAssetPath {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl std::str::FromStr for KAssetPath {
+    type Err = KAssetPathError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        KAssetPath::new(s)
+    }
+}
+
+/// ##### Conversion into a [KAssetPath], accepted by [KAssetBroker](super::KAssetBroker) and other asset APIs.
+///
+/// Implemented for [KAssetPath], [str], [String], [Path] and [PathBuf] so paths can be given in any form.
+pub trait KIntoAssetPath {
+    /// Convert into a [KAssetPath].
+    ///
+    /// # Error(s)
+    /// Returns [KAssetPathError] if path is invalid.
+    fn into_asset_path(self) -> Result<KAssetPath, KAssetPathError>;
+}
+
+impl KIntoAssetPath for KAssetPath {
+    fn into_asset_path(self) -> Result<KAssetPath, KAssetPathError> {
+        Ok(self)
+    }
+}
+
+impl KIntoAssetPath for &KAssetPath {
+    fn into_asset_path(self) -> Result<KAssetPath, KAssetPathError> {
+        Ok(self.clone())
+    }
+}
+
+impl KIntoAssetPath for &str {
+    fn into_asset_path(self) -> Result<KAssetPath, KAssetPathError> {
+        KAssetPath::new(self)
+    }
+}
+
+impl KIntoAssetPath for String {
+    fn into_asset_path(self) -> Result<KAssetPath, KAssetPathError> {
+        KAssetPath::new(&self)
+    }
+}
+
+impl KIntoAssetPath for &String {
+    fn into_asset_path(self) -> Result<KAssetPath, KAssetPathError> {
+        KAssetPath::new(self)
+    }
+}
+
+impl KIntoAssetPath for &Path {
+    fn into_asset_path(self) -> Result<KAssetPath, KAssetPathError> {
+        KAssetPath::from_path(self)
+    }
+}
+
+impl KIntoAssetPath for PathBuf {
+    fn into_asset_path(self) -> Result<KAssetPath, KAssetPathError> {
+        KAssetPath::from_path(&self)
+    }
+}
+
+impl KIntoAssetPath for &PathBuf {
+    fn into_asset_path(self) -> Result<KAssetPath, KAssetPathError> {
+        KAssetPath::from_path(self)
+    }
+}
@@ -0,0 +1,309 @@
+use std::{io::{Read, ErrorKind}, collections::HashSet, sync::Arc, time::{Duration, Instant}};
+use super::{KAssetBroker, KAssetPath, KAssetPathError, KIntoAssetPath, KAssetStreamer, KAssetLoadHandle, KAssetLoadError};
+
+/// Enumeration of possible errors of a [KAssetBundle] manifest.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum KAssetBundleError {
+    /// Happens when no source of broker has the manifest.
+    ManifestNotFound,
+
+    /// Happens when manifest couldn't be read or isn't valid UTF-8.
+    ReadError,
+
+    /// Happens when a path of manifest is invalid or the root.
+    InvalidPath,
+}
+
+impl std::fmt::Debug for KAssetBundleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ManifestNotFound => write!(f, "ManifestNotFound"),
+            Self::ReadError => write!(f, "ReadError"),
+            Self::InvalidPath => write!(f, "InvalidPath"),
+        }
+    }
+}
+
+/// ##### Set of assets preloaded together with [KAssetStreamer::preload], like assets of a level.
+///
+/// A bundle is a list of paths, or a manifest read through a [KAssetBroker] with [KAssetBundle::from_broker].
+/// Manifests have one path per line. Empty lines and lines starting with `#` are ignored.
+///
+/// ```text
+/// # Assets of level 1
+/// levels/1/terrain.raw
+/// levels/1/music.ogg
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KAssetBundle {
+    // Paths of assets, in order added without duplicates
+    paths : Vec<KAssetPath>,
+
+    // Paths of assets, to find duplicates
+    path_set : HashSet<KAssetPath>,
+}
+
+impl KAssetBundle {
+    /// Create an empty [KAssetBundle].
+    pub fn new() -> KAssetBundle {
+        KAssetBundle::default()
+    }
+
+    /// Create a [KAssetBundle] of paths given.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KAssetPathError]`)` if a path is invalid.
+    pub fn from_paths<P : KIntoAssetPath>(paths : impl IntoIterator<Item = P>) -> Result<KAssetBundle, KAssetPathError> {
+        let mut bundle = KAssetBundle::new();
+
+        for path in paths {
+            bundle.add(path)?;
+        }
+
+        Ok(bundle)
+    }
+
+    /// Parse a manifest of one path per line.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KAssetBundleError::InvalidPath]`)` if a path is invalid or the root.
+    pub fn parse(content : &str) -> Result<KAssetBundle, KAssetBundleError> {
+        let mut bundle = KAssetBundle::new();
+
+        for line in content.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')) {
+            match KAssetPath::new(line) {
+                Ok(path) if !path.is_root() => bundle.insert(path),
+                _ => return Err(KAssetBundleError::InvalidPath),
+            }
+        }
+
+        Ok(bundle)
+    }
+
+    /// Read and parse manifest at [`path`][KIntoAssetPath] through `broker`, so manifests can be overridden by mods.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KAssetBundleError::ManifestNotFound]`)` if path is invalid or no source has manifest.
+    ///
+    /// Returns `Err(`[KAssetBundleError::ReadError]`)` if manifest couldn't be read.
+    ///
+    /// Returns errors of [KAssetBundle::parse].
+    pub fn from_broker(broker : &KAssetBroker, path : impl KIntoAssetPath) -> Result<KAssetBundle, KAssetBundleError> {
+        let mut reader = match broker.get_asset(path) {
+            Ok(reader) => reader,
+            Err(err) if matches!(err.kind(), ErrorKind::NotFound | ErrorKind::InvalidInput) => return Err(KAssetBundleError::ManifestNotFound),
+            Err(_) => return Err(KAssetBundleError::ReadError),
+        };
+
+        let mut content = String::new();
+        reader.read_to_string(&mut content).map_err(|_| KAssetBundleError::ReadError)?;
+
+        Self::parse(&content)
+    }
+
+    /// Add asset at [`path`][KIntoAssetPath] to bundle. Adding a path twice does nothing.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KAssetPathError]`)` if path is invalid.
+    pub fn add(&mut self, path : impl KIntoAssetPath) -> Result<(), KAssetPathError> {
+        self.insert(path.into_asset_path()?);
+        Ok(())
+    }
+
+    /// Get paths of assets in order added.
+    pub fn get_paths(&self) -> &[KAssetPath] {
+        &self.paths
+    }
+
+    /// Get the count of assets in bundle.
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    /// Returns true if bundle has no asset.
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    /// Insert path if not in bundle.
+    fn insert(&mut self, path : KAssetPath) {
+        if self.path_set.insert(path.clone()) {
+            self.paths.push(path);
+        }
+    }
+}
+
+/// ##### Progress of a [KAssetPreload], given by [KAssetPreload::get_progress].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KAssetPreloadProgress {
+    /// Count of assets of bundle.
+    pub total_assets : usize,
+
+    /// Count of assets loaded.
+    pub loaded_assets : usize,
+
+    /// Count of assets that couldn't be loaded.
+    pub failed_assets : usize,
+
+    /// Count of assets cancelled.
+    pub cancelled_assets : usize,
+
+    /// Size of assets loaded in bytes. Size of assets not loaded yet is unknown until they are read.
+    pub loaded_bytes : u64,
+
+    /// Time elapsed since preload started.
+    pub elapsed : Duration,
+
+    /// Estimated time remaining, or `None` before any asset is done.
+    pub eta : Option<Duration>,
+}
+
+impl KAssetPreloadProgress {
+    /// Get progress from 0.0 to 1.0 by assets, since sizes of assets not loaded yet are unknown.
+    /// Assets failed and cancelled count as done.
+    pub fn get_ratio(&self) -> f64 {
+        match self.total_assets > 0 {
+            true => self.get_done_assets() as f64 / self.total_assets as f64,
+            false => 1.0,
+        }
+    }
+
+    /// Get the count of assets loaded, failed or cancelled.
+    pub fn get_done_assets(&self) -> usize {
+        self.loaded_assets + self.failed_assets + self.cancelled_assets
+    }
+
+    /// Verify if every asset is loaded, failed or cancelled.
+    pub fn is_done(&self) -> bool {
+        self.get_done_assets() == self.total_assets
+    }
+}
+
+/// ##### Preload of a [KAssetBundle] started with [KAssetStreamer::preload].
+///
+/// Assets are requested to the streamer at once and loaded by its workers. Loaded assets are kept by their
+/// [KAssetLoadHandle] until preload is dropped, and by the [KAssetCache](super::KAssetCache) of broker if any.
+///
+/// # Example(s)
+/// ```
+/// use std::sync::Arc;
+/// use olympus_kleio::asset::{KAssetBroker, KAssetSourceMemory, KAssetStreamer, KAssetBundle, KAssetLoadError};
+///
+/// let source = KAssetSourceMemory::new("level");
+/// source.add_asset("levels/1.bundle", "# Level 1\nlevels/1/terrain.raw\nlevels/1/music.ogg\n".as_bytes().to_vec()).unwrap();
+/// source.add_asset("levels/1/terrain.raw", vec![0; 1024]).unwrap();
+///
+/// let broker = Arc::new(KAssetBroker::new());
+/// broker.add_source(Arc::new(source)).unwrap();
+///
+/// // Bundle is declared by a manifest read through broker
+/// let bundle = KAssetBundle::from_broker(&broker, "levels/1.bundle").unwrap();
+/// let streamer = KAssetStreamer::new(broker, 2).unwrap();
+/// let preload = streamer.preload(&bundle, 0);
+///
+/// // Loading screen polls progress each frame, here it waits
+/// let progress = preload.wait();
+/// assert_eq!((progress.loaded_assets, progress.failed_assets), (1, 1));
+/// assert_eq!(progress.loaded_bytes, 1024);
+/// assert_eq!(preload.get_failures()[0].1, KAssetLoadError::NotFound);
+/// ```
+pub struct KAssetPreload {
+    // Handles of requests in order of bundle
+    handles : Vec<KAssetLoadHandle>,
+
+    // Instant preload started
+    started : Instant,
+}
+
+impl KAssetPreload {
+    /// Get current [KAssetPreloadProgress] of preload without blocking.
+    pub fn get_progress(&self) -> KAssetPreloadProgress {
+        let mut progress = KAssetPreloadProgress { total_assets : self.handles.len(), loaded_assets : 0, failed_assets : 0,
+            cancelled_assets : 0, loaded_bytes : 0, elapsed : self.started.elapsed(), eta : None };
+
+        for handle in &self.handles {
+            match handle.poll() {
+                Some(Ok(data)) => {
+                    progress.loaded_assets += 1;
+                    progress.loaded_bytes += data.len() as u64;
+                },
+                Some(Err(KAssetLoadError::Cancelled)) => progress.cancelled_assets += 1,
+                Some(Err(_)) => progress.failed_assets += 1,
+                None => {},
+            }
+        }
+
+        // Remaining time is extrapolated from time taken by assets done, pending assets counting as not done
+        let ratio = progress.get_ratio();
+        if progress.is_done() {
+            progress.eta = Some(Duration::ZERO);
+        } else if ratio > 0.0 {
+            progress.eta = Some(progress.elapsed.mul_f64((1.0 - ratio) / ratio));
+        }
+
+        progress
+    }
+
+    /// Verify if every asset is loaded, failed or cancelled.
+    pub fn is_done(&self) -> bool {
+        self.handles.iter().all(|handle| handle.is_done())
+    }
+
+    /// Block until every asset is loaded, failed or cancelled.
+    ///
+    /// Returns final [KAssetPreloadProgress].
+    pub fn wait(&self) -> KAssetPreloadProgress {
+        for handle in &self.handles {
+            let _ = handle.wait();
+        }
+
+        self.get_progress()
+    }
+
+    /// Cancel assets not loaded yet, like when the player backs out of a loading screen. Assets being read by
+    /// a worker are discarded once read.
+    ///
+    /// Returns the count of assets cancelled.
+    pub fn cancel(&self) -> usize {
+        self.handles.iter().filter(|handle| handle.cancel()).count()
+    }
+
+    /// Get paths of assets that couldn't be loaded with their [KAssetLoadError], in order of bundle.
+    /// Assets cancelled aren't failures.
+    pub fn get_failures(&self) -> Vec<(KAssetPath, KAssetLoadError)> {
+        self.handles.iter().filter_map(|handle| match handle.poll() {
+            Some(Err(KAssetLoadError::Cancelled)) => None,
+            Some(Err(err)) => Some((handle.get_path().clone(), err)),
+            _ => None,
+        }).collect()
+    }
+
+    /// Get data of asset at path if loaded, or `None` if asset isn't loaded or isn't in bundle.
+    pub fn get_data(&self, path : impl KIntoAssetPath) -> Option<Arc<[u8]>> {
+        let path = path.into_asset_path().ok()?;
+
+        match self.handles.iter().find(|handle| *handle.get_path() == path)?.poll() {
+            Some(Ok(data)) => Some(data),
+            _ => None,
+        }
+    }
+
+    /// Get [KAssetLoadHandle] of each asset in order of bundle.
+    pub fn get_handles(&self) -> &[KAssetLoadHandle] {
+        &self.handles
+    }
+}
+
+impl KAssetStreamer {
+    /// Preload every asset of `bundle` with `priority`, in order of bundle. 0 is the highest priority.
+    ///
+    /// Returns a [KAssetPreload] right away without touching sources, reporting progress while assets are loaded by workers.
+    pub fn preload(&self, bundle : &KAssetBundle, priority : u32) -> KAssetPreload {
+        let started = Instant::now();
+
+        // Paths of bundle are valid, so requests never fail
+        let handles = bundle.get_paths().iter().filter_map(|path| self.request(path, priority).ok()).collect();
+
+        KAssetPreload { handles, started }
+    }
+}
@@ -0,0 +1,258 @@
+use super::{KAssetBroker, KAssetSourceId, KAssetPath, KIntoAssetPath, KTrustError};
+
+/// Source of a [KAssetBroker] as shown in reports.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KAssetSourceInfo {
+    /// Identifier of source.
+    pub id : KAssetSourceId,
+
+    /// Name of source given by [KAssetBroker::set_source_name], empty if not named.
+    pub name : String,
+
+    /// Priority of source, 0 being the highest.
+    pub priority : usize,
+
+    /// Mount point of source.
+    pub mount : KAssetPath,
+}
+
+impl std::fmt::Display for KAssetSourceInfo {
+    /// Name of source, or `#` followed by its identifier if not named.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.name.is_empty() {
+            true => write!(f, "#{}", self.id.get_value()),
+            false => write!(f, "{}", self.name),
+        }
+    }
+}
+
+/// Status of a source for an asset path, given by [KAssetBroker::get_provenance].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KAssetProvenanceStatus {
+    /// Source serves asset since it's the source of highest priority having it.
+    Serves,
+
+    /// Source serves asset from [KAssetCache](super::KAssetCache) since it's the source of highest priority cached.
+    ServesCached,
+
+    /// Source serves asset decompressed from its compressed asset, see [KAssetDecompression](super::KAssetDecompression).
+    ServesDecompressed,
+
+    /// Source has asset but a source of higher priority serves it.
+    Shadowed,
+
+    /// Source is mounted over path but doesn't have asset.
+    Missing,
+
+    /// Path is outside of mount point of source.
+    NotMounted,
+
+    /// Source is disabled by [KTrustPolicy](super::KTrustPolicy) for the reason given.
+    Disabled(KTrustError),
+}
+
+impl std::fmt::Display for KAssetProvenanceStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Serves => write!(f, "serves"),
+            Self::ServesCached => write!(f, "serves (cached)"),
+            Self::ServesDecompressed => write!(f, "serves (decompressed)"),
+            Self::Shadowed => write!(f, "shadowed"),
+            Self::Missing => write!(f, "missing"),
+            Self::NotMounted => write!(f, "not mounted"),
+            Self::Disabled(err) => write!(f, "disabled ({})", err),
+        }
+    }
+}
+
+/// ##### Provenance of an asset path : which source serves it and why, given by [KAssetBroker::get_provenance].
+///
+/// Lists every source of broker by priority with its [KAssetProvenanceStatus]. [Display](std::fmt::Display)
+/// writes a human-readable table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KAssetProvenance {
+    /// Path of asset requested.
+    pub path : KAssetPath,
+
+    /// Path asset resolves to with locale and tags, like [KAssetBroker::get_asset] would, and looked up in sources.
+    pub resolved : KAssetPath,
+
+    /// Sources of broker ordered by priority with their status for path.
+    pub sources : Vec<(KAssetSourceInfo, KAssetProvenanceStatus)>,
+}
+
+impl KAssetProvenance {
+    /// Get source serving asset, or `None` if no source has asset.
+    pub fn get_source(&self) -> Option<&KAssetSourceInfo> {
+        self.sources.iter().find(|(_, status)| matches!(status, KAssetProvenanceStatus::Serves | KAssetProvenanceStatus::ServesCached
+            | KAssetProvenanceStatus::ServesDecompressed))
+            .map(|(source, _)| source)
+    }
+
+    /// Get sources having asset that are shadowed by the source serving it, ordered by priority.
+    pub fn get_shadowed(&self) -> Vec<&KAssetSourceInfo> {
+        self.sources.iter().filter(|(_, status)| *status == KAssetProvenanceStatus::Shadowed).map(|(source, _)| source).collect()
+    }
+}
+
+impl std::fmt::Display for KAssetProvenance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.get_source() {
+            Some(source) => write!(f, "Asset {} served by {}", self.path, source)?,
+            None => write!(f, "Asset {} not found", self.path)?,
+        }
+
+        match self.resolved != self.path {
+            true => writeln!(f, " as {}", self.resolved)?,
+            false => writeln!(f)?,
+        }
+
+        let rows : Vec<Vec<String>> = self.sources.iter().map(|(source, status)| vec![source.priority.to_string(), source.to_string(),
+            format!("/{}", source.mount), status.to_string()]).collect();
+        write_table(f, &["Priority", "Source", "Mount", "Status"], &rows)
+    }
+}
+
+/// Asset overridden by its source over sources of lower priority.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KAssetOverride {
+    /// Path of asset.
+    pub path : KAssetPath,
+
+    /// Source serving asset.
+    pub source : KAssetSourceInfo,
+
+    /// Sources of lower priority also having asset, ordered by priority.
+    pub shadowed : Vec<KAssetSourceInfo>,
+}
+
+/// ##### Assets present in more than one source, given by [KAssetBroker::get_override_report].
+///
+/// [Display](std::fmt::Display) writes a human-readable table.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KAssetOverrideReport {
+    /// Assets overridden, sorted by path.
+    pub overrides : Vec<KAssetOverride>,
+}
+
+impl std::fmt::Display for KAssetOverrideReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{} assets overridden", self.overrides.len())?;
+
+        let rows : Vec<Vec<String>> = self.overrides.iter().map(|entry| vec![entry.path.to_string(), entry.source.to_string(),
+            entry.shadowed.iter().map(|source| source.to_string()).collect::<Vec<_>>().join(", ")]).collect();
+        write_table(f, &["Asset", "Source", "Shadowed"], &rows)
+    }
+}
+
+impl KAssetBroker {
+    /// Get provenance of asset at path : which source serves it, which sources are shadowed and why.
+    ///
+    /// Path is resolved and status are the same as [KAssetBroker::get_asset] would use : localized path, then
+    /// tagged variant, then [KAssetCache](super::KAssetCache), asset of source and decompressed asset of source.
+    ///
+    /// # Error(s)
+    /// Returns [std::io::Error] of kind [InvalidInput](std::io::ErrorKind::InvalidInput) if path is invalid.
+    ///
+    /// # Example(s)
+    /// ```
+    /// // Import crate module
+    /// use std::sync::Arc;
+    /// use olympus_kleio::asset::{KAssetBroker, KAssetSourceMemory, KAssetProvenanceStatus};
+    ///
+    /// let base = KAssetSourceMemory::new("base");
+    /// base.add_asset("textures/wall.png", vec![1]).unwrap();
+    /// let castle = KAssetSourceMemory::new("castle");
+    /// castle.add_asset("textures/wall.png", vec![2]).unwrap();
+    ///
+    /// let broker = KAssetBroker::new();
+    /// let castle_id = broker.add_source(Arc::new(castle)).unwrap();
+    /// let base_id = broker.add_source(Arc::new(base)).unwrap();
+    /// broker.set_source_name(castle_id, "castle").unwrap();
+    /// broker.set_source_name(base_id, "base").unwrap();
+    ///
+    /// let provenance = broker.get_provenance("textures/wall.png").unwrap();
+    /// assert_eq!(provenance.get_source().unwrap().id, castle_id);
+    /// assert_eq!(provenance.sources[1].1, KAssetProvenanceStatus::Shadowed);
+    ///
+    /// // Asset textures/wall.png served by castle
+    /// // Priority | Source | Mount | Status
+    /// // ---------+--------+-------+---------
+    /// // 0        | castle | /     | serves
+    /// // 1        | base   | /     | shadowed
+    /// println!("{}", provenance);
+    /// ```
+    pub fn get_provenance(&self, path : impl KIntoAssetPath) -> Result<KAssetProvenance, std::io::Error> {
+        let requested = path.into_asset_path()?;
+        let path = self.get_resolved_path(&requested);
+        let cache = self.get_cache();
+        let decompression = self.get_decompression();
+        let mut served = false;
+        let mut sources = Vec::new();
+
+        for (priority, src) in self.get_all_sources_snapshot().into_iter().enumerate() {
+            let cached = cache.as_ref().is_some_and(|cache| cache.is_cached(src.id, &path));
+
+            let status = match (src.distrust, src.get_local_path(&path)) {
+                (Some(err), _) => KAssetProvenanceStatus::Disabled(err),
+                (None, None) => KAssetProvenanceStatus::NotMounted,
+                (None, Some(_)) if cached && !served => KAssetProvenanceStatus::ServesCached,
+                (None, Some(local)) if src.source.has_asset(&local) => match served {
+                    true => KAssetProvenanceStatus::Shadowed,
+                    false => KAssetProvenanceStatus::Serves,
+                },
+                (None, Some(local)) if decompression.as_ref().is_some_and(|decompression| decompression.find_asset(&src, &local).is_some()) => match served {
+                    true => KAssetProvenanceStatus::Shadowed,
+                    false => KAssetProvenanceStatus::ServesDecompressed,
+                },
+                (None, Some(_)) => KAssetProvenanceStatus::Missing,
+            };
+
+            served |= matches!(status, KAssetProvenanceStatus::Serves | KAssetProvenanceStatus::ServesCached | KAssetProvenanceStatus::ServesDecompressed);
+            sources.push((KAssetSourceInfo { id : src.id, name : src.name, priority, mount : src.mount }, status));
+        }
+
+        Ok(KAssetProvenance { path : requested, resolved : path, sources })
+    }
+
+    /// Get assets matching glob `pattern` present in more than one source, with source serving them and sources shadowed.
+    ///
+    /// See [KAssetBroker::list_assets] for pattern syntax. Use `**` for all assets.
+    ///
+    /// # Error(s)
+    /// Returns errors of [KAssetBroker::list_assets].
+    pub fn get_override_report(&self, pattern : &str) -> Result<KAssetOverrideReport, std::io::Error> {
+        let sources : Vec<KAssetSourceInfo> = self.get_all_sources_snapshot().into_iter().enumerate()
+            .map(|(priority, src)| KAssetSourceInfo { id : src.id, name : src.name, priority, mount : src.mount }).collect();
+        let get_info = |id : KAssetSourceId| sources.iter().find(|source| source.id == id).cloned();
+
+        let overrides = self.list_assets(pattern)?.into_iter().filter(|entry| !entry.overridden.is_empty()).filter_map(|entry| {
+            // Sources removed while listing are ignored
+            Some(KAssetOverride { source : get_info(entry.source)?, shadowed : entry.overridden.iter().filter_map(|id| get_info(*id)).collect(),
+                path : entry.path })
+        }).collect();
+
+        Ok(KAssetOverrideReport { overrides })
+    }
+}
+
+/// Write rows as a table with `headers`, columns separated by `|`.
+pub(crate) fn write_table(f : &mut std::fmt::Formatter<'_>, headers : &[&str], rows : &[Vec<String>]) -> std::fmt::Result {
+    let widths : Vec<usize> = headers.iter().enumerate().map(|(column, header)| {
+        rows.iter().map(|row| row[column].chars().count()).chain(std::iter::once(header.chars().count())).max().unwrap_or(0)
+    }).collect();
+
+    let write_row = |f : &mut std::fmt::Formatter<'_>, cells : &[&str]| -> std::fmt::Result {
+        let line : Vec<String> = cells.iter().zip(&widths).map(|(cell, width)| format!("{:<width$}", cell, width = width)).collect();
+        writeln!(f, "{}", line.join(" | ").trim_end())
+    };
+
+    write_row(f, headers)?;
+    writeln!(f, "{}", widths.iter().map(|width| "-".repeat(*width)).collect::<Vec<_>>().join("-+-"))?;
+
+    for row in rows {
+        write_row(f, &row.iter().map(String::as_str).collect::<Vec<_>>())?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,145 @@
+use std::{io::{Read, ErrorKind}, sync::Arc};
+
+use super::{KAssetBroker, KAssetBrokerError, KAssetSource, KAssetSourceId, KAssetPath, KIntoAssetPath};
+
+/// ##### Abstraction of a writable destination of assets (saves, screenshots, settings, etc...)
+///
+/// KAssetSink is the writable companion of [KAssetSource]. Paths are relative to the sink, like sources.
+///
+/// A sink that is also a [KAssetSource] can be set on a [KAssetBroker] with [KAssetBroker::set_sink] so
+/// assets written are immediately visible to reads.
+pub trait KAssetSink {
+
+    /// Create asset at [path][KAssetPath] with content of `reader`.
+    ///
+    /// # Error(s)
+    /// Returns [std::io::Error] of kind [AlreadyExists](ErrorKind::AlreadyExists) if asset exists, or other
+    /// [std::io::Error] if asset couldn't be written.
+    fn create_asset(&self, path: &KAssetPath, reader: &mut dyn Read) -> Result<(), std::io::Error>;
+
+    /// Create or overwrite asset at [path][KAssetPath] with content of `reader`.
+    ///
+    /// # Error(s)
+    /// Returns [std::io::Error] if asset couldn't be written.
+    fn write_asset(&self, path: &KAssetPath, reader: &mut dyn Read) -> Result<(), std::io::Error>;
+
+    /// Delete asset at [path][KAssetPath].
+    ///
+    /// # Error(s)
+    /// Returns [std::io::Error] of kind [NotFound](ErrorKind::NotFound) if asset doesn't exist, or other
+    /// [std::io::Error] if asset couldn't be deleted.
+    fn delete_asset(&self, path: &KAssetPath) -> Result<(), std::io::Error>;
+
+    /// Rename asset at [from][KAssetPath] to [to][KAssetPath].
+    ///
+    /// # Error(s)
+    /// Returns [std::io::Error] of kind [AlreadyExists](ErrorKind::AlreadyExists) if asset `to` exists, or other
+    /// [std::io::Error] if asset couldn't be renamed.
+    fn rename_asset(&self, from: &KAssetPath, to: &KAssetPath) -> Result<(), std::io::Error>;
+
+}
+
+impl KAssetBroker {
+    /// Set the writable [KAssetSink] of broker, added as the [KAssetSource] of highest priority.
+    ///
+    /// Previous sink stays in broker as a source but is no longer written by broker. Sink is mounted at the root,
+    /// and can be mounted elsewhere with [KAssetBroker::set_source_mount], like a `saves` folder.
+    ///
+    /// Sink holds data written by the game itself, so it's exempt from the [KTrustPolicy](super::KTrustPolicy) of broker.
+    ///
+    /// Returns `Ok(`[KAssetSourceId]`)` identifying the sink as a source.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KAssetBrokerError::SourceAlreadyExists]`)` if sink is already a source of broker.
+    pub fn set_sink<S : KAssetSink + KAssetSource + Send + Sync + 'static>(&self, sink : Arc<S>) -> Result<KAssetSourceId, KAssetBrokerError> {
+        self.insert_source(sink.clone(), KAssetPath::root(), Some(sink))
+    }
+
+    /// Get [KAssetSourceId] of the sink of broker, if any.
+    pub fn get_sink_id(&self) -> Option<KAssetSourceId> {
+        self.read_sink().as_ref().map(|(id, _)| *id)
+    }
+
+    /// Create asset at path in sink of broker with content of `reader`. See [KAssetSink::create_asset].
+    ///
+    /// # Error(s)
+    /// Returns [std::io::Error] of kind [Unsupported](ErrorKind::Unsupported) if broker has no sink, or of kind
+    /// [InvalidInput](ErrorKind::InvalidInput) if path is invalid or outside of sink mount point.
+    pub fn create_asset(&self, path : impl KIntoAssetPath, reader : &mut dyn Read) -> Result<(), std::io::Error> {
+        let path = path.into_asset_path()?;
+        let (sink, mount) = self.get_sink()?;
+        sink.create_asset(&Self::get_sink_path(&mount, &path)?, reader)?;
+        self.invalidate_cached(&[path]);
+        Ok(())
+    }
+
+    /// Create or overwrite asset at path in sink of broker with content of `reader`. See [KAssetSink::write_asset].
+    ///
+    /// # Error(s)
+    /// Returns [std::io::Error] of kind [Unsupported](ErrorKind::Unsupported) if broker has no sink, or of kind
+    /// [InvalidInput](ErrorKind::InvalidInput) if path is invalid or outside of sink mount point.
+    pub fn write_asset(&self, path : impl KIntoAssetPath, reader : &mut dyn Read) -> Result<(), std::io::Error> {
+        let path = path.into_asset_path()?;
+        let (sink, mount) = self.get_sink()?;
+        sink.write_asset(&Self::get_sink_path(&mount, &path)?, reader)?;
+        self.invalidate_cached(&[path]);
+        Ok(())
+    }
+
+    /// Delete asset at path in sink of broker. See [KAssetSink::delete_asset].
+    ///
+    /// Asset of sources of lower priority becomes visible.
+    ///
+    /// # Error(s)
+    /// Returns [std::io::Error] of kind [Unsupported](ErrorKind::Unsupported) if broker has no sink, or of kind
+    /// [InvalidInput](ErrorKind::InvalidInput) if path is invalid or outside of sink mount point.
+    pub fn delete_asset(&self, path : impl KIntoAssetPath) -> Result<(), std::io::Error> {
+        let path = path.into_asset_path()?;
+        let (sink, mount) = self.get_sink()?;
+        sink.delete_asset(&Self::get_sink_path(&mount, &path)?)?;
+        self.invalidate_cached(&[path]);
+        Ok(())
+    }
+
+    /// Rename asset in sink of broker. See [KAssetSink::rename_asset].
+    ///
+    /// # Error(s)
+    /// Returns [std::io::Error] of kind [Unsupported](ErrorKind::Unsupported) if broker has no sink, or of kind
+    /// [InvalidInput](ErrorKind::InvalidInput) if a path is invalid or outside of sink mount point.
+    pub fn rename_asset(&self, from : impl KIntoAssetPath, to : impl KIntoAssetPath) -> Result<(), std::io::Error> {
+        let (from, to) = (from.into_asset_path()?, to.into_asset_path()?);
+        let (sink, mount) = self.get_sink()?;
+        sink.rename_asset(&Self::get_sink_path(&mount, &from)?, &Self::get_sink_path(&mount, &to)?)?;
+        self.invalidate_cached(&[from, to]);
+        Ok(())
+    }
+
+    /// Get sink of broker with its mount point.
+    fn get_sink(&self) -> Result<(Arc<dyn KAssetSink + Send + Sync>, KAssetPath), std::io::Error> {
+        let (id, sink) = match self.read_sink().as_ref() {
+            Some((id, sink)) => (*id, sink.clone()),
+            None => return Err(std::io::Error::new(ErrorKind::Unsupported, "Broker has no sink!")),
+        };
+
+        Ok((sink, self.get_source_mount(id).unwrap_or_default()))
+    }
+
+    /// Get path within sink mounted at `mount` of a broker path.
+    fn get_sink_path(mount : &KAssetPath, path : &KAssetPath) -> Result<KAssetPath, std::io::Error> {
+        match path.strip_folder(mount) {
+            Some(path) => Ok(path),
+            None => Err(std::io::Error::new(ErrorKind::InvalidInput, "Path is outside of sink mount point!")),
+        }
+    }
+
+    /// Invalidate paths written from cache of broker and forget resolved paths, since a variant may have been written.
+    fn invalidate_cached(&self, paths : &[KAssetPath]) {
+        self.forget_resolved_paths();
+
+        if let Some(cache) = self.get_cache() {
+            for path in paths {
+                cache.invalidate_changed(path);
+            }
+        }
+    }
+}
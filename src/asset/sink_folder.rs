@@ -0,0 +1,250 @@
+use std::{path::{Path, PathBuf}, fs::{self, File}, io::{self, Read, ErrorKind}, sync::atomic::{AtomicU64, Ordering}};
+use crate::asset::{KAssetSink, KAssetSource, KAssetSourceFolder, KAssetChange, KAssetSeekReader, KAssetBytes, KAssetPath, KAssetSourceMetadata, KAssetMetadata};
+
+/// Extension of temporary files written before being renamed.
+const KSINK_TEMP_EXTENSION : &str = "ktmp";
+
+// Counter making temporary file names unique within process.
+static NEXT_TEMP_ID : AtomicU64 = AtomicU64::new(0);
+
+/// ##### [KAssetSink] implementation using a file system folder.
+///
+/// KAssetSinkFolder writes assets in a given folder, typically the user folder of saves, screenshots and settings.
+/// Paths are [KAssetPath] so they can't escape the folder, and paths going through a symbolic link pointing outside of
+/// folder are refused.
+///
+/// Writes are atomic : asset is written to a temporary file in the same folder, then renamed. A crash while
+/// writing never leaves a partially written asset.
+///
+/// KAssetSinkFolder is also a [KAssetSource] reading back its folder, so it can be set on a
+/// [KAssetBroker](super::KAssetBroker) with [set_sink](super::KAssetBroker::set_sink).
+/// Asset count and size of its [metadata](KAssetSource::get_metadata) are counted again after each write.
+///
+/// # Example(s)
+/// ```no_run
+/// // Import crate module
+/// use std::{path::PathBuf, sync::Arc};
+/// use olympus_kleio::asset::{KAssetBroker, KAssetSinkFolder};
+///
+/// // User folder overrides all other sources
+/// let kab = KAssetBroker::new();
+/// kab.set_sink(Arc::new(KAssetSinkFolder::new(PathBuf::from("/home/user/.game")).unwrap())).unwrap();
+///
+/// // Save settings
+/// kab.write_asset(PathBuf::from("settings.cfg"), &mut "volume=0.5".as_bytes()).unwrap();
+/// ```
+pub struct KAssetSinkFolder {
+    // Path of the sink folder
+    folder_path : PathBuf,
+
+    // Source reading back folder
+    source : KAssetSourceFolder,
+}
+
+/// Enumeration of possible [KAssetSinkFolder] errors.
+pub enum KAssetSinkFolderError {
+    /// Happens when [`folder_path`][PathBuf] used to create [KAssetSinkFolder] is not a folder.
+    PathIsNotFolder,
+
+    /// Happens when [`folder_path`][PathBuf] used to create [KAssetSinkFolder] couldn't be created.
+    FolderCreationError,
+
+    /// Happens when an error occurred while creating folder metadata.
+    MetadataCreationError,
+}
+
+impl std::fmt::Debug for KAssetSinkFolderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PathIsNotFolder => write!(f, "PathIsNotFolder"),
+            Self::FolderCreationError => write!(f, "FolderCreationError"),
+            Self::MetadataCreationError => write!(f, "MetadataCreationError"),
+        }
+    }
+}
+
+impl KAssetSinkFolder {
+    /// Create a new [KAssetSinkFolder] from a [`folder_path`][PathBuf]. Folder and its parents are created if missing.
+    ///
+    /// Returns `Ok(`[KAssetSinkFolder]`)` if successful.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KAssetSinkFolderError::PathIsNotFolder]`)` if [`folder_path`][PathBuf] is not a folder.
+    ///
+    /// Returns `Err(`[KAssetSinkFolderError::FolderCreationError]`)` if folder couldn't be created.
+    ///
+    /// Returns `Err(`[KAssetSinkFolderError::MetadataCreationError]`)` if an error occurred while creating metadata.
+    pub fn new(folder_path : PathBuf) -> Result<KAssetSinkFolder, KAssetSinkFolderError> {
+
+        if folder_path.exists() && !folder_path.is_dir() {
+            return Err(KAssetSinkFolderError::PathIsNotFolder);
+        }
+
+        if fs::create_dir_all(&folder_path).is_err() {
+            return Err(KAssetSinkFolderError::FolderCreationError);
+        }
+
+        match KAssetSourceFolder::new(folder_path.clone()) {
+            Ok(source) => Ok(KAssetSinkFolder { folder_path, source }),
+            Err(_) => Err(KAssetSinkFolderError::MetadataCreationError),
+        }
+    }
+
+    /// Get the [KAssetSourceFolder] reading back sink folder, to watch it for example.
+    pub fn get_source(&self) -> &KAssetSourceFolder {
+        &self.source
+    }
+
+    /// Get full path of asset. The root isn't an asset, and paths going through a link pointing outside of folder are refused.
+    fn get_full_path(&self, path : &KAssetPath) -> Result<PathBuf, io::Error> {
+        if path.is_root() {
+            return Err(io::Error::new(ErrorKind::InvalidInput, "Asset path is the root!"));
+        }
+
+        let full_path = self.folder_path.join(path);
+        self.source.check_within(&full_path)?;
+        Ok(full_path)
+    }
+
+    /// Write content of reader to a temporary file next to `full_path`, creating parent folders.
+    ///
+    /// Returns path of temporary file written and synchronized.
+    fn write_temp(full_path : &Path, reader : &mut dyn Read) -> Result<PathBuf, io::Error> {
+        let parent = full_path.parent().unwrap_or(Path::new(""));
+        fs::create_dir_all(parent)?;
+
+        let name = full_path.file_name().unwrap_or_default().to_string_lossy();
+        let temp_path = parent.join(format!(".{}.{}-{}.{}", name, std::process::id(), NEXT_TEMP_ID.fetch_add(1, Ordering::Relaxed), KSINK_TEMP_EXTENSION));
+
+        let written = File::create(&temp_path).and_then(|mut file| {
+            io::copy(reader, &mut file)?;
+            file.sync_all()
+        });
+
+        match written {
+            Ok(_) => Ok(temp_path),
+            Err(err) => {
+                let _ = fs::remove_file(&temp_path);
+                Err(err)
+            },
+        }
+    }
+
+    /// Verify if path is a temporary file of sink.
+    fn is_temp(path : &KAssetPath) -> bool {
+        path.get_extension().is_some_and(|extension| extension == KSINK_TEMP_EXTENSION)
+            && path.get_file_name().is_some_and(|name| name.starts_with('.'))
+    }
+}
+
+impl KAssetSink for KAssetSinkFolder {
+
+    fn create_asset(&self, path: &KAssetPath, reader: &mut dyn Read) -> Result<(), io::Error> {
+        let full_path = self.get_full_path(path)?;
+        if full_path.exists() {
+            return Err(io::Error::new(ErrorKind::AlreadyExists, "Asset already exists!"));
+        }
+
+        let temp_path = Self::write_temp(&full_path, reader)?;
+
+        // Hard link fails if asset was created meanwhile, so an existing asset is never overwritten
+        let linked = fs::hard_link(&temp_path, &full_path);
+        let _ = fs::remove_file(&temp_path);
+        self.source.forget_totals();
+        linked
+    }
+
+    fn write_asset(&self, path: &KAssetPath, reader: &mut dyn Read) -> Result<(), io::Error> {
+        let full_path = self.get_full_path(path)?;
+        if full_path.is_dir() {
+            return Err(io::Error::new(ErrorKind::InvalidInput, "Asset path is a folder!"));
+        }
+
+        let temp_path = Self::write_temp(&full_path, reader)?;
+        let renamed = fs::rename(&temp_path, &full_path);
+        if renamed.is_err() {
+            let _ = fs::remove_file(&temp_path);
+        }
+
+        self.source.forget_totals();
+        renamed
+    }
+
+    fn delete_asset(&self, path: &KAssetPath) -> Result<(), io::Error> {
+        let full_path = self.get_full_path(path)?;
+        if full_path.is_dir() {
+            return Err(io::Error::new(ErrorKind::InvalidInput, "Asset path is a folder!"));
+        }
+
+        let removed = fs::remove_file(full_path);
+        self.source.forget_totals();
+        removed
+    }
+
+    fn rename_asset(&self, from: &KAssetPath, to: &KAssetPath) -> Result<(), io::Error> {
+        let from_path = self.get_full_path(from)?;
+        let to_path = self.get_full_path(to)?;
+
+        if !from_path.is_file() {
+            return Err(io::Error::new(ErrorKind::NotFound, "Asset not found!"));
+        }
+
+        if to_path.exists() {
+            return Err(io::Error::new(ErrorKind::AlreadyExists, "Asset already exists!"));
+        }
+
+        if let Some(parent) = to_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let renamed = fs::rename(from_path, to_path);
+        self.source.forget_totals();
+        renamed
+    }
+
+}
+
+impl KAssetSource for KAssetSinkFolder {
+
+    fn get_metadata(&self) -> KAssetSourceMetadata {
+        self.source.get_metadata()
+    }
+
+    fn has_asset(&self, path: &KAssetPath) -> bool {
+        !Self::is_temp(path) && self.source.has_asset(path)
+    }
+
+    fn get_asset_metadata(&self, path: &KAssetPath) -> Result<KAssetMetadata, io::Error> {
+        self.source.get_asset_metadata(path)
+    }
+
+    fn get_asset(&self, path: &KAssetPath) -> Result<Box<dyn Read>, io::Error> {
+        self.source.get_asset(path)
+    }
+
+    fn get_asset_seekable(&self, path: &KAssetPath) -> Result<KAssetSeekReader, io::Error> {
+        self.source.get_asset_seekable(path)
+    }
+
+    fn get_asset_range(&self, path: &KAssetPath, offset : u64, length : u64) -> Result<Box<dyn Read>, io::Error> {
+        self.source.get_asset_range(path, offset, length)
+    }
+
+    fn get_asset_bytes(&self, path: &KAssetPath) -> Result<KAssetBytes, io::Error> {
+        self.source.get_asset_bytes(path)
+    }
+
+    fn list_assets(&self, folder: &KAssetPath) -> Result<Vec<KAssetPath>, io::Error> {
+        // Temporary files of writes in progress are not assets
+        let mut assets = self.source.list_assets(folder)?;
+        assets.retain(|asset| !Self::is_temp(asset));
+        Ok(assets)
+    }
+
+    fn poll_changes(&self) -> Vec<KAssetChange> {
+        let mut changes = self.source.poll_changes();
+        changes.retain(|change| !Self::is_temp(&change.path));
+        changes
+    }
+
+}
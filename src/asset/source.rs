@@ -1,4 +1,5 @@
-use std::{io::Read, path::{ PathBuf}};
+use std::io::{Read, Seek, SeekFrom, Cursor};
+use super::{KAssetPath, KAssetSourceMetadata, KAssetMetadata, KAssetBytes, KPublicKey, KTrustError};
 
 /// ##### Abstration of a source of assets (audio, models, etc...)
 /// 
@@ -6,7 +7,8 @@ use std::{io::Read, path::{ PathBuf}};
 /// 
 /// KAssetSource offer an interface so all data can be retrieved the same way via a unique path.
 /// 
-/// Always threat KAssetSource as they were a base folder of your assets.
+/// Always threat KAssetSource as they were a base folder of your assets. Paths are [KAssetPath], which can't
+/// escape the source.
 /// 
 /// # Note(s)
 /// KAssetSource assets are ALWAYS read-only since it only require [Read] trait to be implemented.
@@ -17,7 +19,7 @@ use std::{io::Read, path::{ PathBuf}};
 /// // Import needed components
 /// use core::panic;
 /// use std::{path::{PathBuf}, fs::{File}, io::Read};
-/// use olympus_kleio::asset::{KAssetSource};
+/// use olympus_kleio::asset::{KAssetSource, KAssetPath};
 /// 
 /// // Create struct that will keep the base folder_path 
 /// pub struct SourceFolder {
@@ -46,7 +48,7 @@ use std::{io::Read, path::{ PathBuf}};
 /// 
 /// // Implement KAssetSource trait for struct
 /// impl KAssetSource for SourceFolder {
-///    fn has_asset(&self, path: PathBuf) -> bool {
+///    fn has_asset(&self, path: &KAssetPath) -> bool {
 ///        // Get asset full path
 ///        let mut full_path: PathBuf = self.folder_path.clone();
 ///        full_path.push(path);
@@ -55,7 +57,7 @@ use std::{io::Read, path::{ PathBuf}};
 ///        full_path.exists()
 ///    }
 ///
-///    fn get_asset(&self, path: PathBuf) ->  Result<Box<dyn Read>, std::io::Error> {
+///    fn get_asset(&self, path: &KAssetPath) ->  Result<Box<dyn Read>, std::io::Error> {
 ///        // Get asset full path
 ///        let mut full_path: PathBuf = self.folder_path.clone();
 ///        full_path.push(path);
@@ -74,24 +76,190 @@ use std::{io::Read, path::{ PathBuf}};
 /// ```
 pub trait KAssetSource {
 
-    /// Get metadata that identify this source.
+    /// Get metadata that identify this source, to show it in a mod manager for example.
     /// 
-    /// This is a free field to let user create meta tags to identify source in a mod manager, for example.
+    /// Returns [KAssetSourceMetadata] of source. By default, only its kind is given, as [Unknown](super::KAssetSourceKind::Unknown).
+    fn get_metadata(&self) -> KAssetSourceMetadata {
+        KAssetSourceMetadata::default()
+    }
+
+    /// Get [KAssetMetadata] of asset from [path][KAssetPath] without opening asset.
     /// 
-    /// Returns [KAssetSource] metadata as [String]
-    fn get_metadata(&self) -> String {
-        String::from("(Metadata not implemented...)")
+    /// Returns [Ok][Ok]`(`[KAssetMetadata]`)` if found or [std::io::Error] otherwise.
+    /// Sources that can't tell metadata of their assets return an error of kind [Unsupported](std::io::ErrorKind::Unsupported) by default.
+    fn get_asset_metadata(&self, path: &KAssetPath) -> Result<KAssetMetadata, std::io::Error> {
+        let _ = path;
+        Err(std::io::Error::new(std::io::ErrorKind::Unsupported, "Asset metadata not implemented!"))
     }
 
     /// Verify that asset source contains asset from path.
     /// 
     /// Returns `True` if source has the asset or `false` otherwise.
-    fn has_asset(&self, path: PathBuf) -> bool;
+    fn has_asset(&self, path: &KAssetPath) -> bool;
+
+    /// Get an asset [Read] handle from [path][KAssetPath].
+    /// 
+    /// Returns [Ok][Ok]`(`[Box][Box]`(`[Read]`))` if found or [std::io::Error] otherwise.
+    fn get_asset(&self, path: &KAssetPath) -> Result<Box<dyn Read>, std::io::Error>;
+
+    /// Get an asset [KAssetSeekReader] from [path][KAssetPath], supporting [Seek] and reporting asset length.
+    /// 
+    /// By default, asset is read entirely in memory with [KAssetSource::get_asset]. Sources with random access
+    /// should override it.
+    /// 
+    /// Returns [Ok][Ok]`(`[KAssetSeekReader]`)` if found or [std::io::Error] otherwise.
+    fn get_asset_seekable(&self, path: &KAssetPath) -> Result<KAssetSeekReader, std::io::Error> {
+        let mut data = Vec::new();
+        self.get_asset(path)?.read_to_end(&mut data)?;
 
-    /// Get an asset [Read] handle from [path][PathBuf].
+        let length = data.len() as u64;
+        Ok(KAssetSeekReader::new(Box::new(Cursor::new(data)), length))
+    }
+
+    /// Get a [Read] handle of `length` bytes of asset starting at `offset`.
+    /// 
+    /// Range is truncated at the end of asset, so a range starting after the end of asset is empty.
+    /// By default, range is read from [KAssetSource::get_asset_seekable].
     /// 
     /// Returns [Ok][Ok]`(`[Box][Box]`(`[Read]`))` if found or [std::io::Error] otherwise.
-    fn get_asset(&self, path: PathBuf) -> Result<Box<dyn Read>, std::io::Error>;
+    fn get_asset_range(&self, path: &KAssetPath, offset : u64, length : u64) -> Result<Box<dyn Read>, std::io::Error> {
+        let mut reader = self.get_asset_seekable(path)?;
+        let length = length.min(reader.get_length().saturating_sub(offset));
+        reader.seek(SeekFrom::Start(offset))?;
+
+        Ok(Box::new(reader.take(length)))
+    }
+
+    /// Get a read-only [KAssetBytes] view of the whole asset from [path][KAssetPath].
+    /// 
+    /// By default, asset is read entirely in memory with [KAssetSource::get_asset]. Sources of files
+    /// should override it to map assets in memory with [KAssetBytes::map_file] when mapping is enabled.
+    /// 
+    /// Returns [Ok][Ok]`(`[KAssetBytes]`)` if found or [std::io::Error] otherwise.
+    fn get_asset_bytes(&self, path: &KAssetPath) -> Result<KAssetBytes, std::io::Error> {
+        let mut data = Vec::new();
+        self.get_asset(path)?.read_to_end(&mut data)?;
+
+        Ok(data.into())
+    }
+
+    /// List assets within [folder][KAssetPath] and its sub folders. The root lists all assets.
+    /// 
+    /// Returns [Ok][Ok]`(`[Vec]`)` of asset paths relative to source, in no particular order, or [std::io::Error] otherwise.
+    /// Sources that can't list their assets return an error of kind [Unsupported](std::io::ErrorKind::Unsupported) by default.
+    fn list_assets(&self, folder: &KAssetPath) -> Result<Vec<KAssetPath>, std::io::Error> {
+        let _ = folder;
+        Err(std::io::Error::new(std::io::ErrorKind::Unsupported, "Listing not implemented!"))
+    }
+
+    /// Get changes of assets since last call, if source reports changes.
+    /// 
+    /// Returns a [Vec] of [KAssetChange]. Empty by default.
+    fn poll_changes(&self) -> Vec<KAssetChange> {
+        Vec::new()
+    }
+
+    /// Get revision of source content, changed each time the source itself adds, replaces or removes assets.
+    /// 
+    /// A [KAssetBroker](super::KAssetBroker) resolves locale and tagged variants again when the revision of a source changes.
+    /// Returns 0 by default, for sources whose content never changes or whose changes are reported by [KAssetSource::poll_changes].
+    fn get_revision(&self) -> u64 {
+        0
+    }
+
+    /// Verify signature of whole source, like a signed [KAssetSourcePak](super::KAssetSourcePak).
+    /// 
+    /// Returns `Ok(Some(`[KPublicKey]`))` with key that signed source, or `Ok(None)` if source isn't signed, as by default.
+    /// Returns `Err(`[KTrustError::InvalidSignature]`)` if signature doesn't match source content.
+    fn verify_signature(&self) -> Result<Option<KPublicKey>, KTrustError> {
+        Ok(None)
+    }
     
 }
 
+/// Kind of change reported by [KAssetSource::poll_changes].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KAssetChangeKind {
+    /// Asset was created or moved into source.
+    Created,
+
+    /// Asset content was modified.
+    Modified,
+
+    /// Asset was deleted or moved out of source.
+    Deleted,
+}
+
+/// Change of an asset reported by [KAssetSource::poll_changes].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KAssetChange {
+    /// Path of asset changed, relative to source.
+    pub path : KAssetPath,
+
+    /// Kind of change.
+    pub kind : KAssetChangeKind,
+}
+
+
+/// Combination of [Read] and [Seek] boxed by [KAssetSeekReader].
+pub trait KAssetReadSeek : Read + Seek {}
+
+impl<T : Read + Seek> KAssetReadSeek for T {}
+
+/// ##### Reader of an asset supporting [Seek] and reporting asset length.
+/// 
+/// Returned by [KAssetSource::get_asset_seekable] for random access, like audio and video streaming.
+/// 
+/// # Example(s)
+/// ```
+/// use std::{path::PathBuf, io::{Read, Seek, SeekFrom}};
+/// use olympus_kleio::asset::{KAssetSource, KAssetSourceMemory, KAssetPath};
+/// 
+/// let source = KAssetSourceMemory::new("memory");
+/// source.add_asset(PathBuf::from("text.txt"), "Hello, world!".as_bytes().to_vec()).unwrap();
+/// 
+/// // Read the end of asset
+/// let mut reader = source.get_asset_seekable(&KAssetPath::new("text.txt").unwrap()).unwrap();
+/// assert_eq!(reader.get_length(), 13);
+/// 
+/// let mut content = String::new();
+/// reader.seek(SeekFrom::End(-6)).unwrap();
+/// reader.read_to_string(&mut content).unwrap();
+/// assert_eq!(content, "world!");
+/// ```
+pub struct KAssetSeekReader {
+    // Reader of asset
+    reader : Box<dyn KAssetReadSeek>,
+
+    // Length of asset in bytes
+    length : u64,
+}
+
+impl KAssetSeekReader {
+    /// Create a new [KAssetSeekReader] from a reader of asset and asset `length` in bytes.
+    pub fn new(reader : Box<dyn KAssetReadSeek>, length : u64) -> KAssetSeekReader {
+        KAssetSeekReader { reader, length }
+    }
+
+    /// Get length of asset in bytes.
+    pub fn get_length(&self) -> u64 {
+        self.length
+    }
+
+    /// Consume [KAssetSeekReader] and return inner reader.
+    pub fn into_inner(self) -> Box<dyn KAssetReadSeek> {
+        self.reader
+    }
+}
+
+impl Read for KAssetSeekReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.reader.read(buf)
+    }
+}
+
+impl Seek for KAssetSeekReader {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.reader.seek(pos)
+    }
+}
@@ -1,28 +1,54 @@
-use std::{path::{PathBuf}, fs::{File}, io::Read, time::{SystemTime}};
-use crate::asset::KAssetSource;
+use std::{path::{Path, PathBuf}, fs::{self, File}, io::{Read, ErrorKind}, sync::{Mutex, MutexGuard, atomic::{AtomicBool, Ordering}}};
+use crate::asset::{KAssetSource, KAssetChange, KAssetSeekReader, KAssetBytes, KAssetPath, KAssetSourceMetadata, KAssetSourceKind, KAssetMetadata};
+use super::watch::KFolderWatcher;
 
 /// ##### [KAssetSource] implementation using a file system folder.
 /// 
-/// KAssetSourceFolder uses a given folder as a base path to retrieve assets.
+/// KAssetSourceFolder uses a given folder as a base path to retrieve assets. Assets are [KAssetPath] so
+/// they can't escape the folder, and paths going through a symbolic link pointing outside of folder are refused.
+/// 
+/// # Case-insensitive matching
+/// Mods authored on case-insensitive file systems often mix case. With [KAssetSourceFolder::set_case_insensitive],
+/// a path not found as is is matched against folder entries ignoring case.
+/// 
+/// # Hot reload
+/// On Linux, [KAssetSourceFolder::watch] watches the folder with inotify so created, modified and deleted
+/// assets are reported by [KAssetSource::poll_changes].
+/// 
+/// # Memory mapping
+/// With [KAssetSourceFolder::set_memory_mapped], [KAssetSource::get_asset_bytes] maps files in memory instead of
+/// reading them, so large assets like heightmaps and audio banks are never copied.
 /// 
 /// ```no_run
 /// // Import crate module
 /// use std::path::PathBuf;
-/// use olympus::kleio::asset::{KAssetSource , KAssetSourceFolder};
+/// use olympus_kleio::asset::{KAssetSource , KAssetSourceFolder, KAssetPath};
 /// 
 /// // Create KAssetSourceFolder using a folder as base
-/// let source : KAssetSourceFolder = KAssetSourceFolder::new(PathBuf::from("/base_folder"));
+/// let source : KAssetSourceFolder = KAssetSourceFolder::new(PathBuf::from("/base_folder")).unwrap();
 /// 
 /// // Get assets from KAssetSourceFolder with path relative to base
-/// let asset1 = source.get_asset(PathBuf::from("audio/audio1.ogg"));
-/// let asset2 = source.get_asset(PathBuf::from("models/model.obj"));
+/// let asset1 = source.get_asset(&KAssetPath::new("audio/audio1.ogg").unwrap());
+/// let asset2 = source.get_asset(&KAssetPath::new("models/model.obj").unwrap());
 /// ```
 pub struct KAssetSourceFolder {
     // Path of the source folder
     folder_path : PathBuf,
 
-    // Metadata of KAssetSourceFolder (folderpath, created, modified)
-    metadata : String
+    // Canonical path of the source folder, which assets must stay within
+    canonical_path : PathBuf,
+
+    // Watcher of folder when watched
+    watcher : Mutex<Option<KFolderWatcher>>,
+
+    // Paths not found are matched ignoring case
+    case_insensitive : AtomicBool,
+
+    // Bytes of assets are mapped in memory
+    memory_mapped : AtomicBool,
+
+    // Asset count and size of folder, counted when first requested
+    totals : Mutex<Option<(usize, u64)>>,
 }
 
 /// Enumeration of possible [KAssetSourceFolder] errors.
@@ -35,6 +61,12 @@ pub enum KAssetSourceFolderError {
 
     /// Happens when an error occurred while creating folder metadata.
     MetadataCreationError,
+
+    /// Happens when watching folder is not supported on this platform.
+    WatchUnsupported,
+
+    /// Happens when an error occurred while watching folder.
+    WatchError,
 }
 
 impl std::fmt::Debug for KAssetSourceFolderError {
@@ -43,6 +75,8 @@ impl std::fmt::Debug for KAssetSourceFolderError {
             Self::FolderNotFound => write!(f, "FolderNotFound"),
             Self::PathIsNotFolder => write!(f, "PathIsNotFolder"),
             Self::MetadataCreationError => write!(f, "MetadataCreationError"),
+            Self::WatchUnsupported => write!(f, "WatchUnsupported"),
+            Self::WatchError => write!(f, "WatchError"),
         }
     }
 }
@@ -50,12 +84,9 @@ impl std::fmt::Debug for KAssetSourceFolderError {
 impl KAssetSourceFolder {
     /// Create a new [KAssetSourceFolder] from a [`folder_path`][PathBuf].
     /// 
-    /// Metadata JSON format :<br>
-    /// {<br>
-    ///     "path" : "\path\to\folder",<br>
-    ///     "created" : "{epoch_time}",<br>
-    ///     "modified" : "{epoch_time}",<br>
-    /// }
+    /// [Metadata](KAssetSource::get_metadata) gives folder path, times, asset count and size, with folder name as name.
+    /// Assets are counted when metadata is first requested, then counted again after changes are reported by
+    /// [KAssetSource::poll_changes] while [watched](KAssetSourceFolder::watch).
     /// 
     /// Returns `Ok(`[KAssetSourceFolder]`)` if successful.
     /// 
@@ -76,90 +107,228 @@ impl KAssetSourceFolder {
             return Err(KAssetSourceFolderError::PathIsNotFolder);
         }
 
-        match  Self::create_metadata(&folder_path)  {
-            Ok(metadata) => Ok(KAssetSourceFolder {
-                metadata,
+        match fs::canonicalize(&folder_path) {
+            Ok(canonical_path) => Ok(KAssetSourceFolder {
                 folder_path,
-                
+                canonical_path,
+                watcher : Mutex::new(None),
+                case_insensitive : AtomicBool::new(false),
+                memory_mapped : AtomicBool::new(false),
+                totals : Mutex::new(None),
             }),
             Err(_) => Err(KAssetSourceFolderError::MetadataCreationError),
         }
         
     }
 
-    /// Create KAssetSourceFolder metadata which contains path, created and modified in JSON format.
+    /// Watch folder and its sub folders for changes reported by [KAssetSource::poll_changes]. Does nothing if already watched.
+    /// 
+    /// # Error(s)
+    /// Returns `Err(`[KAssetSourceFolderError::WatchUnsupported]`)` on platforms other than Linux.
+    /// 
+    /// Returns `Err(`[KAssetSourceFolderError::WatchError]`)` if folder couldn't be watched.
+    /// 
+    /// # Note(s)
+    /// Folders moved out of source are not reported since their content is unknown, until events are lost. Folder is
+    /// then scanned again, reporting files found as modified and files known but not found as deleted.
+    pub fn watch(&self) -> Result<(), KAssetSourceFolderError> {
+        let mut watcher = self.lock_watcher();
+
+        if watcher.is_none() {
+            match KFolderWatcher::new(&self.folder_path) {
+                Ok(new_watcher) => {
+                    // Changes before watching were never reported
+                    *watcher = Some(new_watcher);
+                    self.forget_totals();
+                },
+                Err(err) if err.kind() == ErrorKind::Unsupported => return Err(KAssetSourceFolderError::WatchUnsupported),
+                Err(_) => return Err(KAssetSourceFolderError::WatchError),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Stop watching folder. Changes not polled are discarded, so assets are counted again when metadata is next requested.
+    pub fn unwatch(&self) {
+        *self.lock_watcher() = None;
+        self.forget_totals();
+    }
+
+    /// Verify if folder is watched.
+    pub fn is_watched(&self) -> bool {
+        self.lock_watcher().is_some()
+    }
+
+    /// Set if paths not found as is are matched against folder entries ignoring case. Disabled by default.
+    /// 
+    /// # Note(s)
+    /// Matching ignoring case reads folders of path, which is slower than an exact match.
+    pub fn set_case_insensitive(&self, case_insensitive : bool) {
+        self.case_insensitive.store(case_insensitive, Ordering::Relaxed);
+    }
+
+    /// Verify if paths not found as is are matched ignoring case.
+    pub fn is_case_insensitive(&self) -> bool {
+        self.case_insensitive.load(Ordering::Relaxed)
+    }
+
+    /// Set if [KAssetSource::get_asset_bytes] maps files in memory instead of reading them. Disabled by default.
+    /// 
+    /// # Safety
+    /// Files of folder must not be truncated or modified while [KAssetBytes] mapped from them are alive, see
+    /// [KAssetBytes::map_file]. Folders modified while running, like assets hot reloaded, shouldn't be mapped.
+    pub unsafe fn set_memory_mapped(&self, memory_mapped : bool) {
+        self.memory_mapped.store(memory_mapped, Ordering::Relaxed);
+    }
+
+    /// Verify if [KAssetSource::get_asset_bytes] maps files in memory.
+    pub fn is_memory_mapped(&self) -> bool {
+        self.memory_mapped.load(Ordering::Relaxed)
+    }
+
+    /// Get full path of asset within source folder.
     /// 
-    /// Return `Ok(String)` with metadata created
-    /// or Err(KAssetSourceFolderError::MetadataCreationError) if an error occurred.
-    fn create_metadata(folder_path : &PathBuf)->Result<String, KAssetSourceFolderError> {
-
-        let mut metadata : String = "{ \"path\":\"".to_owned() +  &folder_path.clone().into_os_string().into_string().unwrap().to_owned() + &"\",".to_owned();
-
-        // Get folder metadata
-        match folder_path.metadata(){
-            // If OK, retieve date created and modified
-            Ok(md) => {
-
-                // Date created, UNIX format
-                match md.created(){
-                    Ok(st) => {
-                        match st.duration_since(SystemTime::UNIX_EPOCH) {
-                            Ok(n) => metadata.push_str(&("\"created\":\"".to_owned() + &n.as_millis().to_string().to_owned() + &"\",".to_owned())),
-                            Err(_) => return Err(KAssetSourceFolderError::MetadataCreationError),
-                        }
-                        
-                    },
-                    Err(_) => {
-                        metadata.push_str("\"created\":\"(metadata _error)\",");
-                    },
-                }
-
-                // Date modified, UNIX format
-                match md.modified(){
-                    Ok(st) => {
-                        match st.duration_since(SystemTime::UNIX_EPOCH) {
-                            Ok(n) => metadata.push_str(&("\"modified\":\"".to_owned() + &n.as_millis().to_string().to_owned() + &"\",".to_owned())),
-                            Err(_) => return Err(KAssetSourceFolderError::MetadataCreationError),
-                        }
-                        
-                    },
-                    Err(_) => metadata.push_str("\"modified\":\"(metadata _error)\","),
-                }
-
-            },
-            Err(_) => {
-                return Err(KAssetSourceFolderError::MetadataCreationError)
-            },
-        }
-
-        Ok(metadata)
+    /// If case-insensitive and path isn't found as is, each segment is matched against folder entries ignoring case.
+    /// 
+    /// # Error(s)
+    /// Returns [std::io::Error] of kind [PermissionDenied](ErrorKind::PermissionDenied) if path goes through a symbolic link
+    /// pointing outside of folder.
+    fn get_full_path(&self, path : &KAssetPath) -> Result<PathBuf, std::io::Error> {
+        let full_path = self.find_full_path(path);
+        self.check_within(&full_path)?;
+        Ok(full_path)
+    }
+
+    /// Verify that `full_path` stays within source folder once symbolic links are resolved.
+    /// 
+    /// Path may not exist yet, so its deepest existing ancestor is verified.
+    /// 
+    /// # Error(s)
+    /// Returns [std::io::Error] of kind [PermissionDenied](ErrorKind::PermissionDenied) if path resolves outside of folder.
+    pub(crate) fn check_within(&self, full_path : &Path) -> Result<(), std::io::Error> {
+        match full_path.ancestors().find_map(|ancestor| fs::canonicalize(ancestor).ok()) {
+            Some(canonical) if canonical.starts_with(&self.canonical_path) => Ok(()),
+            _ => Err(std::io::Error::new(ErrorKind::PermissionDenied, "Asset path escapes folder through a link!")),
+        }
+    }
+
+    /// Find full path of asset within source folder, matching segments ignoring case if case-insensitive.
+    fn find_full_path(&self, path : &KAssetPath) -> PathBuf {
+        let full_path = self.folder_path.join(path);
+        if !self.is_case_insensitive() || full_path.exists() {
+            return full_path;
+        }
+
+        let mut resolved = self.folder_path.clone();
+        for segment in path.get_segments() {
+            let exact = resolved.join(segment);
+            if exact.exists() {
+                resolved = exact;
+                continue;
+            }
+
+            let segment = segment.to_lowercase();
+            let entry = fs::read_dir(&resolved).ok().and_then(|mut entries| entries.find(|entry| entry.as_ref()
+                .is_ok_and(|entry| entry.file_name().to_str().is_some_and(|name| name.to_lowercase() == segment))));
 
+            match entry {
+                Some(Ok(entry)) => resolved = entry.path(),
+                _ => return full_path,
+            }
+        }
+
+        resolved
+    }
+
+    /// Recursively list files of folder relative to source folder. Entries that can't be asset paths are skipped.
+    fn list_files(&self, full_path : &Path, folder : &KAssetPath, files : &mut Vec<KAssetPath>) -> Result<(), std::io::Error> {
+        for entry in fs::read_dir(full_path)? {
+            let entry = entry?;
+            let path = match entry.file_name().to_str().map(|name| folder.join(name)) {
+                Some(Ok(path)) => path,
+                _ => continue,
+            };
+
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                self.list_files(&entry.path(), &path, files)?;
+            } else if !file_type.is_symlink() || (self.check_within(&entry.path()).is_ok() && !entry.path().is_dir()) {
+                // Links pointing outside of folder can't be read and links to folders aren't followed to avoid loops, so they aren't listed
+                files.push(path);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Forget asset count and size of folder so they are counted again when next requested.
+    pub(crate) fn forget_totals(&self) {
+        *self.lock_totals() = None;
+    }
+
+    /// Get asset count and size of folder, counting them if not counted yet. Returns None if folder can't be listed.
+    fn get_totals(&self) -> Option<(usize, u64)> {
+        let mut totals = self.lock_totals();
+        if totals.is_none() {
+            // Assets that can't be read are not counted
+            let mut files = Vec::new();
+            if self.list_files(&self.folder_path, &KAssetPath::root(), &mut files).is_ok() {
+                let size = files.iter().filter_map(|path| self.folder_path.join(path).metadata().ok()).map(|md| md.len()).sum();
+                *totals = Some((files.len(), size));
+            }
+        }
+
+        *totals
+    }
+
+    /// Lock totals. A poisoned lock is recovered since totals are always left consistent.
+    fn lock_totals(&self) -> MutexGuard<'_, Option<(usize, u64)>> {
+        self.totals.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Lock watcher. A poisoned lock is recovered since watcher is always left consistent.
+    fn lock_watcher(&self) -> MutexGuard<'_, Option<KFolderWatcher>> {
+        self.watcher.lock().unwrap_or_else(|err| err.into_inner())
     }
 
 }
 
 impl KAssetSource for KAssetSourceFolder {
 
-    fn get_metadata(&self) -> String {
-        self.metadata.clone()
+    fn get_metadata(&self) -> KAssetSourceMetadata {
+        let md = self.folder_path.metadata().ok();
+        let totals = self.get_totals();
+
+        KAssetSourceMetadata {
+            location : Some(self.folder_path.display().to_string()),
+            created : md.as_ref().and_then(|md| md.created().ok()),
+            modified : md.as_ref().and_then(|md| md.modified().ok()),
+            asset_count : totals.map(|(count, _)| count),
+            size : totals.map(|(_, size)| size),
+            name : self.folder_path.file_name().map(|name| name.to_string_lossy().into_owned()),
+            ..KAssetSourceMetadata::new(KAssetSourceKind::Folder)
+        }
     }
     
-    fn has_asset(&self, path: PathBuf) -> bool {
-        // Get asset full path
-        let mut full_path: PathBuf = self.folder_path.clone();
-        full_path.push(path);
-
-        // Return if file exists
-        full_path.exists()
+    fn has_asset(&self, path: &KAssetPath) -> bool {
+        // Return if file exists within folder
+        self.get_full_path(path).is_ok_and(|full_path| full_path.is_file())
     }
 
-    fn get_asset(&self, path: PathBuf) ->  Result<Box<dyn Read>, std::io::Error> {
-        // Get asset full path
-        let mut full_path: PathBuf = self.folder_path.clone();
-        full_path.push(path);
+    fn get_asset_metadata(&self, path: &KAssetPath) -> Result<KAssetMetadata, std::io::Error> {
+        // Hash would require reading file, so it's not given
+        let md = fs::metadata(self.get_full_path(path)?)?;
+        if !md.is_file() {
+            return Err(std::io::Error::new(ErrorKind::NotFound, "Asset not found!"));
+        }
+
+        Ok(KAssetMetadata { size : md.len(), modified : md.modified().ok(), hash : None })
+    }
 
+    fn get_asset(&self, path: &KAssetPath) ->  Result<Box<dyn Read>, std::io::Error> {
         // Return file opened
-        match File::open(full_path){
+        match File::open(self.get_full_path(path)?){
             // File opened correctly, return handle
             Ok(file) => Ok(Box::new(file)),
 
@@ -168,4 +337,46 @@ impl KAssetSource for KAssetSourceFolder {
         }
     }
 
-} 
+    fn get_asset_seekable(&self, path: &KAssetPath) -> Result<KAssetSeekReader, std::io::Error> {
+        // File handles are seekable, so ranges are read without reading whole asset
+        let file = File::open(self.get_full_path(path)?)?;
+        let length = file.metadata()?.len();
+
+        Ok(KAssetSeekReader::new(Box::new(file), length))
+    }
+
+    fn get_asset_bytes(&self, path: &KAssetPath) -> Result<KAssetBytes, std::io::Error> {
+        let file = File::open(self.get_full_path(path)?)?;
+        let length = file.metadata()?.len();
+
+        match self.is_memory_mapped() {
+            // Caller of set_memory_mapped guarantees files aren't modified while mapped
+            true => unsafe { KAssetBytes::map_file(&file, 0, length) },
+            false => KAssetBytes::read_file(&file, 0, length),
+        }
+    }
+
+    fn list_assets(&self, folder: &KAssetPath) -> Result<Vec<KAssetPath>, std::io::Error> {
+        let full_path = self.get_full_path(folder)?;
+
+        let mut files = Vec::new();
+        if full_path.is_dir() {
+            self.list_files(&full_path, folder, &mut files)?;
+        }
+
+        Ok(files)
+    }
+
+    fn poll_changes(&self) -> Vec<KAssetChange> {
+        let changes = match self.lock_watcher().as_mut() {
+            Some(watcher) => watcher.poll(),
+            None => Vec::new(),
+        };
+
+        if !changes.is_empty() {
+            self.forget_totals();
+        }
+        changes
+    }
+
+} 
\ No newline at end of file
@@ -0,0 +1,225 @@
+use std::{io::{Read, Cursor, ErrorKind}, collections::HashMap, sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard, atomic::{AtomicU64, Ordering}}};
+use crate::asset::{KAssetSource, KAssetSeekReader, KAssetBytes, KAssetPath, KIntoAssetPath, KAssetSourceMetadata, KAssetSourceKind, KAssetMetadata};
+use super::codec::crc32::crc32;
+
+/// ##### [KAssetSource] implementation using buffers in memory.
+///
+/// KAssetSourceMemory keeps a map of paths to byte buffers and serves them as [Cursor].
+/// Assets can be added and removed at any time, even while the source is used by a broker.
+///
+/// Buffers are either owned by the source or `'static` slices embedded in the binary with
+/// [kasset_source_embed!](crate::asset::kasset_source_embed). Getting an asset never copies its buffer.
+///
+/// # Example(s)
+/// ```
+/// // Import crate module
+/// use std::{path::PathBuf, io::Read};
+/// use olympus_kleio::asset::{KAssetSource , KAssetSourceMemory, KAssetPath};
+///
+/// // Create KAssetSourceMemory and fill it
+/// let source = KAssetSourceMemory::new("fallback");
+/// source.add_asset(PathBuf::from("lang/en.lang"), "hello=Hello".as_bytes().to_vec()).unwrap();
+/// source.add_static_asset("textures/error.png", &[0x89, 0x50, 0x4E, 0x47]).unwrap();
+///
+/// // Get assets from KAssetSourceMemory
+/// let mut content = String::new();
+/// source.get_asset(&KAssetPath::new("lang/en.lang").unwrap()).unwrap().read_to_string(&mut content).unwrap();
+/// assert_eq!(content, "hello=Hello");
+/// ```
+pub struct KAssetSourceMemory {
+    // Name of the source used in metadata
+    name : String,
+
+    // Assets buffers indexed by path
+    assets : RwLock<HashMap<KAssetPath, KAssetMemoryBuffer>>,
+
+    // Revision of assets, incremented each time assets change
+    revision : AtomicU64,
+}
+
+/// Enumeration of possible [KAssetSourceMemory] errors.
+pub enum KAssetSourceMemoryError {
+    /// Happens when asset path is the root or an invalid [KAssetPath].
+    InvalidAssetPath,
+
+    /// Happens when removing an asset that is not in [KAssetSourceMemory].
+    AssetNotFound,
+}
+
+impl std::fmt::Debug for KAssetSourceMemoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidAssetPath => write!(f, "InvalidAssetPath"),
+            Self::AssetNotFound => write!(f, "AssetNotFound"),
+        }
+    }
+}
+
+/// Buffer of an asset in memory. Cloning never copies the bytes.
+#[derive(Clone)]
+enum KAssetMemoryBuffer {
+    // Buffer embedded in binary
+    Static(&'static [u8]),
+
+    // Buffer owned by the source
+    Shared(Arc<[u8]>),
+}
+
+impl AsRef<[u8]> for KAssetMemoryBuffer {
+    fn as_ref(&self) -> &[u8] {
+        match self {
+            KAssetMemoryBuffer::Static(data) => data,
+            KAssetMemoryBuffer::Shared(data) => data,
+        }
+    }
+}
+
+impl KAssetSourceMemory {
+    /// Create a new empty [KAssetSourceMemory] identified by `name` in its [metadata](KAssetSource::get_metadata).
+    pub fn new(name : &str) -> KAssetSourceMemory {
+        KAssetSourceMemory { name : name.to_owned(), assets : RwLock::new(HashMap::new()), revision : AtomicU64::new(0) }
+    }
+
+    /// Create a new [KAssetSourceMemory] from a list of `(path, data)` embedded in binary.
+    ///
+    /// Used by [kasset_source_embed!](crate::asset::kasset_source_embed). Invalid paths are ignored.
+    pub fn from_static_assets(name : &str, assets : &[(&str, &'static [u8])]) -> KAssetSourceMemory {
+        let source = KAssetSourceMemory::new(name);
+
+        for (path, data) in assets {
+            let _ = source.add_static_asset(*path, data);
+        }
+
+        source
+    }
+
+    /// Add an asset owning `data` at [`path`][KIntoAssetPath]. Replace asset if path already exists.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KAssetSourceMemoryError::InvalidAssetPath]`)` if path is the root or invalid.
+    pub fn add_asset(&self, path : impl KIntoAssetPath, data : Vec<u8>) -> Result<(), KAssetSourceMemoryError> {
+        self.insert(path, KAssetMemoryBuffer::Shared(data.into()))
+    }
+
+    /// Add an asset referencing `'static` data at [`path`][KIntoAssetPath] without copy. Replace asset if path already exists.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KAssetSourceMemoryError::InvalidAssetPath]`)` if path is the root or invalid.
+    pub fn add_static_asset(&self, path : impl KIntoAssetPath, data : &'static [u8]) -> Result<(), KAssetSourceMemoryError> {
+        self.insert(path, KAssetMemoryBuffer::Static(data))
+    }
+
+    /// Remove the asset at [`path`][KIntoAssetPath].
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KAssetSourceMemoryError::AssetNotFound]`)` if asset is not in source.
+    pub fn remove_asset(&self, path : impl KIntoAssetPath) -> Result<(), KAssetSourceMemoryError> {
+        let path = path.into_asset_path().ok();
+
+        match path.and_then(|path| self.write_assets().remove(&path)) {
+            Some(_) => {
+                self.revision.fetch_add(1, Ordering::Relaxed);
+                Ok(())
+            },
+            None => Err(KAssetSourceMemoryError::AssetNotFound),
+        }
+    }
+
+    /// Remove all assets.
+    pub fn clear(&self) {
+        self.write_assets().clear();
+        self.revision.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Get the count of assets in source.
+    pub fn get_asset_count(&self) -> usize {
+        self.read_assets().len()
+    }
+
+    /// Insert buffer at path.
+    fn insert(&self, path : impl KIntoAssetPath, buffer : KAssetMemoryBuffer) -> Result<(), KAssetSourceMemoryError> {
+        match path.into_asset_path() {
+            Ok(path) if !path.is_root() => {
+                self.write_assets().insert(path, buffer);
+                self.revision.fetch_add(1, Ordering::Relaxed);
+                Ok(())
+            },
+            _ => Err(KAssetSourceMemoryError::InvalidAssetPath),
+        }
+    }
+
+    /// Lock assets for reading. A poisoned lock is recovered since assets are always left consistent.
+    fn read_assets(&self) -> RwLockReadGuard<'_, HashMap<KAssetPath, KAssetMemoryBuffer>> {
+        self.assets.read().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Lock assets for writing. A poisoned lock is recovered since assets are always left consistent.
+    fn write_assets(&self) -> RwLockWriteGuard<'_, HashMap<KAssetPath, KAssetMemoryBuffer>> {
+        self.assets.write().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl KAssetSource for KAssetSourceMemory {
+
+    fn get_metadata(&self) -> KAssetSourceMetadata {
+        let assets = self.read_assets();
+
+        KAssetSourceMetadata {
+            asset_count : Some(assets.len()),
+            size : Some(assets.values().map(|buffer| buffer.as_ref().len() as u64).sum()),
+            name : Some(self.name.clone()),
+            ..KAssetSourceMetadata::new(KAssetSourceKind::Memory)
+        }
+    }
+
+    fn has_asset(&self, path: &KAssetPath) -> bool {
+        self.read_assets().contains_key(path)
+    }
+
+    fn get_asset_metadata(&self, path: &KAssetPath) -> Result<KAssetMetadata, std::io::Error> {
+        // Buffers are in memory, so hash is computed without reading any file
+        match self.read_assets().get(path) {
+            Some(buffer) => Ok(KAssetMetadata { size : buffer.as_ref().len() as u64, modified : None, hash : Some(crc32(buffer.as_ref())) }),
+            None => Err(std::io::Error::new(ErrorKind::NotFound, "Asset not found!")),
+        }
+    }
+
+    fn get_asset(&self, path: &KAssetPath) -> Result<Box<dyn Read>, std::io::Error> {
+        let buffer = self.read_assets().get(path).cloned();
+
+        match buffer {
+            Some(buffer) => Ok(Box::new(Cursor::new(buffer))),
+            None => Err(std::io::Error::new(ErrorKind::NotFound, "Asset not found!")),
+        }
+    }
+
+    fn get_asset_seekable(&self, path: &KAssetPath) -> Result<KAssetSeekReader, std::io::Error> {
+        let buffer = self.read_assets().get(path).cloned();
+
+        match buffer {
+            Some(buffer) => {
+                let length = buffer.as_ref().len() as u64;
+                Ok(KAssetSeekReader::new(Box::new(Cursor::new(buffer)), length))
+            },
+            None => Err(std::io::Error::new(ErrorKind::NotFound, "Asset not found!")),
+        }
+    }
+
+    fn get_asset_bytes(&self, path: &KAssetPath) -> Result<KAssetBytes, std::io::Error> {
+        // Buffers are shared, never copied
+        match self.read_assets().get(path) {
+            Some(KAssetMemoryBuffer::Static(data)) => Ok((*data).into()),
+            Some(KAssetMemoryBuffer::Shared(data)) => Ok(data.clone().into()),
+            None => Err(std::io::Error::new(ErrorKind::NotFound, "Asset not found!")),
+        }
+    }
+
+    fn list_assets(&self, folder: &KAssetPath) -> Result<Vec<KAssetPath>, std::io::Error> {
+        Ok(self.read_assets().keys().filter(|path| path.is_within(folder)).cloned().collect())
+    }
+
+    fn get_revision(&self) -> u64 {
+        self.revision.load(Ordering::Relaxed)
+    }
+
+}
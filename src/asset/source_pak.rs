@@ -0,0 +1,574 @@
+use std::{path::PathBuf, fs::File, io::{Read, Seek, SeekFrom, Cursor, ErrorKind}, sync::{OnceLock, atomic::{AtomicBool, Ordering}}};
+use crate::asset::{KAssetSource, KAssetSeekReader, KAssetBytes, KAssetPath, KAssetSourceMetadata, KAssetSourceKind, KAssetMetadata, KPublicKey, KTrustError};
+use super::codec::{inflate::inflate, crc32::{crc32, KCrc32Reader}, sha256::KSha256, sha512::KSha512};
+use super::archive::{KArchiveWindow, KARCHIVE_DEFLATE_MAX_RATIO, list_archive_entries, create_archive_metadata, get_capacity, read_u16, read_u32, read_u64};
+
+/// Magic bytes at the start of every Kleio pack.
+pub(crate) const KPAK_MAGIC : [u8; 4] = *b"KPAK";
+
+/// Current version of the Kleio pack format.
+pub(crate) const KPAK_VERSION : u16 = 1;
+
+/// Size of the Kleio pack header.
+pub(crate) const KPAK_HEADER_SIZE : usize = 32;
+
+/// Size of an index entry without its path.
+pub(crate) const KPAK_INDEX_ENTRY_SIZE : usize = 64;
+
+/// Header flag of packs followed by a signature.
+pub(crate) const KPAK_FLAG_SIGNED : u16 = 1;
+
+/// Magic bytes of the signature following the index of signed packs.
+pub(crate) const KPAK_SIGNATURE_MAGIC : [u8; 4] = *b"KSIG";
+
+/// Size of the signature : magic, public key and Ed25519 signature.
+pub(crate) const KPAK_SIGNATURE_SIZE : usize = 100;
+
+/// ##### [KAssetSource] implementation using a Kleio pack (.kpak).
+///
+/// A Kleio pack is created with [KPakWriter](super::KPakWriter) and is made of :
+/// * A 32 bytes header (magic `KPAK`, version, entry count, index CRC-32, 64 bits index offset and size).
+/// * Assets data, each asset compressed or not according to its own compression flag.
+/// * An index sorted by path with compression, CRC-32, 64 bits offset, compressed size, size and SHA-256 of each asset.
+/// * For signed packs, a signature (magic `KSIG`, Ed25519 public key and signature of header and index).
+///
+/// Signatures are verified against the index loaded in memory when a [KTrustPolicy](super::KTrustPolicy) checks
+/// the pack with [KAssetSource::verify_signature]. Assets of signed packs are then read entirely and verified against
+/// their signed SHA-256 before being served, so a pack modified or replaced after verification serves no asset.
+///
+/// The index is loaded once on creation, so [KAssetSource::has_asset] is a O(log n) binary search
+/// that never touches the disk.
+///
+/// With [KAssetSourcePak::set_memory_mapped], [KAssetSource::get_asset_bytes] maps uncompressed assets in memory
+/// instead of reading them. Mapped assets are verified like read assets, so every page is touched once but never copied.
+///
+/// ```no_run
+/// // Import crate module
+/// use std::path::PathBuf;
+/// use olympus_kleio::asset::{KAssetSource , KAssetSourcePak, KAssetPath};
+///
+/// // Create KAssetSourcePak from a pack
+/// let source : KAssetSourcePak = KAssetSourcePak::new(PathBuf::from("/base.kpak")).unwrap();
+///
+/// // Get assets from KAssetSourcePak with path relative to pack root
+/// let asset1 = source.get_asset(&KAssetPath::new("audio/audio1.ogg").unwrap());
+/// let asset2 = source.get_asset(&KAssetPath::new("models/model.obj").unwrap());
+/// ```
+pub struct KAssetSourcePak {
+    // Path of the pack
+    pak_path : PathBuf,
+
+    // Index entries sorted by path
+    entries : Vec<KPakEntry>,
+
+    // Metadata of KAssetSourcePak
+    metadata : KAssetSourceMetadata,
+
+    // Signature of signed pack
+    signature : Option<KPakSignature>,
+
+    // Result of signature verification, verified once
+    verified : OnceLock<Result<Option<KPublicKey>, KTrustError>>,
+
+    // Bytes of uncompressed assets are mapped in memory
+    memory_mapped : AtomicBool,
+}
+
+/// Signature of a signed Kleio pack.
+struct KPakSignature {
+    // Header of pack, part of signed message
+    header : [u8; KPAK_HEADER_SIZE],
+
+    // Key that signed pack
+    key : KPublicKey,
+
+    // Ed25519 signature
+    signature : [u8; 64],
+
+    // SHA-512 of index loaded in memory
+    index_hash : [u8; 64],
+}
+
+/// Enumeration of possible [KAssetSourcePak] errors.
+pub enum KAssetSourcePakError {
+    /// Happens when [`pak_path`][PathBuf] used to create [KAssetSourcePak] is not found.
+    FileNotFound,
+
+    /// Happens when [`pak_path`][PathBuf] used to create [KAssetSourcePak] is not a file.
+    PathIsNotFile,
+
+    /// Happens when an IO error occurred while reading the pack.
+    ReadError,
+
+    /// Happens when the file is not a Kleio pack or its structure is invalid.
+    InvalidPak,
+
+    /// Happens when the pack was created with a newer, unsupported version of the format.
+    UnsupportedVersion,
+
+    /// Happens when the pack index checksum doesn't match.
+    CorruptedIndex,
+
+    /// Happens when a pack flagged as signed has no signature after its index.
+    MissingSignature,
+
+    /// Happens when an error occurred while creating pack metadata.
+    MetadataCreationError,
+}
+
+impl std::fmt::Debug for KAssetSourcePakError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::FileNotFound => write!(f, "FileNotFound"),
+            Self::PathIsNotFile => write!(f, "PathIsNotFile"),
+            Self::ReadError => write!(f, "ReadError"),
+            Self::InvalidPak => write!(f, "InvalidPak"),
+            Self::UnsupportedVersion => write!(f, "UnsupportedVersion"),
+            Self::CorruptedIndex => write!(f, "CorruptedIndex"),
+            Self::MissingSignature => write!(f, "MissingSignature"),
+            Self::MetadataCreationError => write!(f, "MetadataCreationError"),
+        }
+    }
+}
+
+/// Compression of an asset within a Kleio pack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KPakCompression {
+    /// Asset is stored without compression.
+    None,
+
+    /// Asset is compressed with deflate (RFC 1951).
+    Deflate,
+}
+
+impl KPakCompression {
+    /// Returns the compression flag stored in pack index.
+    pub(crate) fn to_flag(self) -> u8 {
+        match self {
+            KPakCompression::None => 0,
+            KPakCompression::Deflate => 1,
+        }
+    }
+
+    /// Returns the compression from a pack index flag or `None` if unknown.
+    pub(crate) fn from_flag(flag : u8) -> Option<KPakCompression> {
+        match flag {
+            0 => Some(KPakCompression::None),
+            1 => Some(KPakCompression::Deflate),
+            _ => None,
+        }
+    }
+}
+
+/// Index entry of an asset within a Kleio pack.
+pub(crate) struct KPakEntry {
+    // Path of the asset with `/` separator
+    pub(crate) path : String,
+
+    // Compression of asset data
+    pub(crate) compression : KPakCompression,
+
+    // CRC-32 of uncompressed data
+    pub(crate) checksum : u32,
+
+    // Offset of asset data from the start of the pack
+    pub(crate) offset : u64,
+
+    // Size of asset data in pack
+    pub(crate) compressed_size : u64,
+
+    // Size of uncompressed asset
+    pub(crate) size : u64,
+
+    // SHA-256 of uncompressed data
+    pub(crate) hash : [u8; 32],
+}
+
+impl KPakEntry {
+    /// Append the serialized entry to index.
+    pub(crate) fn write(&self, index : &mut Vec<u8>) {
+        index.extend_from_slice(&(self.path.len() as u16).to_le_bytes());
+        index.extend_from_slice(self.path.as_bytes());
+        index.push(self.compression.to_flag());
+        index.push(0);
+        index.extend_from_slice(&self.checksum.to_le_bytes());
+        index.extend_from_slice(&self.offset.to_le_bytes());
+        index.extend_from_slice(&self.compressed_size.to_le_bytes());
+        index.extend_from_slice(&self.size.to_le_bytes());
+        index.extend_from_slice(&self.hash);
+    }
+
+    /// Read a serialized entry from index at position.
+    ///
+    /// Returns `Some((KPakEntry, next position))` or `None` if entry is invalid.
+    fn read(index : &[u8], position : usize) -> Option<(KPakEntry, usize)> {
+        if position + 2 > index.len() {
+            return None;
+        }
+
+        let path_length = read_u16(index, position) as usize;
+        let data = position + 2 + path_length;
+        if data + KPAK_INDEX_ENTRY_SIZE - 2 > index.len() {
+            return None;
+        }
+
+        let mut hash = [0u8; 32];
+        hash.copy_from_slice(&index[data + 30..data + 62]);
+
+        let entry = KPakEntry {
+            path: String::from_utf8(index[position + 2..data].to_vec()).ok()?,
+            compression: KPakCompression::from_flag(index[data])?,
+            checksum: read_u32(index, data + 2),
+            offset: read_u64(index, data + 6),
+            compressed_size: read_u64(index, data + 14),
+            size: read_u64(index, data + 22),
+            hash,
+        };
+
+        Some((entry, data + KPAK_INDEX_ENTRY_SIZE - 2))
+    }
+}
+
+impl KAssetSourcePak {
+    /// Create a new [KAssetSourcePak] from a [`pak_path`][PathBuf].
+    ///
+    /// The index is read once, verified and kept in memory.
+    ///
+    /// [Metadata](KAssetSource::get_metadata) gives pack path, times, entry count and uncompressed size,
+    /// with pack file name as name.
+    ///
+    /// Returns `Ok(`[KAssetSourcePak]`)` if successful.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KAssetSourcePakError::FileNotFound]`)` if pack is not found.
+    ///
+    /// Returns `Err(`[KAssetSourcePakError::PathIsNotFile]`)` if [`pak_path`][PathBuf] is not a file.
+    ///
+    /// Returns `Err(`[KAssetSourcePakError::ReadError]`)` if pack couldn't be read.
+    ///
+    /// Returns `Err(`[KAssetSourcePakError::InvalidPak]`)` if file is not a Kleio pack, is truncated or an entry has impossible sizes.
+    ///
+    /// Returns `Err(`[KAssetSourcePakError::UnsupportedVersion]`)` if pack version is newer than supported.
+    ///
+    /// Returns `Err(`[KAssetSourcePakError::CorruptedIndex]`)` if pack index is corrupted.
+    ///
+    /// Returns `Err(`[KAssetSourcePakError::MissingSignature]`)` if pack is signed but has no signature.
+    ///
+    /// Returns `Err(`[KAssetSourcePakError::MetadataCreationError]`)` if an error occurred while creating metadata.
+    pub fn new(pak_path : PathBuf) -> Result<KAssetSourcePak, KAssetSourcePakError> {
+
+        if !pak_path.exists() {
+            return Err(KAssetSourcePakError::FileNotFound);
+        }
+
+        if !pak_path.is_file(){
+            return Err(KAssetSourcePakError::PathIsNotFile);
+        }
+
+        let mut file = match File::open(&pak_path) {
+            Ok(file) => file,
+            Err(_) => return Err(KAssetSourcePakError::ReadError),
+        };
+
+        let (entries, signature) = Self::read_index(&mut file)?;
+        let size = entries.iter().fold(0u64, |size, entry| size.saturating_add(entry.size));
+        let metadata = match create_archive_metadata(&pak_path, KAssetSourceKind::Pak, entries.len(), size) {
+            Some(metadata) => metadata,
+            None => return Err(KAssetSourcePakError::MetadataCreationError),
+        };
+
+        Ok(KAssetSourcePak {
+            pak_path,
+            entries,
+            metadata,
+            signature,
+            verified: OnceLock::new(),
+            memory_mapped: AtomicBool::new(false),
+        })
+    }
+
+    /// Get the count of assets in the pack.
+    pub fn get_entry_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Get the [KPublicKey] pack claims to be signed with, or `None` if pack isn't signed.
+    ///
+    /// Signature isn't verified, see [KAssetSource::verify_signature].
+    pub fn get_signing_key(&self) -> Option<KPublicKey> {
+        self.signature.as_ref().map(|signature| signature.key)
+    }
+
+    /// Set if [KAssetSource::get_asset_bytes] maps uncompressed assets in memory instead of reading them. Disabled by default.
+    ///
+    /// # Safety
+    /// Pack must not be truncated or modified while [KAssetBytes] mapped from it are alive, see [KAssetBytes::map_file].
+    pub unsafe fn set_memory_mapped(&self, memory_mapped : bool) {
+        self.memory_mapped.store(memory_mapped, Ordering::Relaxed);
+    }
+
+    /// Verify if [KAssetSource::get_asset_bytes] maps uncompressed assets in memory.
+    pub fn is_memory_mapped(&self) -> bool {
+        self.memory_mapped.load(Ordering::Relaxed)
+    }
+
+    /// Read and verify the pack header and index.
+    ///
+    /// Returns `Ok((Vec, signature))` of entries sorted by path with signature of signed packs.
+    fn read_index(file : &mut File) -> Result<(Vec<KPakEntry>, Option<KPakSignature>), KAssetSourcePakError> {
+
+        let file_size = match file.metadata() {
+            Ok(md) => md.len(),
+            Err(_) => return Err(KAssetSourcePakError::ReadError),
+        };
+
+        let header = read_at(file, 0, KPAK_HEADER_SIZE)?;
+        if header[0..4] != KPAK_MAGIC {
+            return Err(KAssetSourcePakError::InvalidPak);
+        }
+
+        if read_u16(&header, 4) > KPAK_VERSION {
+            return Err(KAssetSourcePakError::UnsupportedVersion);
+        }
+
+        let entry_count = read_u32(&header, 8) as usize;
+        let index_checksum = read_u32(&header, 12);
+        let index_offset = read_u64(&header, 16);
+        let index_size = read_u64(&header, 24);
+
+        if index_offset.checked_add(index_size).is_none_or(|end| end > file_size) {
+            return Err(KAssetSourcePakError::InvalidPak);
+        }
+
+        let index = read_at(file, index_offset, index_size as usize)?;
+        if crc32(&index) != index_checksum {
+            return Err(KAssetSourcePakError::CorruptedIndex);
+        }
+
+        let mut entries : Vec<KPakEntry> = Vec::with_capacity(entry_count.min(index.len() / KPAK_INDEX_ENTRY_SIZE));
+        let mut position = 0;
+        for _ in 0..entry_count {
+            let (entry, next) = match KPakEntry::read(&index, position) {
+                Some(entry) => entry,
+                None => return Err(KAssetSourcePakError::InvalidPak),
+            };
+
+            // Entries must be canonical paths, strictly sorted for binary search and within pack data.
+            // Index checksum doesn't protect sizes from a hostile pack, so size must be possible from compressed size.
+            let valid_size = match entry.compression {
+                KPakCompression::None => entry.size == entry.compressed_size,
+                KPakCompression::Deflate => entry.size <= entry.compressed_size.saturating_mul(KARCHIVE_DEFLATE_MAX_RATIO),
+            };
+
+            if !KAssetPath::new(&entry.path).is_ok_and(|path| !path.is_root() && path == entry.path.as_str())
+                || entries.last().is_some_and(|last| last.path >= entry.path)
+                || entry.offset.checked_add(entry.compressed_size).is_none_or(|end| end > index_offset)
+                || !valid_size {
+                return Err(KAssetSourcePakError::InvalidPak);
+            }
+
+            entries.push(entry);
+            position = next;
+        }
+
+        let signature = match read_u16(&header, 6) & KPAK_FLAG_SIGNED {
+            0 => None,
+            _ => {
+                let trailer = match read_at(file, index_offset + index_size, KPAK_SIGNATURE_SIZE) {
+                    Ok(trailer) if trailer[0..4] == KPAK_SIGNATURE_MAGIC => trailer,
+                    Ok(_) | Err(KAssetSourcePakError::InvalidPak) => return Err(KAssetSourcePakError::MissingSignature),
+                    Err(err) => return Err(err),
+                };
+
+                let mut signed_header = [0u8; KPAK_HEADER_SIZE];
+                let mut key = [0u8; 32];
+                let mut signature = [0u8; 64];
+                signed_header.copy_from_slice(&header);
+                key.copy_from_slice(&trailer[4..36]);
+                signature.copy_from_slice(&trailer[36..KPAK_SIGNATURE_SIZE]);
+
+                // Signature is verified against this index, the one used to serve assets
+                let mut index_hash = KSha512::new();
+                index_hash.update(&index);
+
+                Some(KPakSignature { header : signed_header, key : KPublicKey::from_bytes(key), signature, index_hash : index_hash.finish() })
+            },
+        };
+
+        Ok((entries, signature))
+    }
+
+    /// Verify signature of header and index loaded in memory.
+    fn verify_pak_signature(signature : &KPakSignature) -> Result<Option<KPublicKey>, KTrustError> {
+        match signature.key.verify(&get_signed_message(&signature.header, &signature.index_hash), &signature.signature) {
+            true => Ok(Some(signature.key)),
+            false => Err(KTrustError::InvalidSignature),
+        }
+    }
+
+    /// Open pack at data of asset of path.
+    ///
+    /// Returns `Ok((entry, file))` with file positioned at asset data.
+    fn open_entry(&self, path : &KAssetPath) -> Result<(&KPakEntry, File), std::io::Error> {
+
+        let entry = match self.get_entry(path) {
+            Some(entry) => entry,
+            None => return Err(std::io::Error::new(ErrorKind::NotFound, "Asset not found!")),
+        };
+
+        let mut file = File::open(&self.pak_path)?;
+        file.seek(SeekFrom::Start(entry.offset))?;
+
+        Ok((entry, file))
+    }
+
+    /// Read asset entirely from file positioned at asset data, decompressing it if needed.
+    ///
+    /// Asset is verified against its CRC-32 and, for signed packs, against its signed SHA-256.
+    fn read_entry(&self, entry : &KPakEntry, file : File) -> Result<Vec<u8>, std::io::Error> {
+        let mut compressed = Vec::with_capacity(get_capacity(entry.compressed_size));
+        file.take(entry.compressed_size).read_to_end(&mut compressed)?;
+
+        let data = match entry.compression {
+            KPakCompression::None => compressed,
+            KPakCompression::Deflate => {
+                let mut data = Vec::with_capacity(get_capacity(entry.size));
+                inflate(&compressed, &mut data, usize::try_from(entry.size).unwrap_or(usize::MAX))?;
+                data
+            },
+        };
+
+        if data.len() as u64 != entry.size || crc32(&data) != entry.checksum {
+            return Err(std::io::Error::new(ErrorKind::InvalidData, "Pack asset is corrupted!"));
+        }
+
+        self.verify_entry_hash(entry, &data)?;
+        Ok(data)
+    }
+
+    /// Verify data of asset against its signed SHA-256 if pack is signed.
+    fn verify_entry_hash(&self, entry : &KPakEntry, data : &[u8]) -> Result<(), std::io::Error> {
+        if self.signature.is_none() {
+            return Ok(());
+        }
+
+        let mut hash = KSha256::new();
+        hash.update(data);
+        match hash.finish() == entry.hash {
+            true => Ok(()),
+            false => Err(std::io::Error::new(ErrorKind::InvalidData, "Pack asset doesn't match its signed hash!")),
+        }
+    }
+
+    /// Find entry of path with binary search.
+    fn get_entry(&self, path : &KAssetPath) -> Option<&KPakEntry> {
+        match self.entries.binary_search_by(|entry| entry.path.as_str().cmp(path.as_str())) {
+            Ok(index) => Some(&self.entries[index]),
+            Err(_) => None,
+        }
+    }
+
+}
+
+impl KAssetSource for KAssetSourcePak {
+
+    fn get_metadata(&self) -> KAssetSourceMetadata {
+        self.metadata.clone()
+    }
+
+    fn has_asset(&self, path: &KAssetPath) -> bool {
+        self.get_entry(path).is_some()
+    }
+
+    fn get_asset_metadata(&self, path: &KAssetPath) -> Result<KAssetMetadata, std::io::Error> {
+        // Packs don't keep times of assets, so assets were last modified with pack
+        match self.get_entry(path) {
+            Some(entry) => Ok(KAssetMetadata { size : entry.size, modified : self.metadata.modified, hash : Some(entry.checksum) }),
+            None => Err(std::io::Error::new(ErrorKind::NotFound, "Asset not found!")),
+        }
+    }
+
+    fn get_asset(&self, path: &KAssetPath) -> Result<Box<dyn Read>, std::io::Error> {
+
+        let (entry, file) = self.open_entry(path)?;
+
+        match entry.compression {
+            // Uncompressed assets of unsigned packs are streamed directly from the pack
+            KPakCompression::None if self.signature.is_none() => Ok(Box::new(KCrc32Reader::new(file.take(entry.compressed_size), entry.checksum))),
+
+            // Other assets are verified in memory before being served
+            _ => Ok(Box::new(Cursor::new(self.read_entry(entry, file)?))),
+        }
+    }
+
+    fn get_asset_seekable(&self, path: &KAssetPath) -> Result<KAssetSeekReader, std::io::Error> {
+
+        let (entry, file) = self.open_entry(path)?;
+
+        match entry.compression {
+            // Uncompressed assets of unsigned packs are read directly from the pack
+            KPakCompression::None if self.signature.is_none() => Ok(KAssetSeekReader::new(Box::new(KArchiveWindow::new(file, entry.offset, entry.compressed_size)), entry.compressed_size)),
+
+            // Other assets are verified in memory before being served
+            _ => Ok(KAssetSeekReader::new(Box::new(Cursor::new(self.read_entry(entry, file)?)), entry.size)),
+        }
+    }
+
+    fn get_asset_bytes(&self, path: &KAssetPath) -> Result<KAssetBytes, std::io::Error> {
+
+        let (entry, file) = self.open_entry(path)?;
+
+        match entry.compression {
+            // Uncompressed assets are mapped directly from the pack and verified like read assets.
+            // Caller of set_memory_mapped guarantees pack isn't modified while mapped.
+            KPakCompression::None if self.is_memory_mapped() => {
+                let bytes = unsafe { KAssetBytes::map_file(&file, entry.offset, entry.compressed_size)? };
+                if crc32(&bytes) != entry.checksum {
+                    return Err(std::io::Error::new(ErrorKind::InvalidData, "Pack asset is corrupted!"));
+                }
+
+                self.verify_entry_hash(entry, &bytes)?;
+                Ok(bytes)
+            },
+
+            // Other assets are verified in memory
+            _ => Ok(self.read_entry(entry, file)?.into()),
+        }
+    }
+
+    fn list_assets(&self, folder: &KAssetPath) -> Result<Vec<KAssetPath>, std::io::Error> {
+        Ok(list_archive_entries(self.entries.iter().map(|entry| entry.path.as_str()), folder))
+    }
+
+    fn verify_signature(&self) -> Result<Option<KPublicKey>, KTrustError> {
+        match &self.signature {
+            Some(signature) => *self.verified.get_or_init(|| Self::verify_pak_signature(signature)),
+            None => Ok(None),
+        }
+    }
+
+}
+
+/// Get message signed by signed packs : context, header and SHA-512 of index.
+///
+/// Assets are covered by their SHA-256 within the index.
+pub(crate) fn get_signed_message(header : &[u8], index_hash : &[u8; 64]) -> Vec<u8> {
+    let mut message = Vec::with_capacity(8 + header.len() + index_hash.len());
+    message.extend_from_slice(b"KPAK-SIG");
+    message.extend_from_slice(header);
+    message.extend_from_slice(index_hash);
+    message
+}
+
+/// Read `size` bytes of file at `offset`.
+fn read_at(file : &mut File, offset : u64, size : usize) -> Result<Vec<u8>, KAssetSourcePakError> {
+    let mut buffer = vec![0u8; size];
+
+    match file.seek(SeekFrom::Start(offset)).and_then(|_| file.read_exact(&mut buffer)) {
+        Ok(_) => Ok(buffer),
+        Err(err) => match err.kind() {
+            ErrorKind::UnexpectedEof => Err(KAssetSourcePakError::InvalidPak),
+            _ => Err(KAssetSourcePakError::ReadError),
+        },
+    }
+}
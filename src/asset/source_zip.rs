@@ -0,0 +1,456 @@
+use std::{path::PathBuf, fs::File, io::{Read, Seek, SeekFrom, Cursor, ErrorKind}, collections::HashMap};
+use crate::asset::{KAssetSource, KAssetSeekReader, KAssetPath, KAssetSourceMetadata, KAssetSourceKind, KAssetMetadata};
+use super::codec::{inflate::inflate, crc32::{crc32, KCrc32Reader}};
+use super::archive::{KArchiveWindow, KARCHIVE_DEFLATE_MAX_RATIO, list_archive_entries, create_archive_metadata, dos_time_to_system_time, get_capacity,
+    read_u16, read_u32, read_u64};
+
+/// End of central directory record signature.
+const ZIP_EOCD_SIGNATURE : u32 = 0x06054b50;
+
+/// Zip64 end of central directory locator signature.
+const ZIP64_EOCD_LOCATOR_SIGNATURE : u32 = 0x07064b50;
+
+/// Zip64 end of central directory record signature.
+const ZIP64_EOCD_SIGNATURE : u32 = 0x06064b50;
+
+/// Central directory file header signature.
+const ZIP_CENTRAL_HEADER_SIGNATURE : u32 = 0x02014b50;
+
+/// Local file header signature.
+const ZIP_LOCAL_HEADER_SIGNATURE : u32 = 0x04034b50;
+
+/// Size of the end of central directory record without comment.
+const ZIP_EOCD_SIZE : usize = 22;
+
+/// Size of a local file header without name and extra field.
+const ZIP_LOCAL_HEADER_SIZE : usize = 30;
+
+/// Stored (uncompressed) compression method.
+const ZIP_METHOD_STORED : u16 = 0;
+
+/// Deflate compression method.
+const ZIP_METHOD_DEFLATED : u16 = 8;
+
+/// ##### [KAssetSource] implementation using a zip archive.
+///
+/// KAssetSourceZip reads the archive central directory once on creation and serves
+/// stored and deflated entries directly from the archive, without extraction.
+///
+/// ```no_run
+/// // Import crate module
+/// use std::path::PathBuf;
+/// use olympus_kleio::asset::{KAssetSource , KAssetSourceZip, KAssetPath};
+///
+/// // Create KAssetSourceZip from an archive
+/// let source : KAssetSourceZip = KAssetSourceZip::new(PathBuf::from("/base_archive.zip")).unwrap();
+///
+/// // Get assets from KAssetSourceZip with path relative to archive root
+/// let asset1 = source.get_asset(&KAssetPath::new("audio/audio1.ogg").unwrap());
+/// let asset2 = source.get_asset(&KAssetPath::new("models/model.obj").unwrap());
+/// ```
+pub struct KAssetSourceZip {
+    // Path of the zip archive
+    zip_path : PathBuf,
+
+    // Entries of the archive central directory, indexed by canonical path
+    entries : HashMap<String, KAssetZipEntry>,
+
+    // Metadata of KAssetSourceZip
+    metadata : KAssetSourceMetadata
+}
+
+/// Enumeration of possible [KAssetSourceZip] errors.
+pub enum KAssetSourceZipError {
+    /// Happens when [`zip_path`][PathBuf] used to create [KAssetSourceZip] is not found.
+    FileNotFound,
+
+    /// Happens when [`zip_path`][PathBuf] used to create [KAssetSourceZip] is not a file.
+    PathIsNotFile,
+
+    /// Happens when an IO error occurred while reading the archive.
+    ReadError,
+
+    /// Happens when the archive central directory is invalid or corrupted.
+    InvalidArchive,
+
+    /// Happens when the archive spans multiple disks.
+    MultiDiskArchive,
+
+    /// Happens when an error occurred while creating archive metadata.
+    MetadataCreationError,
+}
+
+impl std::fmt::Debug for KAssetSourceZipError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::FileNotFound => write!(f, "FileNotFound"),
+            Self::PathIsNotFile => write!(f, "PathIsNotFile"),
+            Self::ReadError => write!(f, "ReadError"),
+            Self::InvalidArchive => write!(f, "InvalidArchive"),
+            Self::MultiDiskArchive => write!(f, "MultiDiskArchive"),
+            Self::MetadataCreationError => write!(f, "MetadataCreationError"),
+        }
+    }
+}
+
+/// Central directory informations of a zip entry.
+struct KAssetZipEntry {
+    // General purpose flags
+    flags : u16,
+
+    // Compression method
+    method : u16,
+
+    // MS-DOS time of last modification
+    time : u16,
+
+    // MS-DOS date of last modification
+    date : u16,
+
+    // CRC-32 of uncompressed data
+    crc32 : u32,
+
+    // Compressed size
+    compressed_size : u64,
+
+    // Uncompressed size
+    uncompressed_size : u64,
+
+    // Offset of the local file header
+    header_offset : u64,
+}
+
+impl KAssetSourceZip {
+    /// Create a new [KAssetSourceZip] from a [`zip_path`][PathBuf].
+    ///
+    /// The central directory is read once and kept in memory. Zip64 archives are supported.
+    ///
+    /// [Metadata](KAssetSource::get_metadata) gives archive path, times, entry count and uncompressed size,
+    /// with archive file name as name.
+    ///
+    /// Returns `Ok(`[KAssetSourceZip]`)` if successful.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KAssetSourceZipError::FileNotFound]`)` if archive is not found.
+    ///
+    /// Returns `Err(`[KAssetSourceZipError::PathIsNotFile]`)` if [`zip_path`][PathBuf] is not a file.
+    ///
+    /// Returns `Err(`[KAssetSourceZipError::ReadError]`)` if archive couldn't be read.
+    ///
+    /// Returns `Err(`[KAssetSourceZipError::InvalidArchive]`)` if archive central directory is invalid or an entry has impossible sizes or offset.
+    ///
+    /// Returns `Err(`[KAssetSourceZipError::MultiDiskArchive]`)` if archive spans multiple disks.
+    ///
+    /// Returns `Err(`[KAssetSourceZipError::MetadataCreationError]`)` if an error occurred while creating metadata.
+    pub fn new(zip_path : PathBuf) -> Result<KAssetSourceZip, KAssetSourceZipError> {
+
+        if !zip_path.exists() {
+            return Err(KAssetSourceZipError::FileNotFound);
+        }
+
+        if !zip_path.is_file(){
+            return Err(KAssetSourceZipError::PathIsNotFile);
+        }
+
+        let mut file = match File::open(&zip_path) {
+            Ok(file) => file,
+            Err(_) => return Err(KAssetSourceZipError::ReadError),
+        };
+
+        let entries = Self::read_central_directory(&mut file)?;
+        let size = entries.values().fold(0u64, |size, entry| size.saturating_add(entry.uncompressed_size));
+        let metadata = match create_archive_metadata(&zip_path, KAssetSourceKind::Zip, entries.len(), size) {
+            Some(metadata) => metadata,
+            None => return Err(KAssetSourceZipError::MetadataCreationError),
+        };
+
+        Ok(KAssetSourceZip {
+            zip_path,
+            entries,
+            metadata,
+        })
+    }
+
+    /// Get the count of file entries in the archive.
+    pub fn get_entry_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Read the archive central directory.
+    ///
+    /// Returns `Ok(HashMap)` of file entries indexed by normalized name.
+    fn read_central_directory(file : &mut File) -> Result<HashMap<String, KAssetZipEntry>, KAssetSourceZipError> {
+
+        let file_size = match file.seek(SeekFrom::End(0)) {
+            Ok(size) => size,
+            Err(_) => return Err(KAssetSourceZipError::ReadError),
+        };
+
+        if file_size < ZIP_EOCD_SIZE as u64 {
+            return Err(KAssetSourceZipError::InvalidArchive);
+        }
+
+        // End of central directory is within the last 22 + 65535 (max comment) bytes.
+        let tail_size = file_size.min((ZIP_EOCD_SIZE + u16::MAX as usize) as u64);
+        let tail_offset = file_size - tail_size;
+        let tail = read_at(file, tail_offset, tail_size as usize)?;
+
+        // Search end of central directory signature backward
+        let eocd = match (0..=tail.len() - ZIP_EOCD_SIZE).rev().find(|i| read_u32(&tail, *i) == ZIP_EOCD_SIGNATURE) {
+            Some(eocd) => eocd,
+            None => return Err(KAssetSourceZipError::InvalidArchive),
+        };
+
+        let disk = read_u16(&tail, eocd + 4);
+        let cd_disk = read_u16(&tail, eocd + 6);
+        let mut entry_count = read_u16(&tail, eocd + 10) as u64;
+        let mut cd_size = read_u32(&tail, eocd + 12) as u64;
+        let mut cd_offset = read_u32(&tail, eocd + 16) as u64;
+
+        if disk != 0 || cd_disk != 0 {
+            // Disk numbers of 0xFFFF are resolved by the zip64 record
+            if disk != u16::MAX && cd_disk != u16::MAX {
+                return Err(KAssetSourceZipError::MultiDiskArchive);
+            }
+        }
+
+        // Zip64 end of central directory locator precedes the end of central directory record.
+        if eocd >= 20 && read_u32(&tail, eocd - 20) == ZIP64_EOCD_LOCATOR_SIGNATURE {
+            let zip64_offset = read_u64(&tail, eocd - 12);
+            let zip64 = read_at(file, zip64_offset, 56)?;
+
+            if read_u32(&zip64, 0) != ZIP64_EOCD_SIGNATURE {
+                return Err(KAssetSourceZipError::InvalidArchive);
+            }
+
+            if read_u32(&zip64, 16) != 0 || read_u32(&zip64, 20) != 0 {
+                return Err(KAssetSourceZipError::MultiDiskArchive);
+            }
+
+            entry_count = read_u64(&zip64, 32);
+            cd_size = read_u64(&zip64, 40);
+            cd_offset = read_u64(&zip64, 48);
+        }
+
+        if cd_offset.checked_add(cd_size).is_none_or(|end| end > file_size) {
+            return Err(KAssetSourceZipError::InvalidArchive);
+        }
+
+        let cd = read_at(file, cd_offset, cd_size as usize)?;
+        let mut entries = HashMap::new();
+        let mut position = 0;
+
+        for _ in 0..entry_count {
+            if position + 46 > cd.len() || read_u32(&cd, position) != ZIP_CENTRAL_HEADER_SIGNATURE {
+                return Err(KAssetSourceZipError::InvalidArchive);
+            }
+
+            let name_length = read_u16(&cd, position + 28) as usize;
+            let extra_length = read_u16(&cd, position + 30) as usize;
+            let comment_length = read_u16(&cd, position + 32) as usize;
+            let name_start = position + 46;
+            let extra_start = name_start + name_length;
+            let next = extra_start + extra_length + comment_length;
+
+            if next > cd.len() {
+                return Err(KAssetSourceZipError::InvalidArchive);
+            }
+
+            let mut entry = KAssetZipEntry {
+                flags: read_u16(&cd, position + 8),
+                method: read_u16(&cd, position + 10),
+                time: read_u16(&cd, position + 12),
+                date: read_u16(&cd, position + 14),
+                crc32: read_u32(&cd, position + 16),
+                compressed_size: read_u32(&cd, position + 20) as u64,
+                uncompressed_size: read_u32(&cd, position + 24) as u64,
+                header_offset: read_u32(&cd, position + 42) as u64,
+            };
+
+            Self::read_zip64_extra(&cd[extra_start..extra_start + extra_length], &mut entry)?;
+            Self::check_entry(&entry, file_size)?;
+
+            // Names ending with separator are folders and are not assets.
+            // Names escaping the archive can't be asset paths and are ignored.
+            let name = String::from_utf8_lossy(&cd[name_start..extra_start]).replace('\\', "/");
+            if !name.ends_with('/') {
+                match KAssetPath::new(name.trim_start_matches('/')) {
+                    Ok(path) if !path.is_root() => { entries.insert(path.as_str().to_owned(), entry); },
+                    _ => {},
+                }
+            }
+
+            position = next;
+        }
+
+        Ok(entries)
+    }
+
+    /// Verify sizes and offset of entry are possible within archive of `file_size` bytes, since they aren't trusted.
+    fn check_entry(entry : &KAssetZipEntry, file_size : u64) -> Result<(), KAssetSourceZipError> {
+        // Data follows local header, so it ends after header offset plus compressed size
+        if entry.header_offset.checked_add(entry.compressed_size).is_none_or(|end| end > file_size) {
+            return Err(KAssetSourceZipError::InvalidArchive);
+        }
+
+        let valid_size = match entry.method {
+            ZIP_METHOD_STORED => entry.uncompressed_size == entry.compressed_size,
+            ZIP_METHOD_DEFLATED => entry.uncompressed_size <= entry.compressed_size.saturating_mul(KARCHIVE_DEFLATE_MAX_RATIO),
+            // Unsupported methods fail when read
+            _ => true,
+        };
+
+        match valid_size {
+            true => Ok(()),
+            false => Err(KAssetSourceZipError::InvalidArchive),
+        }
+    }
+
+    /// Open archive at data of entry of path.
+    ///
+    /// Returns `Ok((entry, file, data offset))` with file positioned at entry data.
+    fn open_entry(&self, path : &KAssetPath) -> Result<(&KAssetZipEntry, File, u64), std::io::Error> {
+
+        let entry = match self.entries.get(path.as_str()) {
+            Some(entry) => entry,
+            None => return Err(std::io::Error::new(ErrorKind::NotFound, "Asset not found!")),
+        };
+
+        // Encrypted entries are not supported
+        if entry.flags & 0x1 != 0 {
+            return Err(std::io::Error::new(ErrorKind::Unsupported, "Encrypted zip entries are not supported!"));
+        }
+
+        if entry.method != ZIP_METHOD_STORED && entry.method != ZIP_METHOD_DEFLATED {
+            return Err(std::io::Error::new(ErrorKind::Unsupported, "Unsupported zip compression method!"));
+        }
+
+        // Local header name and extra field length may differ from central directory.
+        let mut file = File::open(&self.zip_path)?;
+        let mut header = [0u8; ZIP_LOCAL_HEADER_SIZE];
+        file.seek(SeekFrom::Start(entry.header_offset))?;
+        file.read_exact(&mut header)?;
+
+        if read_u32(&header, 0) != ZIP_LOCAL_HEADER_SIGNATURE {
+            return Err(std::io::Error::new(ErrorKind::InvalidData, "Invalid zip local header!"));
+        }
+
+        let data_offset = entry.header_offset + ZIP_LOCAL_HEADER_SIZE as u64 + read_u16(&header, 26) as u64 + read_u16(&header, 28) as u64;
+        file.seek(SeekFrom::Start(data_offset))?;
+
+        Ok((entry, file, data_offset))
+    }
+
+    /// Decompress deflated entry from file positioned at entry data and verify its CRC-32.
+    fn inflate_entry(entry : &KAssetZipEntry, file : File) -> Result<Vec<u8>, std::io::Error> {
+        let mut compressed = Vec::with_capacity(get_capacity(entry.compressed_size));
+        file.take(entry.compressed_size).read_to_end(&mut compressed)?;
+
+        let mut data = Vec::with_capacity(get_capacity(entry.uncompressed_size));
+        inflate(&compressed, &mut data, usize::try_from(entry.uncompressed_size).unwrap_or(usize::MAX))?;
+
+        if data.len() as u64 != entry.uncompressed_size || crc32(&data) != entry.crc32 {
+            return Err(std::io::Error::new(ErrorKind::InvalidData, "Zip entry is corrupted!"));
+        }
+
+        Ok(data)
+    }
+
+    /// Replace saturated 32 bits sizes and offset of entry with zip64 extended information if present.
+    fn read_zip64_extra(extra : &[u8], entry : &mut KAssetZipEntry) -> Result<(), KAssetSourceZipError> {
+
+        let mut position = 0;
+
+        while position + 4 <= extra.len() {
+            let id = read_u16(extra, position);
+            let size = read_u16(extra, position + 2) as usize;
+            let data = position + 4;
+
+            if data + size > extra.len() {
+                return Err(KAssetSourceZipError::InvalidArchive);
+            }
+
+            if id == 0x0001 {
+                // Fields are only present if their 32 bits counterpart is saturated, in this order.
+                let mut field = data;
+                for value in [&mut entry.uncompressed_size, &mut entry.compressed_size, &mut entry.header_offset] {
+                    if *value == u32::MAX as u64 {
+                        if field + 8 > data + size {
+                            return Err(KAssetSourceZipError::InvalidArchive);
+                        }
+                        *value = read_u64(extra, field);
+                        field += 8;
+                    }
+                }
+            }
+
+            position = data + size;
+        }
+
+        Ok(())
+    }
+
+}
+
+impl KAssetSource for KAssetSourceZip {
+
+    fn get_metadata(&self) -> KAssetSourceMetadata {
+        self.metadata.clone()
+    }
+
+    fn has_asset(&self, path: &KAssetPath) -> bool {
+        self.entries.contains_key(path.as_str())
+    }
+
+    fn get_asset_metadata(&self, path: &KAssetPath) -> Result<KAssetMetadata, std::io::Error> {
+        match self.entries.get(path.as_str()) {
+            Some(entry) => Ok(KAssetMetadata { size : entry.uncompressed_size, modified : dos_time_to_system_time(entry.date, entry.time), hash : Some(entry.crc32) }),
+            None => Err(std::io::Error::new(ErrorKind::NotFound, "Asset not found!")),
+        }
+    }
+
+    fn get_asset(&self, path: &KAssetPath) -> Result<Box<dyn Read>, std::io::Error> {
+
+        let (entry, file, _) = self.open_entry(path)?;
+
+        match entry.method {
+            // Stored entries are streamed directly from the archive
+            ZIP_METHOD_STORED => Ok(Box::new(KCrc32Reader::new(file.take(entry.compressed_size), entry.crc32))),
+
+            // Deflated entries are decompressed in memory
+            _ => Ok(Box::new(Cursor::new(Self::inflate_entry(entry, file)?))),
+        }
+    }
+
+    fn get_asset_seekable(&self, path: &KAssetPath) -> Result<KAssetSeekReader, std::io::Error> {
+
+        let (entry, file, data_offset) = self.open_entry(path)?;
+
+        match entry.method {
+            // Stored entries are read directly from the archive
+            ZIP_METHOD_STORED => Ok(KAssetSeekReader::new(Box::new(KArchiveWindow::new(file, data_offset, entry.compressed_size)), entry.compressed_size)),
+
+            // Deflated entries are decompressed in memory
+            _ => Ok(KAssetSeekReader::new(Box::new(Cursor::new(Self::inflate_entry(entry, file)?)), entry.uncompressed_size)),
+        }
+    }
+
+    fn list_assets(&self, folder: &KAssetPath) -> Result<Vec<KAssetPath>, std::io::Error> {
+        Ok(list_archive_entries(self.entries.keys().map(|name| name.as_str()), folder))
+    }
+
+}
+
+/// Read `size` bytes of file at `offset`.
+fn read_at(file : &mut File, offset : u64, size : usize) -> Result<Vec<u8>, KAssetSourceZipError> {
+    let mut buffer = vec![0u8; size];
+
+    match file.seek(SeekFrom::Start(offset)).and_then(|_| file.read_exact(&mut buffer)) {
+        Ok(_) => Ok(buffer),
+        Err(err) => match err.kind() {
+            ErrorKind::UnexpectedEof => Err(KAssetSourceZipError::InvalidArchive),
+            _ => Err(KAssetSourceZipError::ReadError),
+        },
+    }
+
+}
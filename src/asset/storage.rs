@@ -0,0 +1,397 @@
+use std::{any::{Any, TypeId}, collections::HashMap, ops::Deref, sync::{Arc, Weak, Mutex, MutexGuard}};
+
+use crate::journal::KJournal;
+use super::{KAssetBroker, KAssetLoaderError, KAssetPath, KIntoAssetPath};
+
+/// ##### Storage of typed assets loaded by a [KAssetBroker], shared with reference-counted handles.
+///
+/// Assets are loaded with the [KAssetLoader](super::KAssetLoader) registered in broker and stored once per path and type,
+/// so requesting the same path twice gives the same instance.
+///
+/// # Unload
+/// With [KAssetUnloadPolicy::Automatic], an asset is unloaded as soon as its last [KAssetHandle] is dropped.
+/// With [KAssetUnloadPolicy::Manual], storage keeps assets loaded until [KAssetStorage::unload] or
+/// [KAssetStorage::unload_unused] is called.
+///
+/// Unloading never invalidates handles : an asset still used is only removed from storage and is loaded
+/// again on next request.
+///
+/// # Example(s)
+/// ```
+/// // Import crate module
+/// use std::{path::PathBuf, io::Read, sync::Arc};
+/// use olympus_kleio::asset::{KAssetBroker, KAssetSourceMemory, KAssetLoader, KAssetLoaderError, KAssetStorage, KAssetPath};
+///
+/// struct TextLoader;
+///
+/// impl KAssetLoader for TextLoader {
+///     type Asset = String;
+///
+///     fn load(&self, _path : &KAssetPath, reader : &mut dyn Read) -> Result<String, KAssetLoaderError> {
+///         let mut content = String::new();
+///         reader.read_to_string(&mut content).map_err(|_| KAssetLoaderError::ReadError)?;
+///         Ok(content)
+///     }
+/// }
+///
+/// let source = KAssetSourceMemory::new("text");
+/// source.add_asset(PathBuf::from("hello.txt"), "Hello".as_bytes().to_vec()).unwrap();
+///
+/// let kab = Arc::new(KAssetBroker::new());
+/// kab.add_source(Arc::new(source)).unwrap();
+/// kab.register_loader(&["txt"], TextLoader);
+///
+/// // Same path gives same instance
+/// let storage = KAssetStorage::new(kab);
+/// let hello = storage.load::<String>("hello.txt").unwrap();
+/// let again = storage.load::<String>("hello.txt").unwrap();
+/// assert!(hello.ptr_eq(&again));
+/// assert_eq!(*hello, "Hello");
+///
+/// // Asset is unloaded when last handle is dropped
+/// drop(hello);
+/// drop(again);
+/// assert!(!storage.is_loaded::<String>("hello.txt"));
+/// ```
+pub struct KAssetStorage {
+    // Broker loading assets
+    broker : Arc<KAssetBroker>,
+
+    // Assets and policy
+    state : Mutex<KAssetStorageState>,
+}
+
+/// Policy of [KAssetStorage] to unload assets no longer used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KAssetUnloadPolicy {
+    /// Assets are unloaded when their last [KAssetHandle] is dropped.
+    Automatic,
+
+    /// Assets stay loaded until [KAssetStorage::unload] or [KAssetStorage::unload_unused] is called.
+    Manual,
+}
+
+/// Asset of [KAssetStorage] listed by [KAssetStorage::get_report].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KAssetReportEntry {
+    /// Path of asset.
+    pub path : KAssetPath,
+
+    /// Name of asset type.
+    pub type_name : &'static str,
+
+    /// Count of [KAssetHandle] alive, without storage own reference.
+    pub handles : usize,
+
+    /// Count of [KAssetWeakHandle] alive.
+    pub weak_handles : usize,
+
+    /// Memory size of asset in bytes given by [KAssetLoader::get_memory_size](super::KAssetLoader::get_memory_size).
+    pub size : usize,
+}
+
+impl std::fmt::Display for KAssetReportEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Asset {} : {}, {} handles, {} weak handles, {} bytes",
+            self.path, self.type_name, self.handles, self.weak_handles, self.size)
+    }
+}
+
+/// ##### Strong handle of an asset of [KAssetStorage].
+///
+/// Cloning a handle never copies the asset. Asset is accessed with [Deref].
+pub struct KAssetHandle<T> {
+    // Asset shared
+    asset : Arc<T>,
+
+    // Path of asset
+    path : Arc<KAssetPath>,
+}
+
+/// ##### Weak handle of an asset of [KAssetStorage] that doesn't keep asset loaded.
+pub struct KAssetWeakHandle<T> {
+    // Asset shared
+    asset : Weak<T>,
+
+    // Path of asset
+    path : Arc<KAssetPath>,
+}
+
+/// Key of a stored asset.
+type KAssetStorageKey = (KAssetPath, TypeId);
+
+/// Asset stored.
+struct KAssetStorageEntry {
+    // Asset, used to upgrade
+    asset : Weak<dyn Any + Send + Sync>,
+
+    // Reference kept by storage with manual unload
+    retained : Option<Arc<dyn Any + Send + Sync>>,
+
+    // Path shared with handles
+    path : Arc<KAssetPath>,
+
+    // Name of asset type
+    type_name : &'static str,
+
+    // Memory size of asset
+    size : usize,
+}
+
+/// Mutable state of [KAssetStorage].
+struct KAssetStorageState {
+    // Assets by path and type
+    entries : HashMap<KAssetStorageKey, KAssetStorageEntry>,
+
+    // Unload policy
+    policy : KAssetUnloadPolicy,
+}
+
+impl KAssetStorage {
+    /// Create a new [KAssetStorage] loading assets from `broker`, with [KAssetUnloadPolicy::Automatic].
+    pub fn new(broker : Arc<KAssetBroker>) -> KAssetStorage {
+        KAssetStorage { broker, state : Mutex::new(KAssetStorageState { entries : HashMap::new(), policy : KAssetUnloadPolicy::Automatic }) }
+    }
+
+    /// Get the [KAssetBroker] loading assets.
+    pub fn get_broker(&self) -> &Arc<KAssetBroker> {
+        &self.broker
+    }
+
+    /// Set [KAssetUnloadPolicy] of storage.
+    ///
+    /// Switching to [KAssetUnloadPolicy::Automatic] releases storage references, unloading unused assets.
+    pub fn set_unload_policy(&self, policy : KAssetUnloadPolicy) {
+        let mut state = self.lock();
+        state.policy = policy;
+
+        for entry in state.entries.values_mut() {
+            entry.retained = match policy {
+                KAssetUnloadPolicy::Automatic => None,
+                KAssetUnloadPolicy::Manual => entry.asset.upgrade(),
+            };
+        }
+
+        state.entries.retain(|_, entry| entry.asset.strong_count() > 0);
+    }
+
+    /// Get [KAssetUnloadPolicy] of storage.
+    pub fn get_unload_policy(&self) -> KAssetUnloadPolicy {
+        self.lock().policy
+    }
+
+    /// Get [KAssetHandle] of asset of path as type `T`, loading it with broker if not loaded.
+    ///
+    /// Returns `Ok(`[KAssetHandle]`)` with the same instance for the same path and type while loaded.
+    ///
+    /// # Error(s)
+    /// Returns [KAssetLoaderError] given by [KAssetBroker::load] if asset couldn't be loaded.
+    pub fn load<T : Any + Send + Sync>(&self, path : impl KIntoAssetPath) -> Result<KAssetHandle<T>, KAssetLoaderError> {
+        let path = match path.into_asset_path() {
+            Ok(path) => path,
+            Err(_) => return Err(KAssetLoaderError::InvalidPath),
+        };
+
+        if let Some(handle) = self.get(&path) {
+            return Ok(handle);
+        }
+
+        // Storage lock isn't held while loading
+        let (asset, size) = self.broker.load_sized::<T>(&path)?;
+        let asset = Arc::new(asset);
+
+        let mut state = self.lock();
+        let key = (path, TypeId::of::<T>());
+
+        // Asset loaded meanwhile by another thread is kept
+        if let Some(handle) = Self::upgrade(&state, &key) {
+            return Ok(handle);
+        }
+
+        let shared : Arc<dyn Any + Send + Sync> = asset.clone();
+        let entry = KAssetStorageEntry {
+            asset : Arc::downgrade(&shared),
+            retained : (state.policy == KAssetUnloadPolicy::Manual).then_some(shared),
+            path : Arc::new(key.0.clone()),
+            type_name : std::any::type_name::<T>(),
+            size,
+        };
+        let handle = KAssetHandle { asset, path : entry.path.clone() };
+
+        state.entries.retain(|_, entry| entry.asset.strong_count() > 0);
+        state.entries.insert(key, entry);
+
+        Ok(handle)
+    }
+
+    /// Get [KAssetHandle] of asset of path as type `T` only if loaded.
+    ///
+    /// Returns `Some(`[KAssetHandle]`)` if loaded, `None` otherwise.
+    pub fn get<T : Any + Send + Sync>(&self, path : impl KIntoAssetPath) -> Option<KAssetHandle<T>> {
+        let path = path.into_asset_path().ok()?;
+        Self::upgrade(&self.lock(), &(path, TypeId::of::<T>()))
+    }
+
+    /// Verify if asset of path is loaded as type `T`.
+    ///
+    /// Returns True if loaded, false otherwise.
+    pub fn is_loaded<T : Any + Send + Sync>(&self, path : impl KIntoAssetPath) -> bool {
+        let path = match path.into_asset_path() {
+            Ok(path) => path,
+            Err(_) => return false,
+        };
+
+        self.lock().entries.get(&(path, TypeId::of::<T>())).is_some_and(|entry| entry.asset.strong_count() > 0)
+    }
+
+    /// Unload assets of path of all types, even if still used by handles.
+    ///
+    /// Returns count of assets unloaded.
+    pub fn unload(&self, path : impl KIntoAssetPath) -> usize {
+        let path = match path.into_asset_path() {
+            Ok(path) => path,
+            Err(_) => return 0,
+        };
+
+        let mut state = self.lock();
+        let mut count = 0;
+
+        state.entries.retain(|(entry_path, _), entry| {
+            let alive = entry.asset.strong_count() > 0;
+            if alive && *entry_path == path {
+                count += 1;
+                return false;
+            }
+            alive
+        });
+
+        count
+    }
+
+    /// Unload assets without any [KAssetHandle] left.
+    ///
+    /// Returns count of assets unloaded.
+    pub fn unload_unused(&self) -> usize {
+        let mut state = self.lock();
+        let mut count = 0;
+
+        state.entries.retain(|_, entry| {
+            let external = entry.asset.strong_count() - usize::from(entry.retained.is_some());
+            if external == 0 && entry.retained.is_some() {
+                count += 1;
+            }
+            external > 0
+        });
+
+        count
+    }
+
+    /// Unload all assets, even if still used by handles.
+    pub fn clear(&self) {
+        self.lock().entries.clear();
+    }
+
+    /// Get count of assets loaded.
+    pub fn get_loaded_count(&self) -> usize {
+        self.lock().entries.values().filter(|entry| entry.asset.strong_count() > 0).count()
+    }
+
+    /// Get [KAssetReportEntry] of each asset loaded, sorted by path.
+    pub fn get_report(&self) -> Vec<KAssetReportEntry> {
+        let state = self.lock();
+
+        let mut report : Vec<KAssetReportEntry> = state.entries.values().filter(|entry| entry.asset.strong_count() > 0).map(|entry| {
+            KAssetReportEntry {
+                path : (*entry.path).clone(),
+                type_name : entry.type_name,
+                handles : entry.asset.strong_count() - usize::from(entry.retained.is_some()),
+                // Storage own weak reference isn't counted
+                weak_handles : entry.asset.weak_count() - 1,
+                size : entry.size,
+            }
+        }).collect();
+
+        report.sort_by(|a, b| a.path.cmp(&b.path).then(a.type_name.cmp(b.type_name)));
+        report
+    }
+
+    /// Write [KAssetReportEntry] of each asset loaded to [KJournal] as entries of `severity`.
+    pub fn write_report(&self, journal : &mut KJournal, severity : u8) {
+        for entry in self.get_report() {
+            journal.write(severity, &entry.to_string());
+        }
+    }
+
+    /// Upgrade stored asset of key as handle.
+    fn upgrade<T : Any + Send + Sync>(state : &KAssetStorageState, key : &KAssetStorageKey) -> Option<KAssetHandle<T>> {
+        let entry = state.entries.get(key)?;
+        let asset = entry.asset.upgrade()?.downcast::<T>().ok()?;
+
+        Some(KAssetHandle { asset, path : entry.path.clone() })
+    }
+
+    /// Lock state. A poisoned lock is recovered since state is always left consistent.
+    fn lock(&self) -> MutexGuard<'_, KAssetStorageState> {
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl<T> KAssetHandle<T> {
+    /// Get path of asset.
+    pub fn get_path(&self) -> &KAssetPath {
+        &self.path
+    }
+
+    /// Create a [KAssetWeakHandle] of asset.
+    pub fn downgrade(&self) -> KAssetWeakHandle<T> {
+        KAssetWeakHandle { asset : Arc::downgrade(&self.asset), path : self.path.clone() }
+    }
+
+    /// Verify if both handles share the same asset instance.
+    ///
+    /// Returns True if same instance, false otherwise.
+    pub fn ptr_eq(&self, other : &KAssetHandle<T>) -> bool {
+        Arc::ptr_eq(&self.asset, &other.asset)
+    }
+}
+
+impl<T> Clone for KAssetHandle<T> {
+    fn clone(&self) -> Self {
+        KAssetHandle { asset : self.asset.clone(), path : self.path.clone() }
+    }
+}
+
+impl<T> Deref for KAssetHandle<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.asset
+    }
+}
+
+impl<T> KAssetWeakHandle<T> {
+    /// Get path of asset.
+    pub fn get_path(&self) -> &KAssetPath {
+        &self.path
+    }
+
+    /// Get [KAssetHandle] of asset if still alive.
+    ///
+    /// Returns `Some(`[KAssetHandle]`)` if asset is alive, `None` otherwise.
+    pub fn upgrade(&self) -> Option<KAssetHandle<T>> {
+        Some(KAssetHandle { asset : self.asset.upgrade()?, path : self.path.clone() })
+    }
+
+    /// Verify if asset is still alive.
+    ///
+    /// Returns True if a [KAssetHandle] or storage keeps asset alive, false otherwise.
+    pub fn is_alive(&self) -> bool {
+        self.asset.strong_count() > 0
+    }
+}
+
+impl<T> Clone for KAssetWeakHandle<T> {
+    fn clone(&self) -> Self {
+        KAssetWeakHandle { asset : self.asset.clone(), path : self.path.clone() }
+    }
+}
@@ -0,0 +1,494 @@
+use std::{io::{Read, ErrorKind}, cmp::Ordering, collections::{BinaryHeap, VecDeque}, panic::{self, AssertUnwindSafe}, thread::{self, JoinHandle}, time::{Duration, Instant}, sync::{Arc, Mutex, MutexGuard, Condvar}};
+
+use super::{KAssetBroker, KAssetPath, KAssetPathError, KIntoAssetPath};
+
+/// ##### Asynchronous loading of assets from a [KAssetBroker] with a pool of worker threads.
+///
+/// [KAssetStreamer::request] returns a [KAssetLoadHandle] right away. Workers fetch and read requested
+/// assets by priority, then the handle can be polled, waited on or cancelled. Handles of assets loaded
+/// since last call are returned by [KAssetStreamer::drain_completed], usually once per frame.
+///
+/// # Priorities
+/// Like sources within [KAssetBroker], requests with priority 0 are loaded first. Requests with the same
+/// priority are loaded in order of request.
+///
+/// # Example(s)
+/// ```
+/// // Import crate module
+/// use std::{path::PathBuf, sync::Arc};
+/// use olympus_kleio::asset::{KAssetBroker, KAssetSourceMemory, KAssetStreamer, KAssetLoadStatus};
+///
+/// // Create broker with a source
+/// let source = KAssetSourceMemory::new("level");
+/// source.add_asset(PathBuf::from("chunks/0.bin"), vec![0, 1, 2, 3]).unwrap();
+/// let broker = Arc::new(KAssetBroker::new());
+/// broker.add_source(Arc::new(source)).unwrap();
+///
+/// // Create streamer with 2 workers and request a nearby chunk
+/// let streamer = KAssetStreamer::new(broker, 2).unwrap();
+/// let handle = streamer.request("chunks/0.bin", 0).unwrap();
+///
+/// // Wait for chunk, which is also reported as completed
+/// assert_eq!(handle.wait().unwrap().as_ref(), &[0, 1, 2, 3]);
+/// assert_eq!(handle.get_status(), KAssetLoadStatus::Loaded);
+/// assert_eq!(streamer.drain_completed().len(), 1);
+/// ```
+pub struct KAssetStreamer {
+    // State shared with workers
+    shared : Arc<KAssetStreamerShared>,
+
+    // Worker threads joined on drop
+    workers : Vec<JoinHandle<()>>,
+}
+
+/// Enumeration of possible [KAssetStreamer] errors.
+pub enum KAssetStreamerError {
+    /// Happens when worker count is 0.
+    InvalidWorkerCount,
+
+    /// Happens when a worker thread couldn't be spawned.
+    WorkerSpawnError,
+}
+
+impl std::fmt::Debug for KAssetStreamerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidWorkerCount => write!(f, "InvalidWorkerCount"),
+            Self::WorkerSpawnError => write!(f, "WorkerSpawnError"),
+        }
+    }
+}
+
+/// Status of a request made with [KAssetStreamer::request].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KAssetLoadStatus {
+    /// Request is waiting for a worker.
+    Queued,
+
+    /// A worker is fetching and reading the asset.
+    Loading,
+
+    /// Asset was loaded.
+    Loaded,
+
+    /// Asset couldn't be loaded.
+    Failed,
+
+    /// Request was cancelled before being loaded.
+    Cancelled,
+}
+
+/// Enumeration of possible errors of a [KAssetLoadHandle].
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum KAssetLoadError {
+    /// Happens when no source of broker has the asset.
+    NotFound,
+
+    /// Happens when an IO error occurred while reading asset.
+    ReadError,
+
+    /// Happens when request was cancelled or the [KAssetStreamer] dropped before asset was loaded.
+    Cancelled,
+
+    /// Happens when a source panicked while loading asset. The worker keeps loading other requests.
+    Panicked,
+}
+
+impl std::fmt::Debug for KAssetLoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotFound => write!(f, "NotFound"),
+            Self::ReadError => write!(f, "ReadError"),
+            Self::Cancelled => write!(f, "Cancelled"),
+            Self::Panicked => write!(f, "Panicked"),
+        }
+    }
+}
+
+/// ##### Handle of an asset requested to a [KAssetStreamer].
+///
+/// Handles are cheap to clone and all clones refer to the same request.
+#[derive(Clone)]
+pub struct KAssetLoadHandle {
+    // Request state shared with workers
+    state : Arc<KAssetLoadState>,
+}
+
+/// State of a request shared between handles and workers.
+struct KAssetLoadState {
+    // Request identifier, also giving request order
+    id : u64,
+
+    // Path of asset requested
+    path : KAssetPath,
+
+    // Priority of request. 0 is highest.
+    priority : u32,
+
+    // Progress and result of request
+    result : Mutex<KAssetLoadResult>,
+
+    // Notified when request is done
+    done : Condvar,
+}
+
+/// Progress and result of a request.
+enum KAssetLoadResult {
+    Queued,
+    Loading,
+    Loaded(Arc<[u8]>),
+    Failed(KAssetLoadError),
+    Cancelled,
+}
+
+/// State shared between streamer and workers.
+struct KAssetStreamerShared {
+    // Broker assets are loaded from
+    broker : Arc<KAssetBroker>,
+
+    // Queue of requests and stop flag
+    queue : Mutex<KAssetStreamerQueue>,
+
+    // Notified when a request is queued or streamer stops
+    queued : Condvar,
+
+    // Handles of requests done since last drain
+    completed : Mutex<VecDeque<KAssetLoadHandle>>,
+}
+
+/// Queue of requests ordered by priority.
+struct KAssetStreamerQueue {
+    // Requests waiting for a worker
+    requests : BinaryHeap<KAssetLoadRequest>,
+
+    // Identifier of next request
+    next_id : u64,
+
+    // Set when streamer is dropped
+    stopped : bool,
+}
+
+/// Request in queue. Ordered so the highest priority, then oldest, request is popped first.
+struct KAssetLoadRequest(Arc<KAssetLoadState>);
+
+impl PartialEq for KAssetLoadRequest {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.id == other.0.id
+    }
+}
+
+impl Eq for KAssetLoadRequest {}
+
+impl PartialOrd for KAssetLoadRequest {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for KAssetLoadRequest {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // BinaryHeap pops the greatest, so lowest (priority, id) must be greatest
+        (other.0.priority, other.0.id).cmp(&(self.0.priority, self.0.id))
+    }
+}
+
+impl KAssetStreamer {
+    /// Create a new [KAssetStreamer] loading assets from `broker` with `worker_count` threads.
+    ///
+    /// Returns `Ok(`[KAssetStreamer]`)` if successful.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KAssetStreamerError::InvalidWorkerCount]`)` if `worker_count` is 0.
+    ///
+    /// Returns `Err(`[KAssetStreamerError::WorkerSpawnError]`)` if a worker thread couldn't be spawned.
+    pub fn new(broker : Arc<KAssetBroker>, worker_count : usize) -> Result<KAssetStreamer, KAssetStreamerError> {
+
+        if worker_count == 0 {
+            return Err(KAssetStreamerError::InvalidWorkerCount);
+        }
+
+        let shared = Arc::new(KAssetStreamerShared {
+            broker,
+            queue : Mutex::new(KAssetStreamerQueue { requests : BinaryHeap::new(), next_id : 0, stopped : false }),
+            queued : Condvar::new(),
+            completed : Mutex::new(VecDeque::new()),
+        });
+
+        // Streamer is created first so workers already spawned are stopped on error
+        let mut streamer = KAssetStreamer { shared, workers : Vec::with_capacity(worker_count) };
+
+        for index in 0..worker_count {
+            let shared = streamer.shared.clone();
+
+            match thread::Builder::new().name(format!("kleio-streamer-{}", index)).spawn(move || shared.work()) {
+                Ok(worker) => streamer.workers.push(worker),
+                Err(_) => return Err(KAssetStreamerError::WorkerSpawnError),
+            }
+        }
+
+        Ok(streamer)
+    }
+
+    /// Request asset at [`path`][KIntoAssetPath] with `priority`. 0 is the highest priority.
+    ///
+    /// Returns `Ok(`[KAssetLoadHandle]`)` right away.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KAssetPathError]`)` if path is invalid.
+    pub fn request(&self, path : impl KIntoAssetPath, priority : u32) -> Result<KAssetLoadHandle, KAssetPathError> {
+        let path = path.into_asset_path()?;
+
+        let mut queue = self.shared.lock_queue();
+
+        let state = Arc::new(KAssetLoadState {
+            id : queue.next_id,
+            path,
+            priority,
+            result : Mutex::new(KAssetLoadResult::Queued),
+            done : Condvar::new(),
+        });
+
+        queue.next_id += 1;
+        queue.requests.push(KAssetLoadRequest(state.clone()));
+        self.shared.queued.notify_one();
+
+        Ok(KAssetLoadHandle { state })
+    }
+
+    /// Get handles of requests loaded or failed since last call, in order of completion.
+    ///
+    /// # Note(s)
+    /// Cancelled requests are never returned since they are cancelled by caller.
+    pub fn drain_completed(&self) -> Vec<KAssetLoadHandle> {
+        lock(&self.shared.completed).drain(..).collect()
+    }
+
+    /// Get the count of requests waiting for a worker.
+    pub fn get_queued_count(&self) -> usize {
+        self.shared.lock_queue().requests.iter().filter(|request| request.0.get_status() == KAssetLoadStatus::Queued).count()
+    }
+
+    /// Get the count of worker threads.
+    pub fn get_worker_count(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// Get the [KAssetBroker] assets are loaded from.
+    pub fn get_broker(&self) -> &Arc<KAssetBroker> {
+        &self.shared.broker
+    }
+}
+
+impl Drop for KAssetStreamer {
+    /// Stop workers once their current request is done. Requests still queued are cancelled.
+    fn drop(&mut self) {
+        let requests = {
+            let mut queue = self.shared.lock_queue();
+            queue.stopped = true;
+            std::mem::take(&mut queue.requests)
+        };
+        self.shared.queued.notify_all();
+
+        for request in requests {
+            request.0.cancel();
+        }
+
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl KAssetStreamerShared {
+    /// Loop of worker threads. Returns when streamer stops.
+    fn work(&self) {
+        while let Some(state) = self.next_request() {
+
+            // Request may have been cancelled while queued
+            {
+                let mut result = lock(&state.result);
+                if !matches!(*result, KAssetLoadResult::Queued) {
+                    continue;
+                }
+                *result = KAssetLoadResult::Loading;
+            }
+
+            // Broker recovers poisoned locks, so it stays usable after a source panicked
+            let loaded = panic::catch_unwind(AssertUnwindSafe(|| self.load(&state.path))).unwrap_or(Err(KAssetLoadError::Panicked));
+
+            let mut result = lock(&state.result);
+            if matches!(*result, KAssetLoadResult::Cancelled) {
+                continue;
+            }
+
+            *result = match loaded {
+                Ok(data) => KAssetLoadResult::Loaded(data),
+                Err(err) => KAssetLoadResult::Failed(err),
+            };
+
+            // Completed before notifying so waiters always find the handle when draining
+            lock(&self.completed).push_back(KAssetLoadHandle { state : state.clone() });
+            state.done.notify_all();
+        }
+    }
+
+    /// Wait for the next request to load. Returns None when streamer stops.
+    fn next_request(&self) -> Option<Arc<KAssetLoadState>> {
+        let mut queue = self.lock_queue();
+
+        loop {
+            if queue.stopped {
+                return None;
+            }
+
+            if let Some(request) = queue.requests.pop() {
+                return Some(request.0);
+            }
+
+            queue = self.queued.wait(queue).unwrap_or_else(|err| err.into_inner());
+        }
+    }
+
+    /// Fetch asset from broker and read it entirely.
+    fn load(&self, path : &KAssetPath) -> Result<Arc<[u8]>, KAssetLoadError> {
+        let mut asset = match self.broker.get_asset(path) {
+            Ok(asset) => asset,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Err(KAssetLoadError::NotFound),
+            Err(_) => return Err(KAssetLoadError::ReadError),
+        };
+
+        let mut data = Vec::new();
+        match asset.read_to_end(&mut data) {
+            Ok(_) => Ok(data.into()),
+            Err(_) => Err(KAssetLoadError::ReadError),
+        }
+    }
+
+    /// Lock queue. A poisoned lock is recovered since queue is always left consistent.
+    fn lock_queue(&self) -> MutexGuard<'_, KAssetStreamerQueue> {
+        lock(&self.queue)
+    }
+}
+
+impl KAssetLoadState {
+    /// Get status from result.
+    fn get_status(&self) -> KAssetLoadStatus {
+        match *lock(&self.result) {
+            KAssetLoadResult::Queued => KAssetLoadStatus::Queued,
+            KAssetLoadResult::Loading => KAssetLoadStatus::Loading,
+            KAssetLoadResult::Loaded(_) => KAssetLoadStatus::Loaded,
+            KAssetLoadResult::Failed(_) => KAssetLoadStatus::Failed,
+            KAssetLoadResult::Cancelled => KAssetLoadStatus::Cancelled,
+        }
+    }
+
+    /// Cancel request if not done. Returns true if cancelled.
+    fn cancel(&self) -> bool {
+        let mut result = lock(&self.result);
+
+        match *result {
+            KAssetLoadResult::Queued | KAssetLoadResult::Loading => {
+                *result = KAssetLoadResult::Cancelled;
+                self.done.notify_all();
+                true
+            },
+            _ => false,
+        }
+    }
+}
+
+impl KAssetLoadHandle {
+    /// Get the identifier of request. Identifiers are given in order of request.
+    pub fn get_id(&self) -> u64 {
+        self.state.id
+    }
+
+    /// Get the path of asset requested.
+    pub fn get_path(&self) -> &KAssetPath {
+        &self.state.path
+    }
+
+    /// Get the priority of request.
+    pub fn get_priority(&self) -> u32 {
+        self.state.priority
+    }
+
+    /// Get the current [KAssetLoadStatus] of request.
+    pub fn get_status(&self) -> KAssetLoadStatus {
+        self.state.get_status()
+    }
+
+    /// Verify if request is loaded, failed or cancelled.
+    pub fn is_done(&self) -> bool {
+        !matches!(self.get_status(), KAssetLoadStatus::Queued | KAssetLoadStatus::Loading)
+    }
+
+    /// Get asset data without blocking.
+    ///
+    /// Returns `None` if request is not done, `Some(Ok(data))` if asset was loaded.
+    ///
+    /// # Error(s)
+    /// Returns `Some(Err(`[KAssetLoadError]`))` if asset couldn't be loaded or request was cancelled.
+    pub fn poll(&self) -> Option<Result<Arc<[u8]>, KAssetLoadError>> {
+        Self::get_result(&lock(&self.state.result))
+    }
+
+    /// Block until request is done.
+    ///
+    /// Returns `Ok(data)` if asset was loaded.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KAssetLoadError]`)` if asset couldn't be loaded or request was cancelled.
+    pub fn wait(&self) -> Result<Arc<[u8]>, KAssetLoadError> {
+        let mut result = lock(&self.state.result);
+
+        loop {
+            if let Some(done) = Self::get_result(&result) {
+                return done;
+            }
+            result = self.state.done.wait(result).unwrap_or_else(|err| err.into_inner());
+        }
+    }
+
+    /// Block until request is done or `timeout` elapsed.
+    ///
+    /// Returns `None` if timeout elapsed, see [KAssetLoadHandle::poll] otherwise.
+    pub fn wait_timeout(&self, timeout : Duration) -> Option<Result<Arc<[u8]>, KAssetLoadError>> {
+        let deadline = Instant::now() + timeout;
+        let mut result = lock(&self.state.result);
+
+        loop {
+            if let Some(done) = Self::get_result(&result) {
+                return Some(done);
+            }
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return None;
+            }
+            result = self.state.done.wait_timeout(result, remaining).unwrap_or_else(|err| err.into_inner()).0;
+        }
+    }
+
+    /// Cancel request. A request being loaded is discarded once read.
+    ///
+    /// Returns true if request was cancelled, false if it was already done.
+    pub fn cancel(&self) -> bool {
+        self.state.cancel()
+    }
+
+    /// Get result of request if done.
+    fn get_result(result : &KAssetLoadResult) -> Option<Result<Arc<[u8]>, KAssetLoadError>> {
+        match result {
+            KAssetLoadResult::Queued | KAssetLoadResult::Loading => None,
+            KAssetLoadResult::Loaded(data) => Some(Ok(data.clone())),
+            KAssetLoadResult::Failed(err) => Some(Err(*err)),
+            KAssetLoadResult::Cancelled => Some(Err(KAssetLoadError::Cancelled)),
+        }
+    }
+}
+
+/// Lock mutex. A poisoned lock is recovered since streamer state is always left consistent.
+fn lock<T>(mutex : &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|err| err.into_inner())
+}
@@ -0,0 +1,269 @@
+use std::io::ErrorKind;
+use super::{KAssetBroker, KAssetPath, KIntoAssetPath};
+use super::broker::KAssetBrokerSource;
+use super::decompression::KAssetDecompression;
+
+/// Maximum count of tagged variants looked for each asset.
+pub const KASSET_TAGS_MAX_VARIANTS : usize = 256;
+
+/// Enumeration of possible errors of [KAssetTags].
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum KAssetTagError {
+    /// Happens when a tag isn't `key=value` or key or value is empty or has characters other than
+    /// ASCII letters, digits, `-` and `_`.
+    InvalidTag,
+
+    /// Happens when tags would give more than [KASSET_TAGS_MAX_VARIANTS] variants to look for.
+    TooManyVariants,
+}
+
+impl std::fmt::Debug for KAssetTagError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidTag => write!(f, "InvalidTag"),
+            Self::TooManyVariants => write!(f, "TooManyVariants"),
+        }
+    }
+}
+
+/// ##### Active tags of a [KAssetBroker], set with [KAssetBroker::set_tags].
+///
+/// Tagged variants of an asset have `@key=value` tags inserted before the extension of its file name, sorted by key,
+/// like `textures/rock@platform=steamdeck@quality=high.png` for `textures/rock.png`.
+///
+/// Keys are ranked in the order they are added, and values of a key in the order they are added, so `quality=ultra`
+/// then `quality=high` falls back to high quality variants when ultra ones are missing. Variants are ranked by their
+/// value of the first key, then of the second key and so on, with no value last.
+///
+/// The best variant found in any source is served, even if a source of higher priority has a variant ranked lower
+/// or the untagged asset. Among sources having the best variant, the one of highest priority serves it.
+///
+/// # Example(s)
+/// ```
+/// use std::{io::Read, sync::Arc};
+/// use olympus_kleio::asset::{KAssetBroker, KAssetSourceMemory, KAssetTags};
+///
+/// let base = KAssetSourceMemory::new("base");
+/// base.add_asset("textures/rock.png", "medium".as_bytes().to_vec()).unwrap();
+///
+/// let dlc = KAssetSourceMemory::new("dlc");
+/// dlc.add_asset("textures/rock@quality=4k.png", "4k".as_bytes().to_vec()).unwrap();
+///
+/// let broker = KAssetBroker::new();
+/// broker.add_source(Arc::new(base)).unwrap();
+/// broker.set_tags(KAssetTags::new(&["quality=4k", "quality=high", "platform=steamdeck"]).unwrap());
+///
+/// // DLC isn't installed, so untagged asset is served
+/// assert!(broker.resolve_tags("textures/rock.png").unwrap().tags.is_empty());
+///
+/// broker.add_source(Arc::new(dlc)).unwrap();
+/// let resolved = broker.resolve_tags("textures/rock.png").unwrap();
+/// assert_eq!(resolved.tags, vec![(String::from("quality"), String::from("4k"))]);
+///
+/// let mut rock = String::new();
+/// broker.get_asset("textures/rock.png").unwrap().read_to_string(&mut rock).unwrap();
+/// assert_eq!(rock, "4k");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KAssetTags {
+    // Keys ranked in order, each with values ranked in order
+    tags : Vec<(String, Vec<String>)>,
+}
+
+/// Tagged variant of an asset picked by [KAssetBroker::resolve_tags].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KAssetTagMatch {
+    /// Path of variant served.
+    pub path : KAssetPath,
+
+    /// `(key, value)` tags of variant in order of keys, or empty for the untagged asset.
+    pub tags : Vec<(String, String)>,
+}
+
+impl KAssetTags {
+    /// Create active tags from `key=value` tags, ranked in order given.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KAssetTagError::InvalidTag]`)` if a tag is invalid.
+    ///
+    /// Returns `Err(`[KAssetTagError::TooManyVariants]`)` if tags give too many variants.
+    pub fn new(tags : &[&str]) -> Result<KAssetTags, KAssetTagError> {
+        let mut kat = KAssetTags::default();
+
+        for tag in tags {
+            match tag.split_once('=') {
+                Some((key, value)) => kat.add(key, value)?,
+                None => return Err(KAssetTagError::InvalidTag),
+            }
+        }
+
+        Ok(kat)
+    }
+
+    /// Add `value` to tag `key`, ranked after values already added. Adding a value twice does nothing.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KAssetTagError::InvalidTag]`)` if key or value is invalid.
+    ///
+    /// Returns `Err(`[KAssetTagError::TooManyVariants]`)` if tags would give too many variants.
+    pub fn add(&mut self, key : &str, value : &str) -> Result<(), KAssetTagError> {
+        Self::check_tag(key)?;
+        Self::check_tag(value)?;
+
+        if self.get_values(key).iter().any(|v| v == value) {
+            return Ok(());
+        }
+
+        let count = self.tags.iter().filter(|(k, _)| k != key).map(|(_, values)| values.len() + 1)
+            .chain(std::iter::once(self.get_values(key).len() + 2))
+            .try_fold(1usize, |count, n| count.checked_mul(n));
+
+        // Untagged asset isn't a variant
+        match count {
+            Some(count) if count - 1 <= KASSET_TAGS_MAX_VARIANTS => {},
+            _ => return Err(KAssetTagError::TooManyVariants),
+        }
+
+        match self.tags.iter_mut().find(|(k, _)| k == key) {
+            Some((_, values)) => values.push(value.to_string()),
+            None => self.tags.push((key.to_string(), vec![value.to_string()])),
+        }
+
+        Ok(())
+    }
+
+    /// Get values of tag `key` in order, or an empty slice if key isn't active.
+    pub fn get_values(&self, key : &str) -> &[String] {
+        match self.tags.iter().find(|(k, _)| k == key) {
+            Some((_, values)) => values,
+            None => &[],
+        }
+    }
+
+    /// Get active keys in order.
+    pub fn get_keys(&self) -> Vec<&str> {
+        self.tags.iter().map(|(key, _)| key.as_str()).collect()
+    }
+
+    /// Returns true if no tag is active.
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+    }
+
+    /// Get tagged variants of asset at path from best to worst, without the untagged asset.
+    ///
+    /// Returns an empty vector for the root or if no tag is active.
+    pub fn get_variants(&self, path : &KAssetPath) -> Vec<KAssetTagMatch> {
+        let mut variants = Vec::new();
+        let name = match path.get_file_name() {
+            Some(name) => name,
+            None => return variants,
+        };
+
+        // Value index of each key, where index equal to values count is no value
+        let mut indexes = vec![0usize; self.tags.len()];
+        loop {
+            let tags : Vec<(String, String)> = self.tags.iter().zip(indexes.iter())
+                .filter_map(|((key, values), index)| values.get(*index).map(|value| (key.clone(), value.clone())))
+                .collect();
+
+            if tags.is_empty() {
+                break;
+            }
+
+            let mut sorted : Vec<&(String, String)> = tags.iter().collect();
+            sorted.sort();
+            let suffix : String = sorted.iter().map(|(key, value)| format!("@{}={}", key, value)).collect();
+
+            let name = match name.rfind('.') {
+                Some(0) | None => format!("{}{}", name, suffix),
+                Some(dot) => format!("{}{}{}", &name[..dot], suffix, &name[dot..]),
+            };
+
+            let variant = match path.get_parent() {
+                Some(parent) => parent.join(&name),
+                None => KAssetPath::new(&name),
+            };
+
+            if let Ok(path) = variant {
+                variants.push(KAssetTagMatch { path, tags });
+            }
+
+            // Next combination, last key changing first
+            for (position, (_, values)) in self.tags.iter().enumerate().rev() {
+                indexes[position] += 1;
+                if indexes[position] <= values.len() {
+                    break;
+                }
+                indexes[position] = 0;
+            }
+        }
+
+        variants
+    }
+
+    /// Verify key or value has only ASCII letters, digits, `-` and `_`.
+    fn check_tag(tag : &str) -> Result<(), KAssetTagError> {
+        match !tag.is_empty() && tag.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+            true => Ok(()),
+            false => Err(KAssetTagError::InvalidTag),
+        }
+    }
+}
+
+impl KAssetBroker {
+    /// Set active [KAssetTags] resolving assets to their tagged variants. Empty tags stop resolving them.
+    ///
+    /// Assets already loaded, like in a [KAssetStorage](super::KAssetStorage), keep their variant until loaded again.
+    pub fn set_tags(&self, tags : KAssetTags) {
+        *self.write_tags() = tags;
+    }
+
+    /// Get active [KAssetTags] resolving assets to their tagged variants.
+    pub fn get_tags(&self) -> KAssetTags {
+        self.read_tags().clone()
+    }
+
+    /// Resolve asset at path to its best tagged variant found in any source, or to the untagged asset.
+    ///
+    /// Path is resolved to its locale variant first, so [KAssetTagMatch::path] is the variant served by
+    /// [KAssetBroker::get_asset].
+    ///
+    /// # Error(s)
+    /// Returns [std::io::Error] of kind [InvalidInput](ErrorKind::InvalidInput) if path is invalid.
+    ///
+    /// Returns [std::io::Error] of kind [NotFound](ErrorKind::NotFound) if no source has asset nor a variant of it.
+    pub fn resolve_tags(&self, path : impl KIntoAssetPath) -> Result<KAssetTagMatch, std::io::Error> {
+        let path = self.get_localized_path(path.into_asset_path()?);
+        let sources = self.get_sources_snapshot();
+        let decompression = self.get_decompression();
+
+        if let Some(variant) = Self::find_tagged_in(&sources, &path, &decompression, &self.read_tags()) {
+            return Ok(variant);
+        }
+
+        match Self::has_asset_in(&sources, &path, &decompression) {
+            true => Ok(KAssetTagMatch { path, tags : Vec::new() }),
+            false => Err(std::io::Error::new(ErrorKind::NotFound, "Asset not found!")),
+        }
+    }
+
+    /// Get path of best tagged variant served for asset at path, or path itself if none.
+    pub(crate) fn get_tagged_path(&self, path : KAssetPath) -> KAssetPath {
+        let tags = self.read_tags();
+        if tags.is_empty() {
+            return path;
+        }
+
+        match Self::find_tagged_in(&self.get_sources_snapshot(), &path, &self.get_decompression(), &tags) {
+            Some(variant) => variant.path,
+            None => path,
+        }
+    }
+
+    /// Get best tagged variant of asset at path a source has, compressed or not.
+    pub(crate) fn find_tagged_in(sources : &[KAssetBrokerSource], path : &KAssetPath, decompression : &Option<KAssetDecompression>,
+        tags : &KAssetTags) -> Option<KAssetTagMatch> {
+        tags.get_variants(path).into_iter().find(|variant| Self::has_asset_in(sources, &variant.path, decompression))
+    }
+}
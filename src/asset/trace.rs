@@ -0,0 +1,526 @@
+// Contains recording of asset accesses of a broker and tools turning traces into bundles and reports
+use std::{io::Read, collections::HashSet, sync::{Arc, Mutex}, time::{Duration, Instant, SystemTime}};
+use super::{KAssetBroker, KAssetSourceId, KAssetPath, KAssetBundle, KAssetSourceInfo, KAssetListEntry, KAssetCompression};
+use super::broker::KAssetBrokerSource;
+use super::decompression::KAssetDecompression;
+use super::provenance::write_table;
+
+/// Kind of asset access recorded in a [KAssetTrace].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KAssetAccessKind {
+    /// Asset was looked for with [KAssetBroker::has_asset].
+    Has,
+
+    /// Asset was opened with [KAssetBroker::get_asset].
+    Get,
+}
+
+impl std::fmt::Display for KAssetAccessKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Has => write!(f, "has"),
+            Self::Get => write!(f, "get"),
+        }
+    }
+}
+
+/// Asset access recorded in a [KAssetTrace].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KAssetAccess {
+    /// Time of access since trace started.
+    pub timestamp : Duration,
+
+    /// Kind of access.
+    pub kind : KAssetAccessKind,
+
+    /// Path of asset requested.
+    pub path : KAssetPath,
+
+    /// Path of asset resolved to its locale and tagged variants.
+    pub resolved : KAssetPath,
+
+    /// Identifier of source serving asset, or `None` if asset wasn't found or couldn't be opened.
+    pub source : Option<KAssetSourceId>,
+
+    /// Bytes read from asset opened, 0 for [KAssetAccessKind::Has].
+    pub bytes : u64,
+
+    /// Time spent resolving and opening asset, plus reading it for [KAssetAccessKind::Get].
+    pub elapsed : Duration,
+}
+
+/// Event recorded in a [KAssetTrace].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KAssetTraceEvent {
+    /// Asset access.
+    Access(KAssetAccess),
+
+    /// Mark set with [KAssetBroker::mark_trace], like the name of a level being loaded, with its time since trace started.
+    Mark(Duration, String),
+}
+
+/// Enumeration of possible [KAssetTrace] errors.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum KAssetTraceError {
+    /// Happens when marking a trace while broker isn't recording.
+    NotTracing,
+
+    /// Happens when a mark label is empty or contains control characters.
+    InvalidLabel,
+
+    /// Happens when a line of an exported trace isn't a valid event.
+    InvalidLine,
+
+    /// Happens when a path of an exported trace is invalid.
+    InvalidPath,
+}
+
+impl std::fmt::Debug for KAssetTraceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotTracing => write!(f, "NotTracing"),
+            Self::InvalidLabel => write!(f, "InvalidLabel"),
+            Self::InvalidLine => write!(f, "InvalidLine"),
+            Self::InvalidPath => write!(f, "InvalidPath"),
+        }
+    }
+}
+
+/// ##### Trace of asset accesses of a [KAssetBroker], recorded with [KAssetBroker::start_trace].
+///
+/// Events are ordered by the time accesses started. Accesses of assets opened are complete once their reader is dropped,
+/// so bytes read of assets still open are only those read so far.
+///
+/// Traces are exported as text with [Display](std::fmt::Display) and imported with [KAssetTrace::parse]. Text starts with
+/// a `started` line with UNIX time in milliseconds, followed by one tab-separated line per event, with times in microseconds :
+/// `timestamp kind source bytes elapsed path resolved` for accesses, `-` as source if not found, and `timestamp mark label`
+/// for marks. Empty lines and lines starting with `#` are ignored.
+///
+/// # Note(s)
+/// Source identifiers are only meaningful for the broker that recorded the trace.
+///
+/// # Example(s)
+/// ```
+/// use std::{io::Read, sync::Arc};
+/// use olympus_kleio::asset::{KAssetBroker, KAssetSourceMemory, KAssetTrace};
+///
+/// let source = KAssetSourceMemory::new("base");
+/// source.add_asset("levels/castle.lvl", vec![1, 2, 3]).unwrap();
+/// source.add_asset("textures/wall.png", vec![4, 5]).unwrap();
+///
+/// let broker = KAssetBroker::new();
+/// broker.add_source(Arc::new(source)).unwrap();
+///
+/// // Record accesses of level
+/// broker.start_trace();
+/// broker.mark_trace("castle").unwrap();
+/// broker.get_asset("levels/castle.lvl").unwrap().read_to_end(&mut Vec::new()).unwrap();
+/// assert!(!broker.has_asset("textures/wall_dirty.png"));
+/// let trace = broker.stop_trace().unwrap();
+///
+/// // Export, then turn into a preload bundle per level
+/// let trace = KAssetTrace::parse(&trace.to_string()).unwrap();
+/// let bundles = trace.get_level_bundles();
+/// assert_eq!(bundles[0].0, "castle");
+/// assert_eq!(bundles[0].1.get_paths()[0], "levels/castle.lvl");
+///
+/// // Report assets never read
+/// let report = broker.get_usage_report(&[trace], "**").unwrap();
+/// assert_eq!(report.unused[0].path, "textures/wall.png");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KAssetTrace {
+    /// Time trace started.
+    pub started : SystemTime,
+
+    /// Events recorded, ordered by time.
+    pub events : Vec<KAssetTraceEvent>,
+}
+
+impl KAssetTrace {
+    /// Parse a trace exported with [Display](std::fmt::Display).
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KAssetTraceError::InvalidLine]`)` if a line isn't a valid event or `started` line.
+    ///
+    /// Returns `Err(`[KAssetTraceError::InvalidPath]`)` if a path is invalid.
+    ///
+    /// Returns `Err(`[KAssetTraceError::InvalidLabel]`)` if a mark label is empty.
+    pub fn parse(content : &str) -> Result<KAssetTrace, KAssetTraceError> {
+        let mut trace = KAssetTrace { started : SystemTime::UNIX_EPOCH, events : Vec::new() };
+
+        for line in content.lines().map(|line| line.trim_end_matches('\r')).filter(|line| !line.trim().is_empty() && !line.starts_with('#')) {
+            if let Some(started) = line.strip_prefix("started ") {
+                trace.started = SystemTime::UNIX_EPOCH + Duration::from_millis(parse_number(started)?);
+                continue;
+            }
+
+            let fields : Vec<&str> = line.split('\t').collect();
+            let timestamp = Duration::from_micros(parse_number(fields[0])?);
+
+            let event = match fields[1..] {
+                ["mark", label] => match is_valid_label(label) {
+                    true => KAssetTraceEvent::Mark(timestamp, label.to_string()),
+                    false => return Err(KAssetTraceError::InvalidLabel),
+                },
+                [kind, source, bytes, elapsed, path, resolved] => {
+                    let kind = match kind {
+                        "has" => KAssetAccessKind::Has,
+                        "get" => KAssetAccessKind::Get,
+                        _ => return Err(KAssetTraceError::InvalidLine),
+                    };
+
+                    let source = match source {
+                        "-" => None,
+                        source => Some(KAssetSourceId(parse_number(source)?)),
+                    };
+
+                    let path = KAssetPath::new(path).map_err(|_| KAssetTraceError::InvalidPath)?;
+                    let resolved = KAssetPath::new(resolved).map_err(|_| KAssetTraceError::InvalidPath)?;
+
+                    KAssetTraceEvent::Access(KAssetAccess { timestamp, kind, path, resolved, source, bytes : parse_number(bytes)?,
+                        elapsed : Duration::from_micros(parse_number(elapsed)?) })
+                },
+                _ => return Err(KAssetTraceError::InvalidLine),
+            };
+
+            trace.events.push(event);
+        }
+
+        Ok(trace)
+    }
+
+    /// Get accesses of trace, ordered by time.
+    pub fn get_accesses(&self) -> Vec<&KAssetAccess> {
+        self.events.iter().filter_map(|event| match event {
+            KAssetTraceEvent::Access(access) => Some(access),
+            KAssetTraceEvent::Mark(..) => None,
+        }).collect()
+    }
+
+    /// Get total bytes read from assets opened.
+    pub fn get_bytes_read(&self) -> u64 {
+        self.get_accesses().iter().map(|access| access.bytes).sum()
+    }
+
+    /// Get a [KAssetBundle] of every asset opened and found, in order of first access.
+    ///
+    /// Paths are those requested, so locale and tags are resolved again when bundle is preloaded.
+    pub fn get_bundle(&self) -> KAssetBundle {
+        let mut bundle = KAssetBundle::new();
+        for access in self.get_accesses() {
+            add_opened(&mut bundle, access);
+        }
+        bundle
+    }
+
+    /// Get a [KAssetBundle] per level, split at marks set with [KAssetBroker::mark_trace], with label of mark.
+    ///
+    /// Bundles are ordered by first mark of their label. Assets opened under a label marked more than once are merged
+    /// in one bundle. Assets opened before the first mark are in a bundle with an empty label, given only if not empty.
+    pub fn get_level_bundles(&self) -> Vec<(String, KAssetBundle)> {
+        let mut bundles = vec![(String::new(), KAssetBundle::new())];
+        let mut current = 0;
+
+        for event in &self.events {
+            match event {
+                KAssetTraceEvent::Access(access) => add_opened(&mut bundles[current].1, access),
+                KAssetTraceEvent::Mark(_, label) => {
+                    current = match bundles.iter().skip(1).position(|(other, _)| other == label) {
+                        Some(position) => position + 1,
+                        None => {
+                            bundles.push((label.clone(), KAssetBundle::new()));
+                            bundles.len() - 1
+                        },
+                    };
+                },
+            }
+        }
+
+        if bundles[0].1.is_empty() {
+            bundles.remove(0);
+        }
+
+        bundles
+    }
+}
+
+impl std::fmt::Display for KAssetTrace {
+    /// Write trace as text, parsable with [KAssetTrace::parse].
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "# Kleio asset trace")?;
+        writeln!(f, "started {}", self.started.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_millis())?;
+
+        for event in &self.events {
+            match event {
+                KAssetTraceEvent::Access(access) => writeln!(f, "{}\t{}\t{}\t{}\t{}\t{}\t{}", access.timestamp.as_micros(), access.kind,
+                    access.source.map_or(String::from("-"), |source| source.get_value().to_string()), access.bytes, access.elapsed.as_micros(),
+                    access.path, access.resolved)?,
+                KAssetTraceEvent::Mark(timestamp, label) => writeln!(f, "{}\tmark\t{}", timestamp.as_micros(), label)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Asset never read in traces, listed in a [KAssetUsageReport].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KAssetUnused {
+    /// Path of asset.
+    pub path : KAssetPath,
+
+    /// Source serving asset.
+    pub source : KAssetSourceInfo,
+
+    /// Size of asset in bytes, if source knows it.
+    pub size : Option<u64>,
+}
+
+/// ##### Assets read or not in [KAssetTrace], given by [KAssetBroker::get_usage_report].
+///
+/// [Display](std::fmt::Display) writes a human-readable table of unused assets.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KAssetUsageReport {
+    /// Count of assets listed that were read.
+    pub used_count : usize,
+
+    /// Assets listed that were never read, sorted by path.
+    pub unused : Vec<KAssetUnused>,
+}
+
+impl KAssetUsageReport {
+    /// Get total size of unused assets of known size.
+    pub fn get_unused_bytes(&self) -> u64 {
+        self.unused.iter().filter_map(|unused| unused.size).sum()
+    }
+}
+
+impl std::fmt::Display for KAssetUsageReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{} of {} assets unused ({} bytes)", self.unused.len(), self.unused.len() + self.used_count, self.get_unused_bytes())?;
+
+        let rows : Vec<Vec<String>> = self.unused.iter().map(|unused| vec![unused.path.to_string(), unused.source.to_string(),
+            unused.size.map_or(String::from("?"), |size| size.to_string())]).collect();
+        write_table(f, &["Asset", "Source", "Size"], &rows)
+    }
+}
+
+/// Recorder of asset accesses of a broker while tracing.
+pub(crate) struct KAssetTraceRecorder {
+    // Time recording started
+    started : SystemTime,
+
+    // Monotonic time recording started, for timestamps
+    instant : Instant,
+
+    // Events recorded
+    events : Mutex<Vec<KAssetTraceEvent>>,
+}
+
+impl KAssetTraceRecorder {
+    /// Record a [KAssetAccessKind::Has] access started at `started`.
+    pub(crate) fn record_has(&self, path : KAssetPath, resolved : KAssetPath, source : Option<KAssetSourceId>, started : Instant) {
+        self.push_access(KAssetAccessKind::Has, path, resolved, source, started);
+    }
+
+    /// Record a [KAssetAccessKind::Get] access started at `started`, wrapping asset opened so bytes read are recorded.
+    pub(crate) fn record_get(self : Arc<Self>, path : KAssetPath, resolved : KAssetPath, asset : Result<(Box<dyn Read>, KAssetSourceId), std::io::Error>,
+        started : Instant) -> Result<Box<dyn Read>, std::io::Error> {
+        let (inner, source) = match asset {
+            Ok(asset) => asset,
+            Err(err) => {
+                self.push_access(KAssetAccessKind::Get, path, resolved, None, started);
+                return Err(err);
+            },
+        };
+
+        let index = self.push_access(KAssetAccessKind::Get, path, resolved, Some(source), started);
+        Ok(Box::new(KAssetTraceReader { inner, recorder : self, index, bytes : 0, elapsed : started.elapsed() }))
+    }
+
+    /// Push an access, returning its index in events.
+    fn push_access(&self, kind : KAssetAccessKind, path : KAssetPath, resolved : KAssetPath, source : Option<KAssetSourceId>, started : Instant) -> usize {
+        let access = KAssetAccess { timestamp : started.saturating_duration_since(self.instant), kind, path, resolved, source, bytes : 0,
+            elapsed : started.elapsed() };
+
+        let mut events = self.lock();
+        events.push(KAssetTraceEvent::Access(access));
+        events.len() - 1
+    }
+
+    /// Lock events. A poisoned lock is recovered since events are always left consistent.
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<KAssetTraceEvent>> {
+        self.events.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Get trace of events recorded so far.
+    fn get_trace(&self) -> KAssetTrace {
+        KAssetTrace { started : self.started, events : self.lock().clone() }
+    }
+}
+
+/// [Read] wrapper counting bytes read and time spent reading, recorded in its access when dropped.
+struct KAssetTraceReader {
+    // Inner reader
+    inner : Box<dyn Read>,
+
+    // Recorder of access
+    recorder : Arc<KAssetTraceRecorder>,
+
+    // Index of access in events
+    index : usize,
+
+    // Bytes read so far
+    bytes : u64,
+
+    // Time spent opening and reading so far
+    elapsed : Duration,
+}
+
+impl Read for KAssetTraceReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let started = Instant::now();
+        let read = self.inner.read(buf);
+        self.elapsed += started.elapsed();
+
+        if let Ok(count) = read {
+            self.bytes += count as u64;
+        }
+
+        read
+    }
+}
+
+impl Drop for KAssetTraceReader {
+    fn drop(&mut self) {
+        if let Some(KAssetTraceEvent::Access(access)) = self.recorder.lock().get_mut(self.index) {
+            access.bytes = self.bytes;
+            access.elapsed = self.elapsed;
+        }
+    }
+}
+
+impl KAssetBroker {
+    /// Start recording accesses of [KAssetBroker::has_asset] and [KAssetBroker::get_asset] in a new [KAssetTrace],
+    /// discarding trace being recorded if any.
+    ///
+    /// Assets opened by [KAssetStreamer](super::KAssetStreamer), [KAssetBroker::load] and preloads are recorded too.
+    pub fn start_trace(&self) {
+        *self.write_trace() = Some(Arc::new(KAssetTraceRecorder { started : SystemTime::now(), instant : Instant::now(), events : Mutex::new(Vec::new()) }));
+    }
+
+    /// Stop recording accesses.
+    ///
+    /// Returns `Some(`[KAssetTrace]`)` recorded, or `None` if broker wasn't recording.
+    pub fn stop_trace(&self) -> Option<KAssetTrace> {
+        self.write_trace().take().map(|recorder| recorder.get_trace())
+    }
+
+    /// Get [KAssetTrace] recorded so far, or `None` if broker isn't recording.
+    pub fn get_trace(&self) -> Option<KAssetTrace> {
+        self.read_trace().as_ref().map(|recorder| recorder.get_trace())
+    }
+
+    /// Verify if broker is recording accesses.
+    pub fn is_tracing(&self) -> bool {
+        self.read_trace().is_some()
+    }
+
+    /// Mark trace being recorded with a label, like the name of a level being loaded, to split it with [KAssetTrace::get_level_bundles].
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KAssetTraceError::InvalidLabel]`)` if label is empty or contains control characters.
+    ///
+    /// Returns `Err(`[KAssetTraceError::NotTracing]`)` if broker isn't recording.
+    pub fn mark_trace(&self, label : &str) -> Result<(), KAssetTraceError> {
+        if !is_valid_label(label) {
+            return Err(KAssetTraceError::InvalidLabel);
+        }
+
+        match self.read_trace().as_ref() {
+            Some(recorder) => {
+                recorder.lock().push(KAssetTraceEvent::Mark(recorder.instant.elapsed(), label.to_string()));
+                Ok(())
+            },
+            None => Err(KAssetTraceError::NotTracing),
+        }
+    }
+
+    /// Get assets matching glob `pattern` that were read or not in `traces`, to find assets shipped but never used.
+    ///
+    /// An asset is read if it's the variant resolved by an access of [KAssetBroker::get_asset] that found it, so default
+    /// assets are unused in traces recorded with locales or tags picking other variants. A compressed asset is read if
+    /// the asset decompressed from it was read. See [KAssetBroker::list_assets] for pattern syntax. Use `**` for all assets.
+    ///
+    /// # Error(s)
+    /// Returns errors of [KAssetBroker::list_assets].
+    pub fn get_usage_report(&self, traces : &[KAssetTrace], pattern : &str) -> Result<KAssetUsageReport, std::io::Error> {
+        let used : HashSet<&KAssetPath> = traces.iter().flat_map(|trace| trace.get_accesses())
+            .filter(|access| access.kind == KAssetAccessKind::Get && access.source.is_some())
+            .map(|access| &access.resolved).collect();
+
+        let sources = self.get_all_sources_snapshot();
+        let decompression = self.get_decompression();
+        let mut report = KAssetUsageReport::default();
+
+        for entry in self.list_assets(pattern)? {
+            // Compressed assets are listed with their extension but served without it
+            let decompressed = decompression.as_ref().and_then(|decompression| get_decompressed_path(&sources, decompression, &entry));
+            if used.contains(&entry.path) || decompressed.is_some_and(|path| used.contains(&path)) {
+                report.used_count += 1;
+                continue;
+            }
+
+            // Sources removed while listing are ignored
+            let (priority, src) = match sources.iter().enumerate().find(|(_, src)| src.id == entry.source) {
+                Some(source) => source,
+                None => continue,
+            };
+
+            let size = src.get_local_path(&entry.path).and_then(|local| src.source.get_asset_metadata(&local).ok()).map(|metadata| metadata.size);
+            let source = KAssetSourceInfo { id : src.id, name : src.name.clone(), priority, mount : src.mount.clone() };
+            report.unused.push(KAssetUnused { path : entry.path, source, size });
+        }
+
+        Ok(report)
+    }
+}
+
+/// Get path of asset served by decompressing compressed asset of listed entry, or `None` if entry isn't the compressed asset served.
+fn get_decompressed_path(sources : &[KAssetBrokerSource], decompression : &KAssetDecompression, entry : &KAssetListEntry) -> Option<KAssetPath> {
+    KAssetCompression::from_path(&entry.path)?;
+    let path = KAssetPath::new(entry.path.as_str().rsplit_once('.')?.0).ok()?;
+
+    // First source having asset serves it, decompressed only if source doesn't have asset itself
+    let (src, local) = sources.iter().filter(|src| src.distrust.is_none()).find_map(|src| {
+        let local = src.get_local_path(&path)?;
+        (src.source.has_asset(&local) || decompression.find_asset(src, &local).is_some()).then_some((src, local))
+    })?;
+
+    match src.id == entry.source && !src.source.has_asset(&local) && decompression.find_asset(src, &local)?.0 == src.get_local_path(&entry.path)? {
+        true => Some(path),
+        false => None,
+    }
+}
+
+/// Add requested path of access to bundle if asset was opened and found.
+fn add_opened(bundle : &mut KAssetBundle, access : &KAssetAccess) {
+    if access.kind == KAssetAccessKind::Get && access.source.is_some() {
+        // Paths recorded are valid
+        let _ = bundle.add(&access.path);
+    }
+}
+
+/// Returns true if mark label isn't empty and has no control characters.
+fn is_valid_label(label : &str) -> bool {
+    !label.is_empty() && !label.chars().any(char::is_control)
+}
+
+/// Parse an unsigned decimal number of an exported trace.
+fn parse_number(value : &str) -> Result<u64, KAssetTraceError> {
+    match !value.is_empty() && value.chars().all(|c| c.is_ascii_digit()) {
+        true => value.parse().map_err(|_| KAssetTraceError::InvalidLine),
+        false => Err(KAssetTraceError::InvalidLine),
+    }
+}
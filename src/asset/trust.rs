@@ -0,0 +1,335 @@
+use std::sync::Arc;
+use super::{KAssetBroker, KAssetBrokerError, KAssetSource, KAssetSourceId, KIntegrityManifest, KIntegrityManifestError};
+use super::codec::ed25519;
+
+/// ##### Ed25519 public key verifying signatures of [KAssetSourcePak](super::KAssetSourcePak) and [KIntegrityManifest].
+///
+/// Written and parsed as 64 lowercase hexadecimal digits.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct KPublicKey([u8; 32]);
+
+impl KPublicKey {
+    /// Create a public key from its 32 bytes.
+    pub fn from_bytes(bytes : [u8; 32]) -> KPublicKey {
+        KPublicKey(bytes)
+    }
+
+    /// Parse a public key written as 64 hexadecimal digits, or `None` if invalid.
+    pub fn from_hex(hex : &str) -> Option<KPublicKey> {
+        parse_hex(hex).map(KPublicKey)
+    }
+
+    /// Get the 32 bytes of key.
+    pub fn get_bytes(&self) -> [u8; 32] {
+        self.0
+    }
+
+    /// Verify Ed25519 `signature` of `message` made with the private key of this public key.
+    ///
+    /// Returns true if signature is valid.
+    pub fn verify(&self, message : &[u8], signature : &[u8; 64]) -> bool {
+        ed25519::verify(&self.0, message, signature)
+    }
+}
+
+impl std::fmt::Display for KPublicKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", to_hex(&self.0))
+    }
+}
+
+impl std::fmt::Debug for KPublicKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "KPublicKey({})", self)
+    }
+}
+
+/// ##### Ed25519 private key signing packs with [KPakWriter::set_signing_key](super::KPakWriter::set_signing_key)
+/// and manifests with [KIntegrityManifest::to_signed_string].
+///
+/// Signing keys are meant for build tools only and should never ship with a game.
+///
+/// # Example(s)
+/// ```
+/// use olympus_kleio::asset::KSigningKey;
+///
+/// let key = KSigningKey::from_seed([7; 32]);
+/// let signature = key.sign(b"official content");
+///
+/// assert!(key.get_public_key().verify(b"official content", &signature));
+/// assert!(!key.get_public_key().verify(b"modified content", &signature));
+/// ```
+#[derive(Clone)]
+pub struct KSigningKey {
+    // Private key
+    seed : [u8; 32],
+
+    // Public key of private key
+    public_key : KPublicKey,
+}
+
+impl KSigningKey {
+    /// Create a signing key from its 32 bytes private key `seed`, as in RFC 8032.
+    pub fn from_seed(seed : [u8; 32]) -> KSigningKey {
+        KSigningKey { seed, public_key : KPublicKey(ed25519::public_key(&seed)) }
+    }
+
+    /// Get the [KPublicKey] verifying signatures of this key.
+    pub fn get_public_key(&self) -> KPublicKey {
+        self.public_key
+    }
+
+    /// Sign `message`, returning its 64 bytes Ed25519 signature.
+    pub fn sign(&self, message : &[u8]) -> [u8; 64] {
+        ed25519::sign(&self.seed, message)
+    }
+}
+
+impl std::fmt::Debug for KSigningKey {
+    /// Private key is never written.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "KSigningKey({})", self.public_key)
+    }
+}
+
+/// Enumeration of reasons a [KAssetSource] fails a [KTrustPolicy].
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum KTrustError {
+    /// Happens when source isn't signed and policy doesn't allow unsigned sources.
+    Unsigned,
+
+    /// Happens when source is signed by a key that isn't trusted by policy.
+    UntrustedKey,
+
+    /// Happens when source signature doesn't match its content, which was modified after signing.
+    InvalidSignature,
+
+    /// Happens when source couldn't be read to verify its signature.
+    ReadError,
+}
+
+impl std::fmt::Debug for KTrustError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unsigned => write!(f, "Unsigned"),
+            Self::UntrustedKey => write!(f, "UntrustedKey"),
+            Self::InvalidSignature => write!(f, "InvalidSignature"),
+            Self::ReadError => write!(f, "ReadError"),
+        }
+    }
+}
+
+impl std::fmt::Display for KTrustError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unsigned => write!(f, "Source is not signed!"),
+            Self::UntrustedKey => write!(f, "Source is signed by an untrusted key!"),
+            Self::InvalidSignature => write!(f, "Source signature doesn't match its content!"),
+            Self::ReadError => write!(f, "Source couldn't be read to verify its signature!"),
+        }
+    }
+}
+
+impl std::error::Error for KTrustError {}
+
+/// ##### Policy of [KAssetBroker] telling which sources can be loaded according to their signature.
+///
+/// A source is signed when it's a signed [KAssetSourcePak](super::KAssetSourcePak), or when it has a
+/// [KIntegrityManifest] signed with [KIntegrityManifest::to_signed_string] at [KINTEGRITY_MANIFEST_PATH](super::KINTEGRITY_MANIFEST_PATH).
+/// Assets of a source signed by its manifest are then verified against the manifest as they're read.
+///
+/// Sources with an invalid signature are always refused. With unsigned sources allowed, sources signed by
+/// keys that aren't trusted are accepted like unsigned mods.
+///
+/// # Example(s)
+/// ```
+/// use std::sync::Arc;
+/// use olympus_kleio::asset::{KAssetBroker, KAssetBrokerError, KAssetSourceMemory, KIntegrityManifest, KSigningKey,
+///     KTrustPolicy, KTrustError, KINTEGRITY_MANIFEST_PATH};
+///
+/// // Official content ships with a manifest signed by the studio
+/// let studio = KSigningKey::from_seed([1; 32]);
+/// let base = KAssetSourceMemory::new("base");
+/// base.add_asset("maps/arena.map", vec![1, 2, 3]).unwrap();
+/// let manifest = KIntegrityManifest::generate(&base).unwrap();
+/// base.add_asset(KINTEGRITY_MANIFEST_PATH, manifest.to_signed_string(&studio).into_bytes()).unwrap();
+///
+/// // Competitive mode only loads official content
+/// let broker = KAssetBroker::new();
+/// broker.set_trust_policy(Some(KTrustPolicy::official_only(studio.get_public_key())));
+/// assert!(broker.add_source(Arc::new(base)).is_ok());
+///
+/// let unsigned_mod = KAssetSourceMemory::new("mod");
+/// assert!(matches!(broker.add_source(Arc::new(unsigned_mod)), Err(KAssetBrokerError::SourceNotTrusted(KTrustError::Unsigned))));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KTrustPolicy {
+    /// Keys whose signed sources are accepted.
+    pub trusted_keys : Vec<KPublicKey>,
+
+    /// Accept unsigned sources, like mods.
+    pub allow_unsigned : bool,
+}
+
+impl KTrustPolicy {
+    /// Policy accepting only sources signed with `official_key`.
+    pub fn official_only(official_key : KPublicKey) -> KTrustPolicy {
+        KTrustPolicy { trusted_keys : vec![official_key], allow_unsigned : false }
+    }
+
+    /// Policy accepting sources signed with `official_key` and unsigned mods.
+    pub fn allow_unsigned_mods(official_key : KPublicKey) -> KTrustPolicy {
+        KTrustPolicy { trusted_keys : vec![official_key], allow_unsigned : true }
+    }
+
+    /// Policy accepting only sources signed with one of `trusted_keys`.
+    pub fn trusted_keys(trusted_keys : Vec<KPublicKey>) -> KTrustPolicy {
+        KTrustPolicy { trusted_keys, allow_unsigned : false }
+    }
+
+    /// Verify signature of `source` and check it against policy. Folders are verified by their signed manifest.
+    ///
+    /// Returns `Ok(Some(`[KPublicKey]`))` with key that signed source, or `Ok(None)` for accepted unsigned sources.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KTrustError::InvalidSignature]`)` if signature of source doesn't match its content.
+    ///
+    /// Returns `Err(`[KTrustError::Unsigned]`)` if source isn't signed and policy doesn't allow unsigned sources.
+    ///
+    /// Returns `Err(`[KTrustError::UntrustedKey]`)` if source is signed by a key that isn't trusted.
+    ///
+    /// Returns `Err(`[KTrustError::ReadError]`)` if source couldn't be read.
+    pub fn check(&self, source : &dyn KAssetSource) -> Result<Option<KPublicKey>, KTrustError> {
+        self.check_source(source).map(|(signer, _)| signer)
+    }
+
+    /// Check `source` against policy.
+    ///
+    /// Returns `Ok((signer, manifest))` with signed manifest verifying assets if source is signed by its manifest.
+    pub(crate) fn check_source(&self, source : &dyn KAssetSource) -> Result<(Option<KPublicKey>, Option<Arc<KIntegrityManifest>>), KTrustError> {
+        let (signer, manifest) = get_source_signer(source)?;
+
+        match signer {
+            Some(key) if self.trusted_keys.contains(&key) => Ok((Some(key), manifest)),
+            Some(_) if self.allow_unsigned => Ok((None, None)),
+            Some(_) => Err(KTrustError::UntrustedKey),
+            None if self.allow_unsigned => Ok((None, None)),
+            None => Err(KTrustError::Unsigned),
+        }
+    }
+}
+
+impl KAssetBroker {
+    /// Set the [KTrustPolicy] of sources, or `None` to accept all sources.
+    ///
+    /// Sources already added are checked again : sources failing policy are disabled and skipped by every read,
+    /// listing and metadata query until a policy accepts them. Sources added afterward failing policy are refused
+    /// with [KAssetBrokerError::SourceNotTrusted].
+    ///
+    /// The sink set with [KAssetBroker::set_sink] holds data written by the game itself and is exempt from policy.
+    ///
+    /// Returns identifiers of sources disabled with the reason, ordered by priority.
+    ///
+    /// # Example(s)
+    /// ```
+    /// use std::sync::Arc;
+    /// use olympus_kleio::asset::{KAssetBroker, KAssetSourceMemory, KSigningKey, KTrustPolicy, KTrustError};
+    ///
+    /// let broker = KAssetBroker::new();
+    /// let id = broker.add_source(Arc::new(KAssetSourceMemory::new("mod"))).unwrap();
+    ///
+    /// // Entering competitive mode disables the unsigned mod
+    /// let studio = KSigningKey::from_seed([1; 32]);
+    /// let disabled = broker.set_trust_policy(Some(KTrustPolicy::official_only(studio.get_public_key())));
+    /// assert_eq!(disabled, vec![(id, KTrustError::Unsigned)]);
+    /// assert_eq!(broker.get_source_trust_error(id).unwrap(), Some(KTrustError::Unsigned));
+    ///
+    /// // Leaving it enables the mod again
+    /// broker.set_trust_policy(None);
+    /// assert_eq!(broker.get_source_trust_error(id).unwrap(), None);
+    /// ```
+    pub fn set_trust_policy(&self, policy : Option<KTrustPolicy>) -> Vec<(KAssetSourceId, KTrustError)> {
+        // Sources are checked under the lock of sources, so a source added meanwhile can't miss policy
+        let mut sources = self.write_sources();
+
+        let mut disabled = Vec::new();
+        for src in sources.iter_mut().filter(|src| !src.trust_exempt) {
+            let (id, result) = (src.id, policy.as_ref().map(|policy| policy.check_source(src.source.as_ref())));
+
+            src.distrust = match result {
+                Some(Err(err)) => {
+                    disabled.push((id, err));
+                    Some(err)
+                },
+                // Assets of a source signed by its manifest are verified from now on
+                Some(Ok((_, Some(manifest)))) if src.integrity.is_none() => {
+                    src.integrity = Some(manifest);
+                    if let Some(cache) = self.get_cache() {
+                        cache.invalidate_source(id);
+                    }
+                    None
+                },
+                _ => None,
+            };
+        }
+
+        *self.write_trust_policy() = policy;
+        disabled
+    }
+
+    /// Get the [KTrustPolicy] of sources, if any.
+    pub fn get_trust_policy(&self) -> Option<KTrustPolicy> {
+        self.read_trust_policy().clone()
+    }
+
+    /// Get the reason why the [KAssetSource] identified by `id` is disabled by [KTrustPolicy], or `None` if it's enabled.
+    ///
+    /// # Error(s)
+    /// Returns `Err(`[KAssetBrokerError::SourceNotFound]`)` if [KAssetSource] is not found.
+    pub fn get_source_trust_error(&self, id : KAssetSourceId) -> Result<Option<KTrustError>, KAssetBrokerError> {
+        match self.get_all_sources_snapshot().into_iter().find(|src| src.id == id) {
+            Some(src) => Ok(src.distrust),
+            None => Err(KAssetBrokerError::SourceNotFound),
+        }
+    }
+}
+
+/// Get key that signed `source`, from its signature or its signed integrity manifest.
+///
+/// Returns `Ok((signer, manifest))` with manifest if source is signed by its manifest.
+fn get_source_signer(source : &dyn KAssetSource) -> Result<(Option<KPublicKey>, Option<Arc<KIntegrityManifest>>), KTrustError> {
+    if let Some(key) = source.verify_signature()? {
+        return Ok((Some(key), None));
+    }
+
+    match KIntegrityManifest::from_source(source) {
+        Ok(manifest) => match manifest.get_signer() {
+            Some(key) => Ok((Some(key), Some(Arc::new(manifest)))),
+            None => Ok((None, None)),
+        },
+        Err(KIntegrityManifestError::InvalidSignature) => Err(KTrustError::InvalidSignature),
+        Err(KIntegrityManifestError::ReadError) => Err(KTrustError::ReadError),
+
+        // A missing or invalid manifest doesn't sign anything
+        Err(_) => Ok((None, None)),
+    }
+}
+
+/// Write bytes as lowercase hexadecimal digits.
+pub(crate) fn to_hex(bytes : &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Parse `N` bytes written as `2 * N` hexadecimal digits.
+pub(crate) fn parse_hex<const N : usize>(hex : &str) -> Option<[u8; N]> {
+    if hex.len() != 2 * N || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let mut bytes = [0u8; N];
+    for (byte, digits) in bytes.iter_mut().zip(hex.as_bytes().chunks_exact(2)) {
+        *byte = u8::from_str_radix(std::str::from_utf8(digits).ok()?, 16).ok()?;
+    }
+
+    Some(bytes)
+}
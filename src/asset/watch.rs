@@ -0,0 +1,306 @@
+// Contains folder watcher using Linux inotify
+use std::{path::{Path, PathBuf}, io, collections::{HashMap, HashSet}};
+
+use super::{KAssetChange, KAssetChangeKind, KAssetPath};
+
+#[cfg(target_os = "linux")]
+use std::{ffi::CString, os::{raw::{c_char, c_int, c_void}, unix::ffi::OsStrExt}};
+
+#[cfg(target_os = "linux")]
+extern "C" {
+    /// Initializes a new inotify instance and returns a file descriptor associated with a new inotify event queue.
+    ///
+    /// # Reference(s)
+    /// https://man7.org/linux/man-pages/man2/inotify_init1.2.html
+    fn inotify_init1(flags : c_int) -> c_int;
+
+    /// Adds a new watch, or modifies an existing watch, for the file whose location is specified in pathname.
+    ///
+    /// # Reference(s)
+    /// https://man7.org/linux/man-pages/man2/inotify_add_watch.2.html
+    fn inotify_add_watch(fd : c_int, pathname : *const c_char, mask : u32) -> c_int;
+
+    /// Attempts to read up to count bytes from file descriptor fd into the buffer starting at buf.
+    ///
+    /// # Reference(s)
+    /// https://man7.org/linux/man-pages/man2/read.2.html
+    fn read(fd : c_int, buf : *mut c_void, count : usize) -> isize;
+
+    /// Closes a file descriptor, so that it no longer refers to any file and may be reused.
+    ///
+    /// # Reference(s)
+    /// https://man7.org/linux/man-pages/man2/close.2.html
+    fn close(fd : c_int) -> c_int;
+}
+
+// inotify_init1 flags
+#[cfg(target_os = "linux")]
+const IN_NONBLOCK : c_int = 0o4000;
+#[cfg(target_os = "linux")]
+const IN_CLOEXEC : c_int = 0o2000000;
+
+// inotify events
+const IN_CLOSE_WRITE : u32 = 0x0000_0008;
+const IN_MOVED_FROM : u32 = 0x0000_0040;
+const IN_MOVED_TO : u32 = 0x0000_0080;
+const IN_CREATE : u32 = 0x0000_0100;
+const IN_DELETE : u32 = 0x0000_0200;
+const IN_Q_OVERFLOW : u32 = 0x0000_4000;
+const IN_IGNORED : u32 = 0x0000_8000;
+const IN_ISDIR : u32 = 0x4000_0000;
+
+// Events watched on each folder
+const KWATCH_MASK : u32 = IN_CLOSE_WRITE | IN_MOVED_FROM | IN_MOVED_TO | IN_CREATE | IN_DELETE;
+
+// Size of inotify_event without name
+const KWATCH_EVENT_SIZE : usize = 16;
+
+/// Watcher of a folder and its sub folders reporting changes of files.
+pub(crate) struct KFolderWatcher {
+    // Root folder watched
+    root : PathBuf,
+
+    // inotify file descriptor
+    #[cfg(target_os = "linux")]
+    fd : c_int,
+
+    // Folders watched, relative to root, by watch descriptor
+    folders : HashMap<i32, PathBuf>,
+
+    // Files known within root, relative to root, so files deleted while events were lost are reported
+    files : HashSet<PathBuf>,
+}
+
+impl KFolderWatcher {
+    /// Create a watcher of `root` and its sub folders.
+    ///
+    /// # Error(s)
+    /// Returns [io::Error] of kind [io::ErrorKind::Unsupported] on platforms without inotify.
+    #[cfg(target_os = "linux")]
+    pub fn new(root : &Path) -> Result<KFolderWatcher, io::Error> {
+        let fd = unsafe { inotify_init1(IN_NONBLOCK | IN_CLOEXEC) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let mut watcher = KFolderWatcher { root : root.to_path_buf(), fd, folders : HashMap::new(), files : HashSet::new() };
+        let mut files = Vec::new();
+        watcher.watch_folder(PathBuf::new(), &mut files)?;
+        watcher.files.extend(files);
+
+        Ok(watcher)
+    }
+
+    /// Create a watcher of `root` and its sub folders.
+    ///
+    /// # Error(s)
+    /// Returns [io::Error] of kind [io::ErrorKind::Unsupported] on platforms without inotify.
+    #[cfg(not(target_os = "linux"))]
+    pub fn new(_root : &Path) -> Result<KFolderWatcher, io::Error> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "Folder watch requires inotify!"))
+    }
+
+    /// Get changes since last poll without blocking, merged per path in order of first change.
+    ///
+    /// If inotify queue overflowed, folder is scanned again : files found are reported as modified, or created if
+    /// unknown, and known files not found are reported as deleted.
+    pub fn poll(&mut self) -> Vec<KAssetChange> {
+        let mut changes : Vec<KAssetChange> = Vec::new();
+        let mut overflowed = false;
+
+        for (mask, wd, name) in self.read_events() {
+            // Events were lost, so folder is scanned once all events are read
+            if mask & IN_Q_OVERFLOW != 0 {
+                overflowed = true;
+                continue;
+            }
+
+            if mask & IN_IGNORED != 0 {
+                self.folders.remove(&wd);
+                continue;
+            }
+
+            let path = match self.folders.get(&wd) {
+                Some(folder) => folder.join(name),
+                None => continue,
+            };
+
+            if mask & IN_ISDIR != 0 {
+                // Files of folders created or moved in are reported as created
+                if mask & (IN_CREATE | IN_MOVED_TO) != 0 {
+                    let mut created = Vec::new();
+                    let _ = self.watch_folder(path, &mut created);
+
+                    for path in created {
+                        self.files.insert(path.clone());
+                        Self::merge(&mut changes, path, KAssetChangeKind::Created);
+                    }
+                }
+                continue;
+            }
+
+            let kind = if mask & (IN_CREATE | IN_MOVED_TO) != 0 {
+                KAssetChangeKind::Created
+            } else if mask & (IN_DELETE | IN_MOVED_FROM) != 0 {
+                KAssetChangeKind::Deleted
+            } else {
+                KAssetChangeKind::Modified
+            };
+
+            match kind {
+                KAssetChangeKind::Created => { self.files.insert(path.clone()); },
+                KAssetChangeKind::Deleted => { self.files.remove(&path); },
+                KAssetChangeKind::Modified => {},
+            }
+            Self::merge(&mut changes, path, kind);
+        }
+
+        if overflowed {
+            self.rescan(&mut changes);
+        }
+
+        changes
+    }
+
+    /// Scan folder again after events were lost, merging a change for each file found and each known file not found.
+    fn rescan(&mut self, changes : &mut Vec<KAssetChange>) {
+        // Folders already watched keep their watch, folders created meanwhile are watched
+        let mut found = Vec::new();
+        let _ = self.watch_folder(PathBuf::new(), &mut found);
+        found.sort();
+
+        let found_set : HashSet<PathBuf> = found.iter().cloned().collect();
+        let mut deleted : Vec<PathBuf> = self.files.difference(&found_set).cloned().collect();
+        deleted.sort();
+
+        for path in deleted {
+            Self::merge(changes, path, KAssetChangeKind::Deleted);
+        }
+
+        for path in found {
+            let kind = match self.files.contains(&path) {
+                true => KAssetChangeKind::Modified,
+                false => KAssetChangeKind::Created,
+            };
+            Self::merge(changes, path, kind);
+        }
+
+        self.files = found_set;
+    }
+
+    /// Merge change of path with previous change of same path. Paths that can't be asset paths are ignored.
+    fn merge(changes : &mut Vec<KAssetChange>, path : PathBuf, kind : KAssetChangeKind) {
+        let path = match KAssetPath::from_path(&path) {
+            Ok(path) => path,
+            Err(_) => return,
+        };
+
+        let index = match changes.iter().position(|change| change.path == path) {
+            Some(index) => index,
+            None => {
+                changes.push(KAssetChange { path, kind });
+                return;
+            },
+        };
+
+        let merged = match (changes[index].kind, kind) {
+            (KAssetChangeKind::Created, KAssetChangeKind::Deleted) => None,
+            (KAssetChangeKind::Created, _) => Some(KAssetChangeKind::Created),
+            (KAssetChangeKind::Deleted, KAssetChangeKind::Deleted) => Some(KAssetChangeKind::Deleted),
+            (KAssetChangeKind::Deleted, _) => Some(KAssetChangeKind::Modified),
+            (KAssetChangeKind::Modified, KAssetChangeKind::Deleted) => Some(KAssetChangeKind::Deleted),
+            (KAssetChangeKind::Modified, _) => Some(KAssetChangeKind::Modified),
+        };
+
+        match merged {
+            Some(kind) => changes[index].kind = kind,
+            None => { changes.remove(index); },
+        }
+    }
+
+    /// Watch folder relative to root and its sub folders. Files found are added to `files`.
+    #[cfg(target_os = "linux")]
+    fn watch_folder(&mut self, folder : PathBuf, files : &mut Vec<PathBuf>) -> Result<(), io::Error> {
+        let full_path = self.root.join(&folder);
+
+        let c_path = match CString::new(full_path.as_os_str().as_bytes()) {
+            Ok(c_path) => c_path,
+            Err(_) => return Err(io::Error::new(io::ErrorKind::InvalidInput, "Path contains nul byte!")),
+        };
+
+        let wd = unsafe { inotify_add_watch(self.fd, c_path.as_ptr(), KWATCH_MASK) };
+        if wd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        self.folders.insert(wd, folder.clone());
+
+        // Folder is listed after watch is added so files created in between are not lost
+        for entry in std::fs::read_dir(&full_path)? {
+            let entry = entry?;
+            let path = folder.join(entry.file_name());
+
+            if entry.file_type()?.is_dir() {
+                self.watch_folder(path, files)?;
+            } else {
+                files.push(path);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Watch folder relative to root and its sub folders.
+    #[cfg(not(target_os = "linux"))]
+    fn watch_folder(&mut self, _folder : PathBuf, _files : &mut Vec<PathBuf>) -> Result<(), io::Error> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "Folder watch requires inotify!"))
+    }
+
+    /// Read pending events as (mask, watch descriptor, name).
+    #[cfg(target_os = "linux")]
+    fn read_events(&mut self) -> Vec<(u32, i32, PathBuf)> {
+        let mut events = Vec::new();
+        let mut buffer = [0u8; 4096];
+
+        loop {
+            let size = unsafe { read(self.fd, buffer.as_mut_ptr() as *mut c_void, buffer.len()) };
+            if size <= 0 {
+                // No more event (EAGAIN) or error
+                break;
+            }
+
+            let mut offset = 0;
+            while offset + KWATCH_EVENT_SIZE <= size as usize {
+                let field = |at : usize| [buffer[offset + at], buffer[offset + at + 1], buffer[offset + at + 2], buffer[offset + at + 3]];
+                let wd = i32::from_ne_bytes(field(0));
+                let mask = u32::from_ne_bytes(field(4));
+                let len = u32::from_ne_bytes(field(12)) as usize;
+                if offset + KWATCH_EVENT_SIZE + len > size as usize {
+                    // Truncated event can't be read
+                    break;
+                }
+
+                // Name is padded with nul bytes
+                let name = &buffer[offset + KWATCH_EVENT_SIZE..offset + KWATCH_EVENT_SIZE + len];
+                let name = &name[..name.iter().position(|b| *b == 0).unwrap_or(len)];
+                events.push((mask, wd, PathBuf::from(std::ffi::OsStr::from_bytes(name))));
+
+                offset += KWATCH_EVENT_SIZE + len;
+            }
+        }
+
+        events
+    }
+
+    /// Read pending events as (mask, watch descriptor, name).
+    #[cfg(not(target_os = "linux"))]
+    fn read_events(&mut self) -> Vec<(u32, i32, PathBuf)> {
+        Vec::new()
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Drop for KFolderWatcher {
+    fn drop(&mut self) {
+        unsafe { close(self.fd); }
+    }
+}
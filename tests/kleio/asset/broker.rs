@@ -1,6 +1,6 @@
-use std::{fs::{self, File}, io::Write, path::PathBuf, vec};
+use std::{fs::{self, File}, io::Write, path::PathBuf, sync::Arc, thread, vec};
 
-use olympus_kleio::asset::{KAssetBroker, KAssetSourceFolder, KAssetSource, KAssetBrokerError};
+use olympus_kleio::asset::{KAssetBroker, KAssetSourceFolder, KAssetSource, KAssetBrokerError, KAssetSourceId};
 
 // Test folder where to create assets
 static TEST_FOLDER: &str = "target/tests/kleio/asset/";
@@ -18,8 +18,7 @@ macro_rules! kasset_broker_test_prepare {
     // This macro call doesn't create KAssetSourceFolder nor the files
     ($kab_var:ident, $folder_var:ident, $folder_name:expr, $test_body:block) => {{
         // Create Broker
-        #[allow(unused_mut)]
-        let mut $kab_var = KAssetBroker::new();
+        let $kab_var = KAssetBroker::new();
 
         // Assign folder name
         let  $folder_var: &str = &(TEST_FOLDER.to_owned() + $folder_name);
@@ -34,7 +33,7 @@ macro_rules! kasset_broker_test_prepare {
     // This macro call will create files and KAssetSourceFolder
     ($kab_var:ident, $folder_var:ident, $folder_name:expr, ($kaf_var:ident $(,$extra:ident)*), $test_body:block) => {{
         // Create Broker
-        let mut $kab_var = KAssetBroker::new();
+        let $kab_var = KAssetBroker::new();
 
         // Assign folder name
         let  $folder_var: &str = &(TEST_FOLDER.to_owned() + $folder_name);
@@ -63,10 +62,11 @@ macro_rules! kasset_broker_test_asset {
         create_test_folder_files($folder_var, $kaf_counter);
 
         // Create KAssetSourceFolder
-        let $kaf_var = KAssetSourceFolder::new(PathBuf::from($folder_var.to_owned() + "subfolder" + $kaf_counter.to_string().as_str() + "/")).unwrap();
+        let kaf = KAssetSourceFolder::new(PathBuf::from($folder_var.to_owned() + "subfolder" + $kaf_counter.to_string().as_str() + "/")).unwrap();
 
-        // Add KAssetSourceFolder to Broker
-        match_source_to_broker($kab_var.add_source(&$kaf_var), false);
+        // Add KAssetSourceFolder to Broker and keep its id
+        #[allow(unused_variables)]
+        let $kaf_var = match_source_to_broker($kab_var.add_source(Arc::new(kaf)), false).unwrap();
     };
 
     // Initial call
@@ -112,7 +112,7 @@ fn kasset_broker_add_source() {
 
             // V1 | KAssetBroker::add_source() without error.
             // Add KAssetSourceFolder to broker
-            match_source_to_broker(kab.add_source(&kaf), false);
+            match_source_to_broker(kab.add_source(Arc::new(kaf)), false);
         }
     );
 }
@@ -129,13 +129,13 @@ fn kasset_broker_add_source_again() {
             create_test_folder_files(folder_name, 0);
 
             // Create KAssetSourceFolder
-            let kaf = KAssetSourceFolder::new(PathBuf::from(folder_name.to_owned() + "subfolder0/")).unwrap();
+            let kaf = Arc::new(KAssetSourceFolder::new(PathBuf::from(folder_name.to_owned() + "subfolder0/")).unwrap());
 
             // Add KAssetSourceFolder to broker
-            match_source_to_broker(kab.add_source(&kaf), false);
+            match_source_to_broker(kab.add_source(kaf.clone()), false);
 
             // V1 | KAssetBroker::add_source() is expected to fail after adding the same source twice.
-            match_source_to_broker(kab.add_source(&kaf), true);
+            match_source_to_broker(kab.add_source(kaf), true);
         }
     );
 }
@@ -149,16 +149,16 @@ fn kasset_broker_add_source_10() {
     kasset_broker_test_prepare!(kab, folder_name, "kasset_broker_add_10_source/", (kaf0, kaf1, kaf2, kaf3, kaf4, kaf5, kaf6, kaf7, kaf8, kaf9),
         {
             // V1 | KAssetBroker can support multiple different sources (10 tested).
-            match_source_to_broker(kab.add_source(&kaf0), true);
-            match_source_to_broker(kab.add_source(&kaf1), true);
-            match_source_to_broker(kab.add_source(&kaf2), true);
-            match_source_to_broker(kab.add_source(&kaf3), true);
-            match_source_to_broker(kab.add_source(&kaf4), true);
-            match_source_to_broker(kab.add_source(&kaf5), true);
-            match_source_to_broker(kab.add_source(&kaf6), true);
-            match_source_to_broker(kab.add_source(&kaf7), true);
-            match_source_to_broker(kab.add_source(&kaf8), true);
-            match_source_to_broker(kab.add_source(&kaf9), true);
+            match_source_to_broker(kab.add_source(kab.get_source(kaf0).unwrap()), true);
+            match_source_to_broker(kab.add_source(kab.get_source(kaf1).unwrap()), true);
+            match_source_to_broker(kab.add_source(kab.get_source(kaf2).unwrap()), true);
+            match_source_to_broker(kab.add_source(kab.get_source(kaf3).unwrap()), true);
+            match_source_to_broker(kab.add_source(kab.get_source(kaf4).unwrap()), true);
+            match_source_to_broker(kab.add_source(kab.get_source(kaf5).unwrap()), true);
+            match_source_to_broker(kab.add_source(kab.get_source(kaf6).unwrap()), true);
+            match_source_to_broker(kab.add_source(kab.get_source(kaf7).unwrap()), true);
+            match_source_to_broker(kab.add_source(kab.get_source(kaf8).unwrap()), true);
+            match_source_to_broker(kab.add_source(kab.get_source(kaf9).unwrap()), true);
         }
     );
 }
@@ -182,13 +182,17 @@ fn kasset_broker_has_source(){
             let kaf2 = KAssetSourceFolder::new(PathBuf::from(folder_name.to_owned() + "subfolder2/")).unwrap();
 
             // V1 | Add 2 sources out of 3
-            match_source_to_broker(kab.add_source(&kaf0), false);
-            match_source_to_broker(kab.add_source(&kaf2), false);
+            let kaf0 = match_source_to_broker(kab.add_source(Arc::new(kaf0)), false).unwrap();
+            let kaf2 = match_source_to_broker(kab.add_source(Arc::new(kaf2)), false).unwrap();
+
+            // Source #1 is added to another broker
+            let kab_other = KAssetBroker::new();
+            let kaf1 = match_source_to_broker(kab_other.add_source(Arc::new(kaf1)), false).unwrap();
 
             // V2 | KAssetBroker::has_source() has 2 sources out of 3.
-            assert!(kab.has_source(&kaf0), "KAssetBroker should contain source #0");
-            assert!(!kab.has_source(&kaf1), "KAssetBroker shouldn't contain source #1");
-            assert!(kab.has_source(&kaf2), "KAssetBroker should contain source #2");
+            assert!(kab.has_source(kaf0), "KAssetBroker should contain source #0");
+            assert!(!kab.has_source(kaf1), "KAssetBroker shouldn't contain source #1");
+            assert!(kab.has_source(kaf2), "KAssetBroker should contain source #2");
         }
     );
 }
@@ -209,7 +213,7 @@ fn kasset_broker_remove_source() {
             print_broker_sources_metadatas(&kab);
 
             // V1 | KAssetBroker::remove_source() remove a source without error.
-            match kab.remove_source(&kaf0){
+            match kab.remove_source(kaf0){
                 Ok(_) => {},
                 Err(_) => assert!(false, "Error while removing source #0!"),
             }
@@ -219,7 +223,7 @@ fn kasset_broker_remove_source() {
             print_broker_sources_metadatas(&kab);
 
             // V1 | KAssetBroker::remove_source() remove a source without error.
-            match kab.remove_source(&kaf1){
+            match kab.remove_source(kaf1){
                 Ok(_) => {},
                 Err(_) => assert!(false, "Error while removing source #1!"),
             }
@@ -229,7 +233,7 @@ fn kasset_broker_remove_source() {
             print_broker_sources_metadatas(&kab);
 
             // V1 | KAssetBroker::remove_source() remove a source without error.
-            match kab.remove_source(&kaf2){
+            match kab.remove_source(kaf2){
                 Ok(_) => {},
                 Err(_) => assert!(false, "Error while removing source #2!"),
             }
@@ -253,11 +257,13 @@ fn kasset_broker_remove_source_inexistant() {
             // Create test files
             create_test_files(folder_name, 10, 10);
         
-            // Create KAssetSources
+            // Create KAssetSources and remove it once
             let kaf = KAssetSourceFolder::new(PathBuf::from(folder_name.to_owned() + "subfolder0/")).unwrap();
+            let kaf = match_source_to_broker(kab.add_source(Arc::new(kaf)), false).unwrap();
+            assert!(kab.remove_source(kaf).is_ok(), "Error while removing source!");
 
             // V1 | KAssetBroker::remove_source() must return Err() since source not in broker.
-            match kab.remove_source(&kaf){
+            match kab.remove_source(kaf){
                 Ok(_) => assert!(false, "Error! Source shouldn't be in broker!"),
                 Err(_) => {},
             }
@@ -277,7 +283,7 @@ fn kasset_broker_set_source_priority() {
     kasset_broker_test_prepare!(kab, folder_name, "kasset_broker_set_source_priority/", (kaf0, kaf1, kaf2, kaf3, kaf4, kaf5, kaf6, kaf7, kaf8, kaf9),
         {
             // V1 | KAssetBroker::set_source_priority() modify priority without error.
-            match kab.set_source_priority(&kaf2, 8){
+            match kab.set_source_priority(kaf2, 8){
                 Ok(_) => {},
                 Err(_) => assert!(false, "Error happens when setting source priority!"),
             }
@@ -286,31 +292,31 @@ fn kasset_broker_set_source_priority() {
             assert!(verify_priorities_order(&kab, vec![0,1,3,4,5,6,7,8,2,9]), "Broker priorities order error!");
 
             // V3 | Repeated multiple time and verified to ensure stability.
-            match kab.set_source_priority(&kaf0, 9){
+            match kab.set_source_priority(kaf0, 9){
                 Ok(_) => {},
                 Err(_) => assert!(false, "Error happens when setting source priority!"),
             }
-            match kab.set_source_priority(&kaf3, 9){
+            match kab.set_source_priority(kaf3, 9){
                 Ok(_) => {},
                 Err(_) => assert!(false, "Error happens when setting source priority!"),
             }
-            match kab.set_source_priority(&kaf4, 9){
+            match kab.set_source_priority(kaf4, 9){
                 Ok(_) => {},
                 Err(_) => assert!(false, "Error happens when setting source priority!"),
             }
-            match kab.set_source_priority(&kaf5, 9){
+            match kab.set_source_priority(kaf5, 9){
                 Ok(_) => {},
                 Err(_) => assert!(false, "Error happens when setting source priority!"),
             }
-            match kab.set_source_priority(&kaf6, 9){
+            match kab.set_source_priority(kaf6, 9){
                 Ok(_) => {},
                 Err(_) => assert!(false, "Error happens when setting source priority!"),
             }
-            match kab.set_source_priority(&kaf7, 9){
+            match kab.set_source_priority(kaf7, 9){
                 Ok(_) => {},
                 Err(_) => assert!(false, "Error happens when setting source priority!"),
             }
-            match kab.set_source_priority(&kaf8, 9){
+            match kab.set_source_priority(kaf8, 9){
                 Ok(_) => {},
                 Err(_) => assert!(false, "Error happens when setting source priority!"),
             }
@@ -338,10 +344,12 @@ fn kasset_broker_set_source_priority_inexistant() {
             let kaf1 = KAssetSourceFolder::new(PathBuf::from(folder_name.to_owned() + "subfolder1/")).unwrap();
 
             // Add sources to broker
-            match_source_to_broker(kab.add_source(&kaf0), false);
+            match_source_to_broker(kab.add_source(Arc::new(kaf0)), false);
+            let kaf1 = match_source_to_broker(kab.add_source(Arc::new(kaf1)), false).unwrap();
+            assert!(kab.remove_source(kaf1).is_ok(), "Error while removing source #1!");
 
             // V1 | KAssetBroker::set_source_priority() must return Err() for inexistant source.
-            match kab.set_source_priority(&kaf1, 0){
+            match kab.set_source_priority(kaf1, 0){
                 Ok(_) => assert!(false, "Error! Setting source priority to inexistant should have failed!"),
                 Err(_) => {},
             }
@@ -365,11 +373,11 @@ fn kasset_broker_set_source_priority_oob() {
             let kaf1 = KAssetSourceFolder::new(PathBuf::from(folder_name.to_owned() + "subfolder1/")).unwrap();
 
             // Add sources to broker
-            match_source_to_broker(kab.add_source(&kaf0), false);
-            match_source_to_broker(kab.add_source(&kaf1), false);
+            match_source_to_broker(kab.add_source(Arc::new(kaf0)), false);
+            let kaf1 = match_source_to_broker(kab.add_source(Arc::new(kaf1)), false).unwrap();
 
             // V1 | KAssetBroker::set_source_priority() must return Err() for out of bounds priority.
-            match kab.set_source_priority(&kaf1, 10){
+            match kab.set_source_priority(kaf1, 10){
                 Ok(_) => assert!(false, "Error! Setting source priority higher than bound should have failed!"),
                 Err(_) => {},
             }
@@ -471,6 +479,84 @@ fn kasset_broker_get_asset_inexistant() {
 
 }
 
+#[test]
+/// Verify that source ids stay the same when priorities change.
+///
+/// # Verification(s)
+/// V1 | KAssetBroker::get_source_priority() follows source after KAssetBroker::set_source_priority().
+/// V2 | KAssetBroker::get_source() returns the same source after priority change.
+/// V3 | KAssetBroker::get_sources() returns ids ordered by priority.
+fn kasset_broker_source_id_stable() {
+
+    kasset_broker_test_prepare!(kab, folder_name, "kasset_broker_source_id_stable/", (kaf0, kaf1, kaf2),
+        {
+            let source = kab.get_source(kaf2).unwrap();
+
+            // V1 | KAssetBroker::get_source_priority() follows source after KAssetBroker::set_source_priority().
+            assert!(kab.set_source_priority(kaf2, 0).is_ok(), "Error happens when setting source priority!");
+            assert!(matches!(kab.get_source_priority(kaf2), Ok(0)), "Source #2 priority should be 0!");
+            assert!(matches!(kab.get_source_priority(kaf0), Ok(1)), "Source #0 priority should be 1!");
+
+            // V2 | KAssetBroker::get_source() returns the same source after priority change.
+            assert!(Arc::ptr_eq(&source, &kab.get_source(kaf2).unwrap()), "Source #2 should be the same!");
+
+            // V3 | KAssetBroker::get_sources() returns ids ordered by priority.
+            assert!(kab.get_sources() == vec![kaf2, kaf0, kaf1], "Sources ids should be ordered by priority!");
+            assert!(kab.get_source_count() == 3, "Broker should contains 3 sources!");
+        }
+    );
+}
+
+#[test]
+/// Share a KAssetBroker between threads.
+///
+/// # Verification(s)
+/// V1 | KAssetBroker is Send + Sync.
+/// V2 | Sources can be added, reprioritised and removed from multiple threads.
+/// V3 | Assets can be fetched from multiple threads.
+fn kasset_broker_threads() {
+
+    // V1 | KAssetBroker is Send + Sync.
+    fn assert_send_sync<T : Send + Sync>() {}
+    assert_send_sync::<KAssetBroker>();
+
+    kasset_broker_test_prepare!(kab, folder_name, "kasset_broker_threads/", (kaf0),
+        {
+            create_test_files(folder_name, 5, 10);
+
+            let kab = Arc::new(kab);
+            let mut handles = Vec::new();
+
+            for i in 1..5 {
+                let kab = kab.clone();
+                let folder = folder_name.to_owned() + "subfolder" + i.to_string().as_str() + "/";
+
+                handles.push(thread::spawn(move || {
+                    for _ in 0..50 {
+                        // V2 | Sources can be added, reprioritised and removed from multiple threads.
+                        let kaf = KAssetSourceFolder::new(PathBuf::from(&folder)).unwrap();
+                        let id = match_source_to_broker(kab.add_source(Arc::new(kaf)), false).unwrap();
+                        assert!(kab.set_source_priority(id, 0).is_ok(), "Error happens when setting source priority!");
+
+                        // V3 | Assets can be fetched from multiple threads.
+                        assert!(kab.get_asset(PathBuf::from("file0.txt")).is_ok(), "Asset should be found!");
+
+                        assert!(kab.remove_source(id).is_ok(), "Error while removing source!");
+                    }
+                }));
+            }
+
+            for handle in handles {
+                handle.join().expect("Thread panicked!");
+            }
+
+            // Only source added by test macro remains
+            assert!(kab.get_sources() == vec![kaf0], "Broker should only contains source #0!");
+            fetch_asset_and_verify(&kab, "file0.txt", &String::from("Hello0, world0!"));
+        }
+    );
+}
+
 #[test]
 #[ignore]
 /// Stress test KAssetBroker to test stability and limit. Ignored by default. 
@@ -486,8 +572,8 @@ fn kasset_broker_stress() {
     kasset_broker_test_prepare!(kab, folder_name, "kasset_broker_get_asset_10_source/", (kaf0, kaf1, kaf2, kaf3, kaf4, kaf5, kaf6, kaf7, kaf8, kaf9),
         {
             // Create KassetSource
-            let kafp0 = KAssetSourceFolder::new(PathBuf::from(folder_name.to_owned() + "subfolder0/")).unwrap();
-            let kafp1 = KAssetSourceFolder::new(PathBuf::from(folder_name.to_owned() + "subfolder1/")).unwrap();
+            let kafp0 = Arc::new(KAssetSourceFolder::new(PathBuf::from(folder_name.to_owned() + "subfolder0/")).unwrap());
+            let kafp1 = Arc::new(KAssetSourceFolder::new(PathBuf::from(folder_name.to_owned() + "subfolder1/")).unwrap());
 
             // V5 | Repeat STRESS_TEST_COUNT times.
             for i in 0..STRESS_TEST_COUNT {
@@ -503,26 +589,26 @@ fn kasset_broker_stress() {
                 }
 
                 // V1 | Add multiple sources.
-                match_source_to_broker(kab.add_source(&kafp0), false);
-                match_source_to_broker(kab.add_source(&kafp1), false);
+                let kafp0_id = match_source_to_broker(kab.add_source(kafp0.clone()), false).unwrap();
+                let kafp1_id = match_source_to_broker(kab.add_source(kafp1.clone()), false).unwrap();
 
                 // Set filename to use
                 let filename = "file".to_owned() + f_index.to_string().as_str() + ".txt";
 
                 // V3 | Fetch multiple assets.
-                let kaf_index = extract_source_folder_index(kaf);
+                let kaf_index = extract_source_folder_index(kab.get_source(kaf).unwrap().as_ref());
                 let filecontent = &String::from("Hello".to_owned() + kaf_index.to_string().as_str() +", world"+ f_index.to_string().as_str() + "!");
 
                 // Compare filecontent
                 fetch_asset_and_verify(&kab, &filename, &filecontent);
 
                 // V2 | Remove multiple sources.
-                match kab.remove_source(&kafp0){
+                match kab.remove_source(kafp0_id){
                     Ok(_) => {},
                     Err(_) => assert!(false, "Couldn't remove source kafp0!"),
                 }
 
-                match kab.remove_source(&kafp1){
+                match kab.remove_source(kafp1_id){
                     Ok(_) => {},
                     Err(_) => assert!(false, "Couldn't remove source kafp1!"),
                 }
@@ -611,14 +697,21 @@ fn create_test_folder_files(folder_name : &str, subf_id: usize){
 /// 
 /// # Panic
 /// Will panic if !expect_fail.
-fn match_source_to_broker<'a>(res : Result<usize, KAssetBrokerError>, expect_fail : bool){
+/// 
+/// Returns the [KAssetSourceId] of source added, if any.
+fn match_source_to_broker(res : Result<KAssetSourceId, KAssetBrokerError>, expect_fail : bool) -> Option<KAssetSourceId> {
 
     match res{
-        Ok(_) => assert!(!expect_fail, "Adding the same source should fail!"),
-        Err(_) => assert!(expect_fail, "Add 1 source failed!"),
+        Ok(id) => {
+            assert!(!expect_fail, "Adding the same source should fail!");
+            Some(id)
+        },
+        Err(_) => {
+            assert!(expect_fail, "Add 1 source failed!");
+            None
+        },
     }
 
-
 }
 
 /// Print KAssetBroker sources metadata.
@@ -627,9 +720,9 @@ fn print_broker_sources_metadatas(kab : &KAssetBroker){
     println!("\n*** START BROKER SOURCES ***");
 
 
-    for src in kab.get_sources().iter() {
+    for id in kab.get_sources() {
 
-        println!("{}", src.get_metadata());
+        println!("{}", kab.get_source(id).unwrap().get_metadata().to_json());
     } 
     
     println!("*** END BROKER SOURCES ***\n");
@@ -644,7 +737,7 @@ fn verify_priorities_order(kab : &KAssetBroker, priority : Vec<u32>) -> bool{
 
     for i in 0..priority.len() {
 
-        let sf = extract_source_folder_index(kab.get_sources()[i]);
+        let sf = extract_source_folder_index(kab.get_source(kab.get_sources()[i]).unwrap().as_ref());
 
         // Compare with order vector
         if sf != priority[i] {
@@ -662,14 +755,11 @@ fn verify_priorities_order(kab : &KAssetBroker, priority : Vec<u32>) -> bool{
 /// Returns asset source folder index from metadata as u32.
 fn extract_source_folder_index(src:&dyn KAssetSource) -> u32 {
 
-    // Extract metadata
-    let metadata = src.get_metadata();
-    let mut split = metadata.split("\",\"");
-    let path  = split.next().unwrap();
-    let index = &path[path.len() - 2..path.len() - 1];
+    // Extract folder name from metadata
+    let name = src.get_metadata().name.unwrap();
 
     // Get source index
-    index.chars().next().unwrap().to_digit(10).unwrap()
+    name.chars().last().unwrap().to_digit(10).unwrap()
 }
 
 /// Fetch asset by name from broker and verify content.
@@ -0,0 +1,178 @@
+use std::{path::{Path, PathBuf}, io::Read, sync::Arc};
+use olympus_kleio::asset::{KAssetBroker, KAssetSourceMemory, KAssetCache, KAssetCacheStatistics};
+use olympus_kleio::journal::{KJournal, KJournalEntrySeverity, KJOURNAL_BUFFER_MIN};
+
+/// Create a broker with a cache of `budget` bytes and a [KAssetSourceMemory] of 10 assets of 100 bytes.
+///
+/// Returns broker, source and cache.
+fn create_broker(budget : usize) -> (KAssetBroker, Arc<KAssetSourceMemory>, Arc<KAssetCache>) {
+    let kasm = Arc::new(KAssetSourceMemory::new("cache"));
+    for i in 0..10u8 {
+        kasm.add_asset(PathBuf::from("file".to_owned() + i.to_string().as_str() + ".bin"), vec![i; 100]).unwrap();
+    }
+
+    let cache = Arc::new(KAssetCache::new(budget));
+    let kab = KAssetBroker::new();
+    kab.add_source(kasm.clone()).unwrap();
+    kab.set_cache(Some(cache.clone()));
+
+    (kab, kasm, cache)
+}
+
+/// Get asset content from broker.
+///
+/// # Panic
+/// Will panic if asset can't be found or read.
+fn get_content(kab : &KAssetBroker, path : &str) -> Vec<u8> {
+    let mut content = Vec::new();
+    kab.get_asset(PathBuf::from(path)).expect("Asset should be found!").read_to_end(&mut content).expect("Asset should be readable!");
+    content
+}
+
+#[test]
+/// Count hits and misses of [KAssetCache].
+///
+/// # Verification(s)
+/// V1 | First get of an asset is a miss and caches it.
+/// V2 | Next gets of an asset are hits with same content.
+/// V3 | Asset bigger than budget is not cached.
+/// V4 | KAssetCache::reset_statistics() resets counts but keeps entries.
+fn kasset_cache_hit_miss() {
+    let (kab, kasm, cache) = create_broker(1000);
+
+    // V1 | First get of an asset is a miss and caches it.
+    assert!(get_content(&kab, "file1.bin") == vec![1; 100], "Content is different that expected!");
+    assert!(cache.contains(Path::new("file1.bin")), "Asset should be cached!");
+    assert!(cache.get_statistics() == KAssetCacheStatistics { hits : 0, misses : 1, evictions : 0, entries : 1, size : 100, budget : 1000 }, "Wrong statistics!");
+
+    // V2 | Next gets of an asset are hits with same content.
+    for _ in 0..5 {
+        assert!(get_content(&kab, "file1.bin") == vec![1; 100], "Content is different that expected!");
+    }
+    assert!(cache.get_statistics().hits == 5, "5 hits expected!");
+
+    // V3 | Asset bigger than budget is not cached.
+    kasm.add_asset(PathBuf::from("big.bin"), vec![0; 2000]).unwrap();
+    assert!(get_content(&kab, "big.bin").len() == 2000, "Content is different that expected!");
+    assert!(!cache.contains(Path::new("big.bin")), "Asset shouldn't be cached!");
+    assert!(cache.get_statistics().misses == 2, "2 misses expected!");
+
+    // V4 | KAssetCache::reset_statistics() resets counts but keeps entries.
+    cache.reset_statistics();
+    assert!(cache.get_statistics() == KAssetCacheStatistics { entries : 1, size : 100, budget : 1000, ..Default::default() }, "Wrong statistics!");
+}
+
+#[test]
+/// Evict least recently used assets and pin assets.
+///
+/// # Verification(s)
+/// V1 | Least recently used asset is evicted when budget is exceeded.
+/// V2 | Pinned assets are never evicted.
+/// V3 | KAssetCache::unpin() makes asset evictable.
+/// V4 | KAssetCache::set_budget() evicts assets exceeding new budget.
+/// V5 | Least recently used assets behind pinned assets are evicted in order.
+fn kasset_cache_eviction() {
+    let (kab, _kasm, cache) = create_broker(300);
+
+    // V1 | Least recently used asset is evicted when budget is exceeded.
+    get_content(&kab, "file0.bin");
+    get_content(&kab, "file1.bin");
+    get_content(&kab, "file2.bin");
+    get_content(&kab, "file0.bin");
+    get_content(&kab, "file3.bin");
+    assert!(!cache.contains(Path::new("file1.bin")), "Least recently used asset should be evicted!");
+    assert!(cache.contains(Path::new("file0.bin")) && cache.contains(Path::new("file2.bin")) && cache.contains(Path::new("file3.bin")), "Recently used assets should be cached!");
+    assert!(cache.get_statistics().evictions == 1, "1 eviction expected!");
+
+    // V2 | Pinned assets are never evicted.
+    assert!(cache.pin(Path::new("file2.bin")), "Asset should be pinned!");
+    assert!(cache.pin(Path::new("file9.bin")), "Asset not cached should be pinned!");
+    for i in 4..9 {
+        get_content(&kab, &("file".to_owned() + i.to_string().as_str() + ".bin"));
+    }
+    get_content(&kab, "file9.bin");
+    assert!(cache.contains(Path::new("file2.bin")) && cache.contains(Path::new("file9.bin")), "Pinned assets should be cached!");
+    assert!(cache.get_statistics().size <= 300, "Cache size should respect budget!");
+
+    // V3 | KAssetCache::unpin() makes asset evictable.
+    assert!(cache.unpin(Path::new("file2.bin")), "Asset should be unpinned!");
+    assert!(!cache.is_pinned(Path::new("file2.bin")), "Asset shouldn't be pinned!");
+    get_content(&kab, "file0.bin");
+    get_content(&kab, "file1.bin");
+    assert!(!cache.contains(Path::new("file2.bin")), "Unpinned asset should be evicted!");
+
+    // V4 | KAssetCache::set_budget() evicts assets exceeding new budget.
+    cache.set_budget(100);
+    assert!(cache.get_statistics().entries == 1 && cache.contains(Path::new("file9.bin")), "Only pinned asset should stay!");
+
+    // V5 | Least recently used assets behind pinned assets are evicted in order.
+    let (kab, _kasm, cache) = create_broker(500);
+    for i in 0..5 {
+        get_content(&kab, &("file".to_owned() + i.to_string().as_str() + ".bin"));
+    }
+    cache.pin(Path::new("file0.bin"));
+    cache.pin(Path::new("file1.bin"));
+    cache.set_budget(300);
+    assert!(!cache.contains(Path::new("file2.bin")) && !cache.contains(Path::new("file3.bin")), "Least recently used assets should be evicted!");
+    assert!(cache.contains(Path::new("file0.bin")) && cache.contains(Path::new("file1.bin")) && cache.contains(Path::new("file4.bin")), "Pinned and recent assets should stay!");
+    assert!(cache.get_statistics().evictions == 2, "2 evictions expected!");
+}
+
+#[test]
+/// Invalidate cached assets.
+///
+/// # Verification(s)
+/// V1 | Cached asset is served even if source changed.
+/// V2 | KAssetCache::invalidate() removes asset so source content is served.
+/// V3 | KAssetBroker::remove_source() invalidates assets of source.
+/// V4 | Asset cached from a lower priority source isn't served once overridden.
+/// V5 | KAssetCache::clear() removes all assets.
+fn kasset_cache_invalidate() {
+    let (kab, kasm, cache) = create_broker(1000);
+    get_content(&kab, "file0.bin");
+
+    // V1 | Cached asset is served even if source changed.
+    kasm.add_asset(PathBuf::from("file0.bin"), vec![42; 10]).unwrap();
+    assert!(get_content(&kab, "file0.bin") == vec![0; 100], "Cached content expected!");
+
+    // V2 | KAssetCache::invalidate() removes asset so source content is served.
+    assert!(cache.invalidate(Path::new("file0.bin")) == 1, "1 asset should be invalidated!");
+    assert!(get_content(&kab, "file0.bin") == vec![42; 10], "Source content expected!");
+
+    // V3 | KAssetBroker::remove_source() invalidates assets of source.
+    let kasm_mod = Arc::new(KAssetSourceMemory::new("mod"));
+    kasm_mod.add_asset(PathBuf::from("file1.bin"), vec![7; 10]).unwrap();
+    let mod_id = kab.add_source(kasm_mod.clone()).unwrap();
+    kab.set_source_priority(mod_id, 0).unwrap();
+    assert!(get_content(&kab, "file1.bin") == vec![7; 10], "Mod content expected!");
+    kab.remove_source(mod_id).unwrap();
+    assert!(get_content(&kab, "file1.bin") == vec![1; 100], "Base content expected!");
+
+    // V4 | Asset cached from a lower priority source isn't served once overridden.
+    let mod_id = kab.add_source(kasm_mod).unwrap();
+    kab.set_source_priority(mod_id, 0).unwrap();
+    assert!(get_content(&kab, "file1.bin") == vec![7; 10], "Mod content expected!");
+
+    // V5 | KAssetCache::clear() removes all assets.
+    cache.clear();
+    assert!(cache.get_statistics().entries == 0 && cache.get_statistics().size == 0, "Cache should be empty!");
+}
+
+#[test]
+/// Write [KAssetCacheStatistics] to [KJournal].
+///
+/// # Verification(s)
+/// V1 | KAssetCache::write_statistics() writes an entry with statistics.
+fn kasset_cache_journal() {
+    let (kab, _kasm, cache) = create_broker(1000);
+    get_content(&kab, "file0.bin");
+    get_content(&kab, "file0.bin");
+
+    // V1 | KAssetCache::write_statistics() writes an entry with statistics.
+    let mut journal = KJournal::new("cache", KJournalEntrySeverity::ALL_WITH_DEBUG, KJOURNAL_BUFFER_MIN).unwrap();
+    cache.write_statistics(&mut journal, KJournalEntrySeverity::DEBUG);
+
+    let entry = journal.read().expect("Journal should have an entry!");
+    assert!(entry.get_severity() == KJournalEntrySeverity::DEBUG, "Wrong severity!");
+    assert!(entry.get_description() == "Asset cache : 1 hits, 1 misses, 0 evictions, 1 entries, 100/1000 bytes", "Wrong description {}!", entry.get_description());
+}
@@ -0,0 +1,248 @@
+use std::{path::PathBuf, io::Read, sync::{Arc, Mutex}};
+use olympus_kleio::asset::{KAssetSource, KAssetSourceMemory, KAssetSourceFolder, KAssetBroker, KAssetCache, KAssetChange, KAssetChangeKind, KAssetChangeEvent, KAssetPath};
+
+#[cfg(target_os = "linux")]
+use std::fs;
+
+#[cfg(not(target_os = "linux"))]
+use olympus_kleio::asset::KAssetSourceFolderError;
+
+/// Root path of test folder
+static TEST_FOLDER: &str = "../target/tests/kleio/asset/";
+
+/// Memory source reporting changes pushed by test.
+struct ChangingSource {
+    // Assets of source
+    memory : KAssetSourceMemory,
+
+    // Changes returned by next poll
+    changes : Mutex<Vec<KAssetChange>>,
+}
+
+impl ChangingSource {
+    /// Create source with assets from paths and content.
+    fn new(assets : &[(&str, &str)]) -> ChangingSource {
+        let memory = KAssetSourceMemory::new("changing");
+        for (path, content) in assets {
+            memory.add_asset(PathBuf::from(path), content.as_bytes().to_vec()).unwrap();
+        }
+        ChangingSource { memory, changes : Mutex::new(Vec::new()) }
+    }
+
+    /// Modify asset and report change.
+    fn modify(&self, path : &str, content : &str) {
+        self.memory.add_asset(PathBuf::from(path), content.as_bytes().to_vec()).unwrap();
+        self.changes.lock().unwrap().push(KAssetChange { path : KAssetPath::new(path).unwrap(), kind : KAssetChangeKind::Modified });
+    }
+}
+
+impl KAssetSource for ChangingSource {
+    fn has_asset(&self, path: &KAssetPath) -> bool {
+        self.memory.has_asset(path)
+    }
+
+    fn get_asset(&self, path: &KAssetPath) -> Result<Box<dyn Read>, std::io::Error> {
+        self.memory.get_asset(path)
+    }
+
+    fn poll_changes(&self) -> Vec<KAssetChange> {
+        std::mem::take(&mut *self.changes.lock().unwrap())
+    }
+}
+
+#[test]
+/// Aggregate changes of sources in [KAssetBroker].
+///
+/// # Verification(s)
+/// V1 | KAssetBroker::poll_changes() returns changes of all sources with source id.
+/// V2 | Change is visible only if no source of higher priority has the asset.
+/// V3 | Changed assets are invalidated from cache.
+/// V4 | Changes are returned once.
+fn kasset_change_broker() {
+    let high = Arc::new(ChangingSource::new(&[("shared.txt", "High shared")]));
+    let low = Arc::new(ChangingSource::new(&[("shared.txt", "Low shared"), ("low.txt", "Low")]));
+
+    let kab = KAssetBroker::new();
+    let high_id = kab.add_source(high.clone()).unwrap();
+    let low_id = kab.add_source(low.clone()).unwrap();
+    kab.set_cache(Some(Arc::new(KAssetCache::new(1024))));
+
+    let mut content = String::new();
+    kab.get_asset(PathBuf::from("low.txt")).unwrap().read_to_string(&mut content).unwrap();
+    assert!(content == "Low", "Content is different that expected!");
+
+    high.modify("shared.txt", "High shared 2");
+    low.modify("shared.txt", "Low shared 2");
+    low.modify("low.txt", "Low 2");
+
+    // V1 | KAssetBroker::poll_changes() returns changes of all sources with source id.
+    // V2 | Change is visible only if no source of higher priority has the asset.
+    let events = kab.poll_changes();
+    assert!(events == vec![
+        KAssetChangeEvent { source : high_id, path : KAssetPath::new("shared.txt").unwrap(), kind : KAssetChangeKind::Modified, visible : true },
+        KAssetChangeEvent { source : low_id, path : KAssetPath::new("shared.txt").unwrap(), kind : KAssetChangeKind::Modified, visible : false },
+        KAssetChangeEvent { source : low_id, path : KAssetPath::new("low.txt").unwrap(), kind : KAssetChangeKind::Modified, visible : true },
+    ], "Wrong events {:?}!", events);
+
+    // V3 | Changed assets are invalidated from cache.
+    let mut content = String::new();
+    kab.get_asset(PathBuf::from("low.txt")).unwrap().read_to_string(&mut content).unwrap();
+    assert!(content == "Low 2", "Changed content expected!");
+
+    // V4 | Changes are returned once.
+    assert!(kab.poll_changes().is_empty(), "Changes should be returned once!");
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+/// Watch [KAssetSourceFolder] with inotify.
+///
+/// # Verification(s)
+/// V1 | KAssetSourceFolder::watch() watches folder.
+/// V2 | Created, modified and deleted files are reported with path relative to folder.
+/// V3 | Files of sub folders created after watch are reported.
+/// V4 | Changes of same file are merged.
+/// V5 | KAssetSourceFolder::unwatch() stops reporting changes.
+fn kasset_change_folder_watch() {
+    let folder_name: &str = &(TEST_FOLDER.to_owned() + "kasset_change_folder_watch/");
+    fs::create_dir_all(folder_name.to_owned() + "sub").unwrap();
+    fs::write(folder_name.to_owned() + "modified.txt", "Hello").unwrap();
+    fs::write(folder_name.to_owned() + "sub/deleted.txt", "Hello").unwrap();
+
+    // V1 | KAssetSourceFolder::watch() watches folder.
+    let kasf = KAssetSourceFolder::new(PathBuf::from(folder_name)).unwrap();
+    assert!(kasf.poll_changes().is_empty(), "Unwatched folder shouldn't report changes!");
+    kasf.watch().expect("Folder should be watched!");
+    assert!(kasf.is_watched(), "Folder should be watched!");
+
+    // V2 | Created, modified and deleted files are reported with path relative to folder.
+    fs::write(folder_name.to_owned() + "created.txt", "Hello").unwrap();
+    fs::write(folder_name.to_owned() + "modified.txt", "Hello, world!").unwrap();
+    fs::remove_file(folder_name.to_owned() + "sub/deleted.txt").unwrap();
+    let changes = kasf.poll_changes();
+    assert!(changes == vec![
+        KAssetChange { path : KAssetPath::new("created.txt").unwrap(), kind : KAssetChangeKind::Created },
+        KAssetChange { path : KAssetPath::new("modified.txt").unwrap(), kind : KAssetChangeKind::Modified },
+        KAssetChange { path : KAssetPath::new("sub/deleted.txt").unwrap(), kind : KAssetChangeKind::Deleted },
+    ], "Wrong changes {:?}!", changes);
+
+    // V3 | Files of sub folders created after watch are reported.
+    fs::create_dir_all(folder_name.to_owned() + "new/deep").unwrap();
+    fs::write(folder_name.to_owned() + "new/deep/file.txt", "Hello").unwrap();
+    assert!(kasf.poll_changes() == vec![KAssetChange { path : KAssetPath::new("new/deep/file.txt").unwrap(), kind : KAssetChangeKind::Created }], "New file should be reported!");
+    fs::write(folder_name.to_owned() + "new/deep/file.txt", "Hello, world!").unwrap();
+    assert!(kasf.poll_changes() == vec![KAssetChange { path : KAssetPath::new("new/deep/file.txt").unwrap(), kind : KAssetChangeKind::Modified }], "New folder should be watched!");
+
+    // V4 | Changes of same file are merged.
+    fs::write(folder_name.to_owned() + "temp.txt", "Hello").unwrap();
+    fs::remove_file(folder_name.to_owned() + "temp.txt").unwrap();
+    fs::write(folder_name.to_owned() + "created.txt", "Hello, world!").unwrap();
+    fs::write(folder_name.to_owned() + "created.txt", "Hello, world!!").unwrap();
+    assert!(kasf.poll_changes() == vec![KAssetChange { path : KAssetPath::new("created.txt").unwrap(), kind : KAssetChangeKind::Modified }], "Changes should be merged!");
+
+    // V5 | KAssetSourceFolder::unwatch() stops reporting changes.
+    kasf.unwatch();
+    fs::write(folder_name.to_owned() + "created.txt", "Hello").unwrap();
+    assert!(kasf.poll_changes().is_empty(), "Unwatched folder shouldn't report changes!");
+
+    fs::remove_dir_all(PathBuf::from(folder_name)).expect("Test couldn't be cleaned!");
+}
+
+#[test]
+#[cfg(not(target_os = "linux"))]
+/// Verify that watching [KAssetSourceFolder] is unsupported outside Linux.
+///
+/// # Verification(s)
+/// V1 | KAssetSourceFolder::watch() must return Err(KAssetSourceFolderError::WatchUnsupported).
+fn kasset_change_folder_watch_unsupported() {
+    let folder_name: &str = &(TEST_FOLDER.to_owned() + "kasset_change_folder_watch_unsupported/");
+    std::fs::create_dir_all(folder_name).unwrap();
+
+    // V1 | KAssetSourceFolder::watch() must return Err(KAssetSourceFolderError::WatchUnsupported).
+    let kasf = KAssetSourceFolder::new(PathBuf::from(folder_name)).unwrap();
+    assert!(matches!(kasf.watch(), Err(KAssetSourceFolderError::WatchUnsupported)), "Watch should be unsupported!");
+    assert!(!kasf.is_watched(), "Folder shouldn't be watched!");
+
+    std::fs::remove_dir_all(PathBuf::from(folder_name)).expect("Test couldn't be cleaned!");
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+/// Scan watched [KAssetSourceFolder] again when inotify queue overflows.
+///
+/// # Verification(s)
+/// V1 | Files found after overflow are reported as modified, or created if unknown.
+/// V2 | Files deleted while events were lost are reported as deleted.
+fn kasset_change_folder_watch_overflow() {
+    // Overflow needs more events than inotify queue holds
+    let max_events : usize = match fs::read_to_string("/proc/sys/fs/inotify/max_queued_events").map(|max| max.trim().parse()) {
+        Ok(Ok(max)) if max <= 100_000 => max,
+        _ => return,
+    };
+
+    let folder_name: &str = &(TEST_FOLDER.to_owned() + "kasset_change_folder_watch_overflow/");
+    let _ = fs::remove_dir_all(folder_name);
+    fs::create_dir_all(folder_name).unwrap();
+    fs::write(folder_name.to_owned() + "kept.txt", "Kept").unwrap();
+    fs::write(folder_name.to_owned() + "deleted.txt", "Deleted").unwrap();
+
+    let kasf = KAssetSourceFolder::new(PathBuf::from(folder_name)).unwrap();
+    kasf.watch().unwrap();
+
+    // Alternate files since identical consecutive events are merged by inotify
+    for i in 0..=max_events {
+        fs::write(folder_name.to_owned() + if i % 2 == 0 { "spam0.txt" } else { "spam1.txt" }, i.to_string()).unwrap();
+    }
+    fs::remove_file(folder_name.to_owned() + "deleted.txt").unwrap();
+    let changes = kasf.poll_changes();
+
+    // V1 | Files found after overflow are reported as modified, or created if unknown.
+    let change = |path : &str, kind : KAssetChangeKind| KAssetChange { path : KAssetPath::new(path).unwrap(), kind };
+    assert!(changes.contains(&change("kept.txt", KAssetChangeKind::Modified)), "Kept file should be modified {:?}!", changes);
+    assert!(changes.contains(&change("spam0.txt", KAssetChangeKind::Created)) && changes.contains(&change("spam1.txt", KAssetChangeKind::Created)),
+        "New files should be created {:?}!", changes);
+
+    // V2 | Files deleted while events were lost are reported as deleted.
+    assert!(changes.contains(&change("deleted.txt", KAssetChangeKind::Deleted)) && changes.len() == 4, "Deleted file should be deleted {:?}!", changes);
+
+    fs::remove_dir_all(PathBuf::from(folder_name)).expect("Test couldn't be cleaned!");
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+/// Report visibility of [KAssetSourceFolder] changes in [KAssetBroker].
+///
+/// # Verification(s)
+/// V1 | Deleting asset of highest priority source is visible.
+/// V2 | Creating asset hidden by higher priority source isn't visible.
+fn kasset_change_folder_broker() {
+    let folder_name: &str = &(TEST_FOLDER.to_owned() + "kasset_change_folder_broker/");
+    fs::create_dir_all(folder_name.to_owned() + "mod").unwrap();
+    fs::create_dir_all(folder_name.to_owned() + "base").unwrap();
+    fs::write(folder_name.to_owned() + "mod/shared.txt", "Mod").unwrap();
+    fs::write(folder_name.to_owned() + "base/shared.txt", "Base").unwrap();
+
+    let kasf_mod = KAssetSourceFolder::new(PathBuf::from(folder_name.to_owned() + "mod")).unwrap();
+    let kasf_base = KAssetSourceFolder::new(PathBuf::from(folder_name.to_owned() + "base")).unwrap();
+    kasf_mod.watch().unwrap();
+    kasf_base.watch().unwrap();
+
+    let kab = KAssetBroker::new();
+    let mod_id = kab.add_source(Arc::new(kasf_mod)).unwrap();
+    let base_id = kab.add_source(Arc::new(kasf_base)).unwrap();
+
+    // V1 | Deleting asset of highest priority source is visible.
+    fs::remove_file(folder_name.to_owned() + "mod/shared.txt").unwrap();
+    assert!(kab.poll_changes() == vec![KAssetChangeEvent { source : mod_id, path : KAssetPath::new("shared.txt").unwrap(), kind : KAssetChangeKind::Deleted, visible : true }], "Deletion should be visible!");
+
+    // V2 | Creating asset hidden by higher priority source isn't visible.
+    fs::write(folder_name.to_owned() + "mod/hidden.txt", "Mod").unwrap();
+    fs::write(folder_name.to_owned() + "base/hidden.txt", "Base").unwrap();
+    let events = kab.poll_changes();
+    assert!(events == vec![
+        KAssetChangeEvent { source : mod_id, path : KAssetPath::new("hidden.txt").unwrap(), kind : KAssetChangeKind::Created, visible : true },
+        KAssetChangeEvent { source : base_id, path : KAssetPath::new("hidden.txt").unwrap(), kind : KAssetChangeKind::Created, visible : false },
+    ], "Wrong events {:?}!", events);
+
+    fs::remove_dir_all(PathBuf::from(folder_name)).expect("Test couldn't be cleaned!");
+}
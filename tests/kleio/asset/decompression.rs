@@ -0,0 +1,156 @@
+use std::{io::{Read, Seek, SeekFrom, ErrorKind}, sync::Arc};
+use olympus_kleio::asset::{KAssetBroker, KAssetSourceMemory, KAssetCache, KAssetPath, KAssetDecompression, KAssetCompression, KDecompressionError};
+
+/// "Hello, world!" compressed with gzip.
+static HELLO_GZIP : [u8; 33] = [0x1F, 0x8B, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF, 0xF3, 0x48, 0xCD, 0xC9, 0xC9, 0xD7, 0x51, 0x28,
+    0xCF, 0x2F, 0xCA, 0x49, 0x51, 0x04, 0x00, 0xE6, 0xC6, 0xE6, 0xEB, 0x0D, 0x00, 0x00, 0x00];
+
+/// Items of [create_items] compressed with Zstandard, using Huffman literals and FSE sequences.
+static ITEMS_ZSTD : [u8; 170] = [0x28, 0xB5, 0x2F, 0xFD, 0x64, 0xE3, 0x03, 0xE5, 0x04, 0x00, 0x52, 0x88, 0x15, 0x12, 0xB0, 0x19, 0x1B, 0xF9,
+    0x9A, 0x05, 0xF0, 0x25, 0x4A, 0x5A, 0xD9, 0xDD, 0xBD, 0xD3, 0x87, 0x31, 0xCE, 0xC5, 0x6B, 0xAB, 0x25, 0x19, 0xEB, 0xD6, 0x5E, 0x7A, 0x3B, 0xC9,
+    0x68, 0x51, 0xEF, 0x3C, 0xB4, 0xCF, 0x8C, 0x5C, 0xF5, 0xF5, 0xA9, 0x74, 0xAD, 0x99, 0x84, 0x78, 0xB7, 0x5D, 0xD9, 0x37, 0x93, 0xE9, 0xB5, 0xAB,
+    0x4F, 0x45, 0x5F, 0x1A, 0x15, 0xFE, 0xF6, 0x91, 0xD7, 0xD4, 0x59, 0xB8, 0x77, 0xD3, 0xF2, 0xD3, 0xC0, 0x32, 0x74, 0x14, 0x89, 0x31, 0xC2, 0x14,
+    0xC7, 0x80, 0x4C, 0x40, 0xC2, 0x10, 0xC6, 0x28, 0x01, 0x62, 0xA8, 0x11, 0x80, 0x7B, 0xFF, 0x37, 0x90, 0xAD, 0x2C, 0x07, 0x10, 0x14, 0x21, 0xD2,
+    0xA2, 0x67, 0x0D, 0x4B, 0x09, 0xA0, 0x2A, 0x08, 0x17, 0x38, 0x0D, 0x7F, 0xA9, 0x19, 0xA9, 0xA4, 0xB1, 0x4D, 0xED, 0xD8, 0x0B, 0xBE, 0x8B, 0x50,
+    0x9C, 0x36, 0xCA, 0x1D, 0x6C, 0xBF, 0x4C, 0xE9, 0xAD, 0x94, 0x04, 0xF3, 0xD6, 0xDB, 0x2E, 0x25, 0x6B, 0x2C, 0x10, 0x36, 0x3F, 0xF9, 0xAF, 0x99,
+    0xC0, 0x82, 0x5D, 0x05, 0x17, 0xE8, 0x6F, 0xB5];
+
+/// 1 000 000 zeros compressed with Zstandard.
+static ZEROS_ZSTD : [u8; 53] = [0x28, 0xB5, 0x2F, 0xFD, 0xA4, 0x40, 0x42, 0x0F, 0x00, 0x4C, 0x00, 0x00, 0x08, 0x00, 0x01, 0x00, 0xFC, 0xFF,
+    0x39, 0x10, 0x02, 0x02, 0x00, 0x10, 0x00, 0x02, 0x00, 0x10, 0x00, 0x02, 0x00, 0x10, 0x00, 0x02, 0x00, 0x10, 0x00, 0x02, 0x00, 0x10, 0x00, 0x02,
+    0x00, 0x10, 0x00, 0x03, 0x12, 0x0A, 0x00, 0xCC, 0xAE, 0xCA, 0x39];
+
+/// Create 50 lines of JSON items compressed in [ITEMS_ZSTD].
+fn create_items() -> Vec<u8> {
+    (0..50).map(|i| format!("{{\"id\":{},\"name\":\"item{}\"}}\n", i, i * 7 % 13)).collect::<String>().into_bytes()
+}
+
+/// Create 100 000 zeros compressed with gzip.
+fn create_zeros_gzip() -> Vec<u8> {
+    let mut gzip = vec![0x1F, 0x8B, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0xED, 0xC1, 0x31, 0x01, 0x00, 0x00, 0x00, 0xC2, 0xA0,
+        0xF5, 0x4F, 0x6D, 0x0D, 0x0F, 0xA0];
+    gzip.extend_from_slice(&[0x00; 96]);
+    gzip.extend_from_slice(&[0x80, 0x57, 0x03, 0x7D, 0x95, 0x11, 0xD4, 0xA0, 0x86, 0x01, 0x00]);
+    gzip
+}
+
+/// Read asset of broker to its end.
+fn read_asset(kab : &KAssetBroker, path : &str) -> Result<Vec<u8>, std::io::Error> {
+    let mut data = Vec::new();
+    kab.get_asset(path)?.read_to_end(&mut data)?;
+    Ok(data)
+}
+
+#[test]
+/// Decompress gzip and Zstandard data with [KAssetDecompression].
+///
+/// # Verification(s)
+/// V1 | KAssetCompression::from_path() gives format of paths ending with extension of format.
+/// V2 | KAssetDecompression::decompress() decompresses gzip and Zstandard data.
+/// V3 | KAssetDecompression::decompress() returns Err(KDecompressionError::Corrupted) for corrupted or truncated data.
+/// V4 | KAssetDecompression::decompress() returns Err() of the limit exceeded by decompressed data.
+fn kasset_decompression_formats() {
+    let decompression = KAssetDecompression::default();
+
+    // V1 | KAssetCompression::from_path() gives format of paths ending with extension of format.
+    for (path, format) in [("a.json.gz", Some(KAssetCompression::Gzip)), ("data/a.zst", Some(KAssetCompression::Zstd)), ("a.json", None), ("gz", None)] {
+        assert!(KAssetCompression::from_path(&KAssetPath::new(path).unwrap()) == format, "Wrong format of {}!", path);
+    }
+    assert!(KAssetCompression::Zstd.get_extension() == "zst", "Wrong extension!");
+
+    // V2 | KAssetDecompression::decompress() decompresses gzip and Zstandard data.
+    assert!(decompression.decompress(&HELLO_GZIP, KAssetCompression::Gzip).unwrap() == b"Hello, world!", "Gzip not decompressed!");
+    assert!(decompression.decompress(&ITEMS_ZSTD, KAssetCompression::Zstd).unwrap() == create_items(), "Zstandard not decompressed!");
+
+    // V3 | KAssetDecompression::decompress() returns Err(KDecompressionError::Corrupted) for corrupted or truncated data.
+    let mut corrupted = ITEMS_ZSTD;
+    corrupted[100] ^= 0x10;
+    assert!(decompression.decompress(&corrupted, KAssetCompression::Zstd) == Err(KDecompressionError::Corrupted), "Corrupted data decompressed!");
+    assert!(decompression.decompress(&HELLO_GZIP[..30], KAssetCompression::Gzip) == Err(KDecompressionError::Corrupted), "Truncated data decompressed!");
+    assert!(decompression.decompress(&HELLO_GZIP, KAssetCompression::Zstd) == Err(KDecompressionError::Corrupted), "Wrong format decompressed!");
+
+    // V4 | KAssetDecompression::decompress() returns Err() of the limit exceeded by decompressed data.
+    let zeros = create_zeros_gzip();
+    assert!(decompression.decompress(&zeros, KAssetCompression::Gzip) == Err(KDecompressionError::RatioLimitExceeded), "Ratio limit not applied!");
+    let decompression = KAssetDecompression { max_size : 50_000, max_ratio : 1000 };
+    assert!(decompression.decompress(&zeros, KAssetCompression::Gzip) == Err(KDecompressionError::SizeLimitExceeded), "Size limit not applied!");
+    assert!(decompression.decompress(&ZEROS_ZSTD, KAssetCompression::Zstd) == Err(KDecompressionError::SizeLimitExceeded), "Size limit not applied!");
+    let decompression = KAssetDecompression { max_size : 1_000_000, max_ratio : 20_000 };
+    assert!(decompression.decompress(&zeros, KAssetCompression::Gzip).unwrap() == vec![0; 100_000], "Zeros not decompressed!");
+    assert!(decompression.decompress(&ZEROS_ZSTD, KAssetCompression::Zstd).unwrap() == vec![0; 1_000_000], "Zeros not decompressed!");
+}
+
+#[test]
+/// Resolve assets stored compressed with [KAssetBroker::set_decompression].
+///
+/// # Verification(s)
+/// V1 | KAssetBroker::get_asset() doesn't resolve compressed assets without decompression.
+/// V2 | KAssetBroker::get_asset() decompresses `path.gz` and `path.zst` when `path` is absent, preferring `path`.
+/// V3 | KAssetBroker::get_asset_seekable(), get_asset_range() and get_asset_metadata() resolve compressed assets.
+/// V4 | Compressed asset of a source of higher priority overrides asset of a source of lower priority.
+/// V5 | Decompressed assets are cached under their path.
+/// V6 | KAssetBroker::get_asset() returns Err() wrapping KDecompressionError for zip bombs.
+/// V7 | Compressed assets larger than max_size are rejected before being decompressed.
+fn kasset_decompression_broker() {
+    let base = KAssetSourceMemory::new("base");
+    base.add_asset("data/items.json.zst", ITEMS_ZSTD.to_vec()).unwrap();
+    base.add_asset("text.txt", b"Plain".to_vec()).unwrap();
+    base.add_asset("text.txt.gz", HELLO_GZIP.to_vec()).unwrap();
+    base.add_asset("bomb.bin.zst", ZEROS_ZSTD.to_vec()).unwrap();
+    base.add_asset("junk.bin.gz", vec![0xFF; 4096]).unwrap();
+
+    let kab = KAssetBroker::new();
+    kab.add_source(Arc::new(base)).unwrap();
+
+    // V1 | KAssetBroker::get_asset() doesn't resolve compressed assets without decompression.
+    assert!(kab.get_decompression().is_none(), "Decompression should be disabled by default!");
+    assert!(read_asset(&kab, "data/items.json").unwrap_err().kind() == ErrorKind::NotFound, "Compressed asset resolved!");
+
+    // V2 | KAssetBroker::get_asset() decompresses `path.gz` and `path.zst` when `path` is absent, preferring `path`.
+    kab.set_decompression(Some(KAssetDecompression::default()));
+    assert!(read_asset(&kab, "data/items.json").unwrap() == create_items(), "Compressed asset not resolved!");
+    assert!(read_asset(&kab, "text.txt").unwrap() == b"Plain", "Asset should be preferred over compressed asset!");
+    assert!(read_asset(&kab, "data/items.json.zst").unwrap() == ITEMS_ZSTD, "Compressed asset should be readable as is!");
+
+    // V3 | KAssetBroker::get_asset_seekable(), get_asset_range() and get_asset_metadata() resolve compressed assets.
+    let mut reader = kab.get_asset_seekable("data/items.json").unwrap();
+    assert!(reader.get_length() == create_items().len() as u64, "Wrong length!");
+    let mut line = String::new();
+    reader.seek(SeekFrom::Start(24)).unwrap();
+    reader.by_ref().take(19).read_to_string(&mut line).unwrap();
+    assert!(line == "{\"id\":1,\"name\":\"ite", "Wrong data {}!", line);
+    let mut range = String::new();
+    kab.get_asset_range("data/items.json", 6, 1).unwrap().read_to_string(&mut range).unwrap();
+    assert!(range == "0", "Wrong range {}!", range);
+    let metadata = kab.get_asset_metadata("data/items.json").unwrap();
+    assert!(metadata.size == create_items().len() as u64 && metadata.hash.is_none(), "Wrong metadata {:?}!", metadata);
+
+    // V4 | Compressed asset of a source of higher priority overrides asset of a source of lower priority.
+    let patch = KAssetSourceMemory::new("patch");
+    patch.add_asset("text.txt.gz", HELLO_GZIP.to_vec()).unwrap();
+    let id = kab.add_source(Arc::new(patch)).unwrap();
+    kab.set_source_priority(id, 0).unwrap();
+    assert!(read_asset(&kab, "text.txt").unwrap() == b"Hello, world!", "Compressed asset should override asset!");
+
+    // V5 | Decompressed assets are cached under their path.
+    let cache = Arc::new(KAssetCache::new(1024 * 1024));
+    kab.set_cache(Some(cache.clone()));
+    read_asset(&kab, "data/items.json").unwrap();
+    assert!(cache.contains("data/items.json") && !cache.contains("data/items.json.zst"), "Decompressed asset not cached!");
+    kab.set_decompression(Some(KAssetDecompression::default()));
+    assert!(!cache.contains("data/items.json"), "Cache should be cleared!");
+
+    // V6 | KAssetBroker::get_asset() returns Err() wrapping KDecompressionError for zip bombs.
+    let err = read_asset(&kab, "bomb.bin").unwrap_err();
+    assert!(err.kind() == ErrorKind::InvalidData && KDecompressionError::from_io_error(&err) == Some(KDecompressionError::RatioLimitExceeded), "Zip bomb decompressed!");
+    kab.set_decompression(Some(KAssetDecompression { max_size : 1024, max_ratio : u64::MAX }));
+    let err = kab.get_asset_metadata("bomb.bin").unwrap_err();
+    assert!(KDecompressionError::from_io_error(&err) == Some(KDecompressionError::SizeLimitExceeded), "Zip bomb decompressed!");
+
+    // V7 | Compressed assets larger than max_size are rejected before being decompressed.
+    let err = read_asset(&kab, "junk.bin").unwrap_err();
+    assert!(KDecompressionError::from_io_error(&err) == Some(KDecompressionError::SizeLimitExceeded), "Compressed asset over limit decompressed!");
+    kab.set_decompression(Some(KAssetDecompression::default()));
+    let err = read_asset(&kab, "junk.bin").unwrap_err();
+    assert!(KDecompressionError::from_io_error(&err) == Some(KDecompressionError::Corrupted), "Junk decompressed!");
+}
@@ -0,0 +1,162 @@
+use std::{path::PathBuf, fs, io::{Read, Seek, SeekFrom}, sync::Arc};
+use olympus_kleio::asset::{KAssetBroker, KAssetSourceFolder, KAssetSourceMemory, KAssetSourcePak, KAssetPath, KAssetCache, KPakWriter,
+    KIntegrityManifest, KIntegrityManifestError, KIntegrityError, KINTEGRITY_MANIFEST_PATH};
+use super::create_memory_source;
+
+/// Root path of test folder
+static TEST_FOLDER: &str = "../target/tests/kleio/asset/";
+
+/// Read asset of broker to its end, returning [KIntegrityError] wrapped by error, if any.
+fn read_integrity(kab : &KAssetBroker, path : &str) -> Result<Vec<u8>, Option<KIntegrityError>> {
+    let mut data = Vec::new();
+    kab.get_asset(path).and_then(|mut reader| reader.read_to_end(&mut data)).map_err(|err| KIntegrityError::from_io_error(&err))?;
+    Ok(data)
+}
+
+#[test]
+/// Generate, write and parse [KIntegrityManifest].
+///
+/// # Verification(s)
+/// V1 | KIntegrityManifest::generate() lists size and SHA-256 of all assets, ignoring manifest.
+/// V2 | KIntegrityManifest is written as sorted `hash size path` lines parsed back by KIntegrityManifest::parse().
+/// V3 | KIntegrityManifest::parse() must return Err() for invalid manifests.
+/// V4 | KIntegrityManifest::from_source() reads manifest of source.
+fn kintegrity_manifest() {
+    let long : Vec<u8> = (0..1000).map(|i| (i % 251) as u8).collect();
+    let source = create_memory_source(&[("abc.txt", b"abc"), ("empty.txt", b""), ("data/long bin.dat", &long),
+        ("a55.txt", &[b'a'; 55]), ("a56.txt", &[b'a'; 56]), ("a64.txt", &[b'a'; 64]), (KINTEGRITY_MANIFEST_PATH, b"# Old")]);
+
+    // V1 | KIntegrityManifest::generate() lists size and SHA-256 of all assets, ignoring manifest.
+    let manifest = KIntegrityManifest::generate(source.as_ref()).unwrap();
+    assert!(manifest.get_entry_count() == 6 && manifest.get_entry(&KAssetPath::new(KINTEGRITY_MANIFEST_PATH).unwrap()).is_none(), "Manifest shouldn't list itself!");
+    let entry = manifest.get_entry(&KAssetPath::new("data/long bin.dat").unwrap()).unwrap();
+    assert!(entry.size == 1000, "Wrong size!");
+
+    // V2 | KIntegrityManifest is written as sorted `hash size path` lines parsed back by KIntegrityManifest::parse().
+    let text = manifest.to_string();
+    let lines : Vec<&str> = text.lines().collect();
+    assert!(lines == vec!["# Kleio integrity manifest",
+        "9f4390f8d30c2dd92ec9f095b65e2b9ae9b0a925a5258e241c9f1e910f734318 55 a55.txt",
+        "b35439a4ac6f0948b6d6f9e3c6af0f5f590ce20f1bde7090ef7970686ec6738a 56 a56.txt",
+        "ffe054fe7ae0cb6dc65c3af9b61d5209f439851db43d0ba5997337df154668eb 64 a64.txt",
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad 3 abc.txt",
+        "4e4c294b331f7a2099a379bec34b9f9fc03dc46ab465d998f4d683da53487e6d 1000 data/long bin.dat",
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855 0 empty.txt"], "Wrong manifest {}!", text);
+    assert!(KIntegrityManifest::parse(&text).unwrap() == manifest, "Manifest parsed should be the same!");
+
+    // V3 | KIntegrityManifest::parse() must return Err() for invalid manifests.
+    let hash = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+    for (content, expected) in [(format!("{} 0", hash), KIntegrityManifestError::InvalidLine), (format!("{} -1 a.txt", hash), KIntegrityManifestError::InvalidLine),
+        (format!("{} 0 a.txt", &hash[1..]), KIntegrityManifestError::InvalidLine), (format!("{}g 0 a.txt", &hash[1..]), KIntegrityManifestError::InvalidLine),
+        (format!("{} 0 ../a.txt", hash), KIntegrityManifestError::InvalidPath), (format!("{} 0 a.txt\n{} 0 A/../a.txt", hash, hash), KIntegrityManifestError::InvalidPath)] {
+        assert!(KIntegrityManifest::parse(&content) == Err(expected), "Manifest {:?} should be {:?}!", content, expected);
+    }
+
+    // V4 | KIntegrityManifest::from_source() reads manifest of source.
+    source.add_asset(KINTEGRITY_MANIFEST_PATH, text.into_bytes()).unwrap();
+    assert!(KIntegrityManifest::from_source(source.as_ref()).unwrap() == manifest, "Manifest of source should be read!");
+    assert!(KIntegrityManifest::from_source(&KAssetSourceMemory::new("empty")) == Err(KIntegrityManifestError::ManifestNotFound), "Manifest shouldn't be found!");
+}
+
+#[test]
+/// Verify a whole [KAssetSourceFolder] and [KAssetSourcePak] with [KIntegrityManifest::verify].
+///
+/// # Verification(s)
+/// V1 | Folder and pack created from it are valid against manifest of folder written in folder.
+/// V2 | Modified, truncated, missing and unlisted assets are reported with distinct errors.
+/// V3 | KIntegrityManifest::verify_asset() verifies a single asset.
+fn kintegrity_verify_source() {
+    // Test folder name
+    let folder_name: &str = &(TEST_FOLDER.to_owned() + "kintegrity_verify/");
+    fs::create_dir_all(folder_name.to_owned() + "patch/textures").unwrap();
+    fs::write(folder_name.to_owned() + "patch/textures/wall.png", "wall").unwrap();
+    fs::write(folder_name.to_owned() + "patch/textures/floor.png", "floor").unwrap();
+    fs::write(folder_name.to_owned() + "patch/config.txt", "speed = 1").unwrap();
+
+    // V1 | Folder and pack created from it are valid against manifest of folder written in folder.
+    let kasf = KAssetSourceFolder::new(PathBuf::from(folder_name.to_owned() + "patch")).unwrap();
+    let manifest = KIntegrityManifest::generate(&kasf).unwrap();
+    fs::write(folder_name.to_owned() + "patch/" + KINTEGRITY_MANIFEST_PATH, manifest.to_string()).unwrap();
+    let report = manifest.verify(&kasf);
+    assert!(report.is_valid() && report.verified == 3, "Folder should be valid {:?}!", report);
+
+    let mut writer = KPakWriter::create(PathBuf::from(folder_name.to_owned() + "patch.kpak")).unwrap();
+    writer.add_folder(PathBuf::from(folder_name.to_owned() + "patch")).unwrap();
+    writer.finish().unwrap();
+    let kasp = KAssetSourcePak::new(PathBuf::from(folder_name.to_owned() + "patch.kpak")).unwrap();
+    assert!(KIntegrityManifest::from_source(&kasp).unwrap().verify(&kasp).is_valid(), "Pack should be valid!");
+
+    // V2 | Modified, truncated, missing and unlisted assets are reported with distinct errors.
+    fs::write(folder_name.to_owned() + "patch/textures/wall.png", "mall").unwrap();
+    fs::write(folder_name.to_owned() + "patch/textures/floor.png", "flo").unwrap();
+    fs::remove_file(folder_name.to_owned() + "patch/config.txt").unwrap();
+    fs::write(folder_name.to_owned() + "patch/cheat.txt", "speed = 9").unwrap();
+    let report = manifest.verify(&kasf);
+    let issues : Vec<(String, KIntegrityError)> = report.issues.iter().map(|(path, err)| (path.to_string(), *err)).collect();
+    assert!(issues == vec![("cheat.txt".to_owned(), KIntegrityError::NotListed), ("config.txt".to_owned(), KIntegrityError::Missing),
+        ("textures/floor.png".to_owned(), KIntegrityError::SizeMismatch), ("textures/wall.png".to_owned(), KIntegrityError::HashMismatch)], "Wrong issues {:?}!", issues);
+    assert!(report.verified == 0 && !report.is_valid(), "No asset should be verified!");
+
+    // V3 | KIntegrityManifest::verify_asset() verifies a single asset.
+    assert!(manifest.verify_asset(&kasp, &KAssetPath::new("textures/wall.png").unwrap()) == Ok(()), "Pack asset should be valid!");
+    assert!(manifest.verify_asset(&kasf, &KAssetPath::new("cheat.txt").unwrap()) == Err(KIntegrityError::NotListed), "Asset shouldn't be listed!");
+
+    // Clean test
+    fs::remove_dir_all(PathBuf::from(folder_name)).expect("Test couldn't be cleaned!");
+}
+
+#[test]
+/// Verify assets when opened through [KAssetBroker].
+///
+/// # Verification(s)
+/// V1 | Intact assets are read from a verified source.
+/// V2 | Corrupted, larger and unlisted assets fail with distinct KIntegrityError when opened, before any byte is served.
+/// V3 | Seekable and range reads are verified.
+/// V4 | Corrupted assets are not cached and setting manifest invalidates cached assets.
+/// V5 | KAssetBroker::set_source_integrity() with None stops verification.
+fn kintegrity_broker() {
+    let source = create_memory_source(&[("config.txt", b"speed = 1"), ("textures/wall.png", b"wall")]);
+    let manifest = Arc::new(KIntegrityManifest::generate(source.as_ref()).unwrap());
+    let kab = KAssetBroker::new();
+    let id = kab.add_source_at(source.clone(), "patch").unwrap();
+    kab.set_source_integrity(id, Some(manifest.clone())).unwrap();
+    assert!(kab.get_source_integrity(id).unwrap() == Some(manifest.clone()), "Manifest should be set!");
+
+    // V1 | Intact assets are read from a verified source.
+    assert!(read_integrity(&kab, "patch/config.txt") == Ok(b"speed = 1".to_vec()), "Config should be intact!");
+
+    // V2 | Corrupted, larger and unlisted assets fail with distinct KIntegrityError when opened, before any byte is served.
+    source.add_asset("config.txt", b"speed = 9".to_vec()).unwrap();
+    assert!(kab.get_asset("patch/config.txt").is_err(), "Corrupted config shouldn't be opened!");
+    assert!(read_integrity(&kab, "patch/config.txt") == Err(Some(KIntegrityError::HashMismatch)), "Config should be corrupted!");
+    source.add_asset("textures/wall.png", b"wall and more".to_vec()).unwrap();
+    assert!(read_integrity(&kab, "patch/textures/wall.png") == Err(Some(KIntegrityError::SizeMismatch)), "Wall should be larger!");
+    source.add_asset("cheat.txt", b"god mode".to_vec()).unwrap();
+    let err = kab.get_asset("patch/cheat.txt").err().unwrap();
+    assert!(KIntegrityError::from_io_error(&err) == Some(KIntegrityError::NotListed), "Cheat shouldn't be listed!");
+
+    // V3 | Seekable and range reads are verified.
+    source.add_asset("textures/wall.png", b"wall".to_vec()).unwrap();
+    let mut reader = kab.get_asset_seekable("patch/textures/wall.png").unwrap();
+    let mut content = String::new();
+    reader.seek(SeekFrom::Start(2)).unwrap();
+    reader.read_to_string(&mut content).unwrap();
+    assert!(content == "ll" && reader.get_length() == 4, "Wrong seekable content!");
+    assert!(kab.get_asset_seekable("patch/config.txt").is_err() && kab.get_asset_range("patch/config.txt", 0, 5).is_err(), "Config should be corrupted!");
+    let mut content = String::new();
+    kab.get_asset_range("patch/textures/wall.png", 1, 2).unwrap().read_to_string(&mut content).unwrap();
+    assert!(content == "al", "Wrong range content!");
+
+    // V4 | Corrupted assets are not cached and setting manifest invalidates cached assets.
+    let cache = Arc::new(KAssetCache::new(1024));
+    kab.set_cache(Some(cache.clone()));
+    assert!(read_integrity(&kab, "patch/config.txt").is_err() && !cache.contains("patch/config.txt"), "Corrupted config shouldn't be cached!");
+    kab.set_source_integrity(id, None).unwrap();
+    assert!(read_integrity(&kab, "patch/cheat.txt").is_ok() && cache.contains("patch/cheat.txt"), "Cheat should be cached!");
+    kab.set_source_integrity(id, Some(manifest)).unwrap();
+    assert!(!cache.contains("patch/cheat.txt"), "Cached assets should be invalidated!");
+
+    // V5 | KAssetBroker::set_source_integrity() with None stops verification.
+    kab.set_source_integrity(id, None).unwrap();
+    assert!(read_integrity(&kab, "patch/config.txt") == Ok(b"speed = 9".to_vec()) && kab.get_source_integrity(id).unwrap().is_none(), "Config shouldn't be verified!");
+}
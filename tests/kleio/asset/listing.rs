@@ -0,0 +1,136 @@
+use std::{path::PathBuf, fs, io::{Read, Cursor}, sync::Arc};
+use olympus_kleio::asset::{KAssetSource, KAssetSourceFolder, KAssetBroker, KAssetPath};
+use super::create_named_source;
+
+/// Root path of test folder
+static TEST_FOLDER: &str = "../target/tests/kleio/asset/";
+
+/// Source without listing.
+struct UnlistedSource;
+
+impl KAssetSource for UnlistedSource {
+    fn has_asset(&self, path: &KAssetPath) -> bool {
+        path.as_str() == "hidden.txt"
+    }
+
+    fn get_asset(&self, _path: &KAssetPath) -> Result<Box<dyn Read>, std::io::Error> {
+        Ok(Box::new(Cursor::new("hidden")))
+    }
+}
+
+/// Get listing of source sorted.
+fn list_sorted(source : &dyn KAssetSource, folder : &str) -> Vec<KAssetPath> {
+    let mut paths = source.list_assets(&KAssetPath::new(folder).unwrap()).expect("Source should be listed!");
+    paths.sort();
+    paths
+}
+
+#[test]
+/// List assets of [KAssetSourceFolder] and [KAssetSourceMemory].
+///
+/// # Verification(s)
+/// V1 | KAssetSourceFolder::list_assets() lists all files of folder and sub folders.
+/// V2 | KAssetSourceFolder::list_assets() lists files within a sub folder only.
+/// V3 | KAssetSourceFolder::list_assets() returns an empty list for inexistant folder.
+/// V4 | Folder escaping source can't be listed since it isn't a KAssetPath.
+/// V5 | KAssetSourceMemory::list_assets() lists assets within folder, not folders sharing prefix.
+fn kasset_listing_sources() {
+    let folder_name: &str = &(TEST_FOLDER.to_owned() + "kasset_listing_sources/");
+    fs::create_dir_all(folder_name.to_owned() + "levels/forest").unwrap();
+    fs::create_dir_all(folder_name.to_owned() + "empty").unwrap();
+    for file in ["readme.txt", "levels/1.lvl", "levels/forest/2.lvl"] {
+        fs::write(folder_name.to_owned() + file, "Hello").unwrap();
+    }
+    let kasf = KAssetSourceFolder::new(PathBuf::from(folder_name)).unwrap();
+
+    // V1 | KAssetSourceFolder::list_assets() lists all files of folder and sub folders.
+    assert!(list_sorted(&kasf, "") == vec![KAssetPath::new("levels/1.lvl").unwrap(), KAssetPath::new("levels/forest/2.lvl").unwrap(), KAssetPath::new("readme.txt").unwrap()], "Wrong listing!");
+
+    // V2 | KAssetSourceFolder::list_assets() lists files within a sub folder only.
+    assert!(list_sorted(&kasf, "levels/forest") == vec![KAssetPath::new("levels/forest/2.lvl").unwrap()], "Wrong listing!");
+
+    // V3 | KAssetSourceFolder::list_assets() returns an empty list for inexistant folder.
+    assert!(list_sorted(&kasf, "missing").is_empty() && list_sorted(&kasf, "empty").is_empty(), "Listing should be empty!");
+
+    // V4 | Folder escaping source can't be listed since it isn't a KAssetPath.
+    assert!(KAssetPath::new("../").is_err(), "Folder escaping source shouldn't be a path!");
+
+    // V5 | KAssetSourceMemory::list_assets() lists assets within folder, not folders sharing prefix.
+    let kasm = create_named_source("listing", &["levels/1.lvl", "levels2/1.lvl", "levels"]);
+    assert!(list_sorted(kasm.as_ref(), "levels") == vec![KAssetPath::new("levels/1.lvl").unwrap()], "Wrong listing!");
+    assert!(list_sorted(kasm.as_ref(), "./").len() == 3, "Wrong listing!");
+
+    fs::remove_dir_all(PathBuf::from(folder_name)).expect("Test couldn't be cleaned!");
+}
+
+#[test]
+/// Merge listing of sources in [KAssetBroker].
+///
+/// # Verification(s)
+/// V1 | KAssetBroker::list_assets() merges and sorts assets of all sources.
+/// V2 | Each asset reports source winning and sources overridden by priority.
+/// V3 | Changing priorities changes source winning.
+/// V4 | Sources without listing are ignored.
+fn kasset_listing_broker() {
+    let kab = KAssetBroker::new();
+    let mod_id = kab.add_source(create_named_source("mod", &["levels/1.lvl", "mod.txt"])).unwrap();
+    let base_id = kab.add_source(create_named_source("base", &["levels/1.lvl", "levels/2.lvl"])).unwrap();
+    let patch_id = kab.add_source(create_named_source("patch", &["levels/1.lvl", "levels/2.lvl"])).unwrap();
+    kab.add_source(Arc::new(UnlistedSource)).unwrap();
+
+    // V1 | KAssetBroker::list_assets() merges and sorts assets of all sources.
+    // V4 | Sources without listing are ignored.
+    let entries = kab.list_assets("**").expect("Assets should be listed!");
+    let paths : Vec<&str> = entries.iter().map(|entry| entry.path.as_str()).collect();
+    assert!(paths == vec!["levels/1.lvl", "levels/2.lvl", "mod.txt"], "Wrong listing {:?}!", paths);
+
+    // V2 | Each asset reports source winning and sources overridden by priority.
+    assert!(entries[0].source == mod_id && entries[0].overridden == vec![base_id, patch_id], "Mod should win level 1!");
+    assert!(entries[1].source == base_id && entries[1].overridden == vec![patch_id], "Base should win level 2!");
+    assert!(entries[2].source == mod_id && entries[2].overridden.is_empty(), "Mod should win mod.txt!");
+
+    // V3 | Changing priorities changes source winning.
+    kab.set_source_priority(patch_id, 0).unwrap();
+    let entries = kab.list_assets("levels/*").expect("Assets should be listed!");
+    assert!(entries.len() == 2, "2 levels expected!");
+    assert!(entries.iter().all(|entry| entry.source == patch_id), "Patch should win all levels!");
+    assert!(entries[0].overridden == vec![mod_id, base_id], "Mod and base should be overridden!");
+}
+
+#[test]
+/// Filter [KAssetBroker] listing with glob patterns.
+///
+/// # Verification(s)
+/// V1 | `*` and `?` match within a folder only.
+/// V2 | `**` matches any folder, including none.
+/// V3 | Character sets match a character of (or not of) the set.
+/// V4 | Pattern without wildcard matches a single asset.
+fn kasset_listing_glob() {
+    let kab = KAssetBroker::new();
+    kab.add_source(create_named_source("listing", &[
+        "en.lang", "lang/fr.lang", "lang/old/de.lang", "levels/1.lvl", "levels/2.lvl", "levels/10.lvl", "levels/forest/3.lvl", "readme.txt",
+    ])).unwrap();
+
+    let list = |pattern : &str| -> Vec<String> {
+        kab.list_assets(pattern).expect("Assets should be listed!").iter().map(|entry| entry.path.to_string()).collect()
+    };
+
+    // V1 | `*` and `?` match within a folder only.
+    assert!(list("levels/*.lvl") == vec!["levels/1.lvl", "levels/10.lvl", "levels/2.lvl"], "Wrong listing for *!");
+    assert!(list("levels/?.lvl") == vec!["levels/1.lvl", "levels/2.lvl"], "Wrong listing for ?!");
+    assert!(list("*.lang") == vec!["en.lang"], "Wrong listing for * at root!");
+
+    // V2 | `**` matches any folder, including none.
+    assert!(list("**/*.lang") == vec!["en.lang", "lang/fr.lang", "lang/old/de.lang"], "Wrong listing for **/!");
+    assert!(list("levels/**") == vec!["levels/1.lvl", "levels/10.lvl", "levels/2.lvl", "levels/forest/3.lvl"], "Wrong listing for /**!");
+    assert!(list("**").len() == 8, "Wrong listing for **!");
+
+    // V3 | Character sets match a character of (or not of) the set.
+    assert!(list("levels/[12].lvl") == vec!["levels/1.lvl", "levels/2.lvl"], "Wrong listing for set!");
+    assert!(list("levels/[!1].lvl") == vec!["levels/2.lvl"], "Wrong listing for negated set!");
+    assert!(list("lang/[a-f]*.lang") == vec!["lang/fr.lang"], "Wrong listing for range!");
+
+    // V4 | Pattern without wildcard matches a single asset.
+    assert!(list("readme.txt") == vec!["readme.txt"], "Wrong listing for literal!");
+    assert!(list("readme").is_empty(), "Literal should match whole path!");
+}
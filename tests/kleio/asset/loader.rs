@@ -0,0 +1,135 @@
+use std::{path::{Path, PathBuf}, io::{Read, ErrorKind}, sync::Arc};
+use olympus_kleio::asset::{KAssetSource, KAssetSourceMemory, KAssetBroker, KAssetLoader, KAssetLoaderError, KAssetPath};
+
+/// Loader of UTF-8 text.
+struct TextLoader;
+
+impl KAssetLoader for TextLoader {
+    type Asset = String;
+
+    fn load(&self, _path : &KAssetPath, reader : &mut dyn Read) -> Result<String, KAssetLoaderError> {
+        let mut content = String::new();
+        match reader.read_to_string(&mut content) {
+            Ok(_) => Ok(content),
+            Err(err) if err.kind() == ErrorKind::InvalidData => Err(KAssetLoaderError::ParseError),
+            Err(_) => Err(KAssetLoaderError::ReadError),
+        }
+    }
+}
+
+/// Loader of UTF-8 text in uppercase.
+struct UppercaseLoader;
+
+impl KAssetLoader for UppercaseLoader {
+    type Asset = String;
+
+    fn load(&self, path : &KAssetPath, reader : &mut dyn Read) -> Result<String, KAssetLoaderError> {
+        TextLoader.load(path, reader).map(|content| content.to_uppercase())
+    }
+}
+
+/// Loader of a decimal number.
+struct NumberLoader;
+
+impl KAssetLoader for NumberLoader {
+    type Asset = u32;
+
+    fn load(&self, path : &KAssetPath, reader : &mut dyn Read) -> Result<u32, KAssetLoaderError> {
+        TextLoader.load(path, reader)?.trim().parse().map_err(|_| KAssetLoaderError::ParseError)
+    }
+}
+
+/// Source failing to read its assets.
+struct FailingSource;
+
+impl KAssetSource for FailingSource {
+    fn has_asset(&self, path: &KAssetPath) -> bool {
+        path.as_str() == "failing.txt"
+    }
+
+    fn get_asset(&self, _path: &KAssetPath) -> Result<Box<dyn Read>, std::io::Error> {
+        Err(std::io::Error::new(ErrorKind::PermissionDenied, "Permission denied!"))
+    }
+}
+
+/// Create a [KAssetBroker] with text assets.
+fn create_broker() -> KAssetBroker {
+    let kasm = KAssetSourceMemory::new("loader");
+    for (path, content) in [("hello.txt", "Hello"), ("UPPER.TXT", "Upper"), ("number.txt", " 42\n"), ("number.num", "7"), ("invalid.num", "abc"), ("hello.unknown", "Unknown")] {
+        kasm.add_asset(PathBuf::from(path), content.as_bytes().to_vec()).unwrap();
+    }
+    kasm.add_asset(PathBuf::from("binary.txt"), vec![0xFF, 0xFE]).unwrap();
+
+    let kab = KAssetBroker::new();
+    kab.add_source(Arc::new(kasm)).unwrap();
+    kab.add_source(Arc::new(FailingSource)).unwrap();
+    kab
+}
+
+#[test]
+/// Load typed assets with loaders registered in [KAssetBroker].
+///
+/// # Verification(s)
+/// V1 | KAssetBroker::load() uses loader registered for extension, case-insensitive.
+/// V2 | KAssetBroker::load() chooses loader of requested type among loaders of extension.
+/// V3 | KAssetBroker::load() uses loader registered for type if extension has no loader of type.
+/// V4 | Last loader registered for an extension and type is used.
+/// V5 | KAssetBroker::clear_loaders() removes all loaders.
+fn kasset_loader_load() {
+    let kab = create_broker();
+    kab.register_loader(&["txt", ".cfg"], TextLoader);
+    kab.register_loader(&["num", "TXT"], NumberLoader);
+
+    // V1 | KAssetBroker::load() uses loader registered for extension, case-insensitive.
+    assert!(kab.load::<String>(PathBuf::from("hello.txt")) == Ok(String::from("Hello")), "Wrong text loaded!");
+    assert!(kab.load::<String>(PathBuf::from("UPPER.TXT")) == Ok(String::from("Upper")), "Extension should be case-insensitive!");
+
+    // V2 | KAssetBroker::load() chooses loader of requested type among loaders of extension.
+    assert!(kab.load::<u32>(PathBuf::from("number.txt")) == Ok(42), "Wrong number loaded!");
+    assert!(kab.load::<String>(PathBuf::from("number.txt")) == Ok(String::from(" 42\n")), "Wrong text loaded!");
+    assert!(kab.load::<u32>(PathBuf::from("number.num")) == Ok(7), "Wrong number loaded!");
+
+    // V3 | KAssetBroker::load() uses loader registered for type if extension has no loader of type.
+    assert!(kab.has_loader::<String>(Path::new("hello.unknown")), "Loader of type should be found!");
+    assert!(kab.load::<String>(PathBuf::from("hello.unknown")) == Ok(String::from("Unknown")), "Wrong text loaded!");
+    assert!(kab.load::<String>(PathBuf::from("number.num")) == Ok(String::from("7")), "Wrong text loaded!");
+
+    // V4 | Last loader registered for an extension and type is used.
+    kab.register_loader(&["txt"], UppercaseLoader);
+    assert!(kab.load::<String>(PathBuf::from("hello.txt")) == Ok(String::from("HELLO")), "Last loader should be used!");
+    assert!(kab.load::<String>(PathBuf::from("hello.unknown")) == Ok(String::from("UNKNOWN")), "Last loader should be used!");
+    assert!(kab.load::<u32>(PathBuf::from("number.txt")) == Ok(42), "Loader of other type shouldn't be replaced!");
+
+    // V5 | KAssetBroker::clear_loaders() removes all loaders.
+    kab.clear_loaders();
+    assert!(!kab.has_loader::<String>(Path::new("hello.txt")), "Loaders should be removed!");
+    assert!(kab.load::<String>(PathBuf::from("hello.txt")) == Err(KAssetLoaderError::LoaderNotFound), "Loaders should be removed!");
+}
+
+#[test]
+/// Verify errors of [KAssetBroker::load].
+///
+/// # Verification(s)
+/// V1 | KAssetBroker::load() must return Err(KAssetLoaderError::LoaderNotFound) if no loader of type is registered.
+/// V2 | KAssetBroker::load() must return Err(KAssetLoaderError::AssetNotFound) if asset is missing.
+/// V3 | KAssetBroker::load() must return Err(KAssetLoaderError::ReadError) if asset couldn't be read.
+/// V4 | KAssetBroker::load() must return Err(KAssetLoaderError::ParseError) if asset couldn't be parsed.
+fn kasset_loader_errors() {
+    let kab = create_broker();
+    kab.register_loader(&["txt"], TextLoader);
+    kab.register_loader(&["num"], NumberLoader);
+
+    // V1 | KAssetBroker::load() must return Err(KAssetLoaderError::LoaderNotFound) if no loader of type is registered.
+    assert!(!kab.has_loader::<f32>(Path::new("number.num")), "No loader of f32 expected!");
+    assert!(kab.load::<f32>(PathBuf::from("number.num")) == Err(KAssetLoaderError::LoaderNotFound), "Wrong error given!");
+
+    // V2 | KAssetBroker::load() must return Err(KAssetLoaderError::AssetNotFound) if asset is missing.
+    assert!(kab.load::<String>(PathBuf::from("missing.txt")) == Err(KAssetLoaderError::AssetNotFound), "Wrong error given!");
+
+    // V3 | KAssetBroker::load() must return Err(KAssetLoaderError::ReadError) if asset couldn't be read.
+    assert!(kab.load::<String>(PathBuf::from("failing.txt")) == Err(KAssetLoaderError::ReadError), "Wrong error given!");
+
+    // V4 | KAssetBroker::load() must return Err(KAssetLoaderError::ParseError) if asset couldn't be parsed.
+    assert!(kab.load::<u32>(PathBuf::from("invalid.num")) == Err(KAssetLoaderError::ParseError), "Wrong error given!");
+    assert!(kab.load::<String>(PathBuf::from("binary.txt")) == Err(KAssetLoaderError::ParseError), "Wrong error given!");
+}
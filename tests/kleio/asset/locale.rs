@@ -0,0 +1,129 @@
+use std::io::{Read, ErrorKind};
+use olympus_kleio::asset::{KAssetBroker, KAssetPath, KAssetLocalization, KAssetLocaleLayout, KAssetLocaleMatch,
+    KAssetLocaleError, KAssetDecompression};
+use super::create_memory_source;
+
+/// Read asset of broker to a string.
+fn read_string(kab : &KAssetBroker, path : &str) -> Result<String, std::io::Error> {
+    let mut text = String::new();
+    kab.get_asset(path)?.read_to_string(&mut text)?;
+    Ok(text)
+}
+
+/// Create a [KAssetLocaleMatch] of path and locale.
+fn create_match(path : &str, locale : Option<&str>) -> KAssetLocaleMatch {
+    KAssetLocaleMatch { path : KAssetPath::new(path).unwrap(), locale : locale.map(String::from) }
+}
+
+#[test]
+/// Create [KAssetLocalization] chains and variant paths.
+///
+/// # Verification(s)
+/// V1 | KAssetLocalization::new() creates a chain of locale followed by its parents.
+/// V2 | KAssetLocalization::new() and with_chain() return Err(KAssetLocaleError::InvalidLocale) for invalid locales.
+/// V3 | KAssetLocaleLayout::Suffix inserts locale before extension of file name.
+/// V4 | KAssetLocaleLayout::Folder puts variants in a folder per locale.
+fn kasset_locale_chain() {
+    // V1 | KAssetLocalization::new() creates a chain of locale followed by its parents.
+    let localization = KAssetLocalization::new("zh-Hant-TW", KAssetLocaleLayout::Suffix).unwrap();
+    assert!(localization.get_chain() == ["zh-Hant-TW", "zh-Hant", "zh"], "Wrong chain {:?}!", localization.get_chain());
+    assert!(KAssetLocalization::new("fr_CA", KAssetLocaleLayout::Suffix).unwrap().get_chain() == ["fr_CA", "fr"], "Wrong chain!");
+    let localization = KAssetLocalization::with_chain(&["fr-CA", "en"], KAssetLocaleLayout::Suffix).unwrap();
+    assert!(localization.get_chain() == ["fr-CA", "en"], "Wrong chain {:?}!", localization.get_chain());
+
+    // V2 | KAssetLocalization::new() and with_chain() return Err(KAssetLocaleError::InvalidLocale) for invalid locales.
+    for locale in ["", "fr/CA", "../fr", "fr.CA", "fr CA"] {
+        assert!(KAssetLocalization::new(locale, KAssetLocaleLayout::Suffix) == Err(KAssetLocaleError::InvalidLocale), "Locale {:?} should be invalid!", locale);
+    }
+    assert!(KAssetLocalization::with_chain(&["fr", ""], KAssetLocaleLayout::Suffix) == Err(KAssetLocaleError::InvalidLocale), "Chain should be invalid!");
+
+    // V3 | KAssetLocaleLayout::Suffix inserts locale before extension of file name.
+    let localization = KAssetLocalization::new("fr", KAssetLocaleLayout::Suffix).unwrap();
+    for (path, variant) in [("ui/title.png", "ui/title.fr.png"), ("title.tar.gz", "title.tar.fr.gz"), ("docs/readme", "docs/readme.fr"), (".config", ".config.fr")] {
+        assert!(localization.get_variant_path(&KAssetPath::new(path).unwrap(), "fr") == Some(KAssetPath::new(variant).unwrap()), "Wrong variant of {}!", path);
+    }
+    assert!(localization.get_variant_path(&KAssetPath::root(), "fr").is_none(), "Root shouldn't have variant!");
+
+    // V4 | KAssetLocaleLayout::Folder puts variants in a folder per locale.
+    let localization = KAssetLocalization::new("fr", KAssetLocaleLayout::Folder(KAssetPath::new("locale").unwrap())).unwrap();
+    assert!(localization.get_variant_path(&KAssetPath::new("ui/title.png").unwrap(), "fr-CA") == Some(KAssetPath::new("locale/fr-CA/ui/title.png").unwrap()), "Wrong variant!");
+    let localization = KAssetLocalization::new("fr", KAssetLocaleLayout::Folder(KAssetPath::root())).unwrap();
+    assert!(localization.get_variant_path(&KAssetPath::new("ui/title.png").unwrap(), "fr") == Some(KAssetPath::new("fr/ui/title.png").unwrap()), "Wrong variant!");
+}
+
+#[test]
+/// Resolve assets to variants named with [KAssetLocaleLayout::Suffix].
+///
+/// # Verification(s)
+/// V1 | Without localization, assets are served as is.
+/// V2 | KAssetBroker::resolve_locale() picks variant of first locale of chain having it, then the default asset.
+/// V3 | KAssetBroker::get_asset(), get_asset_seekable() and get_asset_metadata() serve variant picked.
+/// V4 | KAssetBroker::set_locale() switches locale keeping layout.
+/// V5 | KAssetBroker::resolve_locale() returns Err() of kind NotFound if no variant nor default asset exists.
+fn kasset_locale_suffix() {
+    let kab = KAssetBroker::new();
+    kab.add_source(create_memory_source(&[("ui/title.txt", b"Title"), ("ui/title.fr.txt", b"Titre"), ("ui/title.fr-CA.txt", b"Titre QC"),
+        ("ui/start.txt", b"Start"), ("ui/start.fr.txt", b"Commencer"), ("ui/quit.de.txt", b"Beenden")])).unwrap();
+
+    // V1 | Without localization, assets are served as is.
+    assert!(kab.get_localization().is_none(), "Localization should be none by default!");
+    assert!(read_string(&kab, "ui/title.txt").unwrap() == "Title", "Wrong asset!");
+    assert!(kab.resolve_locale("ui/title.txt").unwrap() == create_match("ui/title.txt", None), "Default asset should be picked!");
+
+    // V2 | KAssetBroker::resolve_locale() picks variant of first locale of chain having it, then the default asset.
+    kab.set_localization(Some(KAssetLocalization::new("fr-CA", KAssetLocaleLayout::Suffix).unwrap()));
+    assert!(kab.resolve_locale("ui/title.txt").unwrap() == create_match("ui/title.fr-CA.txt", Some("fr-CA")), "fr-CA should be picked!");
+    assert!(kab.resolve_locale("ui/start.txt").unwrap() == create_match("ui/start.fr.txt", Some("fr")), "fr should be picked!");
+    assert!(kab.resolve_locale("ui/quit.txt").is_err(), "Variant of another locale shouldn't be picked!");
+
+    // V3 | KAssetBroker::get_asset(), get_asset_seekable() and get_asset_metadata() serve variant picked.
+    assert!(read_string(&kab, "ui/title.txt").unwrap() == "Titre QC", "Wrong variant!");
+    assert!(read_string(&kab, "ui/start.txt").unwrap() == "Commencer", "Wrong variant!");
+    assert!(read_string(&kab, "ui/title.fr.txt").unwrap() == "Titre", "Variant should be readable by its path!");
+    assert!(kab.get_asset_seekable("ui/start.txt").unwrap().get_length() == 9, "Wrong variant length!");
+    assert!(kab.get_asset_metadata("ui/title.txt").unwrap().size == 8, "Wrong variant metadata!");
+
+    // V4 | KAssetBroker::set_locale() switches locale keeping layout.
+    kab.set_locale("de-AT").unwrap();
+    assert!(kab.get_localization().unwrap().get_chain() == ["de-AT", "de"], "Wrong chain!");
+    assert!(read_string(&kab, "ui/title.txt").unwrap() == "Title", "Default asset should be served!");
+    assert!(read_string(&kab, "ui/quit.txt").unwrap() == "Beenden", "Wrong variant!");
+    assert!(kab.set_locale("de/AT") == Err(KAssetLocaleError::InvalidLocale), "Locale should be invalid!");
+    assert!(kab.get_localization().unwrap().get_chain() == ["de-AT", "de"], "Chain shouldn't change!");
+
+    // V5 | KAssetBroker::resolve_locale() returns Err() of kind NotFound if no variant nor default asset exists.
+    assert!(kab.resolve_locale("ui/missing.txt").unwrap_err().kind() == ErrorKind::NotFound, "Missing asset shouldn't resolve!");
+    kab.set_localization(None);
+    assert!(kab.get_asset("ui/quit.txt").is_err(), "Variant shouldn't be served without localization!");
+}
+
+#[test]
+/// Resolve assets to variants of sources mounted per locale with [KAssetLocaleLayout::Folder].
+///
+/// # Verification(s)
+/// V1 | Variants are resolved within sources mounted at folder of their locale.
+/// V2 | Locale chain has precedence over source priority.
+/// V3 | Compressed variants are resolved with decompression.
+fn kasset_locale_folder() {
+    let kab = KAssetBroker::new();
+    let id = kab.add_source(create_memory_source(&[("audio/intro.txt", b"Intro"), ("ui/title.txt", b"Title")])).unwrap();
+    kab.add_source_at(create_memory_source(&[("audio/intro.txt", b"Intro FR"), ("ui/title.txt", b"Titre")]), "locale/fr").unwrap();
+    kab.add_source_at(create_memory_source(&[("ui/title.txt.gz", &[0x1F, 0x8B, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF, 0xF3, 0x48,
+        0xCD, 0xC9, 0xC9, 0xD7, 0x51, 0x28, 0xCF, 0x2F, 0xCA, 0x49, 0x51, 0x04, 0x00, 0xE6, 0xC6, 0xE6, 0xEB, 0x0D, 0x00, 0x00, 0x00])]), "locale/fr-CA").unwrap();
+    kab.set_localization(Some(KAssetLocalization::new("fr-CA", KAssetLocaleLayout::Folder(KAssetPath::new("locale").unwrap())).unwrap()));
+
+    // V1 | Variants are resolved within sources mounted at folder of their locale.
+    assert!(kab.resolve_locale("audio/intro.txt").unwrap() == create_match("locale/fr/audio/intro.txt", Some("fr")), "fr should be picked!");
+    assert!(read_string(&kab, "audio/intro.txt").unwrap() == "Intro FR", "Wrong variant!");
+
+    // V2 | Locale chain has precedence over source priority.
+    kab.set_source_priority(id, 0).unwrap();
+    assert!(kab.get_sources()[0] == id, "Default source should have highest priority!");
+    assert!(read_string(&kab, "audio/intro.txt").unwrap() == "Intro FR", "Variant should be served over default asset of higher priority!");
+
+    // V3 | Compressed variants are resolved with decompression.
+    assert!(read_string(&kab, "ui/title.txt").unwrap() == "Titre", "Compressed variant shouldn't be picked without decompression!");
+    kab.set_decompression(Some(KAssetDecompression::default()));
+    assert!(kab.resolve_locale("ui/title.txt").unwrap() == create_match("locale/fr-CA/ui/title.txt", Some("fr-CA")), "fr-CA should be picked!");
+    assert!(read_string(&kab, "ui/title.txt").unwrap() == "Hello, world!", "Wrong variant!");
+}
@@ -0,0 +1,182 @@
+use std::{path::PathBuf, fs::{self, File}, io::{Read, Cursor, ErrorKind}, sync::Arc};
+use olympus_kleio::asset::{KAssetBytes, KAssetSource, KAssetSourceFolder, KAssetSourcePak, KAssetSourceMemory, KAssetBroker, KAssetPath,
+    KPakWriter, KPakCompression, KAssetCache, KIntegrityManifest, KIntegrityError};
+
+/// Root path of test folder
+static TEST_FOLDER: &str = "../target/tests/kleio/asset/";
+
+/// Files are only mapped on 64 bits Linux
+const MAPPED : bool = cfg!(all(target_os = "linux", target_pointer_width = "64"));
+
+/// Create `size` bytes of a heightmap.
+fn create_heightmap(size : usize) -> Vec<u8> {
+    (0..size).map(|i| (i * 7 % 251) as u8).collect()
+}
+
+/// Create a clean test folder with a 200 KB heightmap and an empty file.
+fn create_test_folder(folder_name : &str) -> PathBuf {
+    let _ = fs::remove_dir_all(folder_name);
+    fs::create_dir_all(folder_name.to_owned() + "terrain").expect("Test folder couldn't be created!");
+    fs::write(folder_name.to_owned() + "terrain/height.raw", create_heightmap(200_000)).unwrap();
+    fs::write(folder_name.to_owned() + "empty.raw", []).unwrap();
+
+    PathBuf::from(folder_name)
+}
+
+#[test]
+/// Map files in memory with [KAssetBytes].
+///
+/// # Verification(s)
+/// V1 | KAssetBytes::map_file() maps range of file at unaligned offset.
+/// V2 | KAssetBytes::read_file() reads range of file in memory.
+/// V3 | KAssetBytes::map_file() and read_file() return Err() of kind UnexpectedEof for a range after the end of file.
+/// V4 | KAssetBytes::map_file() gives empty bytes in memory for an empty range.
+/// V5 | KAssetBytes created from memory aren't mapped and share bytes when cloned.
+fn kasset_bytes_file() {
+    let folder = create_test_folder(&(TEST_FOLDER.to_owned() + "kab_bytes_file/"));
+    let file = File::open(folder.join("terrain/height.raw")).unwrap();
+    let heightmap = create_heightmap(200_000);
+
+    // V1 | KAssetBytes::map_file() maps range of file at unaligned offset.
+    let bytes = unsafe { KAssetBytes::map_file(&file, 70_001, 100_000) }.unwrap();
+    assert!(bytes.is_mapped() == MAPPED, "Bytes should be mapped on 64 bits Linux!");
+    assert!(bytes[..] == heightmap[70_001..170_001], "Wrong bytes mapped!");
+    let whole = unsafe { KAssetBytes::map_file(&file, 0, 200_000) }.unwrap();
+    assert!(whole[..] == heightmap[..], "Wrong bytes mapped!");
+    drop(whole);
+    assert!(bytes[99_999] == heightmap[170_000], "Bytes should stay mapped!");
+
+    // V2 | KAssetBytes::read_file() reads range of file in memory.
+    let bytes = KAssetBytes::read_file(&file, 199_990, 10).unwrap();
+    assert!(!bytes.is_mapped(), "Bytes shouldn't be mapped!");
+    assert!(bytes[..] == heightmap[199_990..], "Wrong bytes read!");
+
+    // V3 | KAssetBytes::map_file() and read_file() return Err() of kind UnexpectedEof for a range after the end of file.
+    assert!(unsafe { KAssetBytes::map_file(&file, 199_990, 11) }.unwrap_err().kind() == ErrorKind::UnexpectedEof, "Wrong error given!");
+    assert!(unsafe { KAssetBytes::map_file(&file, u64::MAX, 2) }.unwrap_err().kind() == ErrorKind::UnexpectedEof, "Wrong error given!");
+    assert!(KAssetBytes::read_file(&file, 200_001, 0).unwrap_err().kind() == ErrorKind::UnexpectedEof, "Wrong error given!");
+
+    // V4 | KAssetBytes::map_file() gives empty bytes in memory for an empty range.
+    let bytes = unsafe { KAssetBytes::map_file(&File::open(folder.join("empty.raw")).unwrap(), 0, 0) }.unwrap();
+    assert!(bytes.is_empty() && !bytes.is_mapped(), "Bytes should be empty!");
+
+    // V5 | KAssetBytes created from memory aren't mapped and share bytes when cloned.
+    let bytes = KAssetBytes::from(vec![1, 2, 3]);
+    let clone = bytes.clone();
+    assert!(!bytes.is_mapped() && clone.as_ref() == [1, 2, 3], "Wrong bytes!");
+    assert!(std::ptr::eq(bytes.as_ptr(), clone.as_ptr()), "Bytes shouldn't be copied!");
+
+    fs::remove_dir_all(folder).expect("Test couldn't be cleaned!");
+}
+
+#[test]
+/// Get [KAssetBytes] of [KAssetSource].
+///
+/// # Verification(s)
+/// V1 | KAssetSourceFolder::get_asset_bytes() reads files unless set_memory_mapped() is set.
+/// V2 | KAssetSourcePak::get_asset_bytes() maps uncompressed assets only when set_memory_mapped() is set.
+/// V3 | KAssetSourcePak::get_asset_bytes() returns Err() of kind InvalidData for a corrupted mapped asset.
+/// V4 | KAssetSourceMemory::get_asset_bytes() shares buffers.
+fn kasset_bytes_source() {
+    let folder = create_test_folder(&(TEST_FOLDER.to_owned() + "kab_bytes_source/"));
+    let heightmap = create_heightmap(200_000);
+    let path = KAssetPath::new("terrain/height.raw").unwrap();
+
+    // V1 | KAssetSourceFolder::get_asset_bytes() reads files unless set_memory_mapped() is set.
+    let kasf = KAssetSourceFolder::new(folder.clone()).unwrap();
+    assert!(!kasf.is_memory_mapped(), "Memory mapping should be disabled by default!");
+    let bytes = kasf.get_asset_bytes(&path).unwrap();
+    assert!(!bytes.is_mapped() && bytes[..] == heightmap[..], "Wrong bytes read!");
+    unsafe { kasf.set_memory_mapped(true); }
+    let bytes = kasf.get_asset_bytes(&path).unwrap();
+    assert!(bytes.is_mapped() == MAPPED && bytes[..] == heightmap[..], "Wrong bytes mapped!");
+    assert!(kasf.get_asset_bytes(&KAssetPath::new("empty.raw").unwrap()).unwrap().is_empty(), "Bytes should be empty!");
+    assert!(kasf.get_asset_bytes(&KAssetPath::new("missing.raw").unwrap()).is_err(), "Missing asset shouldn't be found!");
+
+    // V2 | KAssetSourcePak::get_asset_bytes() maps uncompressed assets only when set_memory_mapped() is set.
+    let pak_path = folder.join("terrain.kpak");
+    let mut writer = KPakWriter::create(pak_path.clone()).unwrap();
+    writer.add_asset_compressed(PathBuf::from("readme.txt"), &mut "Hello, world!".as_bytes(), KPakCompression::None).unwrap();
+    writer.add_asset_compressed(PathBuf::from("terrain/height.raw"), &mut Cursor::new(heightmap.clone()), KPakCompression::None).unwrap();
+    writer.add_asset_compressed(PathBuf::from("terrain/packed.raw"), &mut Cursor::new(heightmap.clone()), KPakCompression::Deflate).unwrap();
+    writer.finish().unwrap();
+
+    let kasp = KAssetSourcePak::new(pak_path).unwrap();
+    let bytes = kasp.get_asset_bytes(&path).unwrap();
+    assert!(!bytes.is_mapped() && bytes[..] == heightmap[..], "Wrong bytes read!");
+    unsafe { kasp.set_memory_mapped(true); }
+    let bytes = kasp.get_asset_bytes(&path).unwrap();
+    assert!(bytes.is_mapped() == MAPPED && bytes[..] == heightmap[..], "Wrong bytes mapped!");
+    assert!(kasp.get_asset_bytes(&KAssetPath::new("readme.txt").unwrap()).unwrap().as_ref() == b"Hello, world!", "Wrong bytes mapped!");
+    let bytes = kasp.get_asset_bytes(&KAssetPath::new("terrain/packed.raw").unwrap()).unwrap();
+    assert!(!bytes.is_mapped() && bytes[..] == heightmap[..], "Compressed asset shouldn't be mapped!");
+
+    // V3 | KAssetSourcePak::get_asset_bytes() returns Err() of kind InvalidData for a corrupted mapped asset.
+    let corrupted_path = folder.join("corrupted.kpak");
+    let data = fs::read(folder.join("terrain.kpak")).unwrap();
+    let position = data.windows(5).position(|window| window == b"Hello").unwrap();
+    fs::write(&corrupted_path, [&data[..position], b"Jello", &data[position + 5..]].concat()).unwrap();
+    let corrupted = KAssetSourcePak::new(corrupted_path).unwrap();
+    unsafe { corrupted.set_memory_mapped(true); }
+    let err = corrupted.get_asset_bytes(&KAssetPath::new("readme.txt").unwrap()).unwrap_err();
+    assert!(err.kind() == ErrorKind::InvalidData, "Corrupted mapped asset should fail!");
+    assert!(corrupted.get_asset_bytes(&path).unwrap()[..] == heightmap[..], "Other assets should be mapped!");
+
+    // V4 | KAssetSourceMemory::get_asset_bytes() shares buffers.
+    let kasm = KAssetSourceMemory::from_static_assets("memory", &[("static.txt", b"Static")]);
+    let first = kasm.get_asset_bytes(&KAssetPath::new("static.txt").unwrap()).unwrap();
+    let second = kasm.get_asset_bytes(&KAssetPath::new("static.txt").unwrap()).unwrap();
+    assert!(!first.is_mapped() && first.as_ref() == b"Static", "Wrong bytes!");
+    assert!(std::ptr::eq(first.as_ptr(), second.as_ptr()), "Bytes shouldn't be copied!");
+
+    fs::remove_dir_all(folder).expect("Test couldn't be cleaned!");
+}
+
+#[test]
+/// Get [KAssetBytes] of assets from [KAssetBroker].
+///
+/// # Verification(s)
+/// V1 | KAssetBroker::get_asset_bytes() gives bytes of source of highest priority having asset.
+/// V2 | KAssetBroker::get_asset_bytes() serves cached assets from cache.
+/// V3 | KAssetBroker::get_asset_bytes() fails with a KIntegrityError for a corrupted asset of a verified source.
+/// V4 | KAssetBroker::get_asset_bytes() returns Err() of kind NotFound for a missing asset.
+fn kasset_bytes_broker() {
+    let folder = create_test_folder(&(TEST_FOLDER.to_owned() + "kab_bytes_broker/"));
+    let heightmap = create_heightmap(200_000);
+
+    let kasf = Arc::new(KAssetSourceFolder::new(folder.clone()).unwrap());
+    unsafe { kasf.set_memory_mapped(true); }
+    let kasm = Arc::new(KAssetSourceMemory::new("patch"));
+    kasm.add_asset("terrain/patched.raw", vec![1, 2, 3]).unwrap();
+
+    let kab = KAssetBroker::new();
+    let folder_id = kab.add_source(kasf.clone()).unwrap();
+    kab.add_source(kasm).unwrap();
+
+    // V1 | KAssetBroker::get_asset_bytes() gives bytes of source of highest priority having asset.
+    let bytes = kab.get_asset_bytes("terrain/height.raw").unwrap();
+    assert!(bytes.is_mapped() == MAPPED && bytes[..] == heightmap[..], "Wrong bytes mapped!");
+    let bytes = kab.get_asset_bytes("terrain/patched.raw").unwrap();
+    assert!(!bytes.is_mapped() && bytes.as_ref() == [1, 2, 3], "Wrong bytes!");
+
+    // V2 | KAssetBroker::get_asset_bytes() serves cached assets from cache.
+    kab.set_cache(Some(Arc::new(KAssetCache::new(1024 * 1024))));
+    assert!(kab.get_asset_bytes("terrain/height.raw").unwrap().is_mapped() == MAPPED, "Bytes shouldn't be cached!");
+    kab.get_asset("terrain/height.raw").unwrap().read_to_end(&mut Vec::new()).unwrap();
+    let bytes = kab.get_asset_bytes("terrain/height.raw").unwrap();
+    assert!(!bytes.is_mapped() && bytes[..] == heightmap[..], "Bytes should be served from cache!");
+    kab.set_cache(None);
+
+    // V3 | KAssetBroker::get_asset_bytes() fails with a KIntegrityError for a corrupted asset of a verified source.
+    let manifest = KIntegrityManifest::generate(kasf.as_ref()).unwrap();
+    kab.set_source_integrity(folder_id, Some(Arc::new(manifest))).unwrap();
+    assert!(kab.get_asset_bytes("terrain/height.raw").unwrap()[..] == heightmap[..], "Intact asset should be verified!");
+    fs::write(folder.join("terrain/height.raw"), create_heightmap(199_999)).unwrap();
+    let err = kab.get_asset_bytes("terrain/height.raw").unwrap_err();
+    assert!(KIntegrityError::from_io_error(&err) == Some(KIntegrityError::SizeMismatch), "Wrong error given!");
+
+    // V4 | KAssetBroker::get_asset_bytes() returns Err() of kind NotFound for a missing asset.
+    assert!(kab.get_asset_bytes("terrain/missing.raw").unwrap_err().kind() == ErrorKind::NotFound, "Wrong error given!");
+
+    fs::remove_dir_all(folder).expect("Test couldn't be cleaned!");
+}
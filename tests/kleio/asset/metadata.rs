@@ -0,0 +1,109 @@
+use std::{path::PathBuf, fs, io::{Cursor, ErrorKind}, sync::Arc, time::{Duration, UNIX_EPOCH}};
+use olympus_kleio::asset::{KAssetBroker, KAssetSource, KAssetSourceFolder, KAssetSourceMemory, KAssetSourcePak, KAssetSourceKind,
+    KAssetSourceMetadata, KAssetPath, KPakWriter};
+
+/// Root path of test folder
+static TEST_FOLDER: &str = "../target/tests/kleio/asset/";
+
+#[test]
+/// Get [KAssetSourceMetadata] of sources and serialise it.
+///
+/// # Verification(s)
+/// V1 | KAssetSourceFolder metadata gives kind, path, name, times, asset count and size.
+/// V2 | KAssetSourceFolder metadata keeps its count until changes are polled, then follows folder content.
+/// V3 | KAssetSourceMetadata::to_json() escapes quotes and backslashes of path.
+/// V4 | KAssetSourceMetadata::to_json() writes times as milliseconds and unknown fields as null.
+fn kasset_metadata_source() {
+    // Test folder name with characters escaped in JSON
+    let folder_name: &str = &(TEST_FOLDER.to_owned() + "kam_\"source\"\\/");
+    let _ = fs::remove_dir_all(folder_name);
+    fs::create_dir_all(folder_name.to_owned() + "textures").unwrap();
+    fs::write(folder_name.to_owned() + "textures/wall.png", "wall").unwrap();
+    fs::write(folder_name.to_owned() + "readme.txt", "Hello, world!").unwrap();
+
+    // V1 | KAssetSourceFolder metadata gives kind, path, name, times, asset count and size.
+    let kasf = KAssetSourceFolder::new(PathBuf::from(folder_name)).unwrap();
+    let watched = kasf.watch().is_ok();
+    let metadata = kasf.get_metadata();
+    assert!(metadata.kind == KAssetSourceKind::Folder && metadata.location.as_deref() == Some(folder_name), "Wrong kind or location!");
+    assert!(metadata.name.as_deref() == Some("kam_\"source\"\\") && metadata.modified.is_some() && metadata.version.is_none(), "Wrong name or times!");
+    assert!(metadata.asset_count == Some(2) && metadata.size == Some(17), "Wrong count or size {:?}!", metadata);
+
+    // V2 | KAssetSourceFolder metadata keeps its count until changes are polled, then follows folder content.
+    fs::write(folder_name.to_owned() + "textures/floor.png", "floor").unwrap();
+    let metadata = kasf.get_metadata();
+    assert!(metadata.asset_count == Some(2) && metadata.size == Some(17), "Metadata should be kept until changes are polled {:?}!", metadata);
+    match watched {
+        true => assert!(!kasf.poll_changes().is_empty(), "Change should be reported!"),
+        false => kasf.unwatch(),
+    }
+    let metadata = kasf.get_metadata();
+    assert!(metadata.asset_count == Some(3) && metadata.size == Some(22), "Metadata should follow folder content {:?}!", metadata);
+
+    // V3 | KAssetSourceMetadata::to_json() escapes quotes and backslashes of path.
+    let json = metadata.to_json();
+    assert!(json.starts_with("{\"kind\":\"folder\",\"location\":\"../target/tests/kleio/asset/kam_\\\"source\\\"\\\\/\",\"created\":"), "Wrong JSON {}!", json);
+    assert!(json.ends_with(",\"asset_count\":3,\"size\":22,\"name\":\"kam_\\\"source\\\"\\\\\",\"version\":null}"), "Wrong JSON {}!", json);
+
+    // V4 | KAssetSourceMetadata::to_json() writes times as milliseconds and unknown fields as null.
+    let metadata = KAssetSourceMetadata { modified : Some(UNIX_EPOCH + Duration::from_millis(1234)), name : Some("line\nbreak".to_owned()),
+        ..KAssetSourceMetadata::default() };
+    assert!(metadata.to_json() == "{\"kind\":\"unknown\",\"location\":null,\"created\":null,\"modified\":1234,\"asset_count\":null,\"size\":null,\"name\":\"line\\nbreak\",\"version\":null}",
+        "Wrong JSON {}!", metadata.to_json());
+
+    // Clean test
+    fs::remove_dir_all(PathBuf::from(folder_name)).expect("Test couldn't be cleaned!");
+}
+
+#[test]
+/// Get [KAssetMetadata](olympus_kleio::asset::KAssetMetadata) of assets from sources and [KAssetBroker].
+///
+/// # Verification(s)
+/// V1 | KAssetSourceFolder gives size and modified time of file without hash.
+/// V2 | KAssetSourceMemory gives size and CRC-32 of buffer.
+/// V3 | KAssetSourcePak gives size and CRC-32 of index, with pack modified time.
+/// V4 | KAssetBroker::get_asset_metadata() gives metadata from source of highest priority mounted over path.
+/// V5 | KAssetBroker::get_asset_metadata() must return Err() for missing assets and folders.
+fn kasset_metadata_asset() {
+    // Test folder name
+    let folder_name: &str = &(TEST_FOLDER.to_owned() + "kam_asset/");
+    fs::create_dir_all(folder_name.to_owned() + "folder").unwrap();
+    fs::write(folder_name.to_owned() + "folder/text.txt", "Hello").unwrap();
+
+    // V1 | KAssetSourceFolder gives size and modified time of file without hash.
+    let kasf = KAssetSourceFolder::new(PathBuf::from(folder_name.to_owned() + "folder")).unwrap();
+    let text = KAssetPath::new("text.txt").unwrap();
+    let metadata = kasf.get_asset_metadata(&text).unwrap();
+    assert!(metadata.size == 5 && metadata.modified == fs::metadata(folder_name.to_owned() + "folder/text.txt").unwrap().modified().ok()
+        && metadata.hash.is_none(), "Wrong folder asset metadata {:?}!", metadata);
+
+    // V2 | KAssetSourceMemory gives size and CRC-32 of buffer.
+    let kasm = KAssetSourceMemory::new("memory");
+    kasm.add_asset("text.txt", "Hello, world!".as_bytes().to_vec()).unwrap();
+    let metadata = kasm.get_asset_metadata(&text).unwrap();
+    assert!(metadata.size == 13 && metadata.hash == Some(0xEBE6C6E6) && metadata.modified.is_none(), "Wrong memory asset metadata {:?}!", metadata);
+
+    // V3 | KAssetSourcePak gives size and CRC-32 of index, with pack modified time.
+    let mut writer = KPakWriter::create(PathBuf::from(folder_name.to_owned() + "test.kpak")).unwrap();
+    writer.add_asset(PathBuf::from("text.txt"), &mut Cursor::new("Hello")).unwrap();
+    writer.finish().unwrap();
+    let kasp = KAssetSourcePak::new(PathBuf::from(folder_name.to_owned() + "test.kpak")).unwrap();
+    let metadata = kasp.get_asset_metadata(&text).unwrap();
+    assert!(metadata.size == 5 && metadata.hash == Some(0xF7D18982) && metadata.modified == kasp.get_metadata().modified, "Wrong pack asset metadata {:?}!", metadata);
+    assert!(kasp.get_metadata().kind == KAssetSourceKind::Pak && kasp.get_metadata().asset_count == Some(1), "Wrong pack metadata!");
+
+    // V4 | KAssetBroker::get_asset_metadata() gives metadata from source of highest priority mounted over path.
+    let kab = KAssetBroker::new();
+    kab.add_source_at(Arc::new(kasm), "texts").unwrap();
+    kab.add_source(Arc::new(kasf)).unwrap();
+    kab.add_source(Arc::new(kasp)).unwrap();
+    assert!(kab.get_asset_metadata("texts/text.txt").unwrap().size == 13, "Memory source should give metadata!");
+    assert!(kab.get_asset_metadata("text.txt").unwrap().hash.is_none(), "Folder source should give metadata!");
+
+    // V5 | KAssetBroker::get_asset_metadata() must return Err() for missing assets and folders.
+    assert!(kab.get_asset_metadata("missing.txt").is_err_and(|err| err.kind() == ErrorKind::NotFound), "Asset shouldn't be found!");
+    assert!(kab.get_asset_metadata("texts").is_err() && kab.get_asset_metadata("../text.txt").is_err(), "Folders and invalid paths shouldn't have metadata!");
+
+    // Clean test
+    fs::remove_dir_all(PathBuf::from(folder_name)).expect("Test couldn't be cleaned!");
+}
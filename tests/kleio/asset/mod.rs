@@ -1,7 +1,124 @@
+use std::sync::Arc;
+use olympus_kleio::asset::KAssetSourceMemory;
+
 // Contains tests for KAssetSourceFolder
 #[cfg(test)]
 pub mod source_folder;
 
+// Contains tests for KAssetSourceZip
+#[cfg(test)]
+pub mod source_zip;
+
+// Contains tests for KAssetSourceMemory and kasset_source_embed!
+#[cfg(test)]
+pub mod source_memory;
+
+// Contains tests for KAssetSourcePak and KPakWriter
+#[cfg(test)]
+pub mod source_pak;
+
 // Contains tests for KAssetBroker
 #[cfg(test)]
-pub mod broker;
\ No newline at end of file
+pub mod broker;
+
+// Contains tests for hot reload and change events
+#[cfg(test)]
+pub mod change;
+
+// Contains tests for KAssetSinkFolder and broker sink
+#[cfg(test)]
+pub mod sink_folder;
+
+// Contains tests for typed asset loaders
+#[cfg(test)]
+pub mod loader;
+
+// Contains tests for KAssetStorage and asset handles
+#[cfg(test)]
+pub mod storage;
+
+// Contains tests for seekable and ranged asset readers
+#[cfg(test)]
+pub mod reader;
+
+// Contains tests for asset listing and glob filtering
+#[cfg(test)]
+pub mod listing;
+
+// Contains tests for KAssetCache
+#[cfg(test)]
+pub mod cache;
+
+// Contains tests for KAssetStreamer
+#[cfg(test)]
+pub mod streamer;
+
+// Contains tests for KAssetPath
+#[cfg(test)]
+pub mod path;
+
+// Contains tests for KAssetBroker mount points
+#[cfg(test)]
+pub mod mount;
+
+// Contains tests for KModManager
+#[cfg(test)]
+pub mod mods;
+
+// Contains tests for KAssetBroker provenance and override reports
+#[cfg(test)]
+pub mod provenance;
+
+// Contains tests for KAssetSource and asset metadata
+#[cfg(test)]
+pub mod metadata;
+
+// Contains tests for KIntegrityManifest and integrity verification
+#[cfg(test)]
+pub mod integrity;
+
+// Contains tests for signatures and KTrustPolicy
+#[cfg(test)]
+pub mod trust;
+
+// Contains tests for decompression of assets stored compressed
+#[cfg(test)]
+pub mod decompression;
+
+// Contains tests for KAssetLocalization
+#[cfg(test)]
+pub mod locale;
+
+// Contains tests for KAssetTags
+#[cfg(test)]
+pub mod tags;
+
+// Contains tests for KAssetBytes
+#[cfg(test)]
+pub mod mapped;
+
+// Contains tests for KAssetPreload
+#[cfg(test)]
+pub mod preload;
+
+// Contains tests for KAssetTrace
+#[cfg(test)]
+pub mod trace;
+
+/// Create a [KAssetSourceMemory] with `(path, content)` assets.
+pub fn create_memory_source(assets : &[(&str, &[u8])]) -> Arc<KAssetSourceMemory> {
+    let kasm = KAssetSourceMemory::new("memory");
+    for (path, data) in assets {
+        kasm.add_asset(*path, data.to_vec()).unwrap();
+    }
+    Arc::new(kasm)
+}
+
+/// Create a [KAssetSourceMemory] with assets of paths having their source name as content.
+pub fn create_named_source(name : &str, paths : &[&str]) -> Arc<KAssetSourceMemory> {
+    let kasm = KAssetSourceMemory::new(name);
+    for path in paths {
+        kasm.add_asset(*path, name.as_bytes().to_vec()).unwrap();
+    }
+    Arc::new(kasm)
+}
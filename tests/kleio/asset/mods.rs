@@ -0,0 +1,177 @@
+use std::{io::Read, sync::Arc};
+use olympus_kleio::asset::{KAssetBroker, KAssetSourceMemory, KModManager, KModManifest, KModManifestError, KModVersion, KModError, KModIssue};
+
+/// Create a mod source with manifest and asset `owner.txt` having mod identifier as content.
+fn create_mod(manifest : &str) -> Arc<KAssetSourceMemory> {
+    let id = KModManifest::parse(manifest).map(|manifest| manifest.id).unwrap_or_default();
+    let source = KAssetSourceMemory::new(&id);
+    source.add_asset("mod.manifest", manifest.as_bytes().to_vec()).unwrap();
+    source.add_asset("owner.txt", id.into_bytes()).unwrap();
+    Arc::new(source)
+}
+
+/// Read asset `owner.txt` of broker.
+fn read_owner(kab : &KAssetBroker) -> String {
+    let mut content = String::new();
+    kab.get_asset("owner.txt").unwrap().read_to_string(&mut content).unwrap();
+    content
+}
+
+#[test]
+/// Parse [KModManifest] and [KModVersion].
+///
+/// # Verification(s)
+/// V1 | KModVersion::parse() parses 1 to 3 numbers and orders versions.
+/// V2 | KModManifest::parse() parses all keys, ignoring comments and unknown keys.
+/// V3 | KModManifest::parse() must return Err() for invalid manifests.
+fn kasset_mods_manifest() {
+    // V1 | KModVersion::parse() parses 1 to 3 numbers and orders versions.
+    assert!(KModVersion::parse("1").unwrap() == KModVersion::new(1, 0, 0) && KModVersion::parse(" 1.2.3 ").unwrap() == KModVersion::new(1, 2, 3), "Wrong version parsed!");
+    assert!(KModVersion::new(1, 10, 0) > KModVersion::new(1, 9, 9) && KModVersion::new(2, 0, 0).to_string() == "2.0.0", "Wrong version order!");
+    for version in ["", "1.", "1.2.3.4", "a.1", "-1", "1.+2"] {
+        assert!(matches!(KModVersion::parse(version), Err(KModManifestError::InvalidVersion)), "Version {} should be invalid!", version);
+    }
+
+    // V2 | KModManifest::parse() parses all keys, ignoring comments and unknown keys.
+    let manifest = KModManifest::parse("# Comment\nid = castle\nversion = 1.2\n\nname = Castle Siege\ndepends = core >= 1.0, music\nincompatible = old_castle\nafter = hd, sky\nauthor = Someone").unwrap();
+    assert!(manifest.id == "castle" && manifest.name == "Castle Siege" && manifest.version == KModVersion::new(1, 2, 0), "Wrong identity!");
+    assert!(manifest.dependencies.len() == 2 && manifest.dependencies[0].id == "core" && manifest.dependencies[0].min_version == Some(KModVersion::new(1, 0, 0))
+        && manifest.dependencies[1].id == "music" && manifest.dependencies[1].min_version.is_none(), "Wrong dependencies!");
+    assert!(manifest.incompatibilities == vec!["old_castle"] && manifest.load_after == vec!["hd", "sky"], "Wrong hints!");
+    assert!(KModManifest::parse("id = core\nversion = 1").unwrap().name == "core", "Name should be identifier!");
+
+    // V3 | KModManifest::parse() must return Err() for invalid manifests.
+    for (content, expected) in [("id = core", KModManifestError::MissingField), ("version = 1", KModManifestError::MissingField),
+        ("id = core\nversion = 1\nid = other", KModManifestError::InvalidLine), ("id = core\nversion = 1\nbroken", KModManifestError::InvalidLine),
+        ("id = my mod\nversion = 1", KModManifestError::InvalidId), ("id = core\nversion = 1\ndepends = ../x", KModManifestError::InvalidId),
+        ("id = core\nversion = one", KModManifestError::InvalidVersion), ("id = core\nversion = 1\ndepends = a >= b", KModManifestError::InvalidVersion)] {
+        assert!(KModManifest::parse(content) == Err(expected), "Manifest {:?} should be {:?}!", content, expected);
+    }
+}
+
+#[test]
+/// Resolve load order of [KModManager].
+///
+/// # Verification(s)
+/// V1 | Mods are loaded after dependencies and after hints, ties ordered by identifier.
+/// V2 | KModManager::add_mod() must return Err() for sources without valid manifest and duplicated mods.
+/// V3 | Mods with missing or too old dependencies aren't loaded, nor their dependents.
+/// V4 | Mods incompatible with a mod loaded and mods in dependency cycles aren't loaded.
+/// V5 | Mods disabled aren't loaded and their dependents report a disabled dependency.
+/// V6 | Hints contradicting dependencies or other hints are ignored and reported without failing mods.
+fn kasset_mods_resolve() {
+    let manager = KModManager::new(Arc::new(KAssetBroker::new()));
+
+    // V1 | Mods are loaded after dependencies and after hints, ties ordered by identifier.
+    manager.add_mod(create_mod("id = castle\nversion = 1\ndepends = core >= 1.2\nafter = zeta, missing")).unwrap();
+    manager.add_mod(create_mod("id = zeta\nversion = 1")).unwrap();
+    manager.add_mod(create_mod("id = core\nversion = 1.2.1")).unwrap();
+    manager.add_mod(create_mod("id = alpha\nversion = 1")).unwrap();
+    let resolution = manager.resolve();
+    assert!(resolution.order == vec!["alpha", "core", "zeta", "castle"] && resolution.is_valid(), "Wrong order {:?}!", resolution);
+
+    // V2 | KModManager::add_mod() must return Err() for sources without valid manifest and duplicated mods.
+    assert!(matches!(manager.add_mod(Arc::new(KAssetSourceMemory::new("empty"))), Err(KModError::ManifestNotFound)), "Manifest shouldn't be found!");
+    let invalid = KAssetSourceMemory::new("invalid");
+    invalid.add_asset("mod.manifest", "id = invalid".as_bytes().to_vec()).unwrap();
+    assert!(matches!(manager.add_mod(Arc::new(invalid)), Err(KModError::InvalidManifest)), "Manifest should be invalid!");
+    assert!(matches!(manager.add_mod(create_mod("id = core\nversion = 2")), Err(KModError::ModAlreadyExists)), "Mod should already exist!");
+
+    // V3 | Mods with missing or too old dependencies aren't loaded, nor their dependents.
+    manager.add_mod(create_mod("id = towers\nversion = 1\ndepends = castle, cannons")).unwrap();
+    manager.add_mod(create_mod("id = modern\nversion = 1\ndepends = core >= 2")).unwrap();
+    manager.add_mod(create_mod("id = modern_ui\nversion = 1\ndepends = modern")).unwrap();
+    let resolution = manager.resolve();
+    assert!(resolution.order == vec!["alpha", "core", "zeta", "castle"], "Wrong order {:?}!", resolution);
+    assert!(resolution.failed == vec!["towers", "modern", "modern_ui"], "Wrong mods failed {:?}!", resolution);
+    assert!(resolution.issues.contains(&KModIssue::MissingDependency { id : "towers".to_owned(), dependency : "cannons".to_owned() }), "Missing dependency should be reported!");
+    assert!(resolution.issues.contains(&KModIssue::VersionMismatch { id : "modern".to_owned(), dependency : "core".to_owned(),
+        required : KModVersion::new(2, 0, 0), found : KModVersion::new(1, 2, 1) }), "Version mismatch should be reported!");
+    assert!(resolution.issues.contains(&KModIssue::DisabledDependency { id : "modern_ui".to_owned(), dependency : "modern".to_owned() }), "Disabled dependency should be reported!");
+    for id in ["towers", "modern", "modern_ui"] {
+        manager.remove_mod(id).unwrap();
+    }
+    assert!(matches!(manager.remove_mod("towers"), Err(KModError::ModNotFound)), "Mod shouldn't be found!");
+
+    // V4 | Mods incompatible with a mod loaded and mods in dependency cycles aren't loaded.
+    manager.add_mod(create_mod("id = classic\nversion = 1\nincompatible = castle")).unwrap();
+    manager.add_mod(create_mod("id = day\nversion = 1\ndepends = night")).unwrap();
+    manager.add_mod(create_mod("id = night\nversion = 1\ndepends = day")).unwrap();
+    manager.add_mod(create_mod("id = dusk\nversion = 1\ndepends = day")).unwrap();
+    let resolution = manager.resolve();
+    assert!(resolution.order == vec!["alpha", "core", "zeta", "castle"], "Wrong order {:?}!", resolution);
+    assert!(resolution.issues.contains(&KModIssue::Incompatible { id : "classic".to_owned(), other : "castle".to_owned() }), "Incompatibility should be reported!");
+    assert!(resolution.issues.contains(&KModIssue::Cycle { ids : vec!["day".to_owned(), "dusk".to_owned(), "night".to_owned()] }), "Cycle should be reported {:?}!", resolution);
+
+    // V5 | Mods disabled aren't loaded and their dependents report a disabled dependency.
+    manager.set_mod_enabled("castle", false).unwrap();
+    let resolution = manager.resolve();
+    assert!(!manager.is_mod_enabled("castle").unwrap() && resolution.order.contains(&"classic".to_owned()), "Classic should be loaded without castle!");
+    assert!(!resolution.failed.contains(&"castle".to_owned()), "Disabled mod shouldn't be failed!");
+    assert!(matches!(manager.set_mod_enabled("missing", true), Err(KModError::ModNotFound)), "Mod shouldn't be found!");
+
+    // V6 | Hints contradicting dependencies or other hints are ignored and reported without failing mods.
+    let manager = KModManager::new(Arc::new(KAssetBroker::new()));
+    manager.add_mod(create_mod("id = moon\nversion = 1\nafter = stars")).unwrap();
+    manager.add_mod(create_mod("id = stars\nversion = 1\ndepends = moon")).unwrap();
+    manager.add_mod(create_mod("id = red\nversion = 1\nafter = blue")).unwrap();
+    manager.add_mod(create_mod("id = blue\nversion = 1\nafter = red")).unwrap();
+    let resolution = manager.resolve();
+    assert!(resolution.order == vec!["moon", "red", "blue", "stars"] && resolution.is_valid() && resolution.failed.is_empty(), "Wrong order {:?}!", resolution);
+    assert!(resolution.ignored_hints == vec![("moon".to_owned(), "stars".to_owned()), ("red".to_owned(), "blue".to_owned())], "Wrong hints ignored {:?}!", resolution);
+}
+
+#[test]
+/// Apply load order of [KModManager] to [KAssetBroker].
+///
+/// # Verification(s)
+/// V1 | KModManager::apply() adds mods loaded with priorities so mods loaded last win.
+/// V2 | Mods come before other sources of broker.
+/// V3 | KModManager::apply() removes sources of mods not loaded anymore.
+/// V4 | KModManager::remove_mod() removes source of mod from broker.
+/// V5 | KModManager::apply() leaves broker unchanged when a mod can't be added.
+fn kasset_mods_apply() {
+    let kab = Arc::new(KAssetBroker::new());
+    let base_id = kab.add_source(create_mod("id = base\nversion = 1")).unwrap();
+    let manager = KModManager::new(kab.clone());
+
+    manager.add_mod(create_mod("id = castle\nversion = 1\ndepends = core")).unwrap();
+    manager.add_mod(create_mod("id = core\nversion = 1")).unwrap();
+    manager.add_mod(create_mod("id = sky\nversion = 1\nafter = castle")).unwrap();
+    assert!(kab.get_source_count() == 1 && manager.get_source_id("core").unwrap().is_none(), "Mods shouldn't be added before apply!");
+
+    // V1 | KModManager::apply() adds mods loaded with priorities so mods loaded last win.
+    // V2 | Mods come before other sources of broker.
+    let resolution = manager.apply().unwrap();
+    assert!(resolution.order == vec!["core", "castle", "sky"], "Wrong order {:?}!", resolution);
+    let ids : Vec<_> = ["sky", "castle", "core"].iter().map(|id| manager.get_source_id(id).unwrap().unwrap()).collect();
+    assert!(kab.get_sources() == vec![ids[0], ids[1], ids[2], base_id], "Wrong priorities!");
+    assert!(read_owner(&kab) == "sky", "Sky should win!");
+
+    // V3 | KModManager::apply() removes sources of mods not loaded anymore.
+    manager.set_mod_enabled("core", false).unwrap();
+    let resolution = manager.apply().unwrap();
+    assert!(resolution.order == vec!["sky"] && resolution.failed == vec!["castle"], "Wrong resolution {:?}!", resolution);
+    assert!(kab.get_sources() == vec![ids[0], base_id] && manager.get_source_id("core").unwrap().is_none(), "Wrong sources!");
+
+    manager.set_mod_enabled("core", true).unwrap();
+    manager.set_mod_enabled("sky", false).unwrap();
+    manager.apply().unwrap();
+    assert!(read_owner(&kab) == "castle" && kab.get_source_count() == 3, "Castle should win!");
+    assert!(kab.get_sources()[2] == base_id, "Base should be last!");
+
+    // V4 | KModManager::remove_mod() removes source of mod from broker.
+    let castle_id = manager.get_source_id("castle").unwrap().unwrap();
+    manager.remove_mod("castle").unwrap();
+    assert!(!kab.has_source(castle_id) && read_owner(&kab) == "core", "Core should win!");
+
+    // V5 | KModManager::apply() leaves broker unchanged when a mod can't be added.
+    let sources = kab.get_sources();
+    let added = create_mod("id = zinc\nversion = 1");
+    kab.add_source(added.clone()).unwrap();
+    let sources = [sources, vec![*kab.get_sources().last().unwrap()]].concat();
+    manager.add_mod(create_mod("id = arena\nversion = 1")).unwrap();
+    manager.add_mod(added).unwrap();
+    assert!(manager.apply().is_err() && kab.get_sources() == sources, "Broker should be unchanged!");
+    assert!(manager.get_source_id("arena").unwrap().is_none() && read_owner(&kab) == "core", "Arena shouldn't be added!");
+}
@@ -0,0 +1,121 @@
+use std::{path::PathBuf, fs, io::{Read, ErrorKind}, sync::Arc};
+use olympus_kleio::asset::{KAssetBroker, KAssetBrokerError, KAssetSinkFolder, KAssetCache, KAssetPath};
+use super::create_named_source;
+
+/// Root path of test folder
+static TEST_FOLDER: &str = "../target/tests/kleio/asset/";
+
+/// Read asset of broker as a string, or `None` if asset couldn't be read.
+fn read_asset(kab : &KAssetBroker, path : &str) -> Option<String> {
+    let mut content = String::new();
+    kab.get_asset(path).ok()?.read_to_string(&mut content).ok()?;
+    Some(content)
+}
+
+#[test]
+/// Mount sources at folders of [KAssetBroker].
+///
+/// # Verification(s)
+/// V1 | KAssetBroker::add_source_at() serves assets of source under mount point only.
+/// V2 | KAssetBroker::add_source_at() must return Err(KAssetBrokerError::InvalidMountPoint) for invalid mount point.
+/// V3 | Priorities apply to sources serving a path, mounted or not.
+/// V4 | KAssetBroker::set_source_mount() moves source and KAssetBroker::get_source_mount() gives mount point.
+/// V5 | Cached assets aren't served from an old mount point.
+fn kasset_mount_get_asset() {
+    let kab = KAssetBroker::new();
+    kab.set_cache(Some(Arc::new(KAssetCache::new(1024))));
+    let base_id = kab.add_source(create_named_source("base", &["audio/theme.ogg", "audio/hit.ogg", "mods/castle/wall.png", "readme.txt"])).unwrap();
+    let audio_id = kab.add_source_at(create_named_source("audio", &["theme.ogg", "music/intro.ogg"]), "audio").unwrap();
+    let castle_id = kab.add_source_at(create_named_source("castle", &["wall.png", "tower.png"]), "mods/castle/").unwrap();
+
+    // V1 | KAssetBroker::add_source_at() serves assets of source under mount point only.
+    assert!(read_asset(&kab, "audio/music/intro.ogg").as_deref() == Some("audio"), "Mounted asset should be found!");
+    assert!(read_asset(&kab, "mods/castle/tower.png").as_deref() == Some("castle"), "Mounted asset should be found!");
+    assert!(read_asset(&kab, "music/intro.ogg").is_none() && read_asset(&kab, "tower.png").is_none(), "Asset shouldn't be found outside of mount point!");
+    assert!(kab.get_asset("audio").is_err_and(|err| err.kind() == ErrorKind::NotFound), "Mount point shouldn't be an asset!");
+
+    // V2 | KAssetBroker::add_source_at() must return Err(KAssetBrokerError::InvalidMountPoint) for invalid mount point.
+    for mount in ["../audio", "/audio", "C:/audio"] {
+        assert!(matches!(kab.add_source_at(create_named_source("invalid", &[]), mount), Err(KAssetBrokerError::InvalidMountPoint)), "Mount point {} should be invalid!", mount);
+    }
+    assert!(kab.get_source_count() == 3, "Invalid source shouldn't be added!");
+
+    // V3 | Priorities apply to sources serving a path, mounted or not.
+    assert!(read_asset(&kab, "audio/theme.ogg").as_deref() == Some("base"), "Base should win theme!");
+    assert!(read_asset(&kab, "audio/hit.ogg").as_deref() == Some("base"), "Base should serve hit!");
+    kab.set_source_priority(audio_id, 0).unwrap();
+    kab.set_source_priority(castle_id, 0).unwrap();
+    assert!(read_asset(&kab, "audio/theme.ogg").as_deref() == Some("audio"), "Audio should win theme!");
+    assert!(read_asset(&kab, "mods/castle/wall.png").as_deref() == Some("castle"), "Castle should win wall!");
+    assert!(read_asset(&kab, "audio/hit.ogg").as_deref() == Some("base") && read_asset(&kab, "readme.txt").as_deref() == Some("base"), "Base should serve other assets!");
+
+    // V4 | KAssetBroker::set_source_mount() moves source and KAssetBroker::get_source_mount() gives mount point.
+    // V5 | Cached assets aren't served from an old mount point.
+    assert!(kab.get_source_mount(base_id).unwrap().is_root() && kab.get_source_mount(audio_id).unwrap() == "audio", "Wrong mount point!");
+    kab.set_source_mount(audio_id, "sound").unwrap();
+    assert!(kab.get_source_mount(audio_id).unwrap() == "sound", "Wrong mount point!");
+    assert!(read_asset(&kab, "sound/theme.ogg").as_deref() == Some("audio"), "Moved asset should be found!");
+    assert!(read_asset(&kab, "audio/theme.ogg").as_deref() == Some("base"), "Base should serve theme again!");
+    assert!(read_asset(&kab, "audio/music/intro.ogg").is_none(), "Asset shouldn't be found at old mount point!");
+    assert!(matches!(kab.set_source_mount(audio_id, ".."), Err(KAssetBrokerError::InvalidMountPoint)), "Mount point should be invalid!");
+    kab.remove_source(audio_id).unwrap();
+    assert!(matches!(kab.get_source_mount(audio_id), Err(KAssetBrokerError::SourceNotFound)), "Source shouldn't be found!");
+}
+
+#[test]
+/// List assets of mounted sources.
+///
+/// # Verification(s)
+/// V1 | KAssetBroker::list_assets() lists assets of mounted sources under their mount point.
+/// V2 | Listing a folder within a mount point lists only the matching assets of source.
+/// V3 | Listing a folder outside of mount point ignores source.
+fn kasset_mount_list_assets() {
+    let kab = KAssetBroker::new();
+    let base_id = kab.add_source(create_named_source("base", &["audio/theme.ogg", "readme.txt"])).unwrap();
+    let audio_id = kab.add_source_at(create_named_source("audio", &["theme.ogg", "music/intro.ogg"]), "audio").unwrap();
+
+    let list = |pattern : &str| -> Vec<String> {
+        kab.list_assets(pattern).expect("Assets should be listed!").iter().map(|entry| entry.path.to_string()).collect()
+    };
+
+    // V1 | KAssetBroker::list_assets() lists assets of mounted sources under their mount point.
+    assert!(list("**") == vec!["audio/music/intro.ogg", "audio/theme.ogg", "readme.txt"], "Wrong listing {:?}!", list("**"));
+    let entries = kab.list_assets("audio/theme.ogg").unwrap();
+    assert!(entries[0].source == base_id && entries[0].overridden == vec![audio_id], "Base should win theme!");
+
+    // V2 | Listing a folder within a mount point lists only the matching assets of source.
+    assert!(list("audio/music/*") == vec!["audio/music/intro.ogg"], "Wrong listing {:?}!", list("audio/music/*"));
+    assert!(list("audio/*.ogg") == vec!["audio/theme.ogg"], "Wrong listing {:?}!", list("audio/*.ogg"));
+
+    // V3 | Listing a folder outside of mount point ignores source.
+    assert!(list("music/**").is_empty() && list("aud/**").is_empty(), "Listing should be empty!");
+}
+
+#[test]
+/// Write assets through a mounted sink of [KAssetBroker].
+///
+/// # Verification(s)
+/// V1 | Assets written under mount point of sink are written relative to sink.
+/// V2 | Writing outside of mount point of sink must return Err() of kind InvalidInput.
+fn kasset_mount_sink() {
+    let folder_name: &str = &(TEST_FOLDER.to_owned() + "kasset_mount_sink/");
+    fs::create_dir_all(folder_name).unwrap();
+
+    let kab = KAssetBroker::new();
+    let sink_id = kab.set_sink(Arc::new(KAssetSinkFolder::new(PathBuf::from(folder_name)).unwrap())).unwrap();
+    kab.set_source_mount(sink_id, "saves").unwrap();
+
+    // V1 | Assets written under mount point of sink are written relative to sink.
+    kab.write_asset("saves/slot1.sav", &mut "Level 1".as_bytes()).unwrap();
+    kab.rename_asset("saves/slot1.sav", "saves/old/slot2.sav").unwrap();
+    assert!(read_asset(&kab, "saves/old/slot2.sav").as_deref() == Some("Level 1"), "Asset should be written!");
+    assert!(fs::read_to_string(folder_name.to_owned() + "old/slot2.sav").unwrap() == "Level 1", "Asset should be written relative to sink!");
+
+    // V2 | Writing outside of mount point of sink must return Err() of kind InvalidInput.
+    assert!(kab.write_asset("slot1.sav", &mut "Level 1".as_bytes()).is_err_and(|err| err.kind() == ErrorKind::InvalidInput), "Asset shouldn't be written!");
+    assert!(kab.write_asset("saves", &mut "Level 1".as_bytes()).is_err_and(|err| err.kind() == ErrorKind::InvalidInput), "Mount point shouldn't be written!");
+    assert!(kab.rename_asset("saves/old/slot2.sav", "slot2.sav").is_err_and(|err| err.kind() == ErrorKind::InvalidInput), "Asset shouldn't be renamed!");
+    assert!(kab.delete_asset(KAssetPath::new("saves/old/slot2.sav").unwrap()).is_ok(), "Asset should be deleted!");
+
+    fs::remove_dir_all(PathBuf::from(folder_name)).expect("Test couldn't be cleaned!");
+}
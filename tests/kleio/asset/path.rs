@@ -0,0 +1,151 @@
+use std::{path::PathBuf, fs, io::{Read, ErrorKind}, sync::Arc};
+use olympus_kleio::asset::{KAssetSource, KAssetSourceFolder, KAssetBroker, KAssetPath, KAssetPathError};
+
+#[cfg(unix)]
+use olympus_kleio::asset::{KAssetSink, KAssetSinkFolder};
+
+/// Root path of test folder
+static TEST_FOLDER: &str = "../target/tests/kleio/asset/";
+
+#[test]
+/// Canonicalise and reject [KAssetPath].
+///
+/// # Verification(s)
+/// V1 | KAssetPath::new() normalises separators, empty, `.` and `..` segments.
+/// V2 | KAssetPath::new() must return Err(KAssetPathError::AbsolutePath) for absolute paths.
+/// V3 | KAssetPath::new() must return Err(KAssetPathError::ParentEscape) for paths escaping root.
+/// V4 | KAssetPath::new() must return Err(KAssetPathError::InvalidCharacter) for control characters and `:`.
+/// V5 | KAssetPath::from_path() must return Err(KAssetPathError::NotUnicode) for paths that aren't unicode.
+fn kasset_path_new() {
+    // V1 | KAssetPath::new() normalises separators, empty, `.` and `..` segments.
+    for (path, expected) in [("a\\b.txt", "a/b.txt"), ("./a//b/./c.txt", "a/b/c.txt"), ("a/../b.txt", "b.txt"), ("a/b/", "a/b"), ("", ""), ("a/..", "")] {
+        assert!(KAssetPath::new(path).unwrap() == expected, "Path {} should be {}!", path, expected);
+    }
+    assert!(KAssetPath::new("a/..").unwrap().is_root(), "Path should be root!");
+
+    // V2 | KAssetPath::new() must return Err(KAssetPathError::AbsolutePath) for absolute paths.
+    for path in ["/x", "\\\\srv\\share", "C:/x", "c:x"] {
+        assert!(matches!(KAssetPath::new(path), Err(KAssetPathError::AbsolutePath)), "Path {} should be absolute!", path);
+    }
+
+    // V3 | KAssetPath::new() must return Err(KAssetPathError::ParentEscape) for paths escaping root.
+    for path in ["..", "../x", "a/../../x", "a\\..\\..\\x"] {
+        assert!(matches!(KAssetPath::new(path), Err(KAssetPathError::ParentEscape)), "Path {} should escape!", path);
+    }
+
+    // V4 | KAssetPath::new() must return Err(KAssetPathError::InvalidCharacter) for control characters and `:`.
+    for path in ["a\0b", "a\nb", "a/b:c"] {
+        assert!(matches!(KAssetPath::new(path), Err(KAssetPathError::InvalidCharacter)), "Path {:?} should be invalid!", path);
+    }
+
+    // V5 | KAssetPath::from_path() must return Err(KAssetPathError::NotUnicode) for paths that aren't unicode.
+    #[cfg(unix)]
+    {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+        let path = PathBuf::from(OsStr::from_bytes(b"a/\xFF.txt"));
+        assert!(matches!(KAssetPath::from_path(&path), Err(KAssetPathError::NotUnicode)), "Path should not be unicode!");
+    }
+    assert!(KAssetPath::from_path(&PathBuf::from("a/b.txt")).unwrap() == "a/b.txt", "Path should be converted!");
+}
+
+#[test]
+/// Use [KAssetPath] methods.
+///
+/// # Verification(s)
+/// V1 | KAssetPath::join() joins relative paths and rejects escaping paths.
+/// V2 | KAssetPath::get_parent() and KAssetPath::get_file_name() split path.
+/// V3 | KAssetPath::get_extension() ignores dot files.
+/// V4 | KAssetPath::is_within() and KAssetPath::strip_folder() don't match folders sharing prefix.
+fn kasset_path_methods() {
+    let path = KAssetPath::new("textures/stone.png").unwrap();
+
+    // V1 | KAssetPath::join() joins relative paths and rejects escaping paths.
+    assert!(KAssetPath::root().join("a.txt").unwrap() == "a.txt", "Root should join!");
+    assert!(path.get_parent().unwrap().join("../wood.png").unwrap() == "wood.png", "Path should join!");
+    assert!(matches!(path.join("/etc"), Err(KAssetPathError::AbsolutePath)), "Absolute path shouldn't join!");
+    assert!(matches!(path.join("../../../x"), Err(KAssetPathError::ParentEscape)), "Escaping path shouldn't join!");
+
+    // V2 | KAssetPath::get_parent() and KAssetPath::get_file_name() split path.
+    assert!(path.get_parent().unwrap() == "textures" && path.get_file_name() == Some("stone.png"), "Wrong split!");
+    assert!(path.get_parent().unwrap().get_parent().unwrap().is_root(), "Parent should be root!");
+    assert!(KAssetPath::root().get_parent().is_none() && KAssetPath::root().get_file_name().is_none(), "Root shouldn't have parent!");
+
+    // V3 | KAssetPath::get_extension() ignores dot files.
+    assert!(path.get_extension() == Some("png"), "Wrong extension!");
+    assert!(KAssetPath::new("a/.hidden").unwrap().get_extension().is_none(), "Dot file shouldn't have extension!");
+    assert!(KAssetPath::new("a.b/file").unwrap().get_extension().is_none(), "Folder extension shouldn't be used!");
+
+    // V4 | KAssetPath::is_within() and KAssetPath::strip_folder() don't match folders sharing prefix.
+    let folder = KAssetPath::new("textures").unwrap();
+    assert!(path.is_within(&folder) && path.is_within(&KAssetPath::root()), "Path should be within folder!");
+    assert!(!KAssetPath::new("textures2/a.png").unwrap().is_within(&folder) && !folder.is_within(&folder), "Path shouldn't be within folder!");
+    assert!(path.strip_folder(&folder).unwrap() == "stone.png", "Wrong path stripped!");
+    assert!(KAssetPath::new("textures2/a.png").unwrap().strip_folder(&folder).is_none(), "Path shouldn't be stripped!");
+}
+
+#[test]
+/// Sandbox [KAssetSourceFolder] with [KAssetPath].
+///
+/// # Verification(s)
+/// V1 | KAssetBroker::get_asset() must return Err() of kind InvalidInput wrapping KAssetPathError for escaping paths.
+/// V2 | KAssetSourceFolder is case-sensitive by default.
+/// V3 | KAssetSourceFolder::set_case_insensitive() matches paths ignoring case.
+/// V4 | Paths going through a symbolic link pointing outside of folder must return Err() of kind PermissionDenied and aren't listed, nor links to folders.
+/// V5 | KAssetSinkFolder refuses writes through a symbolic link pointing outside of folder.
+fn kasset_path_source_folder() {
+    let folder_name: &str = &(TEST_FOLDER.to_owned() + "kasset_path_source_folder/");
+    let _ = fs::remove_dir_all(folder_name);
+    fs::create_dir_all(folder_name.to_owned() + "base/textures").unwrap();
+    fs::write(folder_name.to_owned() + "base/textures/stone.png", "Stone").unwrap();
+    fs::write(folder_name.to_owned() + "secret.txt", "Secret").unwrap();
+
+    let kasf = Arc::new(KAssetSourceFolder::new(PathBuf::from(folder_name.to_owned() + "base")).unwrap());
+    let kab = KAssetBroker::new();
+    kab.add_source(kasf.clone()).unwrap();
+
+    // V1 | KAssetBroker::get_asset() must return Err() of kind InvalidInput wrapping KAssetPathError for escaping paths.
+    for (path, expected) in [("../secret.txt", KAssetPathError::ParentEscape), ("textures/../../secret.txt", KAssetPathError::ParentEscape)] {
+        let err = kab.get_asset(path).err().expect("Asset shouldn't escape source!");
+        assert!(err.kind() == ErrorKind::InvalidInput, "Wrong error kind given!");
+        assert!(err.get_ref().and_then(|err| err.downcast_ref::<KAssetPathError>()) == Some(&expected), "Wrong error given!");
+    }
+    let secret = std::path::absolute(folder_name.to_owned() + "secret.txt").unwrap();
+    assert!(kab.get_asset(secret).is_err_and(|err| err.kind() == ErrorKind::InvalidInput), "Absolute path shouldn't be read!");
+
+    // V2 | KAssetSourceFolder is case-sensitive by default.
+    let mixed = KAssetPath::new("TEXTURES/Stone.PNG").unwrap();
+    assert!(!kasf.is_case_insensitive() && !kasf.has_asset(&mixed), "Path shouldn't match ignoring case!");
+
+    // V3 | KAssetSourceFolder::set_case_insensitive() matches paths ignoring case.
+    kasf.set_case_insensitive(true);
+    assert!(kasf.has_asset(&mixed), "Path should match ignoring case!");
+    let mut content = String::new();
+    kab.get_asset(&mixed).unwrap().read_to_string(&mut content).unwrap();
+    assert!(content == "Stone", "Content is different that expected!");
+    assert!(kasf.list_assets(&KAssetPath::new("Textures").unwrap()).unwrap() == vec![KAssetPath::new("Textures/stone.png").unwrap()], "Folder should be listed ignoring case!");
+
+    #[cfg(unix)]
+    {
+        // V4 | Paths going through a symbolic link pointing outside of folder must return Err() of kind PermissionDenied and aren't listed, nor links to folders.
+        std::os::unix::fs::symlink("..", folder_name.to_owned() + "base/outside").unwrap();
+        std::os::unix::fs::symlink("../secret.txt", folder_name.to_owned() + "base/secret.txt").unwrap();
+        std::os::unix::fs::symlink("stone.png", folder_name.to_owned() + "base/textures/rock.png").unwrap();
+        std::os::unix::fs::symlink("textures", folder_name.to_owned() + "base/images").unwrap();
+        for path in ["outside/secret.txt", "secret.txt", "SECRET.TXT"] {
+            assert!(!kab.has_asset(path) && kab.get_asset(path).is_err(), "Asset {} shouldn't escape folder!", path);
+            let err = kasf.get_asset(&KAssetPath::new(path).unwrap()).err().expect("Asset shouldn't escape folder!");
+            assert!(err.kind() == ErrorKind::PermissionDenied, "Wrong error kind given!");
+        }
+        assert!(kab.has_asset("textures/rock.png"), "Link within folder should be followed!");
+        let assets = kasf.list_assets(&KAssetPath::root()).unwrap();
+        assert!(assets.len() == 2 && !assets.contains(&KAssetPath::new("secret.txt").unwrap()), "Wrong assets {:?}!", assets);
+        assert!(!assets.contains(&KAssetPath::new("images").unwrap()), "Link to folder shouldn't be listed as asset!");
+
+        // V5 | KAssetSinkFolder refuses writes through a symbolic link pointing outside of folder.
+        let kasf = KAssetSinkFolder::new(PathBuf::from(folder_name.to_owned() + "base")).unwrap();
+        let err = kasf.write_asset(&KAssetPath::new("outside/written.txt").unwrap(), &mut "Escape".as_bytes()).expect_err("Write shouldn't escape folder!");
+        assert!(err.kind() == ErrorKind::PermissionDenied && !PathBuf::from(folder_name.to_owned() + "written.txt").exists(), "Write shouldn't escape folder!");
+    }
+
+    fs::remove_dir_all(PathBuf::from(folder_name)).expect("Test couldn't be cleaned!");
+}
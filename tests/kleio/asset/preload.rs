@@ -0,0 +1,173 @@
+use std::{io::{Read, Cursor, ErrorKind}, sync::{Arc, Mutex, mpsc::{self, Receiver, Sender}}, thread, time::Duration};
+use olympus_kleio::asset::{KAssetSource, KAssetSourceMemory, KAssetBroker, KAssetStreamer, KAssetBundle, KAssetBundleError, KAssetLoadStatus,
+    KAssetLoadError, KAssetPath, KAssetPathError};
+
+/// Source blocking on "gate.txt" until released and failing on "broken.txt".
+struct GatedSource {
+    // Receive a message for each gate release
+    gate : Mutex<Receiver<()>>,
+}
+
+impl KAssetSource for GatedSource {
+    fn has_asset(&self, path: &KAssetPath) -> bool {
+        path.as_str() == "gate.txt" || path.as_str() == "broken.txt"
+    }
+
+    fn get_asset(&self, path: &KAssetPath) -> Result<Box<dyn Read>, std::io::Error> {
+        if path.as_str() == "broken.txt" {
+            return Err(std::io::Error::new(ErrorKind::InvalidData, "Broken asset!"));
+        }
+
+        self.gate.lock().unwrap().recv().unwrap();
+        Ok(Box::new(Cursor::new("gate")))
+    }
+}
+
+/// Create a broker with a [GatedSource] and a [KAssetSourceMemory] of 10 assets of 100 bytes.
+///
+/// Returns broker and sender releasing the gate.
+fn create_broker() -> (Arc<KAssetBroker>, Sender<()>) {
+    let (sender, receiver) = mpsc::channel();
+
+    let kasm = KAssetSourceMemory::new("level");
+    for i in 0..10 {
+        kasm.add_asset(format!("file{}.txt", i), vec![i; 100]).unwrap();
+    }
+    kasm.add_asset("level.bundle", "# Level\nfile0.txt\n\n  file1.txt  \n".as_bytes().to_vec()).unwrap();
+
+    let broker = Arc::new(KAssetBroker::new());
+    broker.add_source(Arc::new(GatedSource { gate : Mutex::new(receiver) })).unwrap();
+    broker.add_source(Arc::new(kasm)).unwrap();
+
+    (broker, sender)
+}
+
+#[test]
+/// Declare [KAssetBundle] from paths and manifests.
+///
+/// # Verification(s)
+/// V1 | KAssetBundle::from_paths() and add() keep paths in order without duplicates.
+/// V2 | KAssetBundle::from_paths() returns Err(KAssetPathError) for an invalid path.
+/// V3 | KAssetBundle::parse() ignores empty lines and comments.
+/// V4 | KAssetBundle::parse() returns Err(KAssetBundleError::InvalidPath) for an invalid path or the root.
+/// V5 | KAssetBundle::from_broker() reads manifest through broker, overridden by sources of higher priority.
+/// V6 | KAssetBundle::from_broker() returns Err(KAssetBundleError::ManifestNotFound) for a missing manifest.
+fn kasset_preload_bundle() {
+    // V1 | KAssetBundle::from_paths() and add() keep paths in order without duplicates.
+    let mut bundle = KAssetBundle::from_paths(["b.txt", "a.txt", "./b.txt"]).unwrap();
+    bundle.add("c.txt").unwrap();
+    bundle.add("a.txt").unwrap();
+    let paths : Vec<&str> = bundle.get_paths().iter().map(|path| path.as_str()).collect();
+    assert!(paths == ["b.txt", "a.txt", "c.txt"], "Wrong paths {:?}!", paths);
+    assert!(bundle.len() == 3 && !bundle.is_empty() && KAssetBundle::new().is_empty(), "Wrong length!");
+
+    // V2 | KAssetBundle::from_paths() returns Err(KAssetPathError) for an invalid path.
+    assert!(KAssetBundle::from_paths(["a.txt", "../b.txt"]) == Err(KAssetPathError::ParentEscape), "Path should be invalid!");
+
+    // V3 | KAssetBundle::parse() ignores empty lines and comments.
+    let bundle = KAssetBundle::parse("# Level 1\n\nterrain.raw\r\n  # Music\n audio\\music.ogg \n").unwrap();
+    assert!(bundle == KAssetBundle::from_paths(["terrain.raw", "audio/music.ogg"]).unwrap(), "Wrong bundle {:?}!", bundle);
+
+    // V4 | KAssetBundle::parse() returns Err(KAssetBundleError::InvalidPath) for an invalid path or the root.
+    for content in ["terrain.raw\n../escape.raw", "/etc/passwd", "."] {
+        assert!(KAssetBundle::parse(content) == Err(KAssetBundleError::InvalidPath), "Manifest {:?} should be invalid!", content);
+    }
+
+    // V5 | KAssetBundle::from_broker() reads manifest through broker, overridden by sources of higher priority.
+    let (broker, _sender) = create_broker();
+    assert!(KAssetBundle::from_broker(&broker, "level.bundle").unwrap() == KAssetBundle::from_paths(["file0.txt", "file1.txt"]).unwrap(), "Wrong bundle!");
+    let kasm = KAssetSourceMemory::new("mod");
+    kasm.add_asset("level.bundle", "file9.txt".as_bytes().to_vec()).unwrap();
+    let id = broker.add_source(Arc::new(kasm)).unwrap();
+    broker.set_source_priority(id, 0).unwrap();
+    assert!(KAssetBundle::from_broker(&broker, "level.bundle").unwrap() == KAssetBundle::from_paths(["file9.txt"]).unwrap(), "Manifest of mod should be read!");
+
+    // V6 | KAssetBundle::from_broker() returns Err(KAssetBundleError::ManifestNotFound) for a missing manifest.
+    assert!(KAssetBundle::from_broker(&broker, "missing.bundle") == Err(KAssetBundleError::ManifestNotFound), "Manifest shouldn't be found!");
+    assert!(KAssetBundle::from_broker(&broker, "../level.bundle") == Err(KAssetBundleError::ManifestNotFound), "Manifest shouldn't be found!");
+}
+
+#[test]
+/// Report progress of [KAssetPreload](olympus_kleio::asset::KAssetPreload).
+///
+/// # Verification(s)
+/// V1 | KAssetPreload::get_progress() reports assets and bytes loaded with an ETA while loading, pending assets not done.
+/// V2 | KAssetPreload::wait() reports final progress, with failed assets done.
+/// V3 | KAssetPreload::get_failures() reports each failed asset with its error in order of bundle.
+/// V4 | KAssetPreload::get_data() gives data of loaded assets.
+/// V5 | Preloading an empty bundle is done right away.
+fn kasset_preload_progress() {
+    let (broker, sender) = create_broker();
+    let streamer = KAssetStreamer::new(broker, 1).unwrap();
+    let bundle = KAssetBundle::from_paths(["file0.txt", "file1.txt", "gate.txt", "broken.txt", "missing.txt", "file2.txt"]).unwrap();
+    let preload = streamer.preload(&bundle, 0);
+
+    // V1 | KAssetPreload::get_progress() reports assets and bytes loaded with an ETA while loading, pending assets not done.
+    preload.get_handles()[1].wait().unwrap();
+    let progress = preload.get_progress();
+    assert!(progress.total_assets == 6 && progress.loaded_assets == 2 && progress.failed_assets == 0, "Wrong assets progress {:?}!", progress);
+    assert!(progress.loaded_bytes == 200 && progress.get_done_assets() == 2, "Wrong bytes progress {:?}!", progress);
+    assert!(progress.eta.is_some() && !progress.is_done() && !preload.is_done(), "Preload shouldn't be done!");
+    assert!((progress.get_ratio() - 2.0 / 6.0).abs() < 1e-9, "Wrong ratio!");
+    assert!(preload.get_failures().is_empty(), "Nothing should have failed yet!");
+
+    // V2 | KAssetPreload::wait() reports final progress, with failed assets done.
+    sender.send(()).unwrap();
+    let progress = preload.wait();
+    assert!(progress.loaded_assets == 4 && progress.failed_assets == 2 && progress.cancelled_assets == 0, "Wrong assets progress {:?}!", progress);
+    assert!(progress.loaded_bytes == 304 && progress.get_done_assets() == 6, "Wrong bytes progress {:?}!", progress);
+    assert!(progress.is_done() && progress.get_ratio() == 1.0 && progress.eta == Some(Duration::ZERO), "Preload should be done!");
+    assert!(preload.is_done(), "Preload should be done!");
+
+    // V3 | KAssetPreload::get_failures() reports each failed asset with its error in order of bundle.
+    let failures = preload.get_failures();
+    assert!(failures == [(KAssetPath::new("broken.txt").unwrap(), KAssetLoadError::ReadError), (KAssetPath::new("missing.txt").unwrap(), KAssetLoadError::NotFound)],
+        "Wrong failures {:?}!", failures);
+
+    // V4 | KAssetPreload::get_data() gives data of loaded assets.
+    assert!(preload.get_data("file2.txt").unwrap().as_ref() == [2; 100], "Wrong data!");
+    assert!(preload.get_data("gate.txt").unwrap().as_ref() == b"gate", "Wrong data!");
+    assert!(preload.get_data("broken.txt").is_none() && preload.get_data("file9.txt").is_none(), "Data shouldn't be given!");
+
+    // V5 | Preloading an empty bundle is done right away.
+    let progress = streamer.preload(&KAssetBundle::new(), 0).get_progress();
+    assert!(progress.is_done() && progress.get_ratio() == 1.0, "Empty preload should be done!");
+}
+
+#[test]
+/// Cancel [KAssetPreload](olympus_kleio::asset::KAssetPreload) when player backs out of loading screen.
+///
+/// # Verification(s)
+/// V1 | KAssetPreload::cancel() cancels assets queued and being loaded.
+/// V2 | Assets cancelled are done but aren't failures.
+/// V3 | KAssetPreload::cancel() does nothing once done.
+fn kasset_preload_cancel() {
+    let (broker, sender) = create_broker();
+    let streamer = KAssetStreamer::new(broker, 1).unwrap();
+    let mut bundle = KAssetBundle::from_paths(["gate.txt"]).unwrap();
+    for i in 0..10 {
+        bundle.add(format!("file{}.txt", i)).unwrap();
+    }
+    let preload = streamer.preload(&bundle, 0);
+
+    // Wait for worker to block on gate
+    for _ in 0..1000 {
+        if preload.get_handles()[0].get_status() == KAssetLoadStatus::Loading {
+            break;
+        }
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    // V1 | KAssetPreload::cancel() cancels assets queued and being loaded.
+    assert!(preload.cancel() == 11, "Every asset should be cancelled!");
+    sender.send(()).unwrap();
+
+    // V2 | Assets cancelled are done but aren't failures.
+    let progress = preload.wait();
+    assert!(progress.cancelled_assets == 11 && progress.loaded_assets == 0 && progress.is_done(), "Wrong progress {:?}!", progress);
+    assert!(progress.get_ratio() == 1.0 && progress.loaded_bytes == 0, "Wrong progress {:?}!", progress);
+    assert!(preload.get_failures().is_empty(), "Cancelled assets aren't failures!");
+
+    // V3 | KAssetPreload::cancel() does nothing once done.
+    assert!(preload.cancel() == 0, "Nothing should be cancelled!");
+}
@@ -0,0 +1,138 @@
+use std::{io::Read, sync::Arc};
+use olympus_kleio::asset::{KAssetBroker, KAssetBrokerError, KAssetSourceMemory, KAssetCache, KAssetProvenanceStatus, KModManager, KAssetPath,
+    KAssetLocalization, KAssetLocaleLayout, KAssetTags, KAssetDecompression};
+use super::create_named_source;
+
+/// "Hello, world!" compressed with gzip.
+static HELLO_GZIP : [u8; 33] = [0x1F, 0x8B, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF, 0xF3, 0x48, 0xCD, 0xC9, 0xC9, 0xD7, 0x51, 0x28,
+    0xCF, 0x2F, 0xCA, 0x49, 0x51, 0x04, 0x00, 0xE6, 0xC6, 0xE6, 0xEB, 0x0D, 0x00, 0x00, 0x00];
+
+#[test]
+/// Get provenance of assets of [KAssetBroker].
+///
+/// # Verification(s)
+/// V1 | KAssetBroker::get_provenance() reports source serving asset and sources shadowed, missing or not mounted.
+/// V2 | KAssetBroker::get_provenance() reports asset served from cache.
+/// V3 | KAssetBroker::get_provenance() reports asset not found and must return Err() for invalid paths.
+/// V4 | KAssetProvenance is written as a table with source names.
+fn kasset_provenance_get() {
+    let kab = KAssetBroker::new();
+    let castle_id = kab.add_source(create_named_source("castle", &["textures/wall.png"])).unwrap();
+    let audio_id = kab.add_source_at(create_named_source("audio", &["theme.ogg"]), "audio").unwrap();
+    let empty_id = kab.add_source(create_named_source("empty", &[])).unwrap();
+    let base_id = kab.add_source(create_named_source("base", &["textures/wall.png", "textures/floor.png"])).unwrap();
+    kab.set_source_name(castle_id, "castle").unwrap();
+    kab.set_source_name(base_id, "base").unwrap();
+
+    // V1 | KAssetBroker::get_provenance() reports source serving asset and sources shadowed, missing or not mounted.
+    let provenance = kab.get_provenance("textures/wall.png").unwrap();
+    let status : Vec<_> = provenance.sources.iter().map(|(source, status)| (source.id, *status)).collect();
+    assert!(status == vec![(castle_id, KAssetProvenanceStatus::Serves), (audio_id, KAssetProvenanceStatus::NotMounted),
+        (empty_id, KAssetProvenanceStatus::Missing), (base_id, KAssetProvenanceStatus::Shadowed)], "Wrong status {:?}!", status);
+    assert!(provenance.get_source().unwrap().id == castle_id && provenance.get_shadowed().iter().map(|source| source.id).collect::<Vec<_>>() == vec![base_id], "Wrong sources!");
+    assert!(provenance.sources[1].0.mount == "audio" && provenance.sources[3].0.priority == 3, "Wrong source information!");
+    assert!(kab.get_provenance("textures/floor.png").unwrap().get_source().unwrap().id == base_id, "Base should serve floor!");
+
+    // V2 | KAssetBroker::get_provenance() reports asset served from cache.
+    kab.set_cache(Some(Arc::new(KAssetCache::new(1024))));
+    kab.get_asset("textures/floor.png").unwrap().read_to_end(&mut Vec::new()).unwrap();
+    assert!(kab.get_provenance("textures/floor.png").unwrap().sources[3].1 == KAssetProvenanceStatus::ServesCached, "Floor should be cached!");
+
+    // V3 | KAssetBroker::get_provenance() reports asset not found and must return Err() for invalid paths.
+    let provenance = kab.get_provenance("missing.png").unwrap();
+    assert!(provenance.get_source().is_none() && provenance.get_shadowed().is_empty(), "Asset shouldn't be found!");
+    assert!(kab.get_provenance("../wall.png").is_err(), "Path should be invalid!");
+
+    // V4 | KAssetProvenance is written as a table with source names.
+    let table = kab.get_provenance("textures/wall.png").unwrap().to_string();
+    let lines : Vec<&str> = table.lines().collect();
+    assert!(lines[0] == "Asset textures/wall.png served by castle", "Wrong title {}!", lines[0]);
+    assert!(lines[1] == "Priority | Source | Mount  | Status" && lines[2] == "---------+--------+--------+------------", "Wrong header {}!", table);
+    assert!(lines[3] == "0        | castle | /      | serves", "Wrong row {}!", lines[3]);
+    assert!(lines[4] == format!("1        | #{:<5} | /audio | not mounted", audio_id.get_value()), "Wrong row {}!", lines[4]);
+    assert!(lines[6] == "3        | base   | /      | shadowed", "Wrong row {}!", lines[6]);
+    assert!(matches!(kab.set_source_name(castle_id, "castle"), Ok(())) && kab.get_source_name(castle_id).unwrap() == "castle", "Wrong name!");
+    kab.remove_source(castle_id).unwrap();
+    assert!(matches!(kab.get_source_name(castle_id), Err(KAssetBrokerError::SourceNotFound)), "Source shouldn't be found!");
+}
+
+#[test]
+/// Get provenance of assets resolved like [KAssetBroker::get_asset] would.
+///
+/// # Verification(s)
+/// V1 | KAssetBroker::get_provenance() reports source serving localized variant of asset.
+/// V2 | KAssetBroker::get_provenance() reports source serving tagged variant of asset.
+/// V3 | KAssetBroker::get_provenance() reports source serving asset decompressed, shadowing sources of lower priority.
+/// V4 | KAssetProvenance is written with path resolved.
+fn kasset_provenance_resolution() {
+    let kab = KAssetBroker::new();
+    let ui_id = kab.add_source(create_named_source("ui", &["ui/title.txt", "ui/title.fr.txt"])).unwrap();
+    let textures_id = kab.add_source(create_named_source("textures", &["textures/rock.png", "textures/rock@quality=high.png"])).unwrap();
+    let compressed = KAssetSourceMemory::new("compressed");
+    compressed.add_asset("config.json.gz", HELLO_GZIP.to_vec()).unwrap();
+    let compressed_id = kab.add_source(Arc::new(compressed)).unwrap();
+    let base_id = kab.add_source(create_named_source("base", &["config.json"])).unwrap();
+
+    // V1 | KAssetBroker::get_provenance() reports source serving localized variant of asset.
+    kab.set_localization(Some(KAssetLocalization::new("fr", KAssetLocaleLayout::Suffix).unwrap()));
+    let provenance = kab.get_provenance("ui/title.txt").unwrap();
+    assert!(provenance.path == "ui/title.txt" && provenance.resolved == "ui/title.fr.txt", "Wrong paths {:?}!", provenance);
+    assert!(provenance.get_source().unwrap().id == ui_id && provenance.sources[0].1 == KAssetProvenanceStatus::Serves, "Ui should serve title!");
+
+    // V2 | KAssetBroker::get_provenance() reports source serving tagged variant of asset.
+    kab.set_tags(KAssetTags::new(&["quality=high"]).unwrap());
+    let provenance = kab.get_provenance("textures/rock.png").unwrap();
+    assert!(provenance.resolved == "textures/rock@quality=high.png" && provenance.get_source().unwrap().id == textures_id, "Wrong provenance {:?}!", provenance);
+
+    // V3 | KAssetBroker::get_provenance() reports source serving asset decompressed, shadowing sources of lower priority.
+    assert!(kab.get_provenance("config.json").unwrap().get_source().unwrap().id == base_id, "Base should serve config without decompression!");
+    kab.set_decompression(Some(KAssetDecompression::default()));
+    let provenance = kab.get_provenance("config.json").unwrap();
+    assert!(provenance.sources[2].1 == KAssetProvenanceStatus::ServesDecompressed && provenance.get_source().unwrap().id == compressed_id, "Wrong provenance {:?}!", provenance);
+    assert!(provenance.get_shadowed().iter().map(|source| source.id).collect::<Vec<_>>() == vec![base_id], "Base should be shadowed!");
+    let mut content = String::new();
+    kab.get_asset("config.json").unwrap().read_to_string(&mut content).unwrap();
+    assert!(content == "Hello, world!", "Decompressed config should be served!");
+
+    // V4 | KAssetProvenance is written with path resolved.
+    let table = kab.get_provenance(KAssetPath::new("ui/title.txt").unwrap()).unwrap().to_string();
+    assert!(table.lines().next() == Some(format!("Asset ui/title.txt served by #{} as ui/title.fr.txt", ui_id.get_value()).as_str()), "Wrong title {}!", table);
+    assert!(kab.get_provenance("config.json").unwrap().to_string().contains("serves (decompressed)"), "Wrong status written!");
+}
+
+#[test]
+/// Report assets overridden in [KAssetBroker].
+///
+/// # Verification(s)
+/// V1 | KAssetBroker::get_override_report() reports assets of more than one source with source serving them and sources shadowed.
+/// V2 | Mods applied by KModManager are named in report.
+/// V3 | KAssetOverrideReport is written as a table.
+fn kasset_provenance_override_report() {
+    let kab = Arc::new(KAssetBroker::new());
+    let base_id = kab.add_source(create_named_source("base", &["textures/wall.png", "textures/floor.png", "readme.txt"])).unwrap();
+    kab.set_source_name(base_id, "base").unwrap();
+
+    let manager = KModManager::new(kab.clone());
+    let castle = create_named_source("castle", &["textures/wall.png", "textures/tower.png"]);
+    castle.add_asset("mod.manifest", "id = castle\nversion = 1.2".as_bytes().to_vec()).unwrap();
+    let sky = create_named_source("sky", &["textures/wall.png", "textures/floor.png"]);
+    sky.add_asset("mod.manifest", "id = sky\nversion = 2\nafter = castle".as_bytes().to_vec()).unwrap();
+    manager.add_mod(castle).unwrap();
+    manager.add_mod(sky).unwrap();
+    manager.apply().unwrap();
+
+    // V1 | KAssetBroker::get_override_report() reports assets of more than one source with source serving them and sources shadowed.
+    // V2 | Mods applied by KModManager are named in report.
+    let report = kab.get_override_report("textures/**").unwrap();
+    let overrides : Vec<(String, String, Vec<String>)> = report.overrides.iter().map(|entry| (entry.path.to_string(), entry.source.to_string(),
+        entry.shadowed.iter().map(|source| source.to_string()).collect())).collect();
+    assert!(overrides == vec![("textures/floor.png".to_owned(), "sky 2.0.0".to_owned(), vec!["base".to_owned()]),
+        ("textures/wall.png".to_owned(), "sky 2.0.0".to_owned(), vec!["castle 1.2.0".to_owned(), "base".to_owned()])], "Wrong overrides {:?}!", overrides);
+    assert!(kab.get_override_report("readme.txt").unwrap().overrides.is_empty(), "Readme shouldn't be overridden!");
+
+    // V3 | KAssetOverrideReport is written as a table.
+    let table = report.to_string();
+    let lines : Vec<&str> = table.lines().collect();
+    assert!(lines == vec!["2 assets overridden", "Asset              | Source    | Shadowed", "-------------------+-----------+-------------------",
+        "textures/floor.png | sky 2.0.0 | base", "textures/wall.png  | sky 2.0.0 | castle 1.2.0, base"], "Wrong table {}!", table);
+}
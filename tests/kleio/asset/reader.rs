@@ -0,0 +1,135 @@
+use std::{path::PathBuf, fs, io::{Read, Seek, SeekFrom, Cursor}, sync::Arc};
+use olympus_kleio::asset::{KAssetSource, KAssetSourceFolder, KAssetSourceMemory, KAssetBroker, KAssetCache, KAssetPath};
+
+/// Root path of test folder
+static TEST_FOLDER: &str = "../target/tests/kleio/asset/";
+
+/// Content of assets read by tests.
+static TEST_CONTENT: &str = "Hello, world! This asset is read by ranges.";
+
+/// Source only implementing [KAssetSource::get_asset] with sequential reads.
+struct SequentialSource;
+
+impl KAssetSource for SequentialSource {
+    fn has_asset(&self, path: &KAssetPath) -> bool {
+        path.as_str() == "sequential.txt"
+    }
+
+    fn get_asset(&self, path: &KAssetPath) -> Result<Box<dyn Read>, std::io::Error> {
+        match self.has_asset(path) {
+            true => Ok(Box::new(Cursor::new(TEST_CONTENT))),
+            false => Err(std::io::Error::new(std::io::ErrorKind::NotFound, "Asset not found!")),
+        }
+    }
+}
+
+/// Verify seekable reader and ranges of asset `path` of `source` against [TEST_CONTENT].
+///
+/// # Panic
+/// Will panic if reads are different than expected.
+fn verify_source(source : &dyn KAssetSource, path : &str) {
+    let mut reader = source.get_asset_seekable(&KAssetPath::new(path).unwrap()).expect("Asset should be seekable!");
+    assert!(reader.get_length() == TEST_CONTENT.len() as u64, "Wrong length {}!", reader.get_length());
+
+    let mut content = String::new();
+    reader.seek(SeekFrom::End(-7)).unwrap();
+    reader.read_to_string(&mut content).unwrap();
+    assert!(content == "ranges.", "Content is different that expected!");
+
+    let mut content = String::new();
+    reader.seek(SeekFrom::Start(7)).unwrap();
+    reader.by_ref().take(5).read_to_string(&mut content).unwrap();
+    assert!(content == "world", "Content is different that expected!");
+
+    let mut content = String::new();
+    source.get_asset_range(&KAssetPath::new(path).unwrap(), 14, 4).unwrap().read_to_string(&mut content).unwrap();
+    assert!(content == "This", "Range is different that expected!");
+
+    let mut content = String::new();
+    source.get_asset_range(&KAssetPath::new(path).unwrap(), 36, 100).unwrap().read_to_string(&mut content).unwrap();
+    assert!(content == "ranges.", "Range should be truncated!");
+
+    let mut content = String::new();
+    source.get_asset_range(&KAssetPath::new(path).unwrap(), 100, 10).unwrap().read_to_string(&mut content).unwrap();
+    assert!(content.is_empty(), "Range after asset should be empty!");
+}
+
+#[test]
+/// Seek and read ranges of assets of [KAssetSourceFolder], [KAssetSourceMemory] and default implementation.
+///
+/// # Verification(s)
+/// V1 | KAssetSourceFolder::get_asset_seekable() and get_asset_range() read file correctly.
+/// V2 | KAssetSourceMemory::get_asset_seekable() and get_asset_range() read buffer correctly.
+/// V3 | Default KAssetSource::get_asset_seekable() and get_asset_range() read asset correctly.
+/// V4 | Seekable and ranged reads of missing asset must return Err().
+fn kasset_reader_sources() {
+    let folder_name: &str = &(TEST_FOLDER.to_owned() + "kasset_reader_sources/");
+    fs::create_dir_all(folder_name).unwrap();
+    fs::write(folder_name.to_owned() + "file.txt", TEST_CONTENT).unwrap();
+
+    // V1 | KAssetSourceFolder::get_asset_seekable() and get_asset_range() read file correctly.
+    let kasf = KAssetSourceFolder::new(PathBuf::from(folder_name)).unwrap();
+    verify_source(&kasf, "file.txt");
+
+    // V2 | KAssetSourceMemory::get_asset_seekable() and get_asset_range() read buffer correctly.
+    let kasm = KAssetSourceMemory::new("reader");
+    kasm.add_static_asset(PathBuf::from("static.txt"), TEST_CONTENT.as_bytes()).unwrap();
+    verify_source(&kasm, "static.txt");
+
+    // V3 | Default KAssetSource::get_asset_seekable() and get_asset_range() read asset correctly.
+    verify_source(&SequentialSource, "sequential.txt");
+
+    // V4 | Seekable and ranged reads of missing asset must return Err().
+    let sources : [&dyn KAssetSource; 3] = [&kasf, &kasm, &SequentialSource];
+    for source in sources {
+        assert!(source.get_asset_seekable(&KAssetPath::new("missing.txt").unwrap()).is_err(), "Missing asset shouldn't be seekable!");
+        assert!(source.get_asset_range(&KAssetPath::new("missing.txt").unwrap(), 0, 1).is_err(), "Missing asset shouldn't be read!");
+    }
+
+    fs::remove_dir_all(PathBuf::from(folder_name)).expect("Test couldn't be cleaned!");
+}
+
+#[test]
+/// Seek and read ranges of assets from [KAssetBroker].
+///
+/// # Verification(s)
+/// V1 | KAssetBroker::get_asset_seekable() and get_asset_range() read asset of source of highest priority.
+/// V2 | Cached assets are served from cache.
+/// V3 | Seekable and ranged reads of missing asset must return Err().
+fn kasset_reader_broker() {
+    let kasm = KAssetSourceMemory::new("high");
+    kasm.add_static_asset(PathBuf::from("shared.txt"), TEST_CONTENT.as_bytes()).unwrap();
+    let kasm = Arc::new(kasm);
+
+    let kab = KAssetBroker::new();
+    kab.add_source(kasm.clone()).unwrap();
+    kab.add_source(Arc::new(SequentialSource)).unwrap();
+
+    // V1 | KAssetBroker::get_asset_seekable() and get_asset_range() read asset of source of highest priority.
+    let mut reader = kab.get_asset_seekable(PathBuf::from("shared.txt")).unwrap();
+    assert!(reader.get_length() == TEST_CONTENT.len() as u64, "Wrong length!");
+    let mut content = String::new();
+    reader.seek(SeekFrom::Start(7)).unwrap();
+    reader.take(5).read_to_string(&mut content).unwrap();
+    assert!(content == "world", "Content is different that expected!");
+
+    let mut content = String::new();
+    kab.get_asset_range(PathBuf::from("sequential.txt"), 36, 100).unwrap().read_to_string(&mut content).unwrap();
+    assert!(content == "ranges.", "Range is different that expected!");
+
+    // V2 | Cached assets are served from cache.
+    let cache = Arc::new(KAssetCache::new(1024));
+    kab.set_cache(Some(cache.clone()));
+    kab.get_asset(PathBuf::from("shared.txt")).unwrap();
+    kasm.remove_asset(PathBuf::from("shared.txt")).unwrap();
+
+    let mut content = String::new();
+    kab.get_asset_range(PathBuf::from("shared.txt"), 0, 5).unwrap().read_to_string(&mut content).unwrap();
+    assert!(content == "Hello", "Range should be served from cache!");
+    assert!(kab.get_asset_seekable(PathBuf::from("shared.txt")).unwrap().get_length() == TEST_CONTENT.len() as u64, "Asset should be served from cache!");
+    assert!(cache.get_statistics().hits == 2, "Cache hits expected!");
+
+    // V3 | Seekable and ranged reads of missing asset must return Err().
+    assert!(kab.get_asset_seekable(PathBuf::from("missing.txt")).is_err(), "Missing asset shouldn't be seekable!");
+    assert!(kab.get_asset_range(PathBuf::from("missing.txt"), 0, 1).is_err(), "Missing asset shouldn't be read!");
+}
@@ -0,0 +1,203 @@
+use std::{path::PathBuf, fs, io::{Read, ErrorKind}, sync::Arc};
+use olympus_kleio::asset::{KAssetSink, KAssetSource, KAssetSinkFolder, KAssetSinkFolderError, KAssetSourceMemory, KAssetBroker, KAssetCache, KAssetPath,
+    KAssetDecompression};
+
+/// Root path of test folder
+static TEST_FOLDER: &str = "../target/tests/kleio/asset/";
+
+/// "one" compressed with gzip.
+static ONE_GZIP : [u8; 23] = [0x1F, 0x8B, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0xCB, 0xCF, 0x4B, 0x05, 0x00, 0xF1, 0x86, 0x6C, 0x7A,
+    0x03, 0x00, 0x00, 0x00];
+
+/// "two" compressed with gzip.
+static TWO_GZIP : [u8; 23] = [0x1F, 0x8B, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0x2B, 0x29, 0xCF, 0x07, 0x00, 0x66, 0x8A, 0xCA, 0x11,
+    0x03, 0x00, 0x00, 0x00];
+
+/// Reader giving some bytes, then failing.
+struct FailingReader {
+    // Bytes given before failing
+    given : bool,
+}
+
+impl Read for FailingReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.given {
+            return Err(std::io::Error::other("Reader failed!"));
+        }
+        self.given = true;
+        buf[..7].copy_from_slice(b"Partial");
+        Ok(7)
+    }
+}
+
+/// Read asset of source as string.
+fn read_asset(source : &dyn KAssetSource, path : &str) -> String {
+    let mut content = String::new();
+    source.get_asset(&KAssetPath::new(path).unwrap()).expect("Asset should be found!").read_to_string(&mut content).unwrap();
+    content
+}
+
+/// Read asset of broker as string.
+fn read_broker_asset(kab : &KAssetBroker, path : &str) -> String {
+    let mut content = String::new();
+    kab.get_asset(PathBuf::from(path)).expect("Asset should be found!").read_to_string(&mut content).unwrap();
+    content
+}
+
+#[test]
+/// Create [KAssetSinkFolder].
+///
+/// # Verification(s)
+/// V1 | KAssetSinkFolder::new() creates missing folder and its parents.
+/// V2 | KAssetSinkFolder::new() must return Err(KAssetSinkFolderError::PathIsNotFolder) for a file.
+fn kasset_sink_folder_create() {
+    let folder_name: &str = &(TEST_FOLDER.to_owned() + "kasset_sink_folder_create/");
+    let _ = fs::remove_dir_all(folder_name);
+
+    // V1 | KAssetSinkFolder::new() creates missing folder and its parents.
+    KAssetSinkFolder::new(PathBuf::from(folder_name.to_owned() + "user/saves")).expect("Sink should be created!");
+    assert!(PathBuf::from(folder_name.to_owned() + "user/saves").is_dir(), "Folder should be created!");
+
+    // V2 | KAssetSinkFolder::new() must return Err(KAssetSinkFolderError::PathIsNotFolder) for a file.
+    fs::write(folder_name.to_owned() + "file.txt", "Hello").unwrap();
+    match KAssetSinkFolder::new(PathBuf::from(folder_name.to_owned() + "file.txt")) {
+        Ok(_) => panic!("KAssetSinkFolder::new() must return Err(KAssetSinkFolderError::PathIsNotFolder) for a file."),
+        Err(err) => assert!(matches!(err, KAssetSinkFolderError::PathIsNotFolder), "Wrong error given!"),
+    }
+
+    fs::remove_dir_all(PathBuf::from(folder_name)).expect("Test couldn't be cleaned!");
+}
+
+#[test]
+/// Create, overwrite, delete and rename assets of [KAssetSinkFolder].
+///
+/// # Verification(s)
+/// V1 | KAssetSink::create_asset() creates asset and parent folders, but never overwrites.
+/// V2 | KAssetSink::write_asset() creates or overwrites asset.
+/// V3 | KAssetSink::rename_asset() renames asset, but never overwrites.
+/// V4 | KAssetSink::delete_asset() deletes asset.
+/// V5 | Paths escaping folder are invalid and root must return Err() of kind InvalidInput.
+/// V6 | Failed write leaves asset untouched and no temporary file.
+/// V7 | KAssetSource::get_metadata() counts assets again after each write.
+/// V8 | KAssetSource reads are forwarded to folder source and temporary files aren't assets.
+fn kasset_sink_folder_write() {
+    let folder_name: &str = &(TEST_FOLDER.to_owned() + "kasset_sink_folder_write/");
+    let _ = fs::remove_dir_all(folder_name);
+    let kasf = KAssetSinkFolder::new(PathBuf::from(folder_name)).unwrap();
+
+    // V1 | KAssetSink::create_asset() creates asset and parent folders, but never overwrites.
+    kasf.create_asset(&KAssetPath::new("saves/slot1.sav").unwrap(), &mut "Level 1".as_bytes()).expect("Asset should be created!");
+    assert!(read_asset(&kasf, "saves/slot1.sav") == "Level 1", "Content is different that expected!");
+    let err = kasf.create_asset(&KAssetPath::new("saves/slot1.sav").unwrap(), &mut "Level 2".as_bytes()).expect_err("Asset shouldn't be overwritten!");
+    assert!(err.kind() == ErrorKind::AlreadyExists && read_asset(&kasf, "saves/slot1.sav") == "Level 1", "Asset shouldn't be overwritten!");
+
+    // V2 | KAssetSink::write_asset() creates or overwrites asset.
+    kasf.write_asset(&KAssetPath::new("settings.cfg").unwrap(), &mut "volume=1".as_bytes()).unwrap();
+    kasf.write_asset(&KAssetPath::new("saves/slot1.sav").unwrap(), &mut "Level 2".as_bytes()).unwrap();
+    assert!(read_asset(&kasf, "settings.cfg") == "volume=1" && read_asset(&kasf, "saves/slot1.sav") == "Level 2", "Content is different that expected!");
+
+    // V3 | KAssetSink::rename_asset() renames asset, but never overwrites.
+    kasf.rename_asset(&KAssetPath::new("saves/slot1.sav").unwrap(), &KAssetPath::new("saves/old/slot2.sav").unwrap()).expect("Asset should be renamed!");
+    assert!(!kasf.has_asset(&KAssetPath::new("saves/slot1.sav").unwrap()) && read_asset(&kasf, "saves/old/slot2.sav") == "Level 2", "Asset should be renamed!");
+    let err = kasf.rename_asset(&KAssetPath::new("settings.cfg").unwrap(), &KAssetPath::new("saves/old/slot2.sav").unwrap()).expect_err("Asset shouldn't be overwritten!");
+    assert!(err.kind() == ErrorKind::AlreadyExists, "Wrong error given!");
+    let err = kasf.rename_asset(&KAssetPath::new("missing.cfg").unwrap(), &KAssetPath::new("other.cfg").unwrap()).expect_err("Missing asset shouldn't be renamed!");
+    assert!(err.kind() == ErrorKind::NotFound, "Wrong error given!");
+
+    // V4 | KAssetSink::delete_asset() deletes asset.
+    kasf.delete_asset(&KAssetPath::new("settings.cfg").unwrap()).expect("Asset should be deleted!");
+    assert!(!kasf.has_asset(&KAssetPath::new("settings.cfg").unwrap()), "Asset should be deleted!");
+    assert!(kasf.delete_asset(&KAssetPath::new("settings.cfg").unwrap()).is_err_and(|err| err.kind() == ErrorKind::NotFound), "Wrong error given!");
+
+    // V5 | Paths escaping folder are invalid and root must return Err() of kind InvalidInput.
+    for path in ["../escape.txt", "saves/../../escape.txt"] {
+        assert!(KAssetPath::new(path).is_err(), "Path {} should be invalid!", path);
+    }
+    let root = KAssetPath::root();
+    assert!(kasf.write_asset(&root, &mut "Escape".as_bytes()).is_err_and(|err| err.kind() == ErrorKind::InvalidInput), "Root should be invalid!");
+    assert!(kasf.create_asset(&root, &mut "Escape".as_bytes()).is_err_and(|err| err.kind() == ErrorKind::InvalidInput), "Root should be invalid!");
+    assert!(kasf.delete_asset(&root).is_err_and(|err| err.kind() == ErrorKind::InvalidInput), "Root should be invalid!");
+    assert!(!PathBuf::from(TEST_FOLDER.to_owned() + "escape.txt").exists(), "Asset shouldn't escape folder!");
+
+    // V6 | Failed write leaves asset untouched and no temporary file.
+    assert!(kasf.write_asset(&KAssetPath::new("saves/old/slot2.sav").unwrap(), &mut FailingReader { given : false }).is_err(), "Write should fail!");
+    assert!(read_asset(&kasf, "saves/old/slot2.sav") == "Level 2", "Asset should be untouched!");
+    let assets = kasf.list_assets(&KAssetPath::root()).unwrap();
+    assert!(assets == vec![KAssetPath::new("saves/old/slot2.sav").unwrap()], "Wrong assets {:?}!", assets);
+    assert!(fs::read_dir(folder_name.to_owned() + "saves/old").unwrap().count() == 1, "Temporary file shouldn't be left!");
+
+    // V7 | KAssetSource::get_metadata() counts assets again after each write.
+    assert!(kasf.get_metadata().asset_count == Some(1), "1 asset expected!");
+    kasf.write_asset(&KAssetPath::new("settings.cfg").unwrap(), &mut "volume=1".as_bytes()).unwrap();
+    assert!(kasf.get_metadata().asset_count == Some(2) && kasf.get_metadata().size == Some(15), "Written asset should be counted!");
+    kasf.delete_asset(&KAssetPath::new("settings.cfg").unwrap()).unwrap();
+    assert!(kasf.get_metadata().asset_count == Some(1), "Deleted asset shouldn't be counted!");
+
+    // V8 | KAssetSource reads are forwarded to folder source and temporary files aren't assets.
+    unsafe { kasf.get_source().set_memory_mapped(true); }
+    assert!(kasf.get_asset_bytes(&KAssetPath::new("saves/old/slot2.sav").unwrap()).unwrap().is_mapped(), "Asset should be memory mapped!");
+    let mut range = String::new();
+    kasf.get_asset_range(&KAssetPath::new("saves/old/slot2.sav").unwrap(), 6, 1).unwrap().read_to_string(&mut range).unwrap();
+    assert!(range == "2", "Wrong range {}!", range);
+    fs::write(folder_name.to_owned() + "saves/.slot3.sav.1-0.ktmp", "Level 3").unwrap();
+    assert!(!kasf.has_asset(&KAssetPath::new("saves/.slot3.sav.1-0.ktmp").unwrap()), "Temporary file shouldn't be an asset!");
+
+    fs::remove_dir_all(PathBuf::from(folder_name)).expect("Test couldn't be cleaned!");
+}
+
+#[test]
+/// Write assets through [KAssetBroker] sink.
+///
+/// # Verification(s)
+/// V1 | KAssetBroker::write_asset() must return Err() of kind Unsupported without sink.
+/// V2 | KAssetBroker::set_sink() adds sink as source of highest priority.
+/// V3 | Assets written override other sources immediately, even if cached.
+/// V4 | Deleted assets of sink reveal assets of other sources.
+/// V5 | Writing a compressed asset invalidates asset decompressed from it.
+/// V6 | Removing sink source removes sink.
+fn kasset_sink_folder_broker() {
+    let folder_name: &str = &(TEST_FOLDER.to_owned() + "kasset_sink_folder_broker/");
+    let _ = fs::remove_dir_all(folder_name);
+
+    let kasm = KAssetSourceMemory::new("base");
+    kasm.add_asset(PathBuf::from("settings.cfg"), "volume=1".as_bytes().to_vec()).unwrap();
+
+    let kab = KAssetBroker::new();
+    kab.add_source(Arc::new(kasm)).unwrap();
+    kab.set_cache(Some(Arc::new(KAssetCache::new(1024))));
+
+    // V1 | KAssetBroker::write_asset() must return Err() of kind Unsupported without sink.
+    assert!(kab.get_sink_id().is_none(), "Broker shouldn't have sink!");
+    assert!(kab.write_asset(PathBuf::from("settings.cfg"), &mut "volume=0".as_bytes()).is_err_and(|err| err.kind() == ErrorKind::Unsupported), "Wrong error given!");
+
+    // V2 | KAssetBroker::set_sink() adds sink as source of highest priority.
+    let sink_id = kab.set_sink(Arc::new(KAssetSinkFolder::new(PathBuf::from(folder_name)).unwrap())).unwrap();
+    assert!(kab.get_sink_id() == Some(sink_id) && kab.get_source_priority(sink_id).unwrap() == 0, "Sink should have highest priority!");
+
+    // V3 | Assets written override other sources immediately, even if cached.
+    assert!(read_broker_asset(&kab, "settings.cfg") == "volume=1", "Base asset expected!");
+    kab.write_asset(PathBuf::from("settings.cfg"), &mut "volume=0".as_bytes()).unwrap();
+    assert!(read_broker_asset(&kab, "settings.cfg") == "volume=0", "Written asset expected!");
+    kab.create_asset(PathBuf::from("screenshots/1.png"), &mut "PNG".as_bytes()).unwrap();
+    kab.rename_asset(PathBuf::from("screenshots/1.png"), PathBuf::from("screenshots/2.png")).unwrap();
+    assert!(read_broker_asset(&kab, "screenshots/2.png") == "PNG", "Renamed asset expected!");
+    assert!(kab.get_asset(PathBuf::from("screenshots/1.png")).is_err(), "Asset should be renamed!");
+
+    // V4 | Deleted assets of sink reveal assets of other sources.
+    kab.delete_asset(PathBuf::from("settings.cfg")).unwrap();
+    assert!(read_broker_asset(&kab, "settings.cfg") == "volume=1", "Base asset expected!");
+
+    // V5 | Writing a compressed asset invalidates asset decompressed from it.
+    kab.set_decompression(Some(KAssetDecompression::default()));
+    kab.write_asset(PathBuf::from("a.json.gz"), &mut ONE_GZIP.as_slice()).unwrap();
+    assert!(read_broker_asset(&kab, "a.json") == "one", "Decompressed asset expected!");
+    kab.write_asset(PathBuf::from("a.json.gz"), &mut TWO_GZIP.as_slice()).unwrap();
+    assert!(read_broker_asset(&kab, "a.json") == "two", "Decompressed asset should be invalidated!");
+
+    // V6 | Removing sink source removes sink.
+    kab.remove_source(sink_id).unwrap();
+    assert!(kab.get_sink_id().is_none(), "Sink should be removed!");
+    assert!(kab.delete_asset(PathBuf::from("screenshots/2.png")).is_err_and(|err| err.kind() == ErrorKind::Unsupported), "Wrong error given!");
+
+    fs::remove_dir_all(PathBuf::from(folder_name)).expect("Test couldn't be cleaned!");
+}
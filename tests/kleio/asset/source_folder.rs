@@ -1,5 +1,5 @@
 use std::{path::{PathBuf}, fs::{self, File}, io::Write, cmp::{self, Ordering}};
-use olympus_kleio::asset::{KAssetSourceFolder, KAssetSource, KAssetSourceFolderError};
+use olympus_kleio::asset::{KAssetSourceFolder, KAssetSource, KAssetSourceFolderError, KAssetPath};
 
 /// Root path of test folder
 static TEST_FOLDER: &str = "../target/tests/kleio/asset/";
@@ -85,10 +85,10 @@ fn kasset_source_folder_has_file() {
 
             // For those in range, file should exists
             if i >= 1 && i <= 9 && j >=1 && j <= 9 {
-                assert!(kasf.has_asset(PathBuf::from(&file_name)), "KAssetSourceFolder should have file {}", &file_name);
+                assert!(kasf.has_asset(&KAssetPath::new(&file_name).unwrap()), "KAssetSourceFolder should have file {}", &file_name);
             } else {
                 // For those out of range, file shouldn't exists.
-                assert!(!kasf.has_asset(PathBuf::from(&file_name)), "KAssetSourceFolder shouldn't have file {}", &file_name);
+                assert!(!kasf.has_asset(&KAssetPath::new(&file_name).unwrap()), "KAssetSourceFolder shouldn't have file {}", &file_name);
             }
 
         }
@@ -138,7 +138,7 @@ fn kasset_source_folder_read_file() {
             
             let file_name = "subfolder".to_owned() + i.to_string().as_str() + "/file" + j.to_string().as_str() + ".txt";
 
-            let result = kasf.get_asset(PathBuf::from(&file_name));
+            let result = kasf.get_asset(&KAssetPath::new(&file_name).unwrap());
 
             // For those in range, file should exists, read it
             if i >= 1 && i <= 9 && j >=1 && j <= 9 {
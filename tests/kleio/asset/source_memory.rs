@@ -0,0 +1,139 @@
+use std::{path::PathBuf, io::Read, thread, sync::Arc};
+use olympus_kleio::asset::{KAssetSourceMemory, KAssetSource, KAssetSourceMemoryError, KAssetBroker, KAssetSourceFolder, kasset_source_embed, KAssetPath};
+
+/// Root path of test folder
+static TEST_FOLDER: &str = "../target/tests/kleio/asset/";
+
+
+#[test]
+/// Add and remove assets of [KAssetSourceMemory].
+/// 
+/// # Verification(s)
+/// V1 | KAssetSourceMemory::new() creates an empty source.
+/// V2 | KAssetSourceMemory::add_asset() and KAssetSourceMemory::add_static_asset() add assets.
+/// V3 | KAssetSourceMemory::add_asset() must return Err(KAssetSourceMemoryError::InvalidAssetPath) for escaping, absolute and empty paths.
+/// V4 | KAssetSourceMemory::remove_asset() removes asset and must return Err(KAssetSourceMemoryError::AssetNotFound) if not found.
+/// V5 | KAssetSourceMemory::clear() removes all assets.
+fn kasset_source_memory_add_remove() {
+    // V1 | KAssetSourceMemory::new() creates an empty source.
+    let kasm = KAssetSourceMemory::new("test");
+    assert!(kasm.get_asset_count() == 0, "KAssetSourceMemory should be empty!");
+
+    // V2 | KAssetSourceMemory::add_asset() and KAssetSourceMemory::add_static_asset() add assets.
+    for i in 1..10 {
+        for j in 1..10 {
+            let file_name = "subfolder".to_owned() + i.to_string().as_str() + "/file" + j.to_string().as_str() + ".txt";
+            let content = "Hello".to_owned() + i.to_string().as_str() + ", world" + j.to_string().as_str() + "!";
+            kasm.add_asset(PathBuf::from(file_name), content.into_bytes()).unwrap();
+        }
+    }
+    kasm.add_static_asset(PathBuf::from("static.txt"), "Hello, static!".as_bytes()).unwrap();
+    assert!(kasm.get_asset_count() == 82, "KAssetSourceMemory should have 82 assets!");
+
+    // V3 | KAssetSourceMemory::add_asset() must return Err(KAssetSourceMemoryError::InvalidAssetPath) for escaping, absolute and empty paths.
+    for path in ["../b.txt", "/a/c.txt", ""] {
+        match kasm.add_asset(PathBuf::from(path), Vec::new()) {
+            Ok(_) => panic!("Adding path {} should fail!", path),
+            Err(err) => assert!(matches!(err, KAssetSourceMemoryError::InvalidAssetPath), "Wrong error given!"),
+        }
+    }
+
+    // V4 | KAssetSourceMemory::remove_asset() removes asset and must return Err(KAssetSourceMemoryError::AssetNotFound) if not found.
+    kasm.remove_asset(PathBuf::from("static.txt")).unwrap();
+    assert!(!kasm.has_asset(&KAssetPath::new("static.txt").unwrap()), "KAssetSourceMemory shouldn't have static.txt");
+    match kasm.remove_asset(PathBuf::from("static.txt")) {
+        Ok(_) => panic!("Removing inexistant asset should fail!"),
+        Err(err) => assert!(matches!(err, KAssetSourceMemoryError::AssetNotFound), "Wrong error given!"),
+    }
+
+    // V5 | KAssetSourceMemory::clear() removes all assets.
+    kasm.clear();
+    assert!(kasm.get_asset_count() == 0, "KAssetSourceMemory should be empty!");
+}
+
+#[test]
+/// Create [KAssetSourceMemory] and test read from asset given by KAssetSource::get_asset().
+/// 
+/// # Verification(s)
+/// V1 | KAssetSourceMemory has added assets only.
+/// V2 | Asset content matches correct content.
+/// V3 | Replaced asset content matches new content.
+/// V4 | Assets can be added from another thread while source is shared.
+fn kasset_source_memory_read_file() {
+    let kasm = Arc::new(KAssetSourceMemory::new("test"));
+
+    // V4 | Assets can be added from another thread while source is shared.
+    let filler = kasm.clone();
+    thread::spawn(move || {
+        for i in 1..10 {
+            for j in 1..10 {
+                let file_name = "subfolder".to_owned() + i.to_string().as_str() + "/file" + j.to_string().as_str() + ".txt";
+                let content = "Hello".to_owned() + i.to_string().as_str() + ", world" + j.to_string().as_str() + "!";
+                filler.add_asset(PathBuf::from(file_name), content.into_bytes()).unwrap();
+            }
+        }
+    }).join().unwrap();
+
+    for i in 0..15 {
+        for j in 0..15 {
+            let file_name = "subfolder".to_owned() + i.to_string().as_str() + "/file" + j.to_string().as_str() + ".txt";
+
+            if (1..=9).contains(&i) && (1..=9).contains(&j) {
+                // V1 | KAssetSourceMemory has added assets only.
+                assert!(kasm.has_asset(&KAssetPath::new(&file_name).unwrap()), "KAssetSourceMemory should have file {}", &file_name);
+
+                // V2 | Asset content matches correct content.
+                let mut content = String::new();
+                kasm.get_asset(&KAssetPath::new(&file_name).unwrap()).unwrap().read_to_string(&mut content).expect("Couldn't read asset!");
+                assert!(content.eq(&("Hello".to_owned() + i.to_string().as_str() + ", world" + j.to_string().as_str() + "!")), "Content is different that expected!");
+            } else {
+                // V1 | KAssetSourceMemory has added assets only.
+                assert!(!kasm.has_asset(&KAssetPath::new(&file_name).unwrap()), "KAssetSourceMemory shouldn't have file {}", &file_name);
+                assert!(kasm.get_asset(&KAssetPath::new(&file_name).unwrap()).is_err(), "KAssetSourceMemory shouldn't have file {}", &file_name);
+            }
+        }
+    }
+
+    // V3 | Replaced asset content matches new content.
+    kasm.add_asset(PathBuf::from("subfolder1/file1.txt"), "Replaced".as_bytes().to_vec()).unwrap();
+    let mut content = String::new();
+    kasm.get_asset(&KAssetPath::new("subfolder1/./file1.txt").unwrap()).unwrap().read_to_string(&mut content).expect("Couldn't read asset!");
+    assert!(content.eq("Replaced"), "Content is different that expected!");
+}
+
+#[test]
+/// Embed a folder with [kasset_source_embed!] and use it as lowest priority source of [KAssetBroker].
+/// 
+/// # Verification(s)
+/// V1 | kasset_source_embed! embeds all files of folder and sub folders.
+/// V2 | Embedded asset content matches file content.
+/// V3 | Embedded assets are served by broker when missing from higher priority source.
+fn kasset_source_memory_embed() {
+    // V1 | kasset_source_embed! embeds all files of folder and sub folders.
+    let kasm = kasset_source_embed!("tests/kleio/asset/embed");
+    assert!(kasm.get_asset_count() == 3, "Embedded source should have 3 assets!");
+
+    // V2 | Embedded asset content matches file content.
+    let mut content = String::new();
+    kasm.get_asset(&KAssetPath::new("textures/error.txt").unwrap()).unwrap().read_to_string(&mut content).expect("Couldn't read asset!");
+    assert!(content.eq("Error texture"), "Content is different that expected!");
+
+    // V3 | Embedded assets are served by broker when missing from higher priority source.
+    let folder_name: &str = &(TEST_FOLDER.to_owned() + "kasm_embed/");
+    std::fs::create_dir_all(folder_name.to_owned() + "textures").unwrap();
+    std::fs::write(folder_name.to_owned() + "textures/error.txt", "Override texture").unwrap();
+    let kasf = KAssetSourceFolder::new(PathBuf::from(folder_name)).unwrap();
+
+    let kab = KAssetBroker::new();
+    assert!(kab.add_source(Arc::new(kasf)).is_ok(), "KAssetBroker::add_source() should accept KAssetSourceFolder!");
+    assert!(kab.add_source(Arc::new(kasm)).is_ok(), "KAssetBroker::add_source() should accept KAssetSourceMemory!");
+
+    for (path, expected) in [("textures/error.txt", "Override texture"), ("fonts/default.txt", "Default font")] {
+        let mut content = String::new();
+        kab.get_asset(PathBuf::from(path)).unwrap().read_to_string(&mut content).expect("Couldn't read asset!");
+        assert!(content.eq(expected), "Content is different that expected!");
+    }
+
+    // Clean test
+    std::fs::remove_dir_all(PathBuf::from(folder_name)).expect("Test couldn't be cleaned!");
+}
@@ -0,0 +1,423 @@
+use std::{path::PathBuf, fs::{self, File}, io::{Write, Read, Seek, SeekFrom, Cursor}, sync::Arc};
+use olympus_kleio::asset::{KAssetSourcePak, KAssetSource, KAssetSourcePakError, KAssetBroker, KPakWriter, KPakWriterError, KPakCompression, KAssetPath};
+
+/// Root path of test folder
+static TEST_FOLDER: &str = "../target/tests/kleio/asset/";
+
+
+#[test]
+/// Trying to create [KAssetSourcePak] using a pack that doesn't exists.
+/// 
+/// # Verification(s)
+/// V1 | KAssetSourcePak::new() must return Err(KAssetSourcePakError::FileNotFound) since path doesn't exists.
+fn kasset_source_pak_create_not_found() {
+    // V1 | KAssetSourcePak::new() must return Err(KAssetSourcePakError::FileNotFound) since path doesn't exists.
+    match KAssetSourcePak::new(PathBuf::from("/kasp_not_found.kpak")) {
+        Ok(_) => panic!("KAssetSourcePak::new() must return Err(KAssetSourcePakError::FileNotFound) since path doesn't exists."),
+        Err(err) => assert!(matches!(err, KAssetSourcePakError::FileNotFound), "Wrong error given!"),
+    }
+}
+
+#[test]
+/// Trying to create [KAssetSourcePak] using invalid packs.
+/// 
+/// # Verification(s)
+/// V1 | KAssetSourcePak::new() must return Err(KAssetSourcePakError::InvalidPak) for a text file.
+/// V2 | KAssetSourcePak::new() must return Err(KAssetSourcePakError::UnsupportedVersion) for a newer version.
+/// V3 | KAssetSourcePak::new() must return Err(KAssetSourcePakError::CorruptedIndex) if index is corrupted.
+/// V4 | KAssetSourcePak::new() must return Err(KAssetSourcePakError::InvalidPak) for a truncated pack.
+/// V5 | KAssetSourcePak::new() must return Err(KAssetSourcePakError::InvalidPak) for impossible sizes, even with a valid index checksum.
+fn kasset_source_pak_create_invalid() {
+    // Test folder name
+    let folder_name: &str = &(TEST_FOLDER.to_owned() + "kasp_create_invalid/");
+    create_folder(folder_name);
+
+    // V1 | KAssetSourcePak::new() must return Err(KAssetSourcePakError::InvalidPak) for a text file.
+    create_file_with_content(&(folder_name.to_owned() + "text.kpak"), "Hello, world! This is not a Kleio pack.".as_bytes());
+    match KAssetSourcePak::new(PathBuf::from(folder_name.to_owned() + "text.kpak")) {
+        Ok(_) => panic!("KAssetSourcePak::new() must return Err(KAssetSourcePakError::InvalidPak) for a text file."),
+        Err(err) => assert!(matches!(err, KAssetSourcePakError::InvalidPak), "Wrong error given!"),
+    }
+
+    // Valid pack in memory
+    let pak = create_pak_in_memory(KPakCompression::Deflate);
+
+    // V2 | KAssetSourcePak::new() must return Err(KAssetSourcePakError::UnsupportedVersion) for a newer version.
+    let mut newer = pak.clone();
+    newer[4] = 0xFF;
+    create_file_with_content(&(folder_name.to_owned() + "newer.kpak"), &newer);
+    match KAssetSourcePak::new(PathBuf::from(folder_name.to_owned() + "newer.kpak")) {
+        Ok(_) => panic!("KAssetSourcePak::new() must return Err(KAssetSourcePakError::UnsupportedVersion) for a newer version."),
+        Err(err) => assert!(matches!(err, KAssetSourcePakError::UnsupportedVersion), "Wrong error given!"),
+    }
+
+    // V3 | KAssetSourcePak::new() must return Err(KAssetSourcePakError::CorruptedIndex) if index is corrupted.
+    let mut corrupted = pak.clone();
+    let last = corrupted.len() - 1;
+    corrupted[last] ^= 0xFF;
+    create_file_with_content(&(folder_name.to_owned() + "corrupted.kpak"), &corrupted);
+    match KAssetSourcePak::new(PathBuf::from(folder_name.to_owned() + "corrupted.kpak")) {
+        Ok(_) => panic!("KAssetSourcePak::new() must return Err(KAssetSourcePakError::CorruptedIndex) if index is corrupted."),
+        Err(err) => assert!(matches!(err, KAssetSourcePakError::CorruptedIndex), "Wrong error given!"),
+    }
+
+    // V4 | KAssetSourcePak::new() must return Err(KAssetSourcePakError::InvalidPak) for a truncated pack.
+    create_file_with_content(&(folder_name.to_owned() + "truncated.kpak"), &pak[0..pak.len() - 10]);
+    match KAssetSourcePak::new(PathBuf::from(folder_name.to_owned() + "truncated.kpak")) {
+        Ok(_) => panic!("KAssetSourcePak::new() must return Err(KAssetSourcePakError::InvalidPak) for a truncated pack."),
+        Err(err) => assert!(matches!(err, KAssetSourcePakError::InvalidPak), "Wrong error given!"),
+    }
+
+    // V5 | KAssetSourcePak::new() must return Err(KAssetSourcePakError::InvalidPak) for impossible sizes, even with a valid index checksum.
+    for (compression, size) in [(KPakCompression::Deflate, u64::MAX), (KPakCompression::Deflate, 1 << 40), (KPakCompression::None, 1 << 40)] {
+        let crafted = create_pak_with_first_size(compression, size);
+        create_file_with_content(&(folder_name.to_owned() + "crafted.kpak"), &crafted);
+        match KAssetSourcePak::new(PathBuf::from(folder_name.to_owned() + "crafted.kpak")) {
+            Ok(_) => panic!("KAssetSourcePak::new() must return Err(KAssetSourcePakError::InvalidPak) for impossible sizes."),
+            Err(err) => assert!(matches!(err, KAssetSourcePakError::InvalidPak), "Wrong error given!"),
+        }
+    }
+
+    // Clean test
+    fs::remove_dir_all(PathBuf::from(folder_name)).expect("Test couldn't be cleaned!");
+}
+
+#[test]
+/// Create a pack from a folder with [KPakWriter] and read it with [KAssetSourcePak].
+/// 
+/// # Verification(s)
+/// V1 | KPakWriter::add_folder() adds all files of folder and sub folders.
+/// V2 | KAssetSourcePak::new() opens pack written without error.
+/// V3 | KAssetSourcePak has all files of folder.
+/// V4 | Asset content matches correct content.
+/// V5 | KAssetSourcePak::get_asset() must not return invalid asset.
+fn kasset_source_pak_writer_folder() {
+    // Test folder name
+    let folder_name: &str = &(TEST_FOLDER.to_owned() + "kasp_writer_folder/");
+
+    // Create file with content in multiple subfolders
+    for i in 1..10 {
+        create_folder(&(folder_name.to_owned() + "content/subfolder" + i.to_string().as_str()));
+        for j in 1..10 {
+            let file_name = &(folder_name.to_owned() + "content/subfolder" + i.to_string().as_str() + "/file" + j.to_string().as_str() + ".txt");
+            create_file_with_content(file_name, ("Hello".to_owned() + i.to_string().as_str() + ", world" + j.to_string().as_str() + "!").as_bytes());
+        }
+    }
+
+    // V1 | KPakWriter::add_folder() adds all files of folder and sub folders.
+    let mut writer = KPakWriter::create(PathBuf::from(folder_name.to_owned() + "test.kpak")).unwrap();
+    writer.set_compression(KPakCompression::Deflate);
+    assert!(writer.add_folder(PathBuf::from(folder_name.to_owned() + "content")).unwrap() == 81, "KPakWriter::add_folder() should add 81 files!");
+    writer.finish().unwrap();
+
+    // V2 | KAssetSourcePak::new() opens pack written without error.
+    let kasp = KAssetSourcePak::new(PathBuf::from(folder_name.to_owned() + "test.kpak")).unwrap();
+    assert!(kasp.get_entry_count() == 81, "KAssetSourcePak should have 81 entries!");
+
+    for i in 0..15 {
+        for j in 0..15 {
+            let file_name = "subfolder".to_owned() + i.to_string().as_str() + "/file" + j.to_string().as_str() + ".txt";
+
+            if (1..=9).contains(&i) && (1..=9).contains(&j) {
+                // V3 | KAssetSourcePak has all files of folder.
+                assert!(kasp.has_asset(&KAssetPath::new(&file_name).unwrap()), "KAssetSourcePak should have file {}", &file_name);
+
+                // V4 | Asset content matches correct content.
+                let mut content = String::new();
+                kasp.get_asset(&KAssetPath::new(&file_name).unwrap()).unwrap().read_to_string(&mut content).expect("Couldn't read asset!");
+                assert!(content.eq(&("Hello".to_owned() + i.to_string().as_str() + ", world" + j.to_string().as_str() + "!")), "Content is different that expected!");
+            } else {
+                // V5 | KAssetSourcePak::get_asset() must not return invalid asset.
+                assert!(!kasp.has_asset(&KAssetPath::new(&file_name).unwrap()), "KAssetSourcePak shouldn't have file {}", &file_name);
+                assert!(kasp.get_asset(&KAssetPath::new(&file_name).unwrap()).is_err(), "KAssetSourcePak shouldn't have file {}", &file_name);
+            }
+        }
+    }
+
+    // Clean test
+    fs::remove_dir_all(PathBuf::from(folder_name)).expect("Test couldn't be cleaned!");
+}
+
+#[test]
+/// Create a pack from an iterator with [KPakWriter] with each compression and read it with [KAssetSourcePak].
+/// 
+/// # Verification(s)
+/// V1 | KPakWriter::add_assets() adds all assets of iterator.
+/// V2 | Compressible, incompressible, large and empty assets content matches for each compression.
+fn kasset_source_pak_writer_iterator() {
+    // Test folder name
+    let folder_name: &str = &(TEST_FOLDER.to_owned() + "kasp_writer_iterator/");
+    create_folder(folder_name);
+
+    let assets = create_test_assets();
+
+    for (index, compression) in [KPakCompression::None, KPakCompression::Deflate].iter().enumerate() {
+        let pak_path = folder_name.to_owned() + "test" + index.to_string().as_str() + ".kpak";
+
+        // V1 | KPakWriter::add_assets() adds all assets of iterator.
+        let mut writer = KPakWriter::create(PathBuf::from(&pak_path)).unwrap();
+        writer.set_compression(*compression);
+        let count = writer.add_assets(assets.iter().map(|(path, data)| (PathBuf::from(path), Cursor::new(data)))).unwrap();
+        assert!(count == assets.len(), "KPakWriter::add_assets() should add all assets!");
+        writer.finish().unwrap();
+
+        // V2 | Compressible, incompressible, large and empty assets content matches for each compression.
+        let kasp = KAssetSourcePak::new(PathBuf::from(&pak_path)).unwrap();
+        for (path, data) in &assets {
+            let mut content = Vec::new();
+            kasp.get_asset(&KAssetPath::new(path).unwrap()).unwrap().read_to_end(&mut content).expect("Couldn't read asset!");
+            assert!(content.eq(data), "Content of {} is different that expected!", path);
+        }
+    }
+
+    // Clean test
+    fs::remove_dir_all(PathBuf::from(folder_name)).expect("Test couldn't be cleaned!");
+}
+
+#[test]
+/// Verify [KPakWriter] errors.
+/// 
+/// # Verification(s)
+/// V1 | KPakWriter::add_asset() must return Err(KPakWriterError::AssetAlreadyExists) when adding the same path twice.
+/// V2 | KPakWriter::add_asset() must return Err(KPakWriterError::InvalidAssetPath) for escaping, absolute and empty paths.
+/// V3 | KPakWriter::add_folder() must return Err(KPakWriterError::FolderNotFound) if folder doesn't exists.
+fn kasset_source_pak_writer_errors() {
+    let mut writer = KPakWriter::new(Cursor::new(Vec::new())).unwrap();
+    writer.add_asset(PathBuf::from("a/b.txt"), &mut Cursor::new("Hello")).unwrap();
+
+    // V1 | KPakWriter::add_asset() must return Err(KPakWriterError::AssetAlreadyExists) when adding the same path twice.
+    match writer.add_asset(PathBuf::from("a/./b.txt"), &mut Cursor::new("Hello")) {
+        Ok(_) => panic!("Adding the same path twice should fail!"),
+        Err(err) => assert!(matches!(err, KPakWriterError::AssetAlreadyExists), "Wrong error given!"),
+    }
+
+    // V2 | KPakWriter::add_asset() must return Err(KPakWriterError::InvalidAssetPath) for escaping, absolute and empty paths.
+    for path in ["../b.txt", "/a/c.txt", ""] {
+        match writer.add_asset(PathBuf::from(path), &mut Cursor::new("Hello")) {
+            Ok(_) => panic!("Adding path {} should fail!", path),
+            Err(err) => assert!(matches!(err, KPakWriterError::InvalidAssetPath), "Wrong error given!"),
+        }
+    }
+
+    // V3 | KPakWriter::add_folder() must return Err(KPakWriterError::FolderNotFound) if folder doesn't exists.
+    match writer.add_folder(PathBuf::from("/kasp_not_found/")) {
+        Ok(_) => panic!("Adding inexistant folder should fail!"),
+        Err(err) => assert!(matches!(err, KPakWriterError::FolderNotFound), "Wrong error given!"),
+    }
+}
+
+#[test]
+/// Verify [KAssetSourcePak] lookups with thousands of entries.
+/// 
+/// # Verification(s)
+/// V1 | KAssetSourcePak::get_entry_count() returns count of assets added.
+/// V2 | KAssetSourcePak has all assets added and only those.
+/// V3 | Asset content of a few assets matches correct content.
+fn kasset_source_pak_thousands() {
+    // Test folder name
+    let folder_name: &str = &(TEST_FOLDER.to_owned() + "kasp_thousands/");
+    create_folder(folder_name);
+
+    // Add assets in reverse order to verify that index is sorted
+    let mut writer = KPakWriter::create(PathBuf::from(folder_name.to_owned() + "test.kpak")).unwrap();
+    writer.add_assets((0..5000).rev().map(|i| (PathBuf::from(format!("level{}/chunk{}.bin", i % 10, i)), Cursor::new(i.to_string())))).unwrap();
+    writer.finish().unwrap();
+
+    // V1 | KAssetSourcePak::get_entry_count() returns count of assets added.
+    let kasp = KAssetSourcePak::new(PathBuf::from(folder_name.to_owned() + "test.kpak")).unwrap();
+    assert!(kasp.get_entry_count() == 5000, "KAssetSourcePak should have 5000 entries!");
+
+    // V2 | KAssetSourcePak has all assets added and only those.
+    for i in 0..5100 {
+        assert!(kasp.has_asset(&KAssetPath::new(&format!("level{}/chunk{}.bin", i % 10, i)).unwrap()) == (i < 5000), "Wrong has_asset() for chunk {}!", i);
+        assert!(!kasp.has_asset(&KAssetPath::new(&format!("level{}/chunk{}.bin", (i + 1) % 10, i)).unwrap()), "Wrong has_asset() for chunk {}!", i);
+    }
+
+    // V3 | Asset content of a few assets matches correct content.
+    for i in [0, 1234, 4999] {
+        let mut content = String::new();
+        kasp.get_asset(&KAssetPath::new(&format!("level{}/chunk{}.bin", i % 10, i)).unwrap()).unwrap().read_to_string(&mut content).expect("Couldn't read asset!");
+        assert!(content.eq(&i.to_string()), "Content is different that expected!");
+    }
+
+    // Clean test
+    fs::remove_dir_all(PathBuf::from(folder_name)).expect("Test couldn't be cleaned!");
+}
+
+#[test]
+/// Read corrupted assets from [KAssetSourcePak].
+/// 
+/// # Verification(s)
+/// V1 | Reading a corrupted uncompressed asset must return an error.
+/// V2 | Reading a corrupted compressed asset must return an error.
+fn kasset_source_pak_read_corrupted() {
+    // Test folder name
+    let folder_name: &str = &(TEST_FOLDER.to_owned() + "kasp_read_corrupted/");
+    create_folder(folder_name);
+
+    for (index, compression) in [KPakCompression::None, KPakCompression::Deflate].iter().enumerate() {
+        // Corrupt the first asset data, right after the 32 bytes header
+        let mut pak = create_pak_in_memory(*compression);
+        pak[40] ^= 0xFF;
+        let pak_path = folder_name.to_owned() + "test" + index.to_string().as_str() + ".kpak";
+        create_file_with_content(&pak_path, &pak);
+
+        // V1 | Reading a corrupted uncompressed asset must return an error.
+        // V2 | Reading a corrupted compressed asset must return an error.
+        let kasp = KAssetSourcePak::new(PathBuf::from(&pak_path)).unwrap();
+        let mut content = Vec::new();
+        assert!(kasp.get_asset(&KAssetPath::new("compressible.txt").unwrap()).and_then(|mut asset| asset.read_to_end(&mut content)).is_err(),
+            "Corrupted asset shouldn't be read without error!");
+    }
+
+    // Clean test
+    fs::remove_dir_all(PathBuf::from(folder_name)).expect("Test couldn't be cleaned!");
+}
+
+#[test]
+/// Seek and read ranges of uncompressed and compressed assets of [KAssetSourcePak].
+/// 
+/// # Verification(s)
+/// V1 | KAssetSourcePak::get_asset_seekable() reports uncompressed length of assets.
+/// V2 | Seeking within assets reads correct content.
+/// V3 | KAssetSourcePak::get_asset_range() reads range of asset, truncated at the end of asset.
+fn kasset_source_pak_seek() {
+    let folder_name: &str = &(TEST_FOLDER.to_owned() + "kasp_seek/");
+    create_folder(folder_name);
+
+    for (name, compression) in [("none.kpak", KPakCompression::None), ("deflate.kpak", KPakCompression::Deflate)] {
+        create_file_with_content(&(folder_name.to_owned() + name), &create_pak_in_memory(compression));
+        let kasp = KAssetSourcePak::new(PathBuf::from(folder_name.to_owned() + name)).unwrap();
+
+        for (path, data) in create_test_assets() {
+            // V1 | KAssetSourcePak::get_asset_seekable() reports uncompressed length of assets.
+            let mut reader = kasp.get_asset_seekable(&KAssetPath::new(&path).unwrap()).expect("Asset should be seekable!");
+            assert!(reader.get_length() == data.len() as u64, "Wrong length for {}!", path);
+
+            // V2 | Seeking within assets reads correct content.
+            let middle = data.len() / 2;
+            let mut content = Vec::new();
+            reader.seek(SeekFrom::Start(middle as u64)).unwrap();
+            reader.read_to_end(&mut content).unwrap();
+            assert!(content == data[middle..], "Content of {} is different that expected!", path);
+
+            let mut content = Vec::new();
+            reader.seek(SeekFrom::End(-(middle as i64))).unwrap();
+            reader.read_to_end(&mut content).unwrap();
+            assert!(content == data[data.len() - middle..], "Content of {} is different that expected!", path);
+
+            // V3 | KAssetSourcePak::get_asset_range() reads range of asset, truncated at the end of asset.
+            let mut content = Vec::new();
+            kasp.get_asset_range(&KAssetPath::new(&path).unwrap(), middle as u64, 100).unwrap().read_to_end(&mut content).unwrap();
+            assert!(content == data[middle..data.len().min(middle + 100)], "Range of {} is different that expected!", path);
+        }
+    }
+
+    fs::remove_dir_all(PathBuf::from(folder_name)).expect("Test couldn't be cleaned!");
+}
+
+#[test]
+/// Add [KAssetSourcePak] to [KAssetBroker] and get assets from it.
+/// 
+/// # Verification(s)
+/// V1 | KAssetBroker::add_source() accepts KAssetSourcePak.
+/// V2 | KAssetBroker::get_asset() returns asset content from pack.
+fn kasset_source_pak_broker() {
+    // Test folder name
+    let folder_name: &str = &(TEST_FOLDER.to_owned() + "kasp_broker/");
+    create_folder(folder_name);
+    create_file_with_content(&(folder_name.to_owned() + "test.kpak"), &create_pak_in_memory(KPakCompression::Deflate));
+
+    let kasp = KAssetSourcePak::new(PathBuf::from(folder_name.to_owned() + "test.kpak")).unwrap();
+
+    // V1 | KAssetBroker::add_source() accepts KAssetSourcePak.
+    let kab = KAssetBroker::new();
+    assert!(kab.add_source(Arc::new(kasp)).is_ok(), "KAssetBroker::add_source() should accept KAssetSourcePak!");
+
+    // V2 | KAssetBroker::get_asset() returns asset content from pack.
+    let mut content = Vec::new();
+    kab.get_asset(PathBuf::from("folder/large.bin")).unwrap().read_to_end(&mut content).expect("Couldn't read asset!");
+    assert!(content.eq(&create_test_assets()[3].1), "Content is different that expected!");
+
+    // Clean test
+    fs::remove_dir_all(PathBuf::from(folder_name)).expect("Test couldn't be cleaned!");
+}
+
+
+/************
+* FUNCTIONS * 
+************/
+/// Create test assets as (path, content). Compressible asset is always first.
+fn create_test_assets() -> Vec<(String, Vec<u8>)> {
+    // Pseudo random bytes are incompressible
+    let mut seed : u32 = 12345;
+    let random : Vec<u8> = (0..10000).map(|_| { seed = seed.wrapping_mul(1103515245).wrapping_add(12345); (seed >> 16) as u8 }).collect();
+
+    // Large asset with repetitions and noise
+    let large : Vec<u8> = (0..300000u32).map(|i| if i % 7 == 0 { random[(i % 10000) as usize] } else { (i % 251) as u8 }).collect();
+
+    vec![
+        (String::from("compressible.txt"), (0..1000).map(|k| format!("Line {}: Hello, world!\n", k)).collect::<String>().into_bytes()),
+        (String::from("random.bin"), random),
+        (String::from("empty.txt"), Vec::new()),
+        (String::from("folder/large.bin"), large),
+        (String::from("folder/small.txt"), "Hello".as_bytes().to_vec()),
+    ]
+}
+
+/// Create a pack in memory from test assets with compression.
+fn create_pak_in_memory(compression : KPakCompression) -> Vec<u8> {
+    let mut writer = KPakWriter::new(Cursor::new(Vec::new())).unwrap();
+    writer.set_compression(compression);
+    writer.add_assets(create_test_assets().into_iter().map(|(path, data)| (PathBuf::from(path), Cursor::new(data)))).unwrap();
+    writer.finish().unwrap().into_inner()
+}
+
+/// Create a pack in memory with size of its first asset replaced by `size` and index checksum recomputed, like a hostile pack.
+fn create_pak_with_first_size(compression : KPakCompression, size : u64) -> Vec<u8> {
+    let mut pak = create_pak_in_memory(compression);
+    let read_u64 = |data : &[u8], position : usize| u64::from_le_bytes(data[position..position + 8].try_into().unwrap()) as usize;
+    let (index_offset, index_size) = (read_u64(&pak, 16), read_u64(&pak, 24));
+
+    // First entry is path length, path, then size 24 bytes after path
+    let path_length = u16::from_le_bytes([pak[index_offset], pak[index_offset + 1]]) as usize;
+    let size_position = index_offset + 2 + path_length + 22;
+    pak[size_position..size_position + 8].copy_from_slice(&size.to_le_bytes());
+
+    // CRC-32 of index, bit by bit
+    let crc = !pak[index_offset..index_offset + index_size].iter().fold(!0u32, |crc, byte| {
+        (0..8).fold(crc ^ *byte as u32, |crc, _| if crc & 1 == 1 { (crc >> 1) ^ 0xEDB88320 } else { crc >> 1 })
+    });
+    pak[12..16].copy_from_slice(&crc.to_le_bytes());
+    pak
+}
+
+/// Create a folder with parents folders from path.
+///
+/// # Panic
+/// Will panic if folders not created.
+fn create_folder(folder_path : &str) {
+
+    match fs::create_dir_all(folder_path){
+        Ok(_) => {},
+        Err(_) => panic!("Error when creating folder {}!", folder_path),
+    }
+
+}
+
+/// Create a file and it's content from path and content.
+/// 
+/// # Panic
+/// Will panic if file cannot be created or written.
+fn create_file_with_content(file_path : &str, file_content : &[u8]){
+
+    match File::create(file_path) {
+        Ok(mut file) => { 
+            match file.write_all(file_content) {
+                Ok(_) => {},
+                Err(_) => panic!("Error when writing file {}!", file_path),
+            }
+        },
+        Err(_) => panic!("Error when creating file {}!", file_path),
+    }
+}
@@ -0,0 +1,424 @@
+use std::{path::{PathBuf}, fs::{self, File}, io::{Write, Read, Seek, SeekFrom}, sync::Arc, time::{Duration, UNIX_EPOCH}};
+use olympus_kleio::asset::{KAssetSourceZip, KAssetSource, KAssetSourceZipError, KAssetBroker, KAssetPath, KAssetSourceKind};
+
+/// Root path of test folder
+static TEST_FOLDER: &str = "../target/tests/kleio/asset/";
+
+/// Zip archive created with python zipfile. Contains :
+/// * subfolder1/file1.txt (stored) "Hello1, world1!"
+/// * subfolder1/file2.txt (deflated) "Hello1, world2!"
+/// * subfolder2/file1.txt (deflated) "Hello2, world1!"
+/// * subfolder2/file2.txt (stored) "Hello2, world2!"
+/// * lines.txt (deflated, dynamic Huffman) "Line {0..100}: Hello, world!\n"
+static TEST_ZIP: [u8; 891] = [
+    0x50, 0x4B, 0x03, 0x04, 0x14, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x21, 0x50, 0x42, 0x53,
+    0x05, 0x5D, 0x0F, 0x00, 0x00, 0x00, 0x0F, 0x00, 0x00, 0x00, 0x14, 0x00, 0x00, 0x00, 0x73, 0x75,
+    0x62, 0x66, 0x6F, 0x6C, 0x64, 0x65, 0x72, 0x31, 0x2F, 0x66, 0x69, 0x6C, 0x65, 0x31, 0x2E, 0x74,
+    0x78, 0x74, 0x48, 0x65, 0x6C, 0x6C, 0x6F, 0x31, 0x2C, 0x20, 0x77, 0x6F, 0x72, 0x6C, 0x64, 0x31,
+    0x21, 0x50, 0x4B, 0x03, 0x04, 0x14, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00, 0x21, 0x50, 0x81,
+    0x00, 0x28, 0x76, 0x11, 0x00, 0x00, 0x00, 0x0F, 0x00, 0x00, 0x00, 0x14, 0x00, 0x00, 0x00, 0x73,
+    0x75, 0x62, 0x66, 0x6F, 0x6C, 0x64, 0x65, 0x72, 0x31, 0x2F, 0x66, 0x69, 0x6C, 0x65, 0x32, 0x2E,
+    0x74, 0x78, 0x74, 0xF3, 0x48, 0xCD, 0xC9, 0xC9, 0x37, 0xD4, 0x51, 0x28, 0xCF, 0x2F, 0xCA, 0x49,
+    0x31, 0x52, 0x04, 0x00, 0x50, 0x4B, 0x03, 0x04, 0x14, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00,
+    0x21, 0x50, 0x41, 0xE8, 0x32, 0xB6, 0x11, 0x00, 0x00, 0x00, 0x0F, 0x00, 0x00, 0x00, 0x14, 0x00,
+    0x00, 0x00, 0x73, 0x75, 0x62, 0x66, 0x6F, 0x6C, 0x64, 0x65, 0x72, 0x32, 0x2F, 0x66, 0x69, 0x6C,
+    0x65, 0x31, 0x2E, 0x74, 0x78, 0x74, 0xF3, 0x48, 0xCD, 0xC9, 0xC9, 0x37, 0xD2, 0x51, 0x28, 0xCF,
+    0x2F, 0xCA, 0x49, 0x31, 0x54, 0x04, 0x00, 0x50, 0x4B, 0x03, 0x04, 0x14, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x21, 0x50, 0x82, 0xBB, 0x1F, 0x9D, 0x0F, 0x00, 0x00, 0x00, 0x0F, 0x00, 0x00,
+    0x00, 0x14, 0x00, 0x00, 0x00, 0x73, 0x75, 0x62, 0x66, 0x6F, 0x6C, 0x64, 0x65, 0x72, 0x32, 0x2F,
+    0x66, 0x69, 0x6C, 0x65, 0x32, 0x2E, 0x74, 0x78, 0x74, 0x48, 0x65, 0x6C, 0x6C, 0x6F, 0x32, 0x2C,
+    0x20, 0x77, 0x6F, 0x72, 0x6C, 0x64, 0x32, 0x21, 0x50, 0x4B, 0x03, 0x04, 0x14, 0x00, 0x00, 0x00,
+    0x08, 0x00, 0x00, 0x00, 0x21, 0x50, 0xC4, 0x65, 0x6A, 0x60, 0xF7, 0x00, 0x00, 0x00, 0xF2, 0x08,
+    0x00, 0x00, 0x09, 0x00, 0x00, 0x00, 0x6C, 0x69, 0x6E, 0x65, 0x73, 0x2E, 0x74, 0x78, 0x74, 0x75,
+    0xD6, 0x3B, 0x4E, 0x43, 0x31, 0x10, 0x40, 0xD1, 0x9E, 0x55, 0x3C, 0x7A, 0x0A, 0x3C, 0xFE, 0x8D,
+    0xB3, 0x02, 0x8A, 0x2C, 0x83, 0x14, 0x48, 0x4F, 0x44, 0xA2, 0x61, 0xFB, 0x51, 0xEA, 0x1C, 0xB7,
+    0xB7, 0x3B, 0xB2, 0x3D, 0xE3, 0xEB, 0xCF, 0xEF, 0xED, 0xF8, 0xBC, 0x1C, 0x5F, 0xB7, 0xF3, 0xBC,
+    0x7F, 0x1C, 0xFF, 0xF7, 0xBF, 0xF3, 0xFB, 0xFD, 0xED, 0xFA, 0xAC, 0x85, 0x35, 0x58, 0x2B, 0x6B,
+    0x63, 0xED, 0xAC, 0x83, 0x75, 0xB2, 0x26, 0xEB, 0xB2, 0x62, 0x83, 0xB3, 0xAE, 0x98, 0x57, 0xEC,
+    0x2B, 0x06, 0x16, 0x0B, 0x8B, 0x89, 0xC5, 0xC6, 0x62, 0x64, 0xB1, 0x32, 0xAC, 0x8C, 0xCD, 0x19,
+    0x5A, 0x19, 0x56, 0x86, 0x95, 0x61, 0x65, 0x58, 0x19, 0x56, 0x86, 0x95, 0x61, 0x65, 0xB5, 0xB2,
+    0x5A, 0x59, 0x37, 0x57, 0xD5, 0xCA, 0x6A, 0x65, 0xB5, 0xB2, 0x5A, 0x59, 0xAD, 0xAC, 0x56, 0x56,
+    0x2B, 0x9B, 0x95, 0xCD, 0xCA, 0x66, 0x65, 0xDB, 0xBC, 0x48, 0x2B, 0x9B, 0x95, 0xCD, 0xCA, 0x66,
+    0x65, 0xB3, 0xB2, 0x59, 0xD9, 0xAD, 0xEC, 0x56, 0x76, 0x2B, 0xBB, 0x95, 0x7D, 0x33, 0x78, 0xAC,
+    0xEC, 0x56, 0x76, 0x2B, 0xBB, 0x95, 0xDD, 0xCA, 0x61, 0xE5, 0xB0, 0x72, 0x58, 0x39, 0xAC, 0x1C,
+    0x56, 0x8E, 0xCD, 0x7C, 0xB5, 0x72, 0x58, 0x39, 0xAC, 0x1C, 0x56, 0x4E, 0x2B, 0xA7, 0x95, 0xD3,
+    0xCA, 0x69, 0xE5, 0xB4, 0x72, 0x5A, 0x39, 0x37, 0x6B, 0xC4, 0xCA, 0x69, 0xE5, 0xB4, 0x32, 0xAD,
+    0x4C, 0x2B, 0xD3, 0xCA, 0xB4, 0x32, 0xAD, 0x4C, 0x2B, 0xD3, 0xCA, 0xDC, 0x6C, 0x4B, 0x2B, 0xD3,
+    0xCA, 0x65, 0xE5, 0xB2, 0x72, 0x59, 0xB9, 0xAC, 0x5C, 0x56, 0x2E, 0x2B, 0x97, 0x95, 0xCB, 0xCA,
+    0xB5, 0xF9, 0x14, 0xBC, 0x28, 0x1F, 0x50, 0x4B, 0x01, 0x02, 0x14, 0x03, 0x14, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x21, 0x50, 0x42, 0x53, 0x05, 0x5D, 0x0F, 0x00, 0x00, 0x00, 0x0F, 0x00,
+    0x00, 0x00, 0x14, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0x01,
+    0x00, 0x00, 0x00, 0x00, 0x73, 0x75, 0x62, 0x66, 0x6F, 0x6C, 0x64, 0x65, 0x72, 0x31, 0x2F, 0x66,
+    0x69, 0x6C, 0x65, 0x31, 0x2E, 0x74, 0x78, 0x74, 0x50, 0x4B, 0x01, 0x02, 0x14, 0x03, 0x14, 0x00,
+    0x00, 0x00, 0x08, 0x00, 0x00, 0x00, 0x21, 0x50, 0x81, 0x00, 0x28, 0x76, 0x11, 0x00, 0x00, 0x00,
+    0x0F, 0x00, 0x00, 0x00, 0x14, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x80, 0x01, 0x41, 0x00, 0x00, 0x00, 0x73, 0x75, 0x62, 0x66, 0x6F, 0x6C, 0x64, 0x65, 0x72, 0x31,
+    0x2F, 0x66, 0x69, 0x6C, 0x65, 0x32, 0x2E, 0x74, 0x78, 0x74, 0x50, 0x4B, 0x01, 0x02, 0x14, 0x03,
+    0x14, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00, 0x21, 0x50, 0x41, 0xE8, 0x32, 0xB6, 0x11, 0x00,
+    0x00, 0x00, 0x0F, 0x00, 0x00, 0x00, 0x14, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x80, 0x01, 0x84, 0x00, 0x00, 0x00, 0x73, 0x75, 0x62, 0x66, 0x6F, 0x6C, 0x64, 0x65,
+    0x72, 0x32, 0x2F, 0x66, 0x69, 0x6C, 0x65, 0x31, 0x2E, 0x74, 0x78, 0x74, 0x50, 0x4B, 0x01, 0x02,
+    0x14, 0x03, 0x14, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x21, 0x50, 0x82, 0xBB, 0x1F, 0x9D,
+    0x0F, 0x00, 0x00, 0x00, 0x0F, 0x00, 0x00, 0x00, 0x14, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x80, 0x01, 0xC7, 0x00, 0x00, 0x00, 0x73, 0x75, 0x62, 0x66, 0x6F, 0x6C,
+    0x64, 0x65, 0x72, 0x32, 0x2F, 0x66, 0x69, 0x6C, 0x65, 0x32, 0x2E, 0x74, 0x78, 0x74, 0x50, 0x4B,
+    0x01, 0x02, 0x14, 0x03, 0x14, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00, 0x21, 0x50, 0xC4, 0x65,
+    0x6A, 0x60, 0xF7, 0x00, 0x00, 0x00, 0xF2, 0x08, 0x00, 0x00, 0x09, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0x01, 0x08, 0x01, 0x00, 0x00, 0x6C, 0x69, 0x6E, 0x65,
+    0x73, 0x2E, 0x74, 0x78, 0x74, 0x50, 0x4B, 0x05, 0x06, 0x00, 0x00, 0x00, 0x00, 0x05, 0x00, 0x05,
+    0x00, 0x3F, 0x01, 0x00, 0x00, 0x26, 0x02, 0x00, 0x00, 0x00, 0x00,
+];
+
+
+#[test]
+/// Trying to create [KAssetSourceZip] using an archive that doesn't exists.
+/// 
+/// # Verification(s)
+/// V1 | KAssetSourceZip::new() must return Err(KAssetSourceZipError::FileNotFound) since path doesn't exists.
+fn kasset_source_zip_create_not_found() {
+    // V1 | KAssetSourceZip::new() must return Err(KAssetSourceZipError::FileNotFound) since path doesn't exists.
+    match KAssetSourceZip::new(PathBuf::from("/kasz_not_found.zip")) {
+        Ok(_) => panic!("KAssetSourceZip::new() must return Err(KAssetSourceZipError::FileNotFound) since path doesn't exists."),
+        Err(err) => assert!(matches!(err, KAssetSourceZipError::FileNotFound), "Wrong error given!"),
+    }
+}
+
+#[test]
+/// Trying to create [KAssetSourceZip] using a folder instead of an archive.
+/// 
+/// # Verification(s)
+/// V1 | KAssetSourceZip::new() must return Err(KAssetSourceZipError::PathIsNotFile) since path is a folder.
+fn kasset_source_zip_create_not_file() {
+    // Test folder name
+    let folder_name: &str = &(TEST_FOLDER.to_owned() + "kasz_create_not_file/");
+    create_folder(folder_name);
+
+    // V1 | KAssetSourceZip::new() must return Err(KAssetSourceZipError::PathIsNotFile) since path is a folder.
+    match KAssetSourceZip::new(PathBuf::from(folder_name)) {
+        Ok(_) => panic!("KAssetSourceZip::new() must return Err(KAssetSourceZipError::PathIsNotFile) since path is a folder."),
+        Err(err) => assert!(matches!(err, KAssetSourceZipError::PathIsNotFile), "Wrong error given!"),
+    }
+
+    // Clean test
+    fs::remove_dir_all(PathBuf::from(folder_name)).expect("Test couldn't be cleaned!");
+}
+
+#[test]
+/// Trying to create [KAssetSourceZip] using a file that isn't an archive.
+/// 
+/// # Verification(s)
+/// V1 | KAssetSourceZip::new() must return Err(KAssetSourceZipError::InvalidArchive) for a text file.
+/// V2 | KAssetSourceZip::new() must return Err(KAssetSourceZipError::InvalidArchive) for a truncated archive.
+/// V3 | KAssetSourceZip::new() must return Err(KAssetSourceZipError::InvalidArchive) for entries with impossible sizes or offset.
+fn kasset_source_zip_create_invalid() {
+    // Test folder name
+    let folder_name: &str = &(TEST_FOLDER.to_owned() + "kasz_create_invalid/");
+    create_folder(folder_name);
+
+    // V1 | KAssetSourceZip::new() must return Err(KAssetSourceZipError::InvalidArchive) for a text file.
+    create_file_with_content(&(folder_name.to_owned() + "file.zip"), "Hello, world! This is not a zip archive.".as_bytes());
+    match KAssetSourceZip::new(PathBuf::from(folder_name.to_owned() + "file.zip")) {
+        Ok(_) => panic!("KAssetSourceZip::new() must return Err(KAssetSourceZipError::InvalidArchive) for a text file."),
+        Err(err) => assert!(matches!(err, KAssetSourceZipError::InvalidArchive), "Wrong error given!"),
+    }
+
+    // V2 | KAssetSourceZip::new() must return Err(KAssetSourceZipError::InvalidArchive) for a truncated archive.
+    create_file_with_content(&(folder_name.to_owned() + "truncated.zip"), &TEST_ZIP[400..]);
+    match KAssetSourceZip::new(PathBuf::from(folder_name.to_owned() + "truncated.zip")) {
+        Ok(_) => panic!("KAssetSourceZip::new() must return Err(KAssetSourceZipError::InvalidArchive) for a truncated archive."),
+        Err(err) => assert!(matches!(err, KAssetSourceZipError::InvalidArchive), "Wrong error given!"),
+    }
+
+    // V3 | KAssetSourceZip::new() must return Err(KAssetSourceZipError::InvalidArchive) for entries with impossible sizes or offset.
+    // Central headers of stored subfolder1/file1.txt then deflated subfolder1/file2.txt, with sizes at 20 and 24 and offset at 42
+    let stored = find(&TEST_ZIP, &[0x50, 0x4B, 0x01, 0x02]);
+    let deflated = stored + 46 + find(&TEST_ZIP[stored + 46..], &[0x50, 0x4B, 0x01, 0x02]);
+    for (position, value) in [(stored + 24, 0xFFFF_FFF0u32), (deflated + 24, 0xFFFF_FFF0), (deflated + 20, 0xFFFF_FFF0), (stored + 42, 0xFFFF_FF00)] {
+        let mut crafted = TEST_ZIP;
+        crafted[position..position + 4].copy_from_slice(&value.to_le_bytes());
+        create_file_with_content(&(folder_name.to_owned() + "crafted.zip"), &crafted);
+        match KAssetSourceZip::new(PathBuf::from(folder_name.to_owned() + "crafted.zip")) {
+            Ok(_) => panic!("KAssetSourceZip::new() must return Err(KAssetSourceZipError::InvalidArchive) for impossible sizes."),
+            Err(err) => assert!(matches!(err, KAssetSourceZipError::InvalidArchive), "Wrong error given!"),
+        }
+    }
+
+    // Clean test
+    fs::remove_dir_all(PathBuf::from(folder_name)).expect("Test couldn't be cleaned!");
+}
+
+#[test]
+/// Create [KAssetSourceZip] and test KAssetSource::has_asset().
+/// 
+/// # Verification(s)
+/// V1 | KAssetSourceZip::new() created from valid archive without error.
+/// V2 | KAssetSourceZip::get_entry_count() returns the count of files in archive.
+/// V3 | KAssetSourceZip has archived files.
+/// V4 | KAssetSourceZip doesn't have folders nor inexistant files, and resolves canonical paths.
+fn kasset_source_zip_has_file() {
+    // Test folder name
+    let folder_name: &str = &(TEST_FOLDER.to_owned() + "kasz_has_file/");
+    create_folder(folder_name);
+    create_file_with_content(&(folder_name.to_owned() + "test.zip"), &TEST_ZIP);
+
+    // V1 | KAssetSourceZip::new() created from valid archive without error.
+    let kasz = KAssetSourceZip::new(PathBuf::from(folder_name.to_owned() + "test.zip")).unwrap();
+
+    // V2 | KAssetSourceZip::get_entry_count() returns the count of files in archive.
+    assert!(kasz.get_entry_count() == 5, "KAssetSourceZip should have 5 entries!");
+
+    // V3 | KAssetSourceZip has archived files.
+    for i in 0..4 {
+        for j in 0..4 {
+            let file_name = "subfolder".to_owned() + i.to_string().as_str() + "/file" + j.to_string().as_str() + ".txt";
+
+            if (1..=2).contains(&i) && (1..=2).contains(&j) {
+                assert!(kasz.has_asset(&KAssetPath::new(&file_name).unwrap()), "KAssetSourceZip should have file {}", &file_name);
+            } else {
+                assert!(!kasz.has_asset(&KAssetPath::new(&file_name).unwrap()), "KAssetSourceZip shouldn't have file {}", &file_name);
+            }
+        }
+    }
+    assert!(kasz.has_asset(&KAssetPath::new("lines.txt").unwrap()), "KAssetSourceZip should have file lines.txt");
+    assert!(kasz.has_asset(&KAssetPath::new("./subfolder1/file1.txt").unwrap()), "KAssetSourceZip should have file ./subfolder1/file1.txt");
+
+    // V4 | KAssetSourceZip doesn't have folders nor inexistant files, and resolves canonical paths.
+    assert!(!kasz.has_asset(&KAssetPath::new("subfolder1").unwrap()), "KAssetSourceZip shouldn't have folder subfolder1");
+    assert!(!kasz.has_asset(&KAssetPath::new("lines.txt.bak").unwrap()), "KAssetSourceZip shouldn't have file lines.txt.bak");
+    assert!(kasz.has_asset(&KAssetPath::new("subfolder2/../subfolder1/file1.txt").unwrap()), "KAssetSourceZip should have canonical path subfolder1/file1.txt");
+
+    // Clean test
+    fs::remove_dir_all(PathBuf::from(folder_name)).expect("Test couldn't be cleaned!");
+}
+
+#[test]
+/// Create [KAssetSourceZip] and test read from asset given by KAssetSource::get_asset().
+/// 
+/// # Verification(s)
+/// V1 | KAssetSourceZip::get_asset() return a valid readable asset for stored and deflated entries.
+/// V2 | Asset content matches correct content.
+/// V3 | Dynamic Huffman deflated asset content matches correct content.
+/// V4 | KAssetSourceZip::get_asset() must not return invalid asset.
+fn kasset_source_zip_read_file() {
+    // Test folder name
+    let folder_name: &str = &(TEST_FOLDER.to_owned() + "kasz_read_file/");
+    create_folder(folder_name);
+    create_file_with_content(&(folder_name.to_owned() + "test.zip"), &TEST_ZIP);
+
+    // Create KAssetSourceZip
+    let kasz = KAssetSourceZip::new(PathBuf::from(folder_name.to_owned() + "test.zip")).unwrap();
+
+    for i in 0..4 {
+        for j in 0..4 {
+            let file_name = "subfolder".to_owned() + i.to_string().as_str() + "/file" + j.to_string().as_str() + ".txt";
+
+            match kasz.get_asset(&KAssetPath::new(&file_name).unwrap()) {
+                Ok(mut asset) => {
+                    assert!((1..=2).contains(&i) && (1..=2).contains(&j), "KAssetSourceZip shouldn't have file {}", &file_name);
+
+                    // V1 | KAssetSourceZip::get_asset() return a valid readable asset for stored and deflated entries.
+                    let mut content = String::new();
+                    asset.read_to_string(&mut content).expect("Couldn't read asset!");
+
+                    // V2 | Asset content matches correct content.
+                    assert!(content.eq(&("Hello".to_owned() + i.to_string().as_str() + ", world" + j.to_string().as_str() + "!")), "Content is different that expected!");
+                },
+                // V4 | KAssetSourceZip::get_asset() must not return invalid asset.
+                Err(_) => assert!(!((1..=2).contains(&i) && (1..=2).contains(&j)), "KAssetSourceZip should have file {}", &file_name),
+            }
+        }
+    }
+
+    // V3 | Dynamic Huffman deflated asset content matches correct content.
+    let mut content = String::new();
+    kasz.get_asset(&KAssetPath::new("lines.txt").unwrap()).unwrap().read_to_string(&mut content).expect("Couldn't read asset!");
+    let expected : String = (0..100).map(|k| format!("Line {}: Hello, world!\n", k)).collect();
+    assert!(content.eq(&expected), "Content is different that expected!");
+
+    // Clean test
+    fs::remove_dir_all(PathBuf::from(folder_name)).expect("Test couldn't be cleaned!");
+}
+
+#[test]
+/// Seek and read ranges of stored and deflated entries of [KAssetSourceZip].
+/// 
+/// # Verification(s)
+/// V1 | KAssetSourceZip::get_asset_seekable() reports uncompressed length of stored and deflated entries.
+/// V2 | Seeking within entries reads correct content and never reads outside entry.
+/// V3 | KAssetSourceZip::get_asset_range() reads range of entry, truncated at the end of entry.
+fn kasset_source_zip_seek_file() {
+    // Test folder name
+    let folder_name: &str = &(TEST_FOLDER.to_owned() + "kasz_seek_file/");
+    create_folder(folder_name);
+    create_file_with_content(&(folder_name.to_owned() + "test.zip"), &TEST_ZIP);
+
+    let kasz = KAssetSourceZip::new(PathBuf::from(folder_name.to_owned() + "test.zip")).unwrap();
+
+    // subfolder1/file1.txt is stored and subfolder1/file2.txt is deflated
+    for (path, expected) in [("subfolder1/file1.txt", "Hello1, world1!"), ("subfolder1/file2.txt", "Hello1, world2!")] {
+        // V1 | KAssetSourceZip::get_asset_seekable() reports uncompressed length of stored and deflated entries.
+        let mut reader = kasz.get_asset_seekable(&KAssetPath::new(path).unwrap()).expect("Asset should be seekable!");
+        assert!(reader.get_length() == 15, "Wrong length {}!", reader.get_length());
+
+        // V2 | Seeking within entries reads correct content and never reads outside entry.
+        let mut content = String::new();
+        reader.seek(SeekFrom::Start(8)).unwrap();
+        reader.read_to_string(&mut content).unwrap();
+        assert!(content == expected[8..], "Content is different that expected!");
+
+        let mut content = String::new();
+        reader.seek(SeekFrom::Current(-8)).unwrap();
+        reader.by_ref().take(5).read_to_string(&mut content).unwrap();
+        assert!(content == expected[7..12], "Content is different that expected!");
+
+        // V3 | KAssetSourceZip::get_asset_range() reads range of entry, truncated at the end of entry.
+        let mut content = String::new();
+        kasz.get_asset_range(&KAssetPath::new(path).unwrap(), 0, 6).unwrap().read_to_string(&mut content).unwrap();
+        assert!(content == expected[..6], "Content is different that expected!");
+
+        let mut content = String::new();
+        kasz.get_asset_range(&KAssetPath::new(path).unwrap(), 10, 100).unwrap().read_to_string(&mut content).unwrap();
+        assert!(content == expected[10..], "Range should be truncated!");
+    }
+
+    // Clean test
+    fs::remove_dir_all(PathBuf::from(folder_name)).expect("Test couldn't be cleaned!");
+}
+
+#[test]
+/// Read corrupted entries from [KAssetSourceZip].
+/// 
+/// # Verification(s)
+/// V1 | Reading a corrupted stored entry must return an error.
+/// V2 | Reading a corrupted deflated entry must return an error.
+fn kasset_source_zip_read_corrupted() {
+    // Test folder name
+    let folder_name: &str = &(TEST_FOLDER.to_owned() + "kasz_read_corrupted/");
+    create_folder(folder_name);
+
+    // Corrupt "Hello1, world1!" of stored subfolder1/file1.txt and the deflate stream of lines.txt
+    let mut corrupted = TEST_ZIP;
+    let stored = find(&corrupted, b"Hello1, world1!");
+    corrupted[stored] = b'J';
+    let deflated = find(&corrupted, b"lines.txt") + 9 + 20;
+    corrupted[deflated] ^= 0xFF;
+    create_file_with_content(&(folder_name.to_owned() + "test.zip"), &corrupted);
+
+    let kasz = KAssetSourceZip::new(PathBuf::from(folder_name.to_owned() + "test.zip")).unwrap();
+
+    // V1 | Reading a corrupted stored entry must return an error.
+    let mut content = String::new();
+    assert!(kasz.get_asset(&KAssetPath::new("subfolder1/file1.txt").unwrap()).and_then(|mut asset| asset.read_to_string(&mut content)).is_err(),
+        "Corrupted stored entry shouldn't be read without error!");
+
+    // V2 | Reading a corrupted deflated entry must return an error.
+    let mut content = String::new();
+    assert!(kasz.get_asset(&KAssetPath::new("lines.txt").unwrap()).and_then(|mut asset| asset.read_to_string(&mut content)).is_err(),
+        "Corrupted deflated entry shouldn't be read without error!");
+
+    // Clean test
+    fs::remove_dir_all(PathBuf::from(folder_name)).expect("Test couldn't be cleaned!");
+}
+
+#[test]
+/// Add [KAssetSourceZip] to [KAssetBroker] and get assets from it.
+/// 
+/// # Verification(s)
+/// V1 | KAssetBroker::add_source() accepts KAssetSourceZip.
+/// V2 | KAssetBroker::get_asset() returns asset content from archive.
+fn kasset_source_zip_broker() {
+    // Test folder name
+    let folder_name: &str = &(TEST_FOLDER.to_owned() + "kasz_broker/");
+    create_folder(folder_name);
+    create_file_with_content(&(folder_name.to_owned() + "test.zip"), &TEST_ZIP);
+
+    let kasz = KAssetSourceZip::new(PathBuf::from(folder_name.to_owned() + "test.zip")).unwrap();
+
+    // V1 | KAssetBroker::add_source() accepts KAssetSourceZip.
+    let kab = KAssetBroker::new();
+    assert!(kab.add_source(Arc::new(kasz)).is_ok(), "KAssetBroker::add_source() should accept KAssetSourceZip!");
+
+    // V2 | KAssetBroker::get_asset() returns asset content from archive.
+    let mut content = String::new();
+    kab.get_asset(PathBuf::from("subfolder2/file1.txt")).unwrap().read_to_string(&mut content).expect("Couldn't read asset!");
+    assert!(content.eq("Hello2, world1!"), "Content is different that expected!");
+
+    // Clean test
+    fs::remove_dir_all(PathBuf::from(folder_name)).expect("Test couldn't be cleaned!");
+}
+
+#[test]
+/// Get metadata of [KAssetSourceZip] and of its entries.
+/// 
+/// # Verification(s)
+/// V1 | KAssetSource::get_metadata() gives kind, archive path and name, entry count and uncompressed size.
+/// V2 | KAssetSource::get_asset_metadata() gives entry size, modified time and CRC-32 from central directory.
+/// V3 | KAssetSource::get_asset_metadata() must return Err() for missing entries.
+fn kasset_source_zip_metadata() {
+    // Test folder name
+    let folder_name: &str = &(TEST_FOLDER.to_owned() + "kasz_metadata/");
+    create_folder(folder_name);
+    create_file_with_content(&(folder_name.to_owned() + "test.zip"), &TEST_ZIP);
+
+    let kasz = KAssetSourceZip::new(PathBuf::from(folder_name.to_owned() + "test.zip")).unwrap();
+
+    // V1 | KAssetSource::get_metadata() gives kind, archive path and name, entry count and uncompressed size.
+    let metadata = kasz.get_metadata();
+    assert!(metadata.kind == KAssetSourceKind::Zip && metadata.name.as_deref() == Some("test"), "Wrong kind or name!");
+    assert!(metadata.location == Some(folder_name.to_owned() + "test.zip") && metadata.modified.is_some(), "Wrong location!");
+    assert!(metadata.asset_count == Some(5) && metadata.size == Some(2350), "Wrong count or size {:?}!", metadata);
+
+    // V2 | KAssetSource::get_asset_metadata() gives entry size, modified time and CRC-32 from central directory.
+    let asset = kasz.get_asset_metadata(&KAssetPath::new("subfolder1/file1.txt").unwrap()).unwrap();
+    assert!(asset.size == 15 && asset.hash == Some(0x5D055342), "Wrong size or hash {:?}!", asset);
+    assert!(asset.modified == Some(UNIX_EPOCH + Duration::from_secs(1577836800)), "Entry should be modified on 2020-01-01!");
+    assert!(kasz.get_asset_metadata(&KAssetPath::new("lines.txt").unwrap()).unwrap().size == 2290, "Wrong size of deflated entry!");
+
+    // V3 | KAssetSource::get_asset_metadata() must return Err() for missing entries.
+    assert!(kasz.get_asset_metadata(&KAssetPath::new("subfolder1").unwrap()).is_err(), "Folder shouldn't have metadata!");
+
+    // Clean test
+    fs::remove_dir_all(PathBuf::from(folder_name)).expect("Test couldn't be cleaned!");
+}
+
+
+/************
+* FUNCTIONS * 
+************/
+/// Create a folder with parents folders from path.
+///
+/// # Panic
+/// Will panic if folders not created.
+fn create_folder(folder_path : &str) {
+
+    match fs::create_dir_all(folder_path){
+        Ok(_) => {},
+        Err(_) => panic!("Error when creating folder {}!", folder_path),
+    }
+
+}
+
+/// Create a file and it's content from path and content.
+/// 
+/// # Panic
+/// Will panic if file cannot be created or written.
+fn create_file_with_content(file_path : &str, file_content : &[u8]){
+
+    match File::create(file_path) {
+        Ok(mut file) => { 
+            match file.write_all(file_content) {
+                Ok(_) => {},
+                Err(_) => panic!("Error when writing file {}!", file_path),
+            }
+        },
+        Err(_) => panic!("Error when creating file {}!", file_path),
+    }
+}
+
+/// Find position of first occurrence of `needle` in `data`.
+/// 
+/// # Panic
+/// Will panic if `needle` is not found.
+fn find(data : &[u8], needle : &[u8]) -> usize {
+    data.windows(needle.len()).position(|w| w == needle).expect("Needle not found!")
+}
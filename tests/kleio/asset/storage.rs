@@ -0,0 +1,187 @@
+use std::{path::{Path, PathBuf}, io::Read, sync::{Arc, Barrier}, thread};
+use olympus_kleio::asset::{KAssetSourceMemory, KAssetBroker, KAssetLoader, KAssetLoaderError, KAssetStorage, KAssetUnloadPolicy, KAssetHandle, KAssetPath};
+
+/// Loader of UTF-8 text with memory size of its content.
+struct TextLoader;
+
+impl KAssetLoader for TextLoader {
+    type Asset = String;
+
+    fn load(&self, _path : &KAssetPath, reader : &mut dyn Read) -> Result<String, KAssetLoaderError> {
+        let mut content = String::new();
+        reader.read_to_string(&mut content).map_err(|_| KAssetLoaderError::ParseError)?;
+        Ok(content)
+    }
+
+    fn get_memory_size(&self, asset : &String) -> usize {
+        asset.len()
+    }
+}
+
+/// Loader of text length.
+struct LengthLoader;
+
+impl KAssetLoader for LengthLoader {
+    type Asset = usize;
+
+    fn load(&self, path : &KAssetPath, reader : &mut dyn Read) -> Result<usize, KAssetLoaderError> {
+        TextLoader.load(path, reader).map(|content| content.len())
+    }
+}
+
+/// Create a [KAssetStorage] with text assets.
+fn create_storage() -> KAssetStorage {
+    let kasm = KAssetSourceMemory::new("storage");
+    for (path, content) in [("a.txt", "Hello"), ("b.txt", "Hello, world!"), ("c.txt", "Bye")] {
+        kasm.add_asset(PathBuf::from(path), content.as_bytes().to_vec()).unwrap();
+    }
+
+    let kab = Arc::new(KAssetBroker::new());
+    kab.add_source(Arc::new(kasm)).unwrap();
+    kab.register_loader(&["txt"], TextLoader);
+    kab.register_loader(&["txt"], LengthLoader);
+
+    KAssetStorage::new(kab)
+}
+
+#[test]
+/// Share assets of [KAssetStorage] with handles, unloaded automatically.
+///
+/// # Verification(s)
+/// V1 | KAssetStorage::load() returns the same instance for the same path and type.
+/// V2 | Same path loaded as another type is another asset.
+/// V3 | Asset is unloaded when its last handle is dropped and weak handles can't upgrade anymore.
+/// V4 | Asset unloaded is loaded again as a new instance.
+/// V5 | KAssetStorage::load() returns loader errors.
+fn kasset_storage_automatic() {
+    let storage = create_storage();
+    assert!(storage.get_unload_policy() == KAssetUnloadPolicy::Automatic, "Automatic policy expected by default!");
+
+    // V1 | KAssetStorage::load() returns the same instance for the same path and type.
+    let a1 = storage.load::<String>(PathBuf::from("a.txt")).unwrap();
+    let a2 = storage.load::<String>(PathBuf::from("a.txt")).unwrap();
+    let a3 = storage.get::<String>(Path::new("a.txt")).expect("Asset should be loaded!");
+    assert!(a1.ptr_eq(&a2) && a1.ptr_eq(&a3), "Same instance expected!");
+    assert!(*a1 == "Hello" && a1.get_path().as_str() == "a.txt", "Wrong asset loaded!");
+
+    // V2 | Same path loaded as another type is another asset.
+    let length = storage.load::<usize>(PathBuf::from("a.txt")).unwrap();
+    assert!(*length == 5 && storage.get_loaded_count() == 2, "Another asset expected!");
+
+    // V3 | Asset is unloaded when its last handle is dropped and weak handles can't upgrade anymore.
+    let weak = a1.downgrade();
+    assert!(weak.is_alive() && weak.upgrade().is_some_and(|a| a.ptr_eq(&a2)), "Weak handle should upgrade!");
+    drop(a1);
+    drop(a2);
+    assert!(storage.is_loaded::<String>(Path::new("a.txt")), "Asset still used should be loaded!");
+    drop(a3);
+    assert!(!storage.is_loaded::<String>(Path::new("a.txt")) && storage.get::<String>(Path::new("a.txt")).is_none(), "Asset should be unloaded!");
+    assert!(!weak.is_alive() && weak.upgrade().is_none(), "Weak handle shouldn't upgrade!");
+    assert!(storage.get_loaded_count() == 1, "Only length should be loaded!");
+
+    // V4 | Asset unloaded is loaded again as a new instance.
+    let a4 = storage.load::<String>(PathBuf::from("a.txt")).unwrap();
+    assert!(*a4 == "Hello" && !weak.is_alive(), "Asset should be loaded again!");
+
+    // V5 | KAssetStorage::load() returns loader errors.
+    assert!(storage.load::<String>(PathBuf::from("missing.txt")).err() == Some(KAssetLoaderError::AssetNotFound), "Wrong error given!");
+    assert!(storage.load::<f32>(PathBuf::from("a.txt")).err() == Some(KAssetLoaderError::LoaderNotFound), "Wrong error given!");
+}
+
+#[test]
+/// Unload assets of [KAssetStorage] on demand.
+///
+/// # Verification(s)
+/// V1 | With manual policy, assets stay loaded without handles.
+/// V2 | KAssetStorage::unload_unused() unloads assets without handles only.
+/// V3 | KAssetStorage::unload() unloads assets of path of all types, handles stay valid.
+/// V4 | Switching to automatic policy unloads assets without handles.
+fn kasset_storage_manual() {
+    let storage = create_storage();
+    storage.set_unload_policy(KAssetUnloadPolicy::Manual);
+
+    // V1 | With manual policy, assets stay loaded without handles.
+    let a1 = storage.load::<String>(PathBuf::from("a.txt")).unwrap();
+    drop(storage.load::<String>(PathBuf::from("b.txt")).unwrap());
+    drop(storage.load::<String>(PathBuf::from("c.txt")).unwrap());
+    drop(storage.load::<usize>(PathBuf::from("c.txt")).unwrap());
+    assert!(storage.get_loaded_count() == 4, "Assets should stay loaded!");
+
+    // V2 | KAssetStorage::unload_unused() unloads assets without handles only.
+    let b = storage.get::<String>(Path::new("b.txt")).unwrap();
+    assert!(storage.unload_unused() == 2, "Assets of c.txt should be unloaded!");
+    assert!(storage.is_loaded::<String>(Path::new("a.txt")) && storage.is_loaded::<String>(Path::new("b.txt")), "Used assets should stay loaded!");
+    assert!(!storage.is_loaded::<String>(Path::new("c.txt")) && !storage.is_loaded::<usize>(Path::new("c.txt")), "Unused assets should be unloaded!");
+
+    // V3 | KAssetStorage::unload() unloads assets of path of all types, handles stay valid.
+    drop(storage.load::<usize>(PathBuf::from("a.txt")).unwrap());
+    assert!(storage.unload(Path::new("a.txt")) == 2, "Assets of a.txt should be unloaded!");
+    assert!(*a1 == "Hello", "Handle should stay valid!");
+    let a2 = storage.load::<String>(PathBuf::from("a.txt")).unwrap();
+    assert!(!a1.ptr_eq(&a2), "Asset should be loaded again!");
+
+    // V4 | Switching to automatic policy unloads assets without handles.
+    drop(b);
+    storage.set_unload_policy(KAssetUnloadPolicy::Automatic);
+    assert!(!storage.is_loaded::<String>(Path::new("b.txt")) && storage.is_loaded::<String>(Path::new("a.txt")), "Unused asset should be unloaded!");
+    drop(a2);
+    assert!(storage.get_loaded_count() == 0, "All assets should be unloaded!");
+}
+
+#[test]
+/// Report assets of [KAssetStorage].
+///
+/// # Verification(s)
+/// V1 | KAssetStorage::get_report() lists live assets sorted by path with type and memory size.
+/// V2 | Report counts handles without storage own reference, and weak handles.
+fn kasset_storage_report() {
+    let storage = create_storage();
+    storage.set_unload_policy(KAssetUnloadPolicy::Manual);
+
+    let b1 = storage.load::<String>(PathBuf::from("b.txt")).unwrap();
+    let b2 = b1.clone();
+    let weak = b1.downgrade();
+    let a = storage.load::<String>(PathBuf::from("a.txt")).unwrap();
+    drop(a);
+
+    // V1 | KAssetStorage::get_report() lists live assets sorted by path with type and memory size.
+    let report = storage.get_report();
+    assert!(report.len() == 2, "2 assets expected!");
+    assert!(report[0].path.as_str() == "a.txt" && report[1].path.as_str() == "b.txt", "Report should be sorted by path!");
+    assert!(report[0].type_name == std::any::type_name::<String>() && report[1].size == 13, "Wrong type or size!");
+
+    // V2 | Report counts handles without storage own reference, and weak handles.
+    assert!(report[0].handles == 0 && report[0].weak_handles == 0, "a.txt shouldn't have handles!");
+    assert!(report[1].handles == 2 && report[1].weak_handles == 1, "b.txt should have 2 handles and 1 weak handle!");
+    assert!(report[1].to_string() == format!("Asset b.txt : {}, 2 handles, 1 weak handles, 13 bytes", std::any::type_name::<String>()), "Wrong report line {}!", report[1]);
+
+    drop(b2);
+    drop(weak);
+    let report = storage.get_report();
+    assert!(report[1].handles == 1 && report[1].weak_handles == 0, "b.txt should have 1 handle!");
+    drop(b1);
+}
+
+#[test]
+/// Load the same asset of [KAssetStorage] from threads.
+///
+/// # Verification(s)
+/// V1 | Threads loading the same path concurrently all get the same instance.
+fn kasset_storage_threads() {
+    let storage = Arc::new(create_storage());
+    let barrier = Arc::new(Barrier::new(8));
+
+    // V1 | Threads loading the same path concurrently all get the same instance.
+    let threads : Vec<_> = (0..8).map(|_| {
+        let storage = storage.clone();
+        let barrier = barrier.clone();
+        thread::spawn(move || {
+            barrier.wait();
+            storage.load::<String>(PathBuf::from("b.txt")).unwrap()
+        })
+    }).collect();
+
+    let handles : Vec<KAssetHandle<String>> = threads.into_iter().map(|thread| thread.join().unwrap()).collect();
+    assert!(handles.iter().all(|handle| handle.ptr_eq(&handles[0])), "Same instance expected!");
+    assert!(storage.get_report()[0].handles == 8, "8 handles expected!");
+}
@@ -0,0 +1,202 @@
+use std::{path::PathBuf, io::{Read, Cursor, ErrorKind}, sync::{Arc, Mutex, mpsc::{self, Receiver, Sender}}, thread, time::Duration};
+use olympus_kleio::asset::{KAssetSource, KAssetSourceMemory, KAssetBroker, KAssetStreamer, KAssetStreamerError, KAssetLoadStatus, KAssetLoadError, KAssetPath};
+
+/// Source blocking on "gate.txt" until released, failing on "broken.txt" and panicking on "panic.txt".
+struct GatedSource {
+    // Receive a message for each gate release
+    gate : Mutex<Receiver<()>>,
+}
+
+impl KAssetSource for GatedSource {
+    fn has_asset(&self, path: &KAssetPath) -> bool {
+        ["gate.txt", "broken.txt", "panic.txt"].contains(&path.as_str())
+    }
+
+    fn get_asset(&self, path: &KAssetPath) -> Result<Box<dyn Read>, std::io::Error> {
+        if path.as_str() == "broken.txt" {
+            return Err(std::io::Error::new(ErrorKind::InvalidData, "Broken asset!"));
+        }
+
+        if path.as_str() == "panic.txt" {
+            panic!("Source panicked!");
+        }
+
+        self.gate.lock().unwrap().recv().unwrap();
+        Ok(Box::new(Cursor::new("gate")))
+    }
+}
+
+/// Create a broker with a [GatedSource] and a [KAssetSourceMemory] of 10 assets.
+///
+/// Returns broker and sender releasing the gate.
+fn create_broker() -> (Arc<KAssetBroker>, Sender<()>) {
+    let (sender, receiver) = mpsc::channel();
+
+    let kasm = KAssetSourceMemory::new("streamer");
+    for i in 0..10 {
+        kasm.add_asset(PathBuf::from("file".to_owned() + i.to_string().as_str() + ".txt"), ("Hello, world".to_owned() + i.to_string().as_str() + "!").into_bytes()).unwrap();
+    }
+
+    let broker = Arc::new(KAssetBroker::new());
+    broker.add_source(Arc::new(GatedSource { gate : Mutex::new(receiver) })).unwrap();
+    broker.add_source(Arc::new(kasm)).unwrap();
+
+    (broker, sender)
+}
+
+#[test]
+/// Create [KAssetStreamer] and load assets.
+///
+/// # Verification(s)
+/// V1 | KAssetStreamer::new() must return Err(KAssetStreamerError::InvalidWorkerCount) for 0 worker.
+/// V2 | KAssetStreamer::request() returns handles that can be waited on.
+/// V3 | KAssetLoadHandle::wait() returns asset content.
+/// V4 | KAssetStreamer::drain_completed() returns each loaded handle once.
+fn kasset_streamer_load() {
+    let (broker, _sender) = create_broker();
+
+    // V1 | KAssetStreamer::new() must return Err(KAssetStreamerError::InvalidWorkerCount) for 0 worker.
+    assert!(matches!(KAssetStreamer::new(broker.clone(), 0), Err(KAssetStreamerError::InvalidWorkerCount)), "0 worker should be refused!");
+
+    let streamer = KAssetStreamer::new(broker, 4).unwrap();
+    assert!(streamer.get_worker_count() == 4, "Streamer should have 4 workers!");
+
+    // V2 | KAssetStreamer::request() returns handles that can be waited on.
+    let handles : Vec<_> = (0..10).map(|i| streamer.request("file".to_owned() + i.to_string().as_str() + ".txt", i).unwrap()).collect();
+
+    // V3 | KAssetLoadHandle::wait() returns asset content.
+    for (i, handle) in handles.iter().enumerate() {
+        let data = handle.wait().expect("Asset should be loaded!");
+        assert!(data.as_ref() == ("Hello, world".to_owned() + i.to_string().as_str() + "!").as_bytes(), "Content is different that expected!");
+        assert!(handle.get_status() == KAssetLoadStatus::Loaded, "Handle should be loaded!");
+        assert!(handle.is_done(), "Handle should be done!");
+    }
+
+    // V4 | KAssetStreamer::drain_completed() returns each loaded handle once.
+    let mut ids : Vec<u64> = streamer.drain_completed().iter().map(|handle| handle.get_id()).collect();
+    ids.sort();
+    assert!(ids == handles.iter().map(|handle| handle.get_id()).collect::<Vec<_>>(), "All handles should be completed!");
+    assert!(streamer.drain_completed().is_empty(), "Completed handles should be drained!");
+}
+
+#[test]
+/// Verify that failed loads are reported.
+///
+/// # Verification(s)
+/// V1 | KAssetLoadHandle::wait() must return Err(KAssetLoadError::NotFound) for inexistant asset.
+/// V2 | KAssetLoadHandle::wait() must return Err(KAssetLoadError::ReadError) for asset that can't be read.
+/// V3 | Failed handles are returned by KAssetStreamer::drain_completed().
+/// V4 | KAssetLoadHandle::wait() must return Err(KAssetLoadError::Panicked) for asset panicking source, and worker keeps loading.
+fn kasset_streamer_failed() {
+    let (broker, _sender) = create_broker();
+    let streamer = KAssetStreamer::new(broker, 2).unwrap();
+
+    // V1 | KAssetLoadHandle::wait() must return Err(KAssetLoadError::NotFound) for inexistant asset.
+    let missing = streamer.request("missing.txt", 0).unwrap();
+    assert!(matches!(missing.wait(), Err(KAssetLoadError::NotFound)), "Asset shouldn't be found!");
+    assert!(missing.get_status() == KAssetLoadStatus::Failed, "Handle should be failed!");
+
+    // V2 | KAssetLoadHandle::wait() must return Err(KAssetLoadError::ReadError) for asset that can't be read.
+    let broken = streamer.request("broken.txt", 0).unwrap();
+    assert!(matches!(broken.wait(), Err(KAssetLoadError::ReadError)), "Asset shouldn't be readable!");
+
+    // V3 | Failed handles are returned by KAssetStreamer::drain_completed().
+    assert!(streamer.drain_completed().len() == 2, "Failed handles should be completed!");
+
+    // V4 | KAssetLoadHandle::wait() must return Err(KAssetLoadError::Panicked) for asset panicking source, and worker keeps loading.
+    let (broker, _sender) = create_broker();
+    let streamer = KAssetStreamer::new(broker, 1).unwrap();
+    let panicked = streamer.request("panic.txt", 0).unwrap();
+    assert!(matches!(panicked.wait(), Err(KAssetLoadError::Panicked)), "Load should have panicked!");
+    assert!(panicked.get_status() == KAssetLoadStatus::Failed, "Handle should be failed!");
+    assert!(streamer.request("file0.txt", 0).unwrap().wait().unwrap().as_ref() == b"Hello, world0!", "Worker should keep loading!");
+}
+
+#[test]
+/// Verify that requests are loaded according to priority.
+///
+/// # Verification(s)
+/// V1 | Requests wait in queue while single worker is busy.
+/// V2 | Requests are completed by priority, then in order of request.
+fn kasset_streamer_priority() {
+    let (broker, sender) = create_broker();
+    let streamer = KAssetStreamer::new(broker, 1).unwrap();
+
+    // Keep the only worker busy
+    let gate = streamer.request("gate.txt", 0).unwrap();
+    while gate.get_status() != KAssetLoadStatus::Loading {
+        thread::yield_now();
+    }
+
+    // V1 | Requests wait in queue while single worker is busy.
+    let priorities = [5, 1, 9, 1, 0];
+    let handles : Vec<_> = priorities.iter().enumerate().map(|(i, p)| streamer.request("file".to_owned() + i.to_string().as_str() + ".txt", *p).unwrap()).collect();
+    assert!(streamer.get_queued_count() == 5, "5 requests should be queued!");
+    assert!(handles.iter().all(|handle| handle.poll().is_none()), "Requests shouldn't be done!");
+
+    sender.send(()).unwrap();
+    for handle in handles.iter() {
+        handle.wait().expect("Asset should be loaded!");
+    }
+
+    // V2 | Requests are completed by priority, then in order of request.
+    let order : Vec<u64> = streamer.drain_completed().iter().map(|handle| handle.get_id()).collect();
+    let expected : Vec<u64> = [&gate, &handles[4], &handles[1], &handles[3], &handles[0], &handles[2]].iter().map(|handle| handle.get_id()).collect();
+    assert!(order == expected, "Requests should be completed by priority!");
+}
+
+#[test]
+/// Cancel requests.
+///
+/// # Verification(s)
+/// V1 | KAssetLoadHandle::cancel() cancels a queued request.
+/// V2 | KAssetLoadHandle::cancel() cancels a request being loaded.
+/// V3 | Cancelled requests are not returned by KAssetStreamer::drain_completed().
+/// V4 | KAssetLoadHandle::cancel() returns false once request is done.
+/// V5 | Dropping KAssetStreamer cancels queued requests.
+fn kasset_streamer_cancel() {
+    let (broker, sender) = create_broker();
+    let streamer = KAssetStreamer::new(broker, 1).unwrap();
+
+    let gate = streamer.request("gate.txt", 0).unwrap();
+    while gate.get_status() != KAssetLoadStatus::Loading {
+        thread::yield_now();
+    }
+    let queued = streamer.request("file0.txt", 0).unwrap();
+    let kept = streamer.request("file1.txt", 1).unwrap();
+
+    // V1 | KAssetLoadHandle::cancel() cancels a queued request.
+    assert!(queued.cancel(), "Queued request should be cancelled!");
+    assert!(matches!(queued.wait(), Err(KAssetLoadError::Cancelled)), "Request should be cancelled!");
+
+    // V2 | KAssetLoadHandle::cancel() cancels a request being loaded.
+    assert!(gate.cancel(), "Loading request should be cancelled!");
+    assert!(gate.get_status() == KAssetLoadStatus::Cancelled, "Request should be cancelled!");
+    sender.send(()).unwrap();
+
+    // V3 | Cancelled requests are not returned by KAssetStreamer::drain_completed().
+    kept.wait().expect("Asset should be loaded!");
+    let completed = streamer.drain_completed();
+    assert!(completed.len() == 1 && completed[0].get_id() == kept.get_id(), "Only kept request should be completed!");
+
+    // V4 | KAssetLoadHandle::cancel() returns false once request is done.
+    assert!(!kept.cancel(), "Loaded request can't be cancelled!");
+    assert!(kept.get_status() == KAssetLoadStatus::Loaded, "Request should stay loaded!");
+
+    // V5 | Dropping KAssetStreamer cancels queued requests.
+    let gate = streamer.request("gate.txt", 0).unwrap();
+    while gate.get_status() != KAssetLoadStatus::Loading {
+        thread::yield_now();
+    }
+    let dropped = streamer.request("file2.txt", 0).unwrap();
+
+    // Release gate once queued request is cancelled by drop
+    let waiter = dropped.clone();
+    let release = thread::spawn(move || {
+        assert!(matches!(waiter.wait_timeout(Duration::from_secs(5)), Some(Err(KAssetLoadError::Cancelled))), "Queued request should be cancelled on drop!");
+        sender.send(()).unwrap();
+    });
+    drop(streamer);
+    release.join().expect("Release thread panicked!");
+    assert!(gate.wait().is_ok(), "Request being loaded should be completed on drop!");
+}
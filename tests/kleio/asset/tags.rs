@@ -0,0 +1,254 @@
+use std::{io::{Read, ErrorKind}, sync::{Arc, Mutex}};
+use olympus_kleio::asset::{KAssetSource, KAssetBroker, KAssetSourceMemory, KAssetPath, KAssetTags, KAssetTagMatch, KAssetTagError,
+    KASSET_TAGS_MAX_VARIANTS, KAssetLocalization, KAssetLocaleLayout, KAssetChange, KAssetChangeKind};
+use super::create_memory_source;
+
+/// Memory source counting assets probed and reporting changes pushed by test.
+struct ProbedSource {
+    // Assets of source
+    memory : KAssetSourceMemory,
+
+    // Count of has_asset() calls
+    probes : Mutex<usize>,
+
+    // Changes returned by next poll
+    changes : Mutex<Vec<KAssetChange>>,
+}
+
+impl ProbedSource {
+    /// Get count of has_asset() calls since last call.
+    fn take_probes(&self) -> usize {
+        std::mem::take(&mut *self.probes.lock().unwrap())
+    }
+
+    /// Add asset and report change.
+    fn create(&self, path : &str, content : &[u8]) {
+        self.memory.add_asset(path, content.to_vec()).unwrap();
+        self.changes.lock().unwrap().push(KAssetChange { path : KAssetPath::new(path).unwrap(), kind : KAssetChangeKind::Created });
+    }
+}
+
+impl KAssetSource for ProbedSource {
+    fn has_asset(&self, path: &KAssetPath) -> bool {
+        *self.probes.lock().unwrap() += 1;
+        self.memory.has_asset(path)
+    }
+
+    fn get_asset(&self, path: &KAssetPath) -> Result<Box<dyn Read>, std::io::Error> {
+        self.memory.get_asset(path)
+    }
+
+    fn poll_changes(&self) -> Vec<KAssetChange> {
+        std::mem::take(&mut *self.changes.lock().unwrap())
+    }
+}
+
+/// Read asset of broker to a string.
+fn read_string(kab : &KAssetBroker, path : &str) -> Result<String, std::io::Error> {
+    let mut text = String::new();
+    kab.get_asset(path)?.read_to_string(&mut text)?;
+    Ok(text)
+}
+
+/// Create `(key, value)` tags from `key=value` tags.
+fn create_tags(tags : &[&str]) -> Vec<(String, String)> {
+    tags.iter().map(|tag| {
+        let (key, value) = tag.split_once('=').unwrap();
+        (key.to_string(), value.to_string())
+    }).collect()
+}
+
+/// Create a [KAssetTagMatch] of path and `key=value` tags.
+fn create_match(path : &str, tags : &[&str]) -> KAssetTagMatch {
+    KAssetTagMatch { path : KAssetPath::new(path).unwrap(), tags : create_tags(tags) }
+}
+
+#[test]
+/// Create [KAssetTags] and their ranked variants.
+///
+/// # Verification(s)
+/// V1 | KAssetTags::new() ranks keys and values in order given.
+/// V2 | KAssetTags::new() and add() return Err(KAssetTagError::InvalidTag) for invalid tags.
+/// V3 | KAssetTags::add() returns Err(KAssetTagError::TooManyVariants) past KASSET_TAGS_MAX_VARIANTS variants.
+/// V4 | KAssetTags::get_variants() gives variants from best to worst with tags sorted by key in file name.
+fn kasset_tags_variants() {
+    // V1 | KAssetTags::new() ranks keys and values in order given.
+    let mut tags = KAssetTags::new(&["quality=high", "platform=steamdeck", "quality=medium", "quality=high"]).unwrap();
+    assert!(tags.get_keys() == ["quality", "platform"], "Wrong keys {:?}!", tags.get_keys());
+    assert!(tags.get_values("quality") == ["high", "medium"], "Wrong values!");
+    assert!(tags.get_values("language").is_empty(), "Inactive key shouldn't have values!");
+    assert!(KAssetTags::new(&[]).unwrap().is_empty(), "Tags should be empty!");
+
+    // V2 | KAssetTags::new() and add() return Err(KAssetTagError::InvalidTag) for invalid tags.
+    for tag in ["quality", "=high", "quality=", "quality=high=4k", "quality=hi gh", "qu@lity=high", "quality=high.png"] {
+        assert!(KAssetTags::new(&[tag]) == Err(KAssetTagError::InvalidTag), "Tag {:?} should be invalid!", tag);
+    }
+    assert!(tags.add("platform", "steam/deck") == Err(KAssetTagError::InvalidTag), "Tag should be invalid!");
+
+    // V3 | KAssetTags::add() returns Err(KAssetTagError::TooManyVariants) past KASSET_TAGS_MAX_VARIANTS variants.
+    let mut many = KAssetTags::default();
+    for key in 0..8 {
+        many.add(&format!("key{}", key), "value").unwrap();
+    }
+    assert!(2usize.pow(8) - 1 <= KASSET_TAGS_MAX_VARIANTS, "Variants should be within limit!");
+    assert!(many.add("key8", "value") == Err(KAssetTagError::TooManyVariants), "Variants should exceed limit!");
+    assert!(many.get_keys().len() == 8, "Tag shouldn't be added!");
+
+    // V4 | KAssetTags::get_variants() gives variants from best to worst with tags sorted by key in file name.
+    let variants = tags.get_variants(&KAssetPath::new("textures/rock.png").unwrap());
+    let expected = [
+        create_match("textures/rock@platform=steamdeck@quality=high.png", &["quality=high", "platform=steamdeck"]),
+        create_match("textures/rock@quality=high.png", &["quality=high"]),
+        create_match("textures/rock@platform=steamdeck@quality=medium.png", &["quality=medium", "platform=steamdeck"]),
+        create_match("textures/rock@quality=medium.png", &["quality=medium"]),
+        create_match("textures/rock@platform=steamdeck.png", &["platform=steamdeck"]),
+    ];
+    assert!(variants == expected, "Wrong variants {:?}!", variants);
+    assert!(tags.get_variants(&KAssetPath::new("readme").unwrap())[1].path.as_str() == "readme@quality=high", "Wrong variant!");
+    assert!(tags.get_variants(&KAssetPath::root()).is_empty(), "Root shouldn't have variants!");
+}
+
+#[test]
+/// Resolve assets to their best tagged variant across sources.
+///
+/// # Verification(s)
+/// V1 | Without tags, assets are served as is.
+/// V2 | KAssetBroker::resolve_tags() picks best variant of any source, before untagged asset of sources of higher priority.
+/// V3 | Among sources having best variant, the source of highest priority serves it.
+/// V4 | KAssetBroker::get_asset(), get_asset_seekable() and get_asset_metadata() serve variant picked.
+/// V5 | KAssetBroker::set_tags() switches tags at runtime.
+/// V6 | KAssetBroker::resolve_tags() returns Err() of kind NotFound if no variant nor untagged asset exists.
+fn kasset_tags_broker() {
+    let kab = KAssetBroker::new();
+    let dlc = kab.add_source(create_memory_source(&[("textures/rock@quality=4k.png", b"Rock 4K")])).unwrap();
+    kab.add_source(create_memory_source(&[("textures/rock.png", b"Rock"), ("textures/rock@quality=high.png", b"Rock high"),
+        ("textures/grass.png", b"Grass"), ("textures/grass@platform=steamdeck.png", b"Grass deck"), ("textures/tree@quality=low.png", b"Tree low")])).unwrap();
+    let mod_source = kab.add_source(create_memory_source(&[("textures/rock@quality=high.png", b"Rock high mod")])).unwrap();
+    kab.set_source_priority(mod_source, 0).unwrap();
+    kab.set_source_priority(dlc, 2).unwrap();
+
+    // V1 | Without tags, assets are served as is.
+    assert!(kab.get_tags().is_empty(), "Tags should be empty by default!");
+    assert!(read_string(&kab, "textures/rock.png").unwrap() == "Rock", "Wrong asset!");
+    assert!(kab.resolve_tags("textures/rock.png").unwrap() == create_match("textures/rock.png", &[]), "Untagged asset should be picked!");
+
+    // V2 | KAssetBroker::resolve_tags() picks best variant of any source, before untagged asset of sources of higher priority.
+    kab.set_tags(KAssetTags::new(&["quality=4k", "quality=high", "platform=steamdeck"]).unwrap());
+    assert!(kab.resolve_tags("textures/rock.png").unwrap() == create_match("textures/rock@quality=4k.png", &["quality=4k"]), "4k should be picked!");
+    assert!(kab.resolve_tags("textures/grass.png").unwrap() == create_match("textures/grass@platform=steamdeck.png", &["platform=steamdeck"]), "steamdeck should be picked!");
+    assert!(kab.resolve_tags("textures/tree.png").is_err(), "Variant of inactive value shouldn't be picked!");
+
+    // V3 | Among sources having best variant, the source of highest priority serves it.
+    kab.remove_source(dlc).unwrap();
+    assert!(kab.resolve_tags("textures/rock.png").unwrap() == create_match("textures/rock@quality=high.png", &["quality=high"]), "high should be picked!");
+    assert!(read_string(&kab, "textures/rock.png").unwrap() == "Rock high mod", "Variant of mod should be served!");
+
+    // V4 | KAssetBroker::get_asset(), get_asset_seekable() and get_asset_metadata() serve variant picked.
+    assert!(read_string(&kab, "textures/grass.png").unwrap() == "Grass deck", "Wrong variant!");
+    assert!(read_string(&kab, "textures/grass@platform=steamdeck.png").unwrap() == "Grass deck", "Variant should be readable by its path!");
+    assert!(kab.get_asset_seekable("textures/grass.png").unwrap().get_length() == 10, "Wrong variant length!");
+    assert!(kab.get_asset_metadata("textures/rock.png").unwrap().size == 13, "Wrong variant metadata!");
+
+    // V5 | KAssetBroker::set_tags() switches tags at runtime.
+    kab.set_tags(KAssetTags::new(&["quality=low"]).unwrap());
+    assert!(read_string(&kab, "textures/rock.png").unwrap() == "Rock", "Untagged asset should be served!");
+    assert!(read_string(&kab, "textures/tree.png").unwrap() == "Tree low", "Wrong variant!");
+    kab.set_tags(KAssetTags::default());
+    assert!(kab.get_asset("textures/tree.png").is_err(), "Variant shouldn't be served without tags!");
+
+    // V6 | KAssetBroker::resolve_tags() returns Err() of kind NotFound if no variant nor untagged asset exists.
+    assert!(kab.resolve_tags("textures/missing.png").unwrap_err().kind() == ErrorKind::NotFound, "Missing asset shouldn't resolve!");
+}
+
+#[test]
+/// Resolve tagged variants of locale variants.
+///
+/// # Verification(s)
+/// V1 | Tagged variant of locale variant is served.
+/// V2 | Locale variant having only tagged variants is picked.
+fn kasset_tags_locale() {
+    let kab = KAssetBroker::new();
+    kab.add_source(create_memory_source(&[("ui/title.png", b"Title"), ("ui/title.fr.png", b"Titre"), ("ui/title.fr@quality=high.png", b"Titre high"),
+        ("ui/logo.png", b"Logo"), ("ui/logo.fr@quality=high.png", b"Logo high")])).unwrap();
+    kab.set_localization(Some(KAssetLocalization::new("fr-CA", KAssetLocaleLayout::Suffix).unwrap()));
+    kab.set_tags(KAssetTags::new(&["quality=high"]).unwrap());
+
+    // V1 | Tagged variant of locale variant is served.
+    assert!(kab.resolve_tags("ui/title.png").unwrap() == create_match("ui/title.fr@quality=high.png", &["quality=high"]), "Wrong variant!");
+    assert!(read_string(&kab, "ui/title.png").unwrap() == "Titre high", "Wrong variant!");
+
+    // V2 | Locale variant having only tagged variants is picked.
+    assert!(kab.resolve_locale("ui/logo.png").unwrap().locale.as_deref() == Some("fr"), "fr should be picked!");
+    assert!(read_string(&kab, "ui/logo.png").unwrap() == "Logo high", "Wrong variant!");
+}
+
+#[test]
+/// Remember variants resolved by [KAssetBroker] until they may change.
+///
+/// # Verification(s)
+/// V1 | Variants aren't probed again when asset is fetched again.
+/// V2 | Variants are resolved again when tags or localization change.
+/// V3 | Variants are resolved again when sources change.
+/// V4 | Variants are resolved again when KAssetBroker::poll_changes() reports a change.
+/// V5 | Variant removed from a source without revision is resolved again.
+/// V6 | Variants are resolved again when KAssetSourceMemory content changes.
+fn kasset_tags_memoised() {
+    let kab = KAssetBroker::new();
+    let memory = KAssetSourceMemory::new("probed");
+    for (path, data) in [("ui/title.png", "Title"), ("ui/title.fr.png", "Titre"), ("ui/title.fr@quality=high.png", "Titre high")] {
+        memory.add_asset(path, data.as_bytes().to_vec()).unwrap();
+    }
+    let source = Arc::new(ProbedSource { memory, probes : Mutex::new(0), changes : Mutex::new(Vec::new()) });
+    kab.add_source(source.clone()).unwrap();
+    kab.set_localization(Some(KAssetLocalization::new("fr-CA", KAssetLocaleLayout::Suffix).unwrap()));
+    kab.set_tags(KAssetTags::new(&["quality=high"]).unwrap());
+
+    // V1 | Variants aren't probed again when asset is fetched again.
+    assert!(read_string(&kab, "ui/title.png").unwrap() == "Titre high", "Wrong variant!");
+    assert!(source.take_probes() > 1, "Variants should be probed!");
+    assert!(read_string(&kab, "ui/title.png").unwrap() == "Titre high", "Wrong variant!");
+    assert!(kab.has_asset("ui/title.png"), "Asset should be found!");
+    assert!(source.take_probes() == 4, "Only variant served should be probed!");
+
+    // V2 | Variants are resolved again when tags or localization change.
+    kab.set_tags(KAssetTags::default());
+    assert!(read_string(&kab, "ui/title.png").unwrap() == "Titre", "Tags should be forgotten!");
+    kab.set_localization(None);
+    assert!(read_string(&kab, "ui/title.png").unwrap() == "Title", "Localization should be forgotten!");
+    kab.set_locale("fr").unwrap();
+    kab.set_tags(KAssetTags::new(&["quality=high"]).unwrap());
+    assert!(read_string(&kab, "ui/title.png").unwrap() == "Titre high", "Wrong variant!");
+
+    // V3 | Variants are resolved again when sources change.
+    let id = kab.add_source(create_memory_source(&[("ui/title.fr@quality=high.png", b"Titre mod")])).unwrap();
+    kab.set_source_priority(id, 0).unwrap();
+    assert!(read_string(&kab, "ui/title.png").unwrap() == "Titre mod", "Variant of mod should be served!");
+    kab.remove_source(id).unwrap();
+    kab.set_locale("fr-CA").unwrap();
+    assert!(read_string(&kab, "ui/title.png").unwrap() == "Titre high", "Wrong variant!");
+
+    // V4 | Variants are resolved again when KAssetBroker::poll_changes() reports a change.
+    source.create("ui/title.fr-CA.png", b"Titre CA");
+    assert!(read_string(&kab, "ui/title.png").unwrap() == "Titre high", "Variant should be remembered until polled!");
+    assert!(kab.poll_changes().len() == 1, "Change should be reported!");
+    assert!(read_string(&kab, "ui/title.png").unwrap() == "Titre CA", "New variant should be served!");
+
+    // V5 | Variant removed from a source without revision is resolved again.
+    source.memory.remove_asset("ui/title.fr-CA.png").unwrap();
+    assert!(read_string(&kab, "ui/title.png").unwrap() == "Titre high", "Removed variant shouldn't be served!");
+
+    // V6 | Variants are resolved again when KAssetSourceMemory content changes.
+    let kab = KAssetBroker::new();
+    let memory = Arc::new(KAssetSourceMemory::new("memory"));
+    memory.add_asset("tex.png", b"Tex".to_vec()).unwrap();
+    kab.add_source(memory.clone()).unwrap();
+    kab.set_tags(KAssetTags::new(&["quality=high"]).unwrap());
+    assert!(read_string(&kab, "tex.png").unwrap() == "Tex", "Wrong variant!");
+    memory.add_asset("tex@quality=high.png", b"Tex high".to_vec()).unwrap();
+    assert!(read_string(&kab, "tex.png").unwrap() == "Tex high", "Added variant should be served!");
+    memory.remove_asset("tex@quality=high.png").unwrap();
+    assert!(read_string(&kab, "tex.png").unwrap() == "Tex", "Removed variant shouldn't be served!");
+    memory.add_asset("tex@quality=high.png", b"Tex high".to_vec()).unwrap();
+    memory.remove_asset("tex.png").unwrap();
+    assert!(read_string(&kab, "tex.png").unwrap() == "Tex high", "Variant should be served without default asset!");
+}
@@ -0,0 +1,226 @@
+use std::{io::Read, sync::Arc, time::{Duration, SystemTime}};
+use olympus_kleio::asset::{KAssetBroker, KAssetSourceMemory, KAssetSourceId, KAssetPath, KAssetBundle, KAssetTags, KAssetTrace, KAssetTraceEvent,
+    KAssetTraceError, KAssetAccess, KAssetAccessKind, KAssetStreamer, KAssetDecompression};
+
+/// "Hello, world!" compressed with gzip.
+static HELLO_GZIP : [u8; 33] = [0x1F, 0x8B, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF, 0xF3, 0x48, 0xCD, 0xC9, 0xC9, 0xD7, 0x51, 0x28,
+    0xCF, 0x2F, 0xCA, 0x49, 0x51, 0x04, 0x00, 0xE6, 0xC6, 0xE6, 0xEB, 0x0D, 0x00, 0x00, 0x00];
+
+/// Create a broker with a base source of levels and textures and a patch source, returning their identifiers.
+fn create_broker() -> (KAssetBroker, KAssetSourceId, KAssetSourceId) {
+    let base = KAssetSourceMemory::new("base");
+    base.add_asset("levels/castle.lvl", vec![1; 100]).unwrap();
+    base.add_asset("levels/forest.lvl", vec![2; 50]).unwrap();
+    base.add_asset("textures/wall.png", vec![3; 10]).unwrap();
+    base.add_asset("textures/wall@quality=high.png", vec![4; 40]).unwrap();
+    base.add_asset("textures/tree.png", vec![5; 20]).unwrap();
+    base.add_asset("textures/unused.png", vec![6; 30]).unwrap();
+    let patch = KAssetSourceMemory::new("patch");
+    patch.add_asset("levels/castle.lvl", vec![7; 120]).unwrap();
+
+    let broker = KAssetBroker::new();
+    let patch_id = broker.add_source(Arc::new(patch)).unwrap();
+    let base_id = broker.add_source(Arc::new(base)).unwrap();
+    broker.set_source_name(base_id, "base").unwrap();
+
+    (broker, base_id, patch_id)
+}
+
+/// Read asset of broker entirely, returning bytes read.
+fn read_asset(broker : &KAssetBroker, path : &str) -> usize {
+    broker.get_asset(path).unwrap().read_to_end(&mut Vec::new()).unwrap()
+}
+
+/// Create an access of trace for tools.
+fn create_access(kind : KAssetAccessKind, path : &str, source : Option<KAssetSourceId>) -> KAssetTraceEvent {
+    KAssetTraceEvent::Access(KAssetAccess { timestamp : Duration::ZERO, kind, path : KAssetPath::new(path).unwrap(), resolved : KAssetPath::new(path).unwrap(),
+        source, bytes : 0, elapsed : Duration::ZERO })
+}
+
+#[test]
+/// Record accesses of [KAssetBroker] in a [KAssetTrace].
+///
+/// # Verification(s)
+/// V1 | KAssetBroker::has_asset() and get_asset() aren't recorded unless tracing.
+/// V2 | KAssetBroker::has_asset() is recorded with source resolving asset, or None if not found.
+/// V3 | KAssetBroker::get_asset() is recorded with source, resolved path and bytes read once asset is dropped.
+/// V4 | KAssetBroker::get_asset() failing is recorded without source.
+/// V5 | Assets opened by KAssetStreamer are recorded.
+/// V6 | KAssetBroker::mark_trace() returns Err(KAssetTraceError) for an invalid label or when not tracing.
+/// V7 | KAssetBroker::start_trace() discards trace being recorded and KAssetBroker::stop_trace() gives it.
+fn kasset_trace_record() {
+    let (broker, base_id, patch_id) = create_broker();
+
+    // V1 | KAssetBroker::has_asset() and get_asset() aren't recorded unless tracing.
+    assert!(broker.has_asset("levels/castle.lvl") && !broker.has_asset("levels/missing.lvl") && !broker.has_asset("../escape.lvl"), "Wrong assets found!");
+    read_asset(&broker, "levels/castle.lvl");
+    assert!(!broker.is_tracing() && broker.get_trace().is_none() && broker.stop_trace().is_none(), "Broker shouldn't be tracing!");
+
+    // V2 | KAssetBroker::has_asset() is recorded with source resolving asset, or None if not found.
+    broker.start_trace();
+    assert!(broker.is_tracing(), "Broker should be tracing!");
+    assert!(broker.has_asset("textures/tree.png") && !broker.has_asset("textures/missing.png"), "Wrong assets found!");
+    let trace = broker.get_trace().unwrap();
+    let accesses = trace.get_accesses();
+    assert!(accesses.len() == 2 && accesses.iter().all(|access| access.kind == KAssetAccessKind::Has && access.bytes == 0), "Wrong accesses {:?}!", accesses);
+    assert!(accesses[0].source == Some(base_id) && accesses[1].source.is_none(), "Wrong sources!");
+    assert!(accesses[1].path == "textures/missing.png" && accesses[0].timestamp <= accesses[1].timestamp, "Wrong access!");
+
+    // V3 | KAssetBroker::get_asset() is recorded with source, resolved path and bytes read once asset is dropped.
+    broker.set_tags(KAssetTags::new(&["quality=high"]).unwrap());
+    let mut asset = broker.get_asset("levels/castle.lvl").unwrap();
+    let mut buffer = [0; 20];
+    asset.read_exact(&mut buffer).unwrap();
+    let access = broker.get_trace().unwrap().get_accesses()[2].clone();
+    assert!(access.kind == KAssetAccessKind::Get && access.source == Some(patch_id) && access.bytes == 0, "Bytes shouldn't be recorded while open!");
+    drop(asset);
+    let access = broker.get_trace().unwrap().get_accesses()[2].clone();
+    assert!(access.bytes == 20, "Wrong bytes read {:?}!", access);
+    assert!(read_asset(&broker, "textures/wall.png") == 40, "Variant should be read!");
+    let access = broker.get_trace().unwrap().get_accesses()[3].clone();
+    assert!(access.path == "textures/wall.png" && access.resolved == "textures/wall@quality=high.png" && access.bytes == 40, "Wrong access {:?}!", access);
+
+    // V4 | KAssetBroker::get_asset() failing is recorded without source.
+    assert!(broker.get_asset("textures/missing.png").is_err(), "Asset shouldn't be found!");
+    let access = broker.get_trace().unwrap().get_accesses()[4].clone();
+    assert!(access.kind == KAssetAccessKind::Get && access.source.is_none(), "Failure should be recorded!");
+
+    // V5 | Assets opened by KAssetStreamer are recorded.
+    let broker = Arc::new(broker);
+    let streamer = KAssetStreamer::new(broker.clone(), 1).unwrap();
+    streamer.request("levels/forest.lvl", 0).unwrap().wait().unwrap();
+    let trace = broker.get_trace().unwrap();
+    let access = trace.get_accesses()[5];
+    assert!(access.path == "levels/forest.lvl" && access.bytes == 50, "Streamed asset should be recorded {:?}!", access);
+    assert!(trace.get_bytes_read() == 110, "Wrong bytes read!");
+
+    // V6 | KAssetBroker::mark_trace() returns Err(KAssetTraceError) for an invalid label or when not tracing.
+    assert!(broker.mark_trace("") == Err(KAssetTraceError::InvalidLabel), "Label should be invalid!");
+    assert!(broker.mark_trace("castle\tkeep") == Err(KAssetTraceError::InvalidLabel), "Label should be invalid!");
+    broker.mark_trace("castle keep").unwrap();
+    assert!(matches!(broker.get_trace().unwrap().events.last(), Some(KAssetTraceEvent::Mark(_, label)) if label == "castle keep"), "Mark should be recorded!");
+
+    // V7 | KAssetBroker::start_trace() discards trace being recorded and KAssetBroker::stop_trace() gives it.
+    broker.start_trace();
+    read_asset(&broker, "textures/tree.png");
+    let trace = broker.stop_trace().unwrap();
+    assert!(trace.events.len() == 1 && trace.get_accesses()[0].path == "textures/tree.png", "Trace should be restarted!");
+    assert!(trace.started <= SystemTime::now() && !broker.is_tracing(), "Broker shouldn't be tracing!");
+    assert!(broker.mark_trace("castle") == Err(KAssetTraceError::NotTracing), "Broker shouldn't be tracing!");
+}
+
+#[test]
+/// Export and import [KAssetTrace].
+///
+/// # Verification(s)
+/// V1 | KAssetTrace::parse() reads trace written with Display.
+/// V2 | KAssetTrace::parse() ignores empty lines and comments.
+/// V3 | KAssetTrace::parse() returns Err(KAssetTraceError) for invalid lines, paths and labels.
+fn kasset_trace_export() {
+    let (broker, _, _) = create_broker();
+    broker.start_trace();
+    broker.mark_trace("castle").unwrap();
+    read_asset(&broker, "levels/castle.lvl");
+    broker.has_asset("levels/missing.lvl");
+    broker.mark_trace("forest (night)").unwrap();
+    let trace = broker.stop_trace().unwrap();
+
+    // V1 | KAssetTrace::parse() reads trace written with Display.
+    let text = trace.to_string();
+    let parsed = KAssetTrace::parse(&text).unwrap();
+    assert!(parsed.events.len() == 4 && parsed.get_accesses()[0].bytes == 120, "Wrong trace parsed {:?}!", parsed);
+    assert!(parsed.started.duration_since(SystemTime::UNIX_EPOCH).unwrap().as_millis() == trace.started.duration_since(SystemTime::UNIX_EPOCH).unwrap().as_millis(),
+        "Wrong start time!");
+    assert!(parsed.to_string() == text, "Trace should be identical once exported again!");
+    for (parsed, event) in parsed.events.iter().zip(&trace.events) {
+        match (parsed, event) {
+            (KAssetTraceEvent::Access(parsed), KAssetTraceEvent::Access(access)) => assert!(parsed.path == access.path && parsed.source == access.source
+                && parsed.kind == access.kind && parsed.elapsed.as_micros() == access.elapsed.as_micros(), "Wrong access!"),
+            (KAssetTraceEvent::Mark(_, parsed), KAssetTraceEvent::Mark(_, label)) => assert!(parsed == label, "Wrong mark!"),
+            _ => panic!("Wrong event!"),
+        }
+    }
+
+    // V2 | KAssetTrace::parse() ignores empty lines and comments.
+    let parsed = KAssetTrace::parse("# Trace\r\n\n10\tget\t3\t100\t20\tlevels/my castle.lvl\tlevels/my castle.lvl\r\n  \n20\thas\t-\t0\t5\ta.txt\ta.txt\n").unwrap();
+    let accesses = parsed.get_accesses();
+    assert!(accesses.len() == 2 && accesses[0].path == "levels/my castle.lvl" && accesses[0].source.unwrap().get_value() == 3, "Wrong trace parsed!");
+    assert!(accesses[1].kind == KAssetAccessKind::Has && accesses[1].source.is_none() && accesses[1].timestamp == Duration::from_micros(20), "Wrong trace parsed!");
+    assert!(parsed.started == SystemTime::UNIX_EPOCH, "Start time should be unknown!");
+
+    // V3 | KAssetTrace::parse() returns Err(KAssetTraceError) for invalid lines, paths and labels.
+    for line in ["10\tget\t3\t100\t20\ta.txt", "10\tread\t3\t100\t20\ta.txt\ta.txt", "-10\tget\t3\t100\t20\ta.txt\ta.txt", "10\tget\tx\t100\t20\ta.txt\ta.txt",
+        "started yesterday", "10\tmark", "garbage"] {
+        assert!(KAssetTrace::parse(line) == Err(KAssetTraceError::InvalidLine), "Line {:?} should be invalid!", line);
+    }
+    assert!(KAssetTrace::parse("10\tget\t3\t100\t20\t../a.txt\ta.txt") == Err(KAssetTraceError::InvalidPath), "Path should be invalid!");
+    assert!(KAssetTrace::parse("10\tmark\t") == Err(KAssetTraceError::InvalidLabel), "Label should be invalid!");
+}
+
+#[test]
+/// Turn [KAssetTrace] into preload bundles and reports of unused assets.
+///
+/// # Verification(s)
+/// V1 | KAssetTrace::get_bundle() gives assets opened and found in order of first access.
+/// V2 | KAssetTrace::get_level_bundles() splits accesses at marks, merging labels marked twice.
+/// V3 | KAssetBroker::get_usage_report() lists assets never read across traces, counting resolved variants as read.
+/// V4 | KAssetBroker::get_usage_report() counts compressed assets as read when asset decompressed from them is read.
+fn kasset_trace_tools() {
+    let (broker, base_id, patch_id) = create_broker();
+    let trace = KAssetTrace { started : SystemTime::UNIX_EPOCH, events : vec![
+        create_access(KAssetAccessKind::Get, "textures/tree.png", Some(base_id)),
+        KAssetTraceEvent::Mark(Duration::ZERO, String::from("castle")),
+        create_access(KAssetAccessKind::Get, "levels/castle.lvl", Some(patch_id)),
+        create_access(KAssetAccessKind::Has, "textures/unused.png", Some(base_id)),
+        create_access(KAssetAccessKind::Get, "textures/missing.png", None),
+        KAssetTraceEvent::Mark(Duration::ZERO, String::from("forest")),
+        create_access(KAssetAccessKind::Get, "textures/tree.png", Some(base_id)),
+        KAssetTraceEvent::Mark(Duration::ZERO, String::from("castle")),
+        create_access(KAssetAccessKind::Get, "levels/castle.lvl", Some(patch_id)),
+        create_access(KAssetAccessKind::Get, "textures/tree.png", Some(base_id)),
+        KAssetTraceEvent::Mark(Duration::ZERO, String::from("menu")),
+    ]};
+
+    // V1 | KAssetTrace::get_bundle() gives assets opened and found in order of first access.
+    assert!(trace.get_bundle() == KAssetBundle::from_paths(["textures/tree.png", "levels/castle.lvl"]).unwrap(), "Wrong bundle {:?}!", trace.get_bundle());
+
+    // V2 | KAssetTrace::get_level_bundles() splits accesses at marks, merging labels marked twice.
+    let bundles = trace.get_level_bundles();
+    let labels : Vec<&str> = bundles.iter().map(|(label, _)| label.as_str()).collect();
+    assert!(labels == ["", "castle", "forest", "menu"], "Wrong labels {:?}!", labels);
+    assert!(bundles[1].1 == KAssetBundle::from_paths(["levels/castle.lvl", "textures/tree.png"]).unwrap(), "Wrong bundle {:?}!", bundles[1].1);
+    assert!(bundles[2].1 == KAssetBundle::from_paths(["textures/tree.png"]).unwrap() && bundles[3].1.is_empty(), "Wrong bundles!");
+    let marked = KAssetTrace { started : SystemTime::UNIX_EPOCH, events : trace.events[1..].to_vec() };
+    assert!(marked.get_level_bundles()[0].0 == "castle", "Empty bundle before first mark shouldn't be given!");
+
+    // V3 | KAssetBroker::get_usage_report() lists assets never read across traces, counting resolved variants as read.
+    broker.set_tags(KAssetTags::new(&["quality=high"]).unwrap());
+    broker.start_trace();
+    read_asset(&broker, "textures/wall.png");
+    let report = broker.get_usage_report(&[trace, broker.stop_trace().unwrap()], "**").unwrap();
+    let unused : Vec<&str> = report.unused.iter().map(|unused| unused.path.as_str()).collect();
+    assert!(unused == ["levels/forest.lvl", "textures/unused.png", "textures/wall.png"], "Wrong unused assets {:?}!", unused);
+    assert!(report.used_count == 3 && report.get_unused_bytes() == 90, "Wrong report {:?}!", report);
+    assert!(report.unused[0].source.id == base_id && report.unused[0].size == Some(50), "Wrong unused asset!");
+    assert!(report.to_string().starts_with("3 of 6 assets unused (90 bytes)") && report.to_string().contains("base"), "Wrong report:\n{}", report);
+    assert!(broker.get_usage_report(&[], "levels/*").unwrap().unused.len() == 2, "Every level should be unused!");
+
+    // V4 | KAssetBroker::get_usage_report() counts compressed assets as read when asset decompressed from them is read.
+    let (broker, _, _) = create_broker();
+    let compressed = KAssetSourceMemory::new("compressed");
+    compressed.add_asset("config.json.gz", HELLO_GZIP.to_vec()).unwrap();
+    compressed.add_asset("intro.txt.gz", HELLO_GZIP.to_vec()).unwrap();
+    compressed.add_asset("outro.txt.gz", HELLO_GZIP.to_vec()).unwrap();
+    let plain = KAssetSourceMemory::new("plain");
+    plain.add_asset("intro.txt", b"Hello, world!".to_vec()).unwrap();
+    let plain_id = broker.add_source(Arc::new(plain)).unwrap();
+    broker.set_source_priority(plain_id, 0).unwrap();
+    broker.add_source(Arc::new(compressed)).unwrap();
+    broker.set_decompression(Some(KAssetDecompression::default()));
+    broker.start_trace();
+    read_asset(&broker, "config.json");
+    read_asset(&broker, "intro.txt");
+    let report = broker.get_usage_report(&[broker.stop_trace().unwrap()], "*.*").unwrap();
+    let unused : Vec<&str> = report.unused.iter().map(|unused| unused.path.as_str()).collect();
+    assert!(unused == ["intro.txt.gz", "outro.txt.gz"] && report.used_count == 2, "Wrong unused assets {:?}!", unused);
+}
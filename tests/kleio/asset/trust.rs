@@ -0,0 +1,281 @@
+use std::{path::PathBuf, fs, io::{Cursor, Read, ErrorKind}, sync::Arc};
+use olympus_kleio::asset::{KAssetBroker, KAssetBrokerError, KAssetSource, KAssetSourceFolder, KAssetSourceMemory, KAssetSourcePak, KAssetSinkFolder, KAssetSourcePakError,
+    KAssetProvenanceStatus, KAssetPath, KPakWriter, KPakWriterError, KIntegrityManifest, KIntegrityManifestError, KIntegrityError, KPublicKey, KSigningKey,
+    KTrustPolicy, KTrustError, KINTEGRITY_MANIFEST_PATH};
+
+/// Root path of test folder
+static TEST_FOLDER: &str = "../target/tests/kleio/asset/";
+
+/// Decode hexadecimal digits into bytes.
+fn from_hex(hex : &str) -> Vec<u8> {
+    (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap()).collect()
+}
+
+/// Write a pack at `pak_path` with `(path, content)` assets, signed with `key` if any.
+fn write_pak(pak_path : &str, assets : &[(&str, &str)], key : Option<&KSigningKey>) {
+    let mut writer = KPakWriter::create(PathBuf::from(pak_path)).unwrap();
+    if let Some(key) = key {
+        writer.set_signing_key(key.clone()).unwrap();
+    }
+
+    for (path, content) in assets {
+        writer.add_asset(PathBuf::from(path), &mut Cursor::new(content)).unwrap();
+    }
+    writer.finish().unwrap();
+}
+
+/// Read asset of broker to a string.
+fn read_string(kab : &KAssetBroker, path : &str) -> String {
+    let mut content = String::new();
+    kab.get_asset(path).unwrap().read_to_string(&mut content).unwrap();
+    content
+}
+
+#[test]
+/// Sign and verify with [KSigningKey] and [KPublicKey].
+///
+/// # Verification(s)
+/// V1 | KSigningKey gives public keys and signatures of RFC 8032 test vectors.
+/// V2 | KPublicKey::verify() accepts signatures of RFC 8032 test vectors and refuses modified messages and signatures.
+/// V3 | KPublicKey is written and parsed as 64 hexadecimal digits.
+fn kasset_trust_keys() {
+    let vectors = [
+        ("9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60", "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a", "",
+            "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b"),
+        ("4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb", "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c", "72",
+            "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00"),
+        ("c5aa8df43f9f837bedb7442f31dcb7b166d38535076f094b85ce3a2e0b4458f7", "fc51cd8e6218a1a38da47ed00230f0580816ed13ba3303ac5deb911548908025", "af82",
+            "6291d657deec24024827e69c3abe01a30ce548a284743a445e3680d7db5ac3ac18ff9b538d16f290ae67f760984dc6594a7c15e9716ed28dc027beceea1ec40a"),
+    ];
+
+    for (seed, public_key, message, signature) in vectors {
+        // V1 | KSigningKey gives public keys and signatures of RFC 8032 test vectors.
+        let key = KSigningKey::from_seed(from_hex(seed).try_into().unwrap());
+        let message = from_hex(message);
+        let signature : [u8; 64] = from_hex(signature).try_into().unwrap();
+        assert!(key.get_public_key().to_string() == public_key, "Wrong public key {}!", key.get_public_key());
+        assert!(key.sign(&message) == signature, "Wrong signature of {:?}!", message);
+
+        // V2 | KPublicKey::verify() accepts signatures of RFC 8032 test vectors and refuses modified messages and signatures.
+        let public_key = KPublicKey::from_hex(public_key).unwrap();
+        assert!(public_key.verify(&message, &signature), "Signature should be valid!");
+        assert!(!public_key.verify(&[message.as_slice(), b"!"].concat(), &signature), "Signature of modified message should be invalid!");
+
+        let mut modified = signature;
+        modified[40] ^= 1;
+        assert!(!public_key.verify(&message, &modified), "Modified signature should be invalid!");
+    }
+
+    // V3 | KPublicKey is written and parsed as 64 hexadecimal digits.
+    let key = KSigningKey::from_seed([3; 32]).get_public_key();
+    assert!(KPublicKey::from_hex(&key.to_string()) == Some(key) && KPublicKey::from_bytes(key.get_bytes()) == key, "Key should be parsed back!");
+    assert!(KPublicKey::from_hex("d75a98").is_none() && KPublicKey::from_hex(&"g".repeat(64)).is_none(), "Invalid keys shouldn't be parsed!");
+    assert!(format!("{:?}", KSigningKey::from_seed([3; 32])) == format!("KSigningKey({})", key), "Private key shouldn't be written!");
+}
+
+#[test]
+/// Sign [KAssetSourcePak] with [KPakWriter::set_signing_key] and verify them.
+///
+/// # Verification(s)
+/// V1 | Signed pack gives its signing key and verifies with it, while its assets stay readable.
+/// V2 | Unsigned pack isn't signed.
+/// V3 | Pack modified after signing has an invalid signature or serves no modified asset.
+/// V4 | Pack replaced after verification serves no asset of the replacement.
+/// V5 | Pack flagged as signed without signature must return Err(MissingSignature).
+/// V6 | KPakWriter::set_signing_key() must return Err(AssetsAlreadyAdded) after adding assets.
+fn kasset_trust_signed_pak() {
+    // Test folder name
+    let folder_name: &str = &(TEST_FOLDER.to_owned() + "katrust_pak/");
+    fs::create_dir_all(folder_name).unwrap();
+    let key = KSigningKey::from_seed([1; 32]);
+
+    // V1 | Signed pack gives its signing key and verifies with it, while its assets stay readable.
+    let signed = folder_name.to_owned() + "signed.kpak";
+    write_pak(&signed, &[("maps/arena.map", "arena"), ("rules.txt", "no mods")], Some(&key));
+    let kasp = KAssetSourcePak::new(PathBuf::from(&signed)).unwrap();
+    assert!(kasp.get_signing_key() == Some(key.get_public_key()), "Pack should claim signing key!");
+    assert!(kasp.verify_signature() == Ok(Some(key.get_public_key())), "Pack signature should be valid!");
+    let mut content = String::new();
+    kasp.get_asset(&KAssetPath::new("rules.txt").unwrap()).unwrap().read_to_string(&mut content).unwrap();
+    assert!(content == "no mods", "Asset of signed pack should be readable!");
+
+    // V2 | Unsigned pack isn't signed.
+    let unsigned = folder_name.to_owned() + "unsigned.kpak";
+    write_pak(&unsigned, &[("rules.txt", "no mods")], None);
+    let kasp = KAssetSourcePak::new(PathBuf::from(&unsigned)).unwrap();
+    assert!(kasp.get_signing_key().is_none() && kasp.verify_signature() == Ok(None), "Pack shouldn't be signed!");
+
+    // V3 | Pack modified after signing has an invalid signature or serves no modified asset.
+    let mut data = fs::read(&signed).unwrap();
+    let position = data.windows(5).position(|window| window == b"arena").unwrap();
+    data[position] = b'A';
+    fs::write(&signed, &data).unwrap();
+    let kasp = KAssetSourcePak::new(PathBuf::from(&signed)).unwrap();
+    unsafe { kasp.set_memory_mapped(true); }
+    for path in ["maps/arena.map", "rules.txt"] {
+        let path = KAssetPath::new(path).unwrap();
+        let expected = path.as_str() == "rules.txt";
+        assert!(kasp.get_asset(&path).is_ok_and(|mut reader| reader.read_to_end(&mut Vec::new()).is_ok()) == expected, "Wrong read of {}!", path);
+        assert!(kasp.get_asset_seekable(&path).is_ok() == expected && kasp.get_asset_bytes(&path).is_ok() == expected, "Wrong read of {}!", path);
+    }
+
+    let mut header = data.clone();
+    header[6] |= 2;
+    fs::write(&signed, &header).unwrap();
+    let kasp = KAssetSourcePak::new(PathBuf::from(&signed)).unwrap();
+    assert!(kasp.verify_signature() == Err(KTrustError::InvalidSignature), "Modified pack signature should be invalid!");
+
+    // V4 | Pack replaced after verification serves no asset of the replacement.
+    let replaced = folder_name.to_owned() + "replaced.kpak";
+    write_pak(&replaced, &[("rules.txt", "no mods")], Some(&key));
+    let kasp = KAssetSourcePak::new(PathBuf::from(&replaced)).unwrap();
+    assert!(kasp.verify_signature().is_ok(), "Pack signature should be valid!");
+    write_pak(&replaced, &[("rules.txt", "all mod")], Some(&key));
+    let err = kasp.get_asset(&KAssetPath::new("rules.txt").unwrap()).err().unwrap();
+    assert!(err.kind() == ErrorKind::InvalidData && kasp.verify_signature().is_ok(), "Replaced asset shouldn't be served!");
+
+    // V5 | Pack flagged as signed without signature must return Err(MissingSignature).
+    fs::write(&signed, &data[..data.len() - 100]).unwrap();
+    assert!(matches!(KAssetSourcePak::new(PathBuf::from(&signed)), Err(KAssetSourcePakError::MissingSignature)), "Signature should be missing!");
+
+    // V6 | KPakWriter::set_signing_key() must return Err(AssetsAlreadyAdded) after adding assets.
+    let mut writer = KPakWriter::new(Cursor::new(Vec::new())).unwrap();
+    writer.add_asset(PathBuf::from("rules.txt"), &mut Cursor::new("no mods")).unwrap();
+    assert!(matches!(writer.set_signing_key(key), Err(KPakWriterError::AssetsAlreadyAdded)), "Signing key should be refused!");
+
+    // Clean test
+    fs::remove_dir_all(PathBuf::from(folder_name)).expect("Test couldn't be cleaned!");
+}
+
+#[test]
+/// Sign [KIntegrityManifest] with [KIntegrityManifest::to_signed_string] and parse them.
+///
+/// # Verification(s)
+/// V1 | Signed manifest ends with a signature line and is parsed back with its signer.
+/// V2 | Modified signed manifest must return Err(InvalidSignature).
+/// V3 | Manifests without signature or modified after parsing have no signer.
+fn kasset_trust_signed_manifest() {
+    let key = KSigningKey::from_seed([2; 32]);
+    let source = KAssetSourceMemory::new("base");
+    source.add_asset("maps/arena.map", b"arena".to_vec()).unwrap();
+    let manifest = KIntegrityManifest::generate(&source).unwrap();
+
+    // V1 | Signed manifest ends with a signature line and is parsed back with its signer.
+    let text = manifest.to_signed_string(&key);
+    assert!(text.starts_with(&manifest.to_string()) && text.lines().last().unwrap().starts_with(&format!("signature {} ", key.get_public_key())),
+        "Wrong signed manifest {}!", text);
+    let parsed = KIntegrityManifest::parse(&text).unwrap();
+    assert!(parsed.get_signer() == Some(key.get_public_key()) && parsed.get_entry_count() == 1, "Manifest should be signed!");
+
+    // V2 | Modified signed manifest must return Err(InvalidSignature).
+    let modified = text.replace(" 5 maps", " 6 maps");
+    assert!(KIntegrityManifest::parse(&modified) == Err(KIntegrityManifestError::InvalidSignature), "Modified manifest should be refused!");
+    let truncated = &text[..text.len() - 3];
+    assert!(KIntegrityManifest::parse(truncated) == Err(KIntegrityManifestError::InvalidSignature), "Truncated signature should be refused!");
+
+    // V3 | Manifests without signature or modified after parsing have no signer.
+    assert!(KIntegrityManifest::parse(&manifest.to_string()).unwrap().get_signer().is_none(), "Manifest shouldn't be signed!");
+    let mut parsed = parsed;
+    parsed.remove(&KAssetPath::new("maps/arena.map").unwrap());
+    assert!(parsed.get_signer().is_none(), "Modified manifest shouldn't be signed anymore!");
+}
+
+#[test]
+/// Refuse and disable sources of [KAssetBroker] with [KTrustPolicy].
+///
+/// # Verification(s)
+/// V1 | Official only policy accepts official pack and folder with official manifest, refusing others with the reason.
+/// V2 | Assets of folder signed by its manifest are verified against it.
+/// V3 | Policy set afterward disables sources failing it, skipped by reads and shown by provenance.
+/// V4 | Policy allowing unsigned mods accepts unsigned sources and sources of other keys, never invalid signatures.
+/// V5 | Policy of trusted keys accepts sources of any key listed.
+/// V6 | Removing policy enables all sources.
+/// V7 | Sink is exempt from policy, set before or after it.
+fn kasset_trust_broker() {
+    // Test folder name
+    let folder_name: &str = &(TEST_FOLDER.to_owned() + "katrust_broker/");
+    fs::create_dir_all(folder_name.to_owned() + "official").unwrap();
+    fs::create_dir_all(folder_name.to_owned() + "mod").unwrap();
+    let official = KSigningKey::from_seed([1; 32]);
+    let tournament = KSigningKey::from_seed([2; 32]);
+
+    write_pak(&(folder_name.to_owned() + "base.kpak"), &[("rules.txt", "official rules")], Some(&official));
+    write_pak(&(folder_name.to_owned() + "tournament.kpak"), &[("rules.txt", "tournament rules")], Some(&tournament));
+    fs::write(folder_name.to_owned() + "official/maps.txt", "arena").unwrap();
+    let manifest = KIntegrityManifest::generate(&KAssetSourceFolder::new(PathBuf::from(folder_name.to_owned() + "official")).unwrap()).unwrap();
+    fs::write(folder_name.to_owned() + "official/" + KINTEGRITY_MANIFEST_PATH, manifest.to_signed_string(&official)).unwrap();
+    fs::write(folder_name.to_owned() + "mod/rules.txt", "modded rules").unwrap();
+
+    let base = Arc::new(KAssetSourcePak::new(PathBuf::from(folder_name.to_owned() + "base.kpak")).unwrap());
+    let tournament_pak = Arc::new(KAssetSourcePak::new(PathBuf::from(folder_name.to_owned() + "tournament.kpak")).unwrap());
+    let official_folder = Arc::new(KAssetSourceFolder::new(PathBuf::from(folder_name.to_owned() + "official")).unwrap());
+    let mod_folder = Arc::new(KAssetSourceFolder::new(PathBuf::from(folder_name.to_owned() + "mod")).unwrap());
+
+    // V1 | Official only policy accepts official pack and folder with official manifest, refusing others with the reason.
+    let kab = KAssetBroker::new();
+    kab.set_trust_policy(Some(KTrustPolicy::official_only(official.get_public_key())));
+    assert!(matches!(kab.add_source(mod_folder.clone()), Err(KAssetBrokerError::SourceNotTrusted(KTrustError::Unsigned))), "Unsigned mod should be refused!");
+    assert!(matches!(kab.add_source(tournament_pak.clone()), Err(KAssetBrokerError::SourceNotTrusted(KTrustError::UntrustedKey))), "Other key should be refused!");
+    let folder_id = kab.add_source(official_folder.clone()).unwrap();
+    kab.add_source(base.clone()).unwrap();
+    assert!(kab.get_source_count() == 2 && read_string(&kab, "rules.txt") == "official rules", "Official sources should be accepted!");
+    assert!(kab.get_source_integrity(folder_id).unwrap().is_some_and(|manifest| manifest.get_signer() == Some(official.get_public_key())),
+        "Folder should be verified by its manifest!");
+
+    // V2 | Assets of folder signed by its manifest are verified against it.
+    fs::write(folder_name.to_owned() + "official/maps.txt", "trap!").unwrap();
+    let err = kab.get_asset("maps.txt").err().unwrap();
+    assert!(KIntegrityError::from_io_error(&err) == Some(KIntegrityError::HashMismatch), "Modified official asset should fail!");
+
+    // V3 | Policy set afterward disables sources failing it, skipped by reads and shown by provenance.
+    let kab = KAssetBroker::new();
+    let mod_id = kab.add_source(mod_folder.clone()).unwrap();
+    let base_id = kab.add_source(base.clone()).unwrap();
+    assert!(read_string(&kab, "rules.txt") == "modded rules", "Mod should override rules!");
+    let disabled = kab.set_trust_policy(Some(KTrustPolicy::official_only(official.get_public_key())));
+    assert!(disabled == vec![(mod_id, KTrustError::Unsigned)], "Mod should be disabled {:?}!", disabled);
+    assert!(kab.get_source_trust_error(mod_id).unwrap() == Some(KTrustError::Unsigned) && kab.get_source_trust_error(base_id).unwrap().is_none(), "Wrong trust errors!");
+    assert!(read_string(&kab, "rules.txt") == "official rules" && kab.has_source(mod_id), "Disabled mod should be skipped!");
+    let provenance = kab.get_provenance("rules.txt").unwrap();
+    assert!(provenance.sources[0].1 == KAssetProvenanceStatus::Disabled(KTrustError::Unsigned) && provenance.get_source().unwrap().id == base_id,
+        "Provenance should show disabled mod!");
+    assert!(provenance.to_string().contains("disabled (Source is not signed!)"), "Provenance should give reason {}!", provenance);
+
+    // V4 | Policy allowing unsigned mods accepts unsigned sources and sources of other keys, never invalid signatures.
+    let kab = KAssetBroker::new();
+    kab.set_trust_policy(Some(KTrustPolicy::allow_unsigned_mods(official.get_public_key())));
+    kab.add_source(mod_folder.clone()).unwrap();
+    kab.add_source(tournament_pak.clone()).unwrap();
+    let tampered = KAssetSourceMemory::new("tampered");
+    tampered.add_asset(KINTEGRITY_MANIFEST_PATH, manifest.to_signed_string(&official).replace("maps.txt", "rules.txt").into_bytes()).unwrap();
+    assert!(matches!(kab.add_source(Arc::new(tampered)), Err(KAssetBrokerError::SourceNotTrusted(KTrustError::InvalidSignature))), "Tampered source should be refused!");
+
+    // V5 | Policy of trusted keys accepts sources of any key listed.
+    let kab = KAssetBroker::new();
+    let policy = KTrustPolicy::trusted_keys(vec![official.get_public_key(), tournament.get_public_key()]);
+    assert!(policy.check(tournament_pak.as_ref()) == Ok(Some(tournament.get_public_key())) && policy.check(mod_folder.as_ref()) == Err(KTrustError::Unsigned),
+        "Wrong policy check!");
+    kab.set_trust_policy(Some(policy.clone()));
+    kab.add_source(tournament_pak.clone()).unwrap();
+    kab.add_source(base.clone()).unwrap();
+    assert!(read_string(&kab, "rules.txt") == "tournament rules" && kab.get_trust_policy() == Some(policy), "Tournament pack should be accepted!");
+
+    // V6 | Removing policy enables all sources.
+    let kab = KAssetBroker::new();
+    let mod_id = kab.add_source(mod_folder).unwrap();
+    kab.set_trust_policy(Some(KTrustPolicy::official_only(official.get_public_key())));
+    assert!(kab.set_trust_policy(None).is_empty() && kab.get_source_trust_error(mod_id).unwrap().is_none(), "Mod should be enabled!");
+    assert!(read_string(&kab, "rules.txt") == "modded rules", "Mod should be read again!");
+
+    // V7 | Sink is exempt from policy, set before or after it.
+    let kab = KAssetBroker::new();
+    kab.set_trust_policy(Some(KTrustPolicy::official_only(official.get_public_key())));
+    let sink_id = kab.set_sink(Arc::new(KAssetSinkFolder::new(PathBuf::from(folder_name.to_owned() + "saves")).unwrap())).unwrap();
+    kab.write_asset("save.txt", &mut Cursor::new("level 2")).unwrap();
+    assert!(read_string(&kab, "save.txt") == "level 2", "Sink should be accepted!");
+    assert!(kab.set_trust_policy(Some(KTrustPolicy::official_only(official.get_public_key()))).is_empty(), "Sink shouldn't be disabled!");
+    assert!(kab.get_source_trust_error(sink_id).unwrap().is_none() && read_string(&kab, "save.txt") == "level 2", "Sink should stay enabled!");
+
+    // Clean test
+    fs::remove_dir_all(PathBuf::from(folder_name)).expect("Test couldn't be cleaned!");
+}